@@ -1,3 +1,4 @@
+pub mod rate_limiter;
 pub mod twitch_api;
 pub mod youtube_api;
 pub mod youtube_live_chat;
@@ -0,0 +1,309 @@
+use crate::collectors::collector_trait::Collector;
+use crate::constants::{database as db_constants, rate_limit, twitch};
+use crate::database::models::{Channel, ScheduledStreamData, StreamData};
+use async_trait::async_trait;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// トークンバケット方式のレートリミッター
+///
+/// `capacity` 個までのバーストを許容し、`refill_per_sec` の速度でトークンを補充する。
+pub struct TokenBucket {
+    capacity: f64,
+    refill_per_sec: f64,
+    state: Mutex<(f64, Instant)>,
+}
+
+impl TokenBucket {
+    pub fn new(capacity: u32, refill_per_sec: f64) -> Self {
+        Self {
+            capacity: capacity as f64,
+            refill_per_sec,
+            state: Mutex::new((capacity as f64, Instant::now())),
+        }
+    }
+
+    /// 1分あたりの上限回数から作成
+    pub fn per_minute(limit: u32) -> Self {
+        Self::new(limit, limit as f64 / 60.0)
+    }
+
+    /// トークンを1つ取得する（不足している場合は補充されるまで待機）
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().await;
+                let (tokens, last_refill) = &mut *state;
+                let now = Instant::now();
+                *tokens = (*tokens
+                    + now.duration_since(*last_refill).as_secs_f64() * self.refill_per_sec)
+                    .min(self.capacity);
+                *last_refill = now;
+
+                if *tokens >= 1.0 {
+                    *tokens -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - *tokens) / self.refill_per_sec)
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// 429 / 503 など、時間をおけば成功する可能性があるHTTPエラー
+#[derive(Debug)]
+pub struct RetryableHttpError {
+    pub status: u16,
+    pub retry_after: Option<Duration>,
+    pub message: String,
+}
+
+impl RetryableHttpError {
+    /// レスポンスのステータスがリトライ対象なら Retry-After ヘッダー付きのエラーを作成
+    ///
+    /// 対象外のステータスの場合は `None`（レスポンスは消費される）。
+    pub async fn from_response(response: reqwest::Response) -> Option<Self> {
+        let status = response.status();
+        if !is_retryable_status(status.as_u16()) {
+            return None;
+        }
+
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        let message = response.text().await.unwrap_or_default();
+
+        Self::from_status(status.as_u16(), retry_after, message)
+    }
+
+    /// ステータスコードがリトライ対象ならエラーを作成（外部ライブラリのエラーの変換用）
+    pub fn from_status(
+        status: u16,
+        retry_after: Option<Duration>,
+        message: String,
+    ) -> Option<Self> {
+        is_retryable_status(status).then_some(Self {
+            status,
+            retry_after,
+            message,
+        })
+    }
+}
+
+impl fmt::Display for RetryableHttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "HTTP {}: {}", self.status, self.message)
+    }
+}
+
+impl std::error::Error for RetryableHttpError {}
+
+fn is_retryable_status(status: u16) -> bool {
+    status == 429 || status == 503
+}
+
+/// エラーがリトライ対象か判定し、対象なら Retry-After（あれば）を返す
+///
+/// 各コレクター・APIクライアントが 429 / 503 を `RetryableHttpError` に変換して返す。
+/// メッセージ文字列では判定しない（チャンネル名などに "503" が含まれると誤ってリトライするため）。
+fn retry_hint(error: &(dyn std::error::Error + Send + Sync + 'static)) -> Option<Option<Duration>> {
+    if let Some(http_error) = error.downcast_ref::<RetryableHttpError>() {
+        return Some(http_error.retry_after);
+    }
+    if let Some(status) = error
+        .downcast_ref::<reqwest::Error>()
+        .and_then(reqwest::Error::status)
+    {
+        return is_retryable_status(status.as_u16()).then_some(None);
+    }
+    None
+}
+
+/// 指数バックオフの設定
+#[derive(Debug, Clone, Copy)]
+pub struct BackoffPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for BackoffPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: rate_limit::RETRY_MAX_ATTEMPTS,
+            initial_backoff: Duration::from_millis(rate_limit::RETRY_INITIAL_BACKOFF_MS),
+            max_backoff: Duration::from_secs(rate_limit::RETRY_MAX_BACKOFF_SECS),
+        }
+    }
+}
+
+/// 429 / 503 の場合に指数バックオフ（Retry-After があればそれを優先）でリトライする
+pub async fn retry_with_backoff<T, F, Fut>(
+    policy: BackoffPolicy,
+    mut operation: F,
+) -> Result<T, Box<dyn std::error::Error + Send + Sync>>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, Box<dyn std::error::Error + Send + Sync>>>,
+{
+    let mut backoff = policy.initial_backoff;
+    let mut attempt = 0;
+
+    loop {
+        match operation().await {
+            Ok(value) => return Ok(value),
+            Err(e) => {
+                let Some(retry_after) = retry_hint(e.as_ref()) else {
+                    return Err(e);
+                };
+                attempt += 1;
+                if attempt > policy.max_attempts {
+                    return Err(e);
+                }
+
+                let wait = retry_after.unwrap_or(backoff).min(policy.max_backoff);
+                eprintln!(
+                    "[RateLimit] Retryable error ({}), retrying in {:?} (attempt {}/{})",
+                    e, wait, attempt, policy.max_attempts
+                );
+                tokio::time::sleep(wait).await;
+                backoff = (backoff * 2).min(policy.max_backoff);
+            }
+        }
+    }
+}
+
+/// レート制限とリトライを透過的に付与する Collector ラッパー
+///
+/// ポーリング1回ごとにトークンを1つ消費する。プラットフォーム単位でバケットを共有するため、
+/// 監視チャンネルが増えてもAPIの上限を超えないようにポーリングが自動的に間引かれる。
+pub struct RateLimitedCollector {
+    inner: Arc<dyn Collector + Send + Sync>,
+    bucket: TokenBucket,
+    policy: BackoffPolicy,
+}
+
+impl RateLimitedCollector {
+    pub fn new(inner: Arc<dyn Collector + Send + Sync>, bucket: TokenBucket) -> Self {
+        Self {
+            inner,
+            bucket,
+            policy: BackoffPolicy::default(),
+        }
+    }
+
+    /// プラットフォームごとの既定の上限でラップする
+    pub fn for_platform(platform: &str, inner: Arc<dyn Collector + Send + Sync>) -> Self {
+        let bucket = match platform {
+            p if p == db_constants::PLATFORM_TWITCH => TokenBucket::new(
+                twitch::RATE_LIMIT_BUCKET_CAPACITY as u32,
+                twitch::RATE_LIMIT_BUCKET_CAPACITY as f64 / twitch::RATE_LIMIT_WINDOW_SECS as f64,
+            ),
+            p if p == db_constants::PLATFORM_YOUTUBE => {
+                TokenBucket::per_minute(rate_limit::YOUTUBE_POLLS_PER_MINUTE)
+            }
+            _ => TokenBucket::per_minute(rate_limit::PUBLIC_API_POLLS_PER_MINUTE),
+        };
+        Self::new(inner, bucket)
+    }
+}
+
+#[async_trait]
+impl Collector for RateLimitedCollector {
+    async fn poll_channel(
+        &self,
+        channel: &Channel,
+    ) -> Result<Option<StreamData>, Box<dyn std::error::Error + Send + Sync>> {
+        retry_with_backoff(self.policy, || async {
+            self.bucket.acquire().await;
+            self.inner.poll_channel(channel).await
+        })
+        .await
+    }
+
+    async fn start_collection(
+        &self,
+        channel: &Channel,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.start_collection(channel).await
+    }
+
+    async fn fetch_schedule(
+        &self,
+        channel: &Channel,
+    ) -> Result<Vec<ScheduledStreamData>, Box<dyn std::error::Error + Send + Sync>> {
+        self.bucket.acquire().await;
+        self.inner.fetch_schedule(channel).await
+    }
+
+    async fn poll_interval_multiplier(&self) -> u32 {
+        self.inner.poll_interval_multiplier().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test]
+    async fn retries_only_retryable_errors() {
+        let policy = BackoffPolicy {
+            max_attempts: 2,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+        };
+
+        let calls = AtomicU32::new(0);
+        let result: Result<u32, _> = retry_with_backoff(policy, || async {
+            let n = calls.fetch_add(1, Ordering::SeqCst);
+            if n < 2 {
+                Err(
+                    RetryableHttpError::from_status(429, None, "Too Many Requests".to_string())
+                        .unwrap()
+                        .into(),
+                )
+            } else {
+                Ok(n)
+            }
+        })
+        .await;
+        assert_eq!(result.unwrap(), 2);
+
+        let calls = AtomicU32::new(0);
+        let result: Result<u32, _> = retry_with_backoff(policy, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err("HTTP 404 Not Found".into())
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // ステータスを含むだけのメッセージはリトライしない
+        let calls = AtomicU32::new(0);
+        let result: Result<u32, _> = retry_with_backoff(policy, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err("channel 'error429' not found".into())
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn from_status_accepts_only_retryable_statuses() {
+        assert!(RetryableHttpError::from_status(429, None, String::new()).is_some());
+        let unavailable =
+            RetryableHttpError::from_status(503, Some(Duration::from_secs(5)), String::new())
+                .unwrap();
+        assert_eq!(retry_hint(&unavailable), Some(Some(Duration::from_secs(5))));
+        assert!(RetryableHttpError::from_status(404, None, String::new()).is_none());
+        assert!(RetryableHttpError::from_status(500, None, String::new()).is_none());
+    }
+}
@@ -1,4 +1,5 @@
-use crate::config::keyring_store::KeyringStore;
+use crate::api::rate_limiter::RetryableHttpError;
+use crate::config::keyring_store::{KeyringStore, TokenKind};
 use crate::constants::{database as db_constants, twitch};
 use crate::oauth::twitch::TwitchOAuth;
 use chrono::{DateTime, Local};
@@ -10,10 +11,12 @@ use tauri::Emitter;
 use tokio::sync::Mutex;
 use twitch_api::{
     helix::{
+        schedule::{GetChannelStreamScheduleRequest, Segment},
         search::{Category, SearchCategoriesRequest},
         streams::{GetStreamsRequest, Stream},
         users::{GetUsersRequest, User},
-        HelixClient,
+        videos::{GetVideosRequest, Video, VideoTypeFilter},
+        ClientRequestError, HelixClient, HelixRequestGetError,
     },
     twitch_oauth2::{AccessToken, UserToken as TwitchApiUserToken},
     types,
@@ -70,9 +73,11 @@ impl TwitchApiClient {
     ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
         // Keyringからトークンを取得を試みる（Device Code Flowで取得したユーザートークン）
         if let Some(ref handle) = self.app_handle {
-            if let Ok(token_str) =
-                KeyringStore::get_token_with_app(handle, db_constants::PLATFORM_TWITCH)
-            {
+            if let Ok(token_str) = KeyringStore::get_token_with_app(
+                handle,
+                db_constants::PLATFORM_TWITCH,
+                TokenKind::Access,
+            ) {
                 return Ok(token_str);
             }
         }
@@ -98,6 +103,7 @@ impl TwitchApiClient {
                 KeyringStore::save_token_with_app(
                     handle,
                     db_constants::PLATFORM_TWITCH,
+                    TokenKind::Access,
                     &access_token_str,
                 )?;
             }
@@ -112,6 +118,20 @@ impl TwitchApiClient {
         )
     }
 
+    /// 保存済みユーザートークンとその持ち主のログイン名を取得（IRCの認証接続用）
+    pub async fn get_token_login(
+        &self,
+    ) -> Result<(String, String), Box<dyn std::error::Error + Send + Sync>> {
+        let token = self.get_access_token().await?;
+        {
+            let mut limiter = self.rate_limiter.lock().await;
+            limiter.track_request();
+        }
+        let user_token =
+            TwitchApiUserToken::from_token(&*self.client, AccessToken::from(token.clone())).await?;
+        Ok((user_token.login.to_string(), token))
+    }
+
     /// トークンをリフレッシュ（Mutex保護付き）
     ///
     /// 複数のリクエストが同時にリフレッシュを試みる競合状態を防止
@@ -125,9 +145,17 @@ impl TwitchApiClient {
         // （別のリクエストがすでにリフレッシュを完了した可能性がある）
         // Note: KeyringStoreの結果をawaitの前に解決してからasync操作を行う
         let maybe_current_token: Option<String> = self.app_handle.as_ref().and_then(|handle| {
-            KeyringStore::get_token_with_app(handle, db_constants::PLATFORM_TWITCH).ok()
+            KeyringStore::get_token_with_app(
+                handle,
+                db_constants::PLATFORM_TWITCH,
+                TokenKind::Access,
+            )
+            .ok()
         });
 
+        // 有効期限が迫っている場合は、トークンがまだ有効でもリフレッシュする
+        let maybe_current_token = maybe_current_token.filter(|_| !self.is_token_expiring());
+
         if let Some(current_token) = maybe_current_token {
             let access_token_typed = AccessToken::from(current_token.clone());
             // トークンが有効かどうか軽量チェック
@@ -172,9 +200,14 @@ impl TwitchApiClient {
                         let _ = KeyringStore::delete_token_with_app(
                             handle,
                             db_constants::PLATFORM_TWITCH,
+                            TokenKind::Access,
                         );
                         // リフレッシュトークンを削除
-                        let _ = KeyringStore::delete_token_with_app(handle, "twitch_refresh");
+                        let _ = KeyringStore::delete_token_with_app(
+                            handle,
+                            db_constants::PLATFORM_TWITCH,
+                            TokenKind::Refresh,
+                        );
                         // メタデータを削除
                         let _ = KeyringStore::delete_token_metadata_with_app(
                             handle,
@@ -206,6 +239,16 @@ impl TwitchApiClient {
     async fn get_user_token(
         &self,
     ) -> Result<TwitchApiUserToken, Box<dyn std::error::Error + Send + Sync>> {
+        // APIコール前に有効期限を確認し、期限が近ければ先にリフレッシュしておく
+        if self.app_handle.is_some() {
+            if let Err(e) = self.check_and_refresh_token_if_needed().await {
+                eprintln!(
+                    "[TwitchAPI] Proactive token refresh failed, continuing with current token: {}",
+                    e
+                );
+            }
+        }
+
         let access_token = self.get_access_token().await?;
 
         // トークン検証を試行（これもAPIコールなのでトラッキング）
@@ -226,7 +269,13 @@ impl TwitchApiClient {
 
                 // リフレッシュトークンの存在確認
                 if let Some(ref handle) = self.app_handle {
-                    if KeyringStore::get_token_with_app(handle, "twitch_refresh").is_err() {
+                    if KeyringStore::get_token_with_app(
+                        handle,
+                        db_constants::PLATFORM_TWITCH,
+                        TokenKind::Refresh,
+                    )
+                    .is_err()
+                    {
                         return Err(
                             "Refresh token not found. Please re-authenticate via Device Code Flow."
                                 .into(),
@@ -264,6 +313,30 @@ impl TwitchApiClient {
         Ok(())
     }
 
+    /// 保存済みメタデータから有効期限までの残り時間（分）を取得
+    ///
+    /// メタデータがない（古い形式で保存された）場合や解析できない場合は None を返す。
+    fn minutes_until_token_expiry(&self) -> Option<i64> {
+        let handle = self.app_handle.as_ref()?;
+        let metadata =
+            KeyringStore::get_token_metadata_with_app(handle, db_constants::PLATFORM_TWITCH)
+                .ok()?;
+
+        match DateTime::parse_from_rfc3339(&metadata.expires_at) {
+            Ok(expires_at) => Some(expires_at.signed_duration_since(Local::now()).num_minutes()),
+            Err(e) => {
+                eprintln!("[TwitchAPI] Failed to parse expiration time: {}", e);
+                None
+            }
+        }
+    }
+
+    /// トークンの有効期限が閾値以内に迫っているか
+    fn is_token_expiring(&self) -> bool {
+        self.minutes_until_token_expiry()
+            .is_some_and(|minutes| minutes < twitch::TOKEN_EXPIRY_THRESHOLD_MINUTES)
+    }
+
     /// トークンの有効期限をチェックし、期限が近い場合はリフレッシュ
     ///
     /// Returns: Ok(true) if token was refreshed, Ok(false) if refresh was not needed
@@ -272,58 +345,47 @@ impl TwitchApiClient {
     ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
         let handle = self.app_handle.as_ref().ok_or("No app handle available")?;
 
-        // メタデータを取得
-        let metadata = match KeyringStore::get_token_metadata_with_app(
-            handle,
-            db_constants::PLATFORM_TWITCH,
-        ) {
-            Ok(m) => m,
-            Err(_) => {
+        let minutes_until_expiry = match self.minutes_until_token_expiry() {
+            Some(minutes) => minutes,
+            None => {
                 // メタデータがない場合は、トークンが古い形式で保存されている可能性
                 eprintln!("[TwitchAPI] Token metadata not found, skipping proactive refresh");
                 return Ok(false);
             }
         };
 
-        let expires_at = match DateTime::parse_from_rfc3339(&metadata.expires_at) {
-            Ok(dt) => dt,
-            Err(e) => {
-                eprintln!("[TwitchAPI] Failed to parse expiration time: {}", e);
-                return Ok(false);
-            }
-        };
+        if minutes_until_expiry >= twitch::TOKEN_EXPIRY_THRESHOLD_MINUTES {
+            // まだ有効期限まで余裕がある
+            return Ok(false);
+        }
 
-        let now = Local::now();
-        let time_until_expiry = expires_at.signed_duration_since(now);
-        let minutes_until_expiry = time_until_expiry.num_minutes();
+        eprintln!(
+            "[TwitchAPI] Token expires in {} minutes, refreshing proactively...",
+            minutes_until_expiry
+        );
 
-        // 有効期限まで30分以内の場合はリフレッシュ
-        if minutes_until_expiry < 30 {
-            eprintln!(
-                "[TwitchAPI] Token expires in {} minutes, refreshing proactively...",
-                minutes_until_expiry
-            );
+        // リフレッシュトークンの存在確認
+        if KeyringStore::get_token_with_app(
+            handle,
+            db_constants::PLATFORM_TWITCH,
+            TokenKind::Refresh,
+        )
+        .is_err()
+        {
+            eprintln!("[TwitchAPI] No refresh token available, cannot refresh proactively");
+            return Ok(false);
+        }
 
-            // リフレッシュトークンの存在確認
-            if KeyringStore::get_token_with_app(handle, "twitch_refresh").is_err() {
-                eprintln!("[TwitchAPI] No refresh token available, cannot refresh proactively");
-                return Ok(false);
+        // トークンリフレッシュ
+        match self.refresh_token().await {
+            Ok(_) => {
+                eprintln!("[TwitchAPI] Token refreshed successfully (proactive)");
+                Ok(true)
             }
-
-            // トークンリフレッシュ
-            match self.refresh_token().await {
-                Ok(_) => {
-                    eprintln!("[TwitchAPI] Token refreshed successfully (proactive)");
-                    Ok(true)
-                }
-                Err(e) => {
-                    eprintln!("[TwitchAPI] Failed to refresh token proactively: {}", e);
-                    Err(e)
-                }
+            Err(e) => {
+                eprintln!("[TwitchAPI] Failed to refresh token proactively: {}", e);
+                Err(e)
             }
-        } else {
-            // まだ有効期限まで余裕がある
-            Ok(false)
         }
     }
 
@@ -366,58 +428,15 @@ impl TwitchApiClient {
                     let response = self
                         .client
                         .req_get(GetUsersRequest::logins(login_refs), &refreshed_token)
-                        .await?;
+                        .await
+                        .map_err(helix_error)?;
                     response
                         .data
                         .into_iter()
                         .next()
                         .ok_or_else(|| "User not found".into())
                 } else {
-                    Err(e.into())
-                }
-            }
-        }
-    }
-
-    pub async fn get_stream_by_user_id(
-        &self,
-        user_id: &str,
-    ) -> Result<Option<Stream>, Box<dyn std::error::Error + Send + Sync>> {
-        let token = self.get_user_token().await?;
-
-        let user_id_refs: &[&types::UserIdRef] = &[user_id.into()];
-        let request = GetStreamsRequest::user_ids(user_id_refs);
-
-        // リクエストをトラッキング
-        {
-            let mut limiter = self.rate_limiter.lock().await;
-            limiter.track_request();
-        }
-
-        match self.client.req_get(request, &token).await {
-            Ok(response) => Ok(response.data.into_iter().next()),
-            Err(e) => {
-                // 401エラーの場合、トークンをリフレッシュして再試行
-                if e.to_string().contains(twitch::ERROR_UNAUTHORIZED)
-                    || e.to_string().contains(twitch::ERROR_UNAUTHORIZED_TEXT)
-                {
-                    eprintln!("Token expired, attempting refresh...");
-                    let _new_token = self.refresh_token().await?;
-                    let refreshed_token = self.get_user_token().await?;
-
-                    // 再試行もトラッキング
-                    {
-                        let mut limiter = self.rate_limiter.lock().await;
-                        limiter.track_request();
-                    }
-
-                    let response = self
-                        .client
-                        .req_get(GetStreamsRequest::user_ids(user_id_refs), &refreshed_token)
-                        .await?;
-                    Ok(response.data.into_iter().next())
-                } else {
-                    Err(e.into())
+                    Err(helix_error(e))
                 }
             }
         }
@@ -435,7 +454,10 @@ impl TwitchApiClient {
         let token = self.get_user_token().await?;
 
         let user_id_refs: Vec<&types::UserIdRef> = user_ids.iter().map(|id| (*id).into()).collect();
-        let request = GetStreamsRequest::user_ids(user_id_refs.as_slice());
+        // first を指定しないと既定の20件で打ち切られるため、要求数に合わせる
+        let first = user_ids.len().min(twitch::MAX_STREAMS_PER_REQUEST);
+        let mut request = GetStreamsRequest::user_ids(user_id_refs.as_slice());
+        request.first = Some(first);
 
         // リクエストをトラッキング
         {
@@ -460,16 +482,16 @@ impl TwitchApiClient {
                         limiter.track_request();
                     }
 
+                    let mut retry_request = GetStreamsRequest::user_ids(user_id_refs.as_slice());
+                    retry_request.first = Some(first);
                     let response = self
                         .client
-                        .req_get(
-                            GetStreamsRequest::user_ids(user_id_refs.as_slice()),
-                            &refreshed_token,
-                        )
-                        .await?;
+                        .req_get(retry_request, &refreshed_token)
+                        .await
+                        .map_err(helix_error)?;
                     Ok(response.data)
                 } else {
-                    Err(e.into())
+                    Err(helix_error(e))
                 }
             }
         }
@@ -514,10 +536,11 @@ impl TwitchApiClient {
                             GetUsersRequest::ids(user_id_refs.as_slice()),
                             &refreshed_token,
                         )
-                        .await?;
+                        .await
+                        .map_err(helix_error)?;
                     Ok(response.data)
                 } else {
-                    Err(e.into())
+                    Err(helix_error(e))
                 }
             }
         }
@@ -563,10 +586,11 @@ impl TwitchApiClient {
                             GetUsersRequest::logins(login_refs.as_slice()),
                             &refreshed_token,
                         )
-                        .await?;
+                        .await
+                        .map_err(helix_error)?;
                     Ok(response.data)
                 } else {
-                    Err(e.into())
+                    Err(helix_error(e))
                 }
             }
         }
@@ -677,9 +701,12 @@ impl TwitchApiClient {
 
                     let mut retry_request = GetStreamsRequest::default();
                     retry_request.first = Some(100);
-                    self.client.req_get(retry_request, &refreshed_token).await?
+                    self.client
+                        .req_get(retry_request, &refreshed_token)
+                        .await
+                        .map_err(helix_error)?
                 } else {
-                    return Err(e.into());
+                    return Err(helix_error(e));
                 }
             }
         };
@@ -731,9 +758,12 @@ impl TwitchApiClient {
                         limiter.track_request();
                     }
 
-                    self.client.req_get(request, &refreshed_token).await?
+                    self.client
+                        .req_get(request, &refreshed_token)
+                        .await
+                        .map_err(helix_error)?
                 } else {
-                    return Err(e.into());
+                    return Err(helix_error(e));
                 }
             }
         };
@@ -783,14 +813,144 @@ impl TwitchApiClient {
                         limiter.track_request();
                     }
 
-                    let response = self.client.req_get(request, &refreshed_token).await?;
+                    let response = self
+                        .client
+                        .req_get(request, &refreshed_token)
+                        .await
+                        .map_err(helix_error)?;
+                    Ok(response.data)
+                } else {
+                    Err(helix_error(e))
+                }
+            }
+        }
+    }
+
+    /// ユーザーの最新のアーカイブ動画（VOD）を取得（Get Videos API）
+    ///
+    /// 配信中のアーカイブも含まれ、`stream_id` で元の配信と対応付けられる。
+    pub async fn get_archive_videos(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<Video>, Box<dyn std::error::Error + Send + Sync>> {
+        let token = self.get_user_token().await?;
+
+        let user_id_ref: &types::UserIdRef = user_id.into();
+        let mut request = GetVideosRequest::user_id(user_id_ref);
+        request.type_ = Some(VideoTypeFilter::Archive);
+        request.first = Some(twitch::VOD_LOOKUP_COUNT);
+
+        // リクエストをトラッキング
+        {
+            let mut limiter = self.rate_limiter.lock().await;
+            limiter.track_request();
+        }
+
+        match self.client.req_get(request.clone(), &token).await {
+            Ok(response) => Ok(response.data),
+            Err(e) => {
+                // 401エラーの場合、トークンをリフレッシュして再試行
+                if e.to_string().contains(twitch::ERROR_UNAUTHORIZED)
+                    || e.to_string().contains(twitch::ERROR_UNAUTHORIZED_TEXT)
+                {
+                    eprintln!("Token expired, attempting refresh...");
+                    let _new_token = self.refresh_token().await?;
+                    let refreshed_token = self.get_user_token().await?;
+
+                    // 再試行もトラッキング
+                    {
+                        let mut limiter = self.rate_limiter.lock().await;
+                        limiter.track_request();
+                    }
+
+                    let response = self
+                        .client
+                        .req_get(request, &refreshed_token)
+                        .await
+                        .map_err(helix_error)?;
                     Ok(response.data)
                 } else {
-                    Err(e.into())
+                    Err(helix_error(e))
                 }
             }
         }
     }
+
+    /// チャンネルの配信スケジュールを取得（Get Channel Stream Schedule API）
+    ///
+    /// スケジュールを設定していないチャンネルは 404 が返るため、空として扱う。
+    /// キャンセル済みのセグメントは含めない。
+    pub async fn get_channel_schedule(
+        &self,
+        broadcaster_id: &str,
+    ) -> Result<Vec<Segment>, Box<dyn std::error::Error + Send + Sync>> {
+        let token = self.get_user_token().await?;
+
+        let broadcaster_id_ref: &types::UserIdRef = broadcaster_id.into();
+        let mut request = GetChannelStreamScheduleRequest::broadcaster_id(broadcaster_id_ref);
+        request.first = Some(twitch::SCHEDULE_LOOKUP_COUNT);
+
+        // リクエストをトラッキング
+        {
+            let mut limiter = self.rate_limiter.lock().await;
+            limiter.track_request();
+        }
+
+        let result = match self.client.req_get(request.clone(), &token).await {
+            Ok(response) => Ok(response.data.segments),
+            Err(e) => {
+                // 401エラーの場合、トークンをリフレッシュして再試行
+                if e.to_string().contains(twitch::ERROR_UNAUTHORIZED)
+                    || e.to_string().contains(twitch::ERROR_UNAUTHORIZED_TEXT)
+                {
+                    eprintln!("Token expired, attempting refresh...");
+                    let _new_token = self.refresh_token().await?;
+                    let refreshed_token = self.get_user_token().await?;
+
+                    // 再試行もトラッキング
+                    {
+                        let mut limiter = self.rate_limiter.lock().await;
+                        limiter.track_request();
+                    }
+
+                    self.client
+                        .req_get(request, &refreshed_token)
+                        .await
+                        .map(|response| response.data.segments)
+                } else {
+                    Err(e)
+                }
+            }
+        };
+
+        match result {
+            Ok(segments) => Ok(segments
+                .into_iter()
+                .filter(|segment| segment.canceled_until.is_none())
+                .collect()),
+            Err(e) if e.to_string().contains(twitch::ERROR_NOT_FOUND) => Ok(Vec::new()),
+            Err(e) => Err(helix_error(e)),
+        }
+    }
+}
+
+/// Helix のリクエストエラーを呼び出し元へ返すエラーに変換する
+///
+/// 429 / 503 は `RetryableHttpError` にしてレート制限付きコレクターのリトライ対象にする。
+fn helix_error(e: ClientRequestError<reqwest::Error>) -> Box<dyn std::error::Error + Send + Sync> {
+    if let ClientRequestError::HelixRequestGetError(HelixRequestGetError::Error {
+        status,
+        message,
+        ..
+    }) = &e
+    {
+        if let Some(retryable) =
+            RetryableHttpError::from_status(status.as_u16(), None, message.clone())
+        {
+            return Box::new(retryable);
+        }
+    }
+    e.into()
 }
 
 /// Twitch APIレート制限トラッカー
@@ -909,9 +1069,3 @@ pub struct TwitchRateLimitStatus {
     /// 直近1分間のリクエスト数
     pub request_count: u32,
 }
-
-// 既存コードとの互換性のため残す
-#[allow(dead_code)]
-pub struct RateLimiter {
-    // 将来的に実装
-}
@@ -1,17 +1,22 @@
 // Keyring is not used in this file as it doesn't have AppHandle access
+use crate::api::rate_limiter::RetryableHttpError;
 use crate::constants::youtube;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
 use google_youtube3::YouTube;
 use hyper_rustls::HttpsConnectorBuilder;
 use hyper_util::client::legacy::connect::HttpConnector;
 use hyper_util::client::legacy::Client;
 use hyper_util::rt::TokioExecutor;
+use serde::Serialize;
 use std::sync::Arc;
+use tokio::sync::Mutex;
 use yup_oauth2::{ApplicationSecret, InstalledFlowAuthenticator, InstalledFlowReturnMethod};
 
 #[allow(dead_code)]
 pub struct YouTubeApiClient {
     hub: Arc<YouTube<hyper_rustls::HttpsConnector<HttpConnector>>>,
     access_token: Option<String>,
+    quota: Arc<Mutex<YouTubeQuotaTracker>>,
 }
 
 #[allow(dead_code)]
@@ -50,7 +55,14 @@ impl YouTubeApiClient {
         // Note: Token retrieval requires AppHandle which this struct doesn't have
         let access_token = None;
 
-        Ok(Self { hub, access_token })
+        Ok(Self {
+            hub,
+            access_token,
+            quota: Arc::new(Mutex::new(YouTubeQuotaTracker::new(
+                youtube::DEFAULT_DAILY_QUOTA_LIMIT,
+                false,
+            ))),
+        })
     }
 
     // アクセストークンの取得は不要（hubに組み込まれている）
@@ -69,13 +81,15 @@ impl YouTubeApiClient {
             youtube::PART_SNIPPET.to_string(),
             youtube::PART_CONTENT_DETAILS.to_string(),
         ];
+        self.track_quota(youtube::QUOTA_COST_LIST).await;
         let (_, response) = self
             .hub
             .channels()
             .list(&part)
             .for_username(username)
             .doit()
-            .await?;
+            .await
+            .map_err(youtube_error)?;
 
         Ok(response.items.and_then(|items| items.into_iter().next()))
     }
@@ -92,6 +106,7 @@ impl YouTubeApiClient {
             "statistics".to_string(),
         ];
 
+        self.track_quota(youtube::QUOTA_COST_SEARCH).await;
         let (_, response) = self
             .hub
             .search()
@@ -101,19 +116,22 @@ impl YouTubeApiClient {
             .add_type(youtube::TYPE_VIDEO)
             .max_results(youtube::MAX_RESULTS_DEFAULT)
             .doit()
-            .await?;
+            .await
+            .map_err(youtube_error)?;
 
         if let Some(items) = response.items {
             if let Some(search_result) = items.into_iter().next() {
                 if let Some(video_id) = search_result.id.and_then(|id| id.video_id) {
                     // 動画の詳細を取得
+                    self.track_quota(youtube::QUOTA_COST_LIST).await;
                     let (_, video_response) = self
                         .hub
                         .videos()
                         .list(&part)
                         .add_id(&video_id)
                         .doit()
-                        .await?;
+                        .await
+                        .map_err(youtube_error)?;
 
                     return Ok(video_response
                         .items
@@ -125,6 +143,95 @@ impl YouTubeApiClient {
         Ok(None)
     }
 
+    /// チャンネルの配信予定（upcoming）の動画を取得
+    ///
+    /// search.list（100ユニット）は使わず、アップロード再生リストの新しい動画を playlistItems.list で取得し、
+    /// videos.list で配信予定（liveBroadcastContent が upcoming）のものに絞り込む。
+    pub async fn get_upcoming_streams(
+        &mut self,
+        channel_id: &str,
+    ) -> Result<Vec<google_youtube3::api::Video>, Box<dyn std::error::Error + Send + Sync>> {
+        let Some(uploads_playlist_id) = self.get_uploads_playlist_id(channel_id).await? else {
+            return Ok(Vec::new());
+        };
+
+        let item_part = vec![youtube::PART_CONTENT_DETAILS.to_string()];
+        self.track_quota(youtube::QUOTA_COST_LIST).await;
+        let (_, response) = self
+            .hub
+            .playlist_items()
+            .list(&item_part)
+            .playlist_id(&uploads_playlist_id)
+            .max_results(youtube::UPCOMING_MAX_RESULTS)
+            .doit()
+            .await
+            .map_err(youtube_error)?;
+
+        let video_ids: Vec<String> = response
+            .items
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|item| item.content_details.and_then(|details| details.video_id))
+            .collect();
+        if video_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let video_part = vec![
+            youtube::PART_SNIPPET.to_string(),
+            "liveStreamingDetails".to_string(),
+        ];
+        let mut request = self.hub.videos().list(&video_part);
+        for video_id in &video_ids {
+            request = request.add_id(video_id);
+        }
+        self.track_quota(youtube::QUOTA_COST_LIST).await;
+        let (_, video_response) = request.doit().await.map_err(youtube_error)?;
+
+        Ok(video_response
+            .items
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|video| {
+                video
+                    .snippet
+                    .as_ref()
+                    .and_then(|snippet| snippet.live_broadcast_content.as_deref())
+                    == Some(youtube::EVENT_TYPE_UPCOMING)
+            })
+            .collect())
+    }
+
+    /// チャンネルのアップロード再生リストIDを取得
+    ///
+    /// UC で始まるチャンネルIDは UU に置き換えたものがアップロード再生リストになるため、APIを呼ばずに求める。
+    async fn get_uploads_playlist_id(
+        &mut self,
+        channel_id: &str,
+    ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(suffix) = channel_id.strip_prefix("UC") {
+            return Ok(Some(format!("UU{}", suffix)));
+        }
+
+        let part = vec![youtube::PART_CONTENT_DETAILS.to_string()];
+        self.track_quota(youtube::QUOTA_COST_LIST).await;
+        let (_, response) = self
+            .hub
+            .channels()
+            .list(&part)
+            .add_id(channel_id)
+            .doit()
+            .await
+            .map_err(youtube_error)?;
+
+        Ok(response
+            .items
+            .and_then(|items| items.into_iter().next())
+            .and_then(|channel| channel.content_details)
+            .and_then(|details| details.related_playlists)
+            .and_then(|playlists| playlists.uploads))
+    }
+
     pub async fn get_channel_by_id(
         &mut self,
         channel_id: &str,
@@ -135,13 +242,15 @@ impl YouTubeApiClient {
             "snippet".to_string(),
             "statistics".to_string(),
         ];
+        self.track_quota(youtube::QUOTA_COST_LIST).await;
         let (_, response) = self
             .hub
             .channels()
             .list(&part)
             .add_id(channel_id)
             .doit()
-            .await?;
+            .await
+            .map_err(youtube_error)?;
 
         Ok(response.items.and_then(|items| items.into_iter().next()))
     }
@@ -150,6 +259,15 @@ impl YouTubeApiClient {
         Arc::clone(&self.hub)
     }
 
+    /// クォータ使用量トラッカーを取得（ライブチャット収集と共有する）
+    pub fn get_quota_tracker(&self) -> Arc<Mutex<YouTubeQuotaTracker>> {
+        Arc::clone(&self.quota)
+    }
+
+    async fn track_quota(&self, cost: u32) {
+        self.quota.lock().await.track(cost);
+    }
+
     pub fn set_access_token(&mut self, token: String) {
         // Note: Token saving requires AppHandle which this struct doesn't have
         // The token will be saved through other means (e.g., via commands)
@@ -164,3 +282,196 @@ impl YouTubeApiClient {
         Ok(())
     }
 }
+
+/// YouTube API のエラーを呼び出し元へ返すエラーに変換する
+///
+/// 429 / 503 は `RetryableHttpError` にしてレート制限付きコレクターのリトライ対象にする。
+fn youtube_error(e: google_youtube3::Error) -> Box<dyn std::error::Error + Send + Sync> {
+    let retryable = match &e {
+        google_youtube3::Error::Failure(response) => {
+            let retry_after = response
+                .headers()
+                .get("retry-after")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .map(std::time::Duration::from_secs);
+            RetryableHttpError::from_status(response.status().as_u16(), retry_after, e.to_string())
+        }
+        google_youtube3::Error::BadRequest(body) => body["error"]["code"]
+            .as_u64()
+            .and_then(|code| u16::try_from(code).ok())
+            .and_then(|code| RetryableHttpError::from_status(code, None, e.to_string())),
+        _ => None,
+    };
+    match retryable {
+        Some(retryable) => Box::new(retryable),
+        None => e.into(),
+    }
+}
+
+/// 太平洋時間のUTCからのオフセット（時間）。夏時間は3月第2日曜 2:00 〜 11月第1日曜 2:00
+fn pacific_offset_hours(at: DateTime<Utc>) -> i64 {
+    let nth_sunday = |month: u32, n: u32| {
+        let first = NaiveDate::from_ymd_opt(at.year(), month, 1).unwrap_or_default();
+        let to_sunday = (7 - first.weekday().num_days_from_sunday()) % 7;
+        first + Duration::days((to_sunday + 7 * (n - 1)) as i64)
+    };
+    // 切り替え時刻（PST 2:00 = 10:00 UTC、PDT 2:00 = 9:00 UTC）
+    let dst_start = nth_sunday(3, 2).and_hms_opt(10, 0, 0).unwrap_or_default();
+    let dst_end = nth_sunday(11, 1).and_hms_opt(9, 0, 0).unwrap_or_default();
+    let naive = at.naive_utc();
+    if naive >= dst_start && naive < dst_end {
+        -7
+    } else {
+        -8
+    }
+}
+
+/// `now` の次のクォータリセット時刻（太平洋時間の0時）
+fn next_quota_reset(now: DateTime<Utc>) -> DateTime<Utc> {
+    let offset = pacific_offset_hours(now);
+    let local_date = (now + Duration::hours(offset)).date_naive();
+    let midnight = (local_date + Duration::days(1))
+        .and_hms_opt(0, 0, 0)
+        .unwrap_or_default()
+        .and_utc();
+    let reset = midnight - Duration::hours(offset);
+    // 日付をまたいで夏時間が切り替わる場合は翌日のオフセットで補正する
+    let next_offset = pacific_offset_hours(reset);
+    midnight - Duration::hours(next_offset)
+}
+
+/// YouTube Data API のクォータ使用量トラッカー
+///
+/// API呼び出しごとのコストを加算し、クォータがリセットされる太平洋時間の0時に使用量を0に戻します。
+/// 使用量はこのアプリからの呼び出しのみを数えた推定値で、アプリを再起動するとリセットされます。
+pub struct YouTubeQuotaTracker {
+    used: u32,
+    daily_limit: u32,
+    resets_at: DateTime<Utc>,
+    /// 残量が少ないときにポーリング間隔を延ばすか
+    adaptive: bool,
+}
+
+impl YouTubeQuotaTracker {
+    pub fn new(daily_limit: u32, adaptive: bool) -> Self {
+        Self {
+            used: 0,
+            daily_limit: daily_limit.max(1),
+            resets_at: next_quota_reset(Utc::now()),
+            adaptive,
+        }
+    }
+
+    /// 1日の上限とアダプティブモードを変更する（使用量は保持）
+    pub fn configure(&mut self, daily_limit: u32, adaptive: bool) {
+        self.daily_limit = daily_limit.max(1);
+        self.adaptive = adaptive;
+    }
+
+    /// API呼び出しのコストを加算
+    pub fn track(&mut self, cost: u32) {
+        self.roll_over(Utc::now());
+        self.used = self.used.saturating_add(cost);
+    }
+
+    fn roll_over(&mut self, now: DateTime<Utc>) {
+        if now >= self.resets_at {
+            self.used = 0;
+            self.resets_at = next_quota_reset(now);
+        }
+    }
+
+    /// 現在のステータスを取得
+    pub fn get_status(&mut self) -> YouTubeQuotaStatus {
+        self.roll_over(Utc::now());
+        YouTubeQuotaStatus {
+            used: self.used,
+            estimated_limit: self.daily_limit,
+            remaining: self.daily_limit.saturating_sub(self.used),
+            usage_percent: (self.used as f32 / self.daily_limit as f32) * 100.0,
+            resets_at: self.resets_at.to_rfc3339(),
+            adaptive: self.adaptive,
+        }
+    }
+
+    /// アダプティブモードでのポーリング間隔の倍率（無効時・残量が十分な場合は1）
+    pub fn poll_interval_multiplier(&mut self) -> u32 {
+        self.roll_over(Utc::now());
+        if !self.adaptive {
+            return 1;
+        }
+        let remaining_ratio =
+            self.daily_limit.saturating_sub(self.used) as f64 / self.daily_limit as f64;
+        if remaining_ratio <= youtube::ADAPTIVE_QUOTA_CRITICAL_RATIO {
+            4
+        } else if remaining_ratio <= youtube::ADAPTIVE_QUOTA_LOW_RATIO {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+/// YouTube Data API のクォータ使用状況
+#[derive(Debug, Clone, Serialize)]
+pub struct YouTubeQuotaStatus {
+    /// 当日（太平洋時間）の推定使用ユニット数
+    pub used: u32,
+    /// 1日あたりのクォータ上限（設定値）
+    pub estimated_limit: u32,
+    pub remaining: u32,
+    /// 使用率（0.0 - 100.0）
+    pub usage_percent: f32,
+    /// 次にクォータがリセットされる時刻（RFC3339）
+    pub resets_at: String,
+    /// アダプティブモードが有効か
+    pub adaptive: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_next_quota_reset_follows_pacific_midnight() {
+        // 冬時間（PST, UTC-8）: 1/15 12:00 UTC → 1/16 0:00 PST = 1/16 8:00 UTC
+        let winter = Utc.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap();
+        assert_eq!(
+            next_quota_reset(winter),
+            Utc.with_ymd_and_hms(2024, 1, 16, 8, 0, 0).unwrap()
+        );
+        // 夏時間（PDT, UTC-7）: 7/1 6:00 UTC は 6/30 23:00 PDT → 7/1 7:00 UTC
+        let summer = Utc.with_ymd_and_hms(2024, 7, 1, 6, 0, 0).unwrap();
+        assert_eq!(
+            next_quota_reset(summer),
+            Utc.with_ymd_and_hms(2024, 7, 1, 7, 0, 0).unwrap()
+        );
+        // 夏時間開始の前日（2024/3/9 PST）→ 3/10 0:00 はまだ PST
+        let before_dst = Utc.with_ymd_and_hms(2024, 3, 9, 20, 0, 0).unwrap();
+        assert_eq!(
+            next_quota_reset(before_dst),
+            Utc.with_ymd_and_hms(2024, 3, 10, 8, 0, 0).unwrap()
+        );
+        // 夏時間終了の前日（2024/11/2 PDT）→ 11/3 0:00 はまだ PDT
+        let before_std = Utc.with_ymd_and_hms(2024, 11, 2, 20, 0, 0).unwrap();
+        assert_eq!(
+            next_quota_reset(before_std),
+            Utc.with_ymd_and_hms(2024, 11, 3, 7, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_adaptive_multiplier() {
+        let mut tracker = YouTubeQuotaTracker::new(1000, false);
+        tracker.track(950);
+        assert_eq!(tracker.poll_interval_multiplier(), 1);
+
+        tracker.configure(1000, true);
+        assert_eq!(tracker.poll_interval_multiplier(), 4);
+        tracker.configure(1200, true);
+        assert_eq!(tracker.poll_interval_multiplier(), 2);
+        assert_eq!(tracker.get_status().remaining, 250);
+    }
+}
@@ -1,30 +1,47 @@
+use crate::api::youtube_api::YouTubeQuotaTracker;
 use crate::constants::youtube;
 use crate::database::models::ChatMessage;
+use crate::database::repositories::ChannelRepository;
+use crate::database::writer::DatabaseWriter;
+use crate::database::DatabaseManager;
 use google_youtube3::api::LiveChatMessage;
 use google_youtube3::{hyper_rustls, hyper_util, YouTube};
 use hyper_util::client::legacy::connect::HttpConnector;
 use std::sync::Arc;
-use tokio::sync::{mpsc, Mutex};
-use tokio::time::{interval, Duration, MissedTickBehavior};
+use tokio::sync::{watch, Mutex};
+use tokio::task::JoinHandle;
+use tokio::time::Duration;
+
+pub type YouTubeHub = YouTube<hyper_rustls::HttpsConnector<HttpConnector>>;
+
+/// liveChatMessages.list 1回分の取得結果
+pub struct LiveChatPage {
+    pub messages: Vec<ChatMessage>,
+    /// API が指定する次回取得までの待機時間（pollingIntervalMillis）
+    pub polling_interval: Option<Duration>,
+    /// チャットが終了している（offlineAt が設定されている）
+    pub is_offline: bool,
+}
 
 /// YouTube Live Chat APIクライアント
-#[allow(dead_code)]
 pub struct YouTubeLiveChatClient {
-    hub: Arc<YouTube<hyper_rustls::HttpsConnector<HttpConnector>>>,
-    stream_id: i64,
+    hub: Arc<YouTubeHub>,
+    quota: Arc<Mutex<YouTubeQuotaTracker>>,
+    channel_id: i64,
     live_chat_id: Option<String>,
     next_page_token: Option<String>,
 }
 
-#[allow(dead_code)]
 impl YouTubeLiveChatClient {
     pub fn new(
-        hub: Arc<YouTube<hyper_rustls::HttpsConnector<HttpConnector>>>,
-        stream_id: i64,
+        hub: Arc<YouTubeHub>,
+        quota: Arc<Mutex<YouTubeQuotaTracker>>,
+        channel_id: i64,
     ) -> Self {
         Self {
             hub,
-            stream_id,
+            quota,
+            channel_id,
             live_chat_id: None,
             next_page_token: None,
         }
@@ -43,6 +60,7 @@ impl YouTubeLiveChatClient {
     ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
         let part = vec!["liveStreamingDetails".to_string()];
 
+        self.quota.lock().await.track(youtube::QUOTA_COST_LIST);
         let (_, response) = self
             .hub
             .videos()
@@ -66,41 +84,56 @@ impl YouTubeLiveChatClient {
     }
 
     /// ライブチャットメッセージを取得
+    ///
+    /// 前回の nextPageToken を指定するため、2回目以降は前回以降の新着メッセージのみが返る。
     pub async fn fetch_chat_messages(
         &mut self,
-    ) -> Result<Vec<ChatMessage>, Box<dyn std::error::Error + Send + Sync>> {
-        if self.live_chat_id.is_none() {
-            return Ok(vec![]);
-        }
+    ) -> Result<LiveChatPage, Box<dyn std::error::Error + Send + Sync>> {
+        let Some(live_chat_id) = self.live_chat_id.clone() else {
+            return Ok(LiveChatPage {
+                messages: vec![],
+                polling_interval: None,
+                is_offline: false,
+            });
+        };
 
-        let live_chat_id = self.live_chat_id.as_ref().unwrap();
         let part = vec![
             youtube::PART_SNIPPET.to_string(),
             youtube::PART_AUTHOR_DETAILS.to_string(),
         ];
 
-        let mut request = self.hub.live_chat_messages().list(live_chat_id, &part);
+        let mut request = self
+            .hub
+            .live_chat_messages()
+            .list(&live_chat_id, &part)
+            .max_results(youtube::LIVE_CHAT_MAX_RESULTS);
 
         if let Some(page_token) = &self.next_page_token {
             request = request.page_token(page_token);
         }
 
+        self.quota.lock().await.track(youtube::QUOTA_COST_LIVE_CHAT);
         let (_, response) = request.doit().await?;
 
         // 次のページトークンを保存
-        self.next_page_token = response.next_page_token;
-
-        let mut chat_messages = Vec::new();
-
-        if let Some(items) = response.items {
-            for item in items {
-                if let Some(chat_message) = self.convert_to_chat_message(item) {
-                    chat_messages.push(chat_message);
-                }
-            }
+        if response.next_page_token.is_some() {
+            self.next_page_token = response.next_page_token;
         }
 
-        Ok(chat_messages)
+        let messages = response
+            .items
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|item| self.convert_to_chat_message(item))
+            .collect();
+
+        Ok(LiveChatPage {
+            messages,
+            polling_interval: response
+                .polling_interval_millis
+                .map(|ms| Duration::from_millis(ms as u64)),
+            is_offline: response.offline_at.is_some(),
+        })
     }
 
     /// LiveChatMessageをChatMessageに変換
@@ -114,22 +147,20 @@ impl YouTubeLiveChatClient {
         let user_name = author_details.display_name?;
         let published_at = snippet.published_at?;
 
-        // YouTubeのタイムスタンプはすでにRFC3339形式
-        let timestamp = published_at;
-
         Some(ChatMessage {
             id: None,
-            channel_id: None, // YouTube の場合は channel_id を保存しない
+            channel_id: Some(self.channel_id),
             display_name: Some(user_name.clone()),
-            stream_id: Some(self.stream_id),
-            timestamp: timestamp.to_string(),
+            stream_id: None, // 保存時に動画IDから streams.id を解決する
+            timestamp: published_at.to_rfc3339(),
             platform: youtube::PLATFORM_NAME.to_string(),
             user_id,
-            user_name: user_name.clone(),
+            user_name,
             message: message_text,
             message_type,
             badges: None,     // YouTube の場合は badges を保存しない（現状未対応）
             badge_info: None, // YouTube の場合は badge_info も未対応
+            bits: None,
         })
     }
 
@@ -148,179 +179,177 @@ impl YouTubeLiveChatClient {
             youtube::MESSAGE_TYPE_NORMAL.to_string()
         }
     }
-
-    /// 定期的にチャットメッセージを収集してチャンネルに送信
-    pub async fn start_collection(
-        &mut self,
-        message_tx: tokio::sync::mpsc::UnboundedSender<crate::database::models::ChatMessage>,
-        poll_interval_secs: u64,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let mut interval = interval(Duration::from_secs(poll_interval_secs));
-        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
-
-        println!(
-            "Starting YouTube live chat collection for stream_id: {}",
-            self.stream_id
-        );
-
-        loop {
-            interval.tick().await;
-
-            match self.fetch_chat_messages().await {
-                Ok(messages) => {
-                    let message_count = messages.len();
-                    if message_count > 0 {
-                        for message in messages {
-                            if let Err(e) = message_tx.send(message) {
-                                eprintln!("Failed to send YouTube chat message: {}", e);
-                                // エラーが発生しても継続
-                            }
-                        }
-                        println!("Sent {} YouTube chat messages to channel", message_count);
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Failed to fetch YouTube chat messages: {}", e);
-                    // エラーが発生しても継続
-                }
-            }
-        }
-    }
 }
 
-/// YouTube Live Chatコレクター（コレクター統合用）
-#[allow(dead_code)]
+/// YouTube ライブチャットの収集セッション
+///
+/// liveChatMessages.list を nextPageToken 付きでポーリングし、chat_messages に保存する。
+/// クォータ消費を抑えるため、取得間隔は API の pollingIntervalMillis と
+/// チャンネルのポーリング間隔の長い方を使用する（アダプティブモードではさらに延長する）。
 pub struct YouTubeLiveChatCollector {
-    client: Arc<Mutex<YouTubeLiveChatClient>>,
-    message_tx: mpsc::UnboundedSender<ChatMessage>,
-    db_handler_task: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    pub video_id: String,
+    shutdown_tx: watch::Sender<bool>,
+    task: JoinHandle<()>,
 }
 
-#[allow(dead_code)]
 impl YouTubeLiveChatCollector {
-    pub fn new(
-        hub: Arc<YouTube<hyper_rustls::HttpsConnector<HttpConnector>>>,
-        db_conn: Arc<Mutex<duckdb::Connection>>,
-        stream_id: i64,
-    ) -> Self {
-        let (message_tx, message_rx) = mpsc::unbounded_channel();
+    /// 動画のライブチャットIDを取得して収集を開始する
+    pub async fn start(
+        hub: Arc<YouTubeHub>,
+        quota: Arc<Mutex<YouTubeQuotaTracker>>,
+        db_manager: Arc<DatabaseManager>,
+        channel_id: i64,
+        video_id: &str,
+        min_poll_interval: Duration,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let mut client = YouTubeLiveChatClient::new(hub, quota, channel_id);
+        let live_chat_id = client
+            .get_live_chat_id_from_video(video_id)
+            .await?
+            .ok_or("Live chat not available for this video")?;
+
+        eprintln!(
+            "[YouTubeLiveChat] Found live chat ID: {} for video: {}",
+            live_chat_id, video_id
+        );
 
-        // データベース書き込みハンドラーを起動
-        let db_conn_clone = Arc::clone(&db_conn);
-        let db_handler_task = tokio::spawn(async move {
-            Self::start_db_handler(db_conn_clone, message_rx).await;
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let task_video_id = video_id.to_string();
+        let task = tokio::spawn(async move {
+            Self::run(
+                client,
+                db_manager,
+                channel_id,
+                task_video_id,
+                min_poll_interval,
+                shutdown_rx,
+            )
+            .await;
         });
 
-        let client = YouTubeLiveChatClient::new(Arc::clone(&hub), stream_id);
-        Self {
-            client: Arc::new(Mutex::new(client)),
-            message_tx,
-            db_handler_task: Arc::new(Mutex::new(Some(db_handler_task))),
-        }
+        Ok(Self {
+            video_id: video_id.to_string(),
+            shutdown_tx,
+            task,
+        })
     }
 
-    /// ライブチャットIDを設定してコレクションを開始
-    pub async fn start_with_video_id(
-        &self,
-        video_id: &str,
-        poll_interval_secs: u64,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let mut client = self.client.lock().await;
-
-        // ビデオIDからライブチャットIDを取得
-        let video_id_clone = video_id.to_string();
-        if let Some(live_chat_id) = client.get_live_chat_id_from_video(&video_id_clone).await? {
-            println!(
-                "Found live chat ID: {} for video: {}",
-                live_chat_id, video_id
-            );
-
-            // コレクションを開始 - clientを独立したインスタンスとして作成
-            let message_tx_clone = self.message_tx.clone();
-            let mut client_for_task = YouTubeLiveChatClient::new(
-                Arc::clone(&self.client.lock().await.hub),
-                self.client.lock().await.stream_id,
-            );
-            let collection_task = tokio::spawn(async move {
-                if let Err(e) = client_for_task
-                    .start_collection(message_tx_clone, poll_interval_secs)
-                    .await
-                {
-                    eprintln!(
-                        "YouTube live chat collection failed for video {}: {}",
-                        video_id_clone, e
-                    );
-                }
-            });
-
-            let mut db_handler_task = self.db_handler_task.lock().await;
-            *db_handler_task = Some(collection_task);
-
-            Ok(())
-        } else {
-            Err("Live chat not available for this video".into())
-        }
+    /// 収集が終了しているか（チャット終了・エラー時に再開判定に使う）
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
     }
 
-    /// コレクションを停止
-    pub async fn stop_collection(&self) {
-        let mut task = self.db_handler_task.lock().await;
-        if let Some(task_handle) = task.take() {
-            task_handle.abort();
-        }
+    /// 収集を停止
+    pub fn stop(&self) {
+        let _ = self.shutdown_tx.send(true);
     }
 
-    /// データベース書き込みハンドラーを起動
-    async fn start_db_handler(
-        db_conn: Arc<Mutex<duckdb::Connection>>,
-        mut message_rx: mpsc::UnboundedReceiver<ChatMessage>,
+    async fn run(
+        mut client: YouTubeLiveChatClient,
+        db_manager: Arc<DatabaseManager>,
+        channel_id: i64,
+        video_id: String,
+        min_poll_interval: Duration,
+        mut shutdown_rx: watch::Receiver<bool>,
     ) {
-        let mut batch = Vec::new();
-        let mut interval = tokio::time::interval(Duration::from_secs(5));
+        let max_backoff = Duration::from_secs(youtube::LIVE_CHAT_MAX_BACKOFF_SECS);
+        let mut error_backoff = min_poll_interval;
 
         loop {
-            tokio::select! {
-                msg = message_rx.recv() => {
-                    match msg {
-                        Some(chat_message) => {
-                            batch.push(chat_message);
-                            // バッチサイズに達したら即座に書き込み
-                            if batch.len() >= 100 {
-                                Self::flush_batch(&db_conn, &mut batch).await;
-                            }
-                        }
-                        None => break, // チャンネルが閉じられた
+            // チャンネルが削除・無効化された、またはチャット収集が無効にされた場合は終了
+            let channel = db_manager
+                .with_connection(|conn| ChannelRepository::get_by_id(conn, channel_id))
+                .await;
+            if !matches!(channel, Ok(Some(ref ch)) if ch.enabled && ch.collect_chat) {
+                break;
+            }
+
+            let wait = match client.fetch_chat_messages().await {
+                Ok(page) => {
+                    error_backoff = min_poll_interval;
+                    Self::save_messages(&db_manager, channel_id, &video_id, page.messages).await;
+
+                    if page.is_offline {
+                        eprintln!("[YouTubeLiveChat] Live chat ended for video {}", video_id);
+                        break;
                     }
+                    let wait = page.polling_interval.map_or(min_poll_interval, |interval| {
+                        interval.max(min_poll_interval)
+                    });
+                    wait * client.quota.lock().await.poll_interval_multiplier()
                 }
-                _ = interval.tick() => {
-                    // 定期的にバッチをフラッシュ
-                    Self::flush_batch(&db_conn, &mut batch).await;
+                Err(e) => {
+                    let error = e.to_string();
+                    if error.contains("liveChatEnded")
+                        || error.contains("liveChatNotFound")
+                        || error.contains("liveChatDisabled")
+                    {
+                        eprintln!(
+                            "[YouTubeLiveChat] Live chat unavailable for video {}: {}",
+                            video_id, error
+                        );
+                        break;
+                    }
+                    eprintln!(
+                        "[YouTubeLiveChat] Failed to fetch chat messages for video {}: {}",
+                        video_id, error
+                    );
+                    // 一時的なエラーは待機時間を倍にしてクォータの浪費を避ける
+                    let wait = error_backoff;
+                    error_backoff = (error_backoff * 2).min(max_backoff);
+                    wait
                 }
+            };
+
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = shutdown_rx.changed() => break,
             }
         }
 
-        // ループ終了時に残りのメッセージを書き込み
-        if !batch.is_empty() {
-            Self::flush_batch(&db_conn, &mut batch).await;
-        }
+        eprintln!(
+            "[YouTubeLiveChat] Stopped chat collection for video {}",
+            video_id
+        );
     }
 
-    /// バッチメッセージをデータベースに書き込み
-    async fn flush_batch(db_conn: &Arc<Mutex<duckdb::Connection>>, batch: &mut Vec<ChatMessage>) {
-        if batch.is_empty() {
+    /// メッセージを動画IDに対応する配信（streams.id）に紐付けて保存
+    async fn save_messages(
+        db_manager: &Arc<DatabaseManager>,
+        channel_id: i64,
+        video_id: &str,
+        mut messages: Vec<ChatMessage>,
+    ) {
+        if messages.is_empty() {
             return;
         }
 
-        let conn = db_conn.lock().await;
-        match crate::database::writer::DatabaseWriter::insert_chat_messages_batch(&conn, batch) {
-            Ok(_) => {
-                println!("Saved {} YouTube chat messages to database", batch.len());
-                batch.clear();
-            }
-            Err(e) => {
-                eprintln!("Failed to save YouTube chat messages batch: {}", e);
-                // エラーが発生してもバッチはクリアせず、次回再試行
-            }
+        let result = db_manager
+            .with_connection(|conn| {
+                let stream_id = DatabaseWriter::find_stream_id(conn, channel_id, video_id)?;
+                for message in messages.iter_mut() {
+                    message.stream_id = stream_id;
+                }
+                DatabaseWriter::insert_chat_messages_batch(conn, &messages)
+            })
+            .await;
+
+        match result {
+            Ok(_) => eprintln!(
+                "[YouTubeLiveChat] Saved {} chat messages for video {}",
+                messages.len(),
+                video_id
+            ),
+            Err(e) => eprintln!(
+                "[YouTubeLiveChat] Failed to save chat messages for video {}: {}",
+                video_id, e
+            ),
         }
     }
 }
+
+impl Drop for YouTubeLiveChatCollector {
+    fn drop(&mut self) {
+        let _ = self.shutdown_tx.send(true);
+    }
+}
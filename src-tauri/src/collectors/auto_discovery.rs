@@ -1,14 +1,18 @@
 use crate::api::twitch_api::TwitchApiClient;
+use crate::collectors::poller::ChannelPoller;
 use crate::commands::discovery::DiscoveredStreamInfo;
 use crate::config::settings::{AutoDiscoverySettings, SettingsManager};
+use crate::constants::{database as db_constants, twitch as twitch_constants};
+use crate::database::models::Channel;
 use crate::database::repositories::base;
+use crate::database::repositories::channel_repository::CreateChannelParams;
 use crate::database::repositories::game_category_repository::GameCategoryRepository;
 use crate::database::repositories::stream_stats_repository::StreamStatsRepository;
 use crate::database::repositories::ChannelRepository;
 use crate::database::DatabaseManager;
 use crate::error::ResultExt;
 use crate::DiscoveredStreamsCache;
-use chrono::Local;
+use chrono::Utc;
 use std::collections::HashMap;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
@@ -173,7 +177,30 @@ impl AutoDiscoveryPoller {
                     }
                 }
 
-                // 配信終了したチャンネルをクリーンアップ
+                // 発見した配信を自動収集対象に登録
+                if current_auto_discovery.auto_collect {
+                    match Self::auto_collect_discovered(
+                        current_auto_discovery,
+                        &db_manager,
+                        &app_handle,
+                    )
+                    .await
+                    {
+                        Ok(started) if started > 0 => {
+                            eprintln!(
+                                "[AutoDiscovery] Started auto-collection for {} channels",
+                                started
+                            );
+                            let _ = app_handle.emit("channels-updated", ());
+                        }
+                        Ok(_) => {}
+                        Err(e) => {
+                            eprintln!("[AutoDiscovery] Error starting auto-collection: {}", e);
+                        }
+                    }
+                }
+
+                // 配信終了したチャンネルの収集を解除
                 if let Err(e) = Self::cleanup_offline_channels(&db_manager, &app_handle).await {
                     eprintln!("[AutoDiscovery] Error cleaning up offline channels: {}", e);
                 }
@@ -281,7 +308,7 @@ impl AutoDiscoveryPoller {
         // game_id -> game_name
         let mut categories_to_upsert: HashMap<String, String> = HashMap::new();
         let mut stats_to_insert: Vec<(String, i32, String, String, String, String)> = Vec::new();
-        let now = Local::now().to_rfc3339();
+        let now = Utc::now().to_rfc3339();
 
         for stream in filtered_streams {
             let user_id = stream.user_id.to_string();
@@ -432,17 +459,129 @@ impl AutoDiscoveryPoller {
         Ok(discovered_count)
     }
 
-    /// オフラインになった自動発見チャンネルをクリーンアップ
+    /// キャッシュ上の発見済み配信を一時的な収集対象（is_auto_discovered=true）として登録し、ポーリングを開始
+    ///
+    /// 手動登録済みのチャンネルと収集中のチャンネルはスキップする。収集中のチャンネル数が
+    /// `max_auto_collect` に達した時点で登録を打ち切る（キャッシュは視聴者数順のため上位を優先）。
+    /// 戻り値: 収集を開始したチャンネル数
+    async fn auto_collect_discovered(
+        settings: &AutoDiscoverySettings,
+        db_manager: &Arc<DatabaseManager>,
+        app_handle: &AppHandle,
+    ) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let discovered: Vec<DiscoveredStreamInfo> = {
+            let cache: tauri::State<'_, Arc<DiscoveredStreamsCache>> = app_handle.state();
+            let streams = cache.streams.lock().await;
+            streams.clone()
+        };
+        if discovered.is_empty() {
+            return Ok(0);
+        }
+
+        let max_auto_collect = settings.max_auto_collect as i64;
+        let channels_to_start = db_manager
+            .with_connection(|conn| {
+                Self::register_auto_collect_channels(conn, &discovered, max_auto_collect)
+            })
+            .await?;
+
+        if channels_to_start.is_empty() {
+            return Ok(0);
+        }
+
+        let started = channels_to_start.len();
+        if let Some(poller) = app_handle.try_state::<Arc<Mutex<ChannelPoller>>>() {
+            let db_state = app_handle.state::<DatabaseManager>();
+            let mut poller = poller.lock().await;
+            for channel in channels_to_start {
+                eprintln!(
+                    "[AutoDiscovery] Auto-collecting channel: {} (id: {:?})",
+                    channel.channel_id, channel.id
+                );
+                if let Err(e) = poller.start_polling(channel, &db_state, app_handle.clone()) {
+                    eprintln!("[AutoDiscovery] Failed to start auto-collection: {}", e);
+                }
+            }
+        }
+
+        Ok(started)
+    }
+
+    /// 発見済み配信のチャンネルを自動収集対象として登録し、ポーリングを開始するチャンネルを返す
+    ///
+    /// 有効な自動収集チャンネルが `max_auto_collect` に達した時点で登録を打ち切る。
+    fn register_auto_collect_channels(
+        conn: &duckdb::Connection,
+        discovered: &[DiscoveredStreamInfo],
+        max_auto_collect: i64,
+    ) -> Result<Vec<Channel>, duckdb::Error> {
+        let mut active = ChannelRepository::count_enabled_auto_discovered(conn)?;
+        let mut to_start = Vec::new();
+
+        for stream in discovered {
+            if active >= max_auto_collect {
+                break;
+            }
+
+            let id = match ChannelRepository::get_registration_state(
+                conn,
+                db_constants::PLATFORM_TWITCH,
+                &stream.channel_id,
+            )? {
+                // 手動登録済み、または既に収集中
+                Some((_, false, _)) | Some((_, true, true)) => continue,
+                // 以前に自動収集して解除済みのチャンネルを再開
+                Some((id, true, false)) => {
+                    ChannelRepository::update_enabled(conn, id, true)?;
+                    id
+                }
+                None => ChannelRepository::create(
+                    conn,
+                    CreateChannelParams {
+                        platform: db_constants::PLATFORM_TWITCH.to_string(),
+                        channel_id: stream.channel_id.clone(),
+                        channel_name: stream
+                            .display_name
+                            .clone()
+                            .unwrap_or_else(|| stream.channel_name.clone()),
+                        poll_interval: twitch_constants::AUTO_COLLECT_POLL_INTERVAL_SECS,
+                        twitch_user_id: Some(stream.twitch_user_id),
+                    },
+                )?,
+            };
+            ChannelRepository::update_auto_discovered(
+                conn,
+                db_constants::PLATFORM_TWITCH,
+                &stream.channel_id,
+                true,
+                Some(stream.twitch_user_id),
+            )?;
+
+            if let Some(channel) = ChannelRepository::get_by_id(conn, id)? {
+                to_start.push(channel);
+                active += 1;
+            }
+        }
+
+        Ok(to_start)
+    }
+
+    /// 配信が終了した自動収集チャンネルの収集を解除する
+    ///
+    /// 収集済みのデータは残し、チャンネルを無効化してポーリングを停止する。
     async fn cleanup_offline_channels(
         db_manager: &Arc<DatabaseManager>,
         app_handle: &AppHandle,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         // トランザクションで処理して競合状態を防ぐ
-        let channels_to_notify = db_manager
+        let released_channels = db_manager
             .with_connection(|conn| {
-                base::with_transaction::<Vec<(i64, String)>, duckdb::Error, _>(conn, |conn| {
-                    let channels =
-                        ChannelRepository::get_offline_auto_discovered_channels(conn)?;
+                base::with_transaction::<Vec<i64>, duckdb::Error, _>(conn, |conn| {
+                    let channels = ChannelRepository::get_offline_auto_discovered_channels(
+                        conn,
+                        twitch_constants::AUTO_COLLECT_GRACE_SECS,
+                    )?;
+                    let mut released = Vec::new();
 
                     for (channel_id, channel_name) in &channels {
                         let is_live = ChannelRepository::is_channel_live(conn, *channel_id)?;
@@ -454,24 +593,123 @@ impl AutoDiscoveryPoller {
                             continue;
                         }
 
-                        ChannelRepository::delete(conn, *channel_id)?;
+                        ChannelRepository::update_enabled(conn, *channel_id, false)?;
+                        released.push(*channel_id);
                         eprintln!(
-                            "[AutoDiscovery] Cleaned up offline channel: {} (id: {})",
+                            "[AutoDiscovery] Released offline channel: {} (id: {})",
                             channel_name, channel_id
                         );
                     }
 
-                    Ok(channels)
+                    Ok(released)
                 })
                 .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
             })
             .await?;
 
-        // イベント発行
-        for (channel_id, _) in channels_to_notify {
-            let _ = app_handle.emit("channel-removed", channel_id);
+        if released_channels.is_empty() {
+            return Ok(());
         }
 
+        if let Some(poller) = app_handle.try_state::<Arc<Mutex<ChannelPoller>>>() {
+            let mut poller = poller.lock().await;
+            for channel_id in &released_channels {
+                poller.stop_polling(*channel_id).await;
+            }
+        }
+
+        let _ = app_handle.emit("channels-updated", ());
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{init_test_db, insert_channel};
+
+    fn discovered(channel_id: &str, twitch_user_id: i64) -> DiscoveredStreamInfo {
+        DiscoveredStreamInfo {
+            id: twitch_user_id,
+            twitch_user_id,
+            channel_id: channel_id.to_string(),
+            channel_name: channel_id.to_string(),
+            display_name: None,
+            profile_image_url: None,
+            discovered_at: None,
+            title: None,
+            category: None,
+            viewer_count: None,
+            follower_count: 0,
+            broadcaster_type: None,
+        }
+    }
+
+    fn started_logins(channels: &[Channel]) -> Vec<String> {
+        channels.iter().map(|c| c.channel_id.clone()).collect()
+    }
+
+    #[test]
+    fn test_register_auto_collect_channels_respects_max() {
+        let conn = init_test_db();
+        // シーケンスで採番される ID と衝突しないよう、手動登録チャンネルは大きい ID で追加する
+        insert_channel(&conn, 100, "twitch", "manual");
+        let streams = vec![
+            discovered("manual", 1),
+            discovered("top", 2),
+            discovered("second", 3),
+            discovered("third", 4),
+        ];
+
+        let started =
+            AutoDiscoveryPoller::register_auto_collect_channels(&conn, &streams, 2).unwrap();
+
+        // 手動登録済みのチャンネルは上限に数えず、視聴者数順に上限まで登録する
+        assert_eq!(started_logins(&started), vec!["top", "second"]);
+        assert!(started.iter().all(|c| c.is_auto_discovered));
+        assert_eq!(
+            ChannelRepository::count_enabled_auto_discovered(&conn).unwrap(),
+            2
+        );
+
+        // 収集中のチャンネルで上限に達していれば何も登録しない
+        let started =
+            AutoDiscoveryPoller::register_auto_collect_channels(&conn, &streams, 2).unwrap();
+        assert!(started.is_empty());
+        assert!(
+            ChannelRepository::get_registration_state(&conn, "twitch", "third")
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_register_auto_collect_channels_resumes_released_channel() {
+        let conn = init_test_db();
+        let streams = vec![discovered("returning", 10)];
+        let started =
+            AutoDiscoveryPoller::register_auto_collect_channels(&conn, &streams, 1).unwrap();
+        let id = started[0].id.unwrap();
+        ChannelRepository::update_enabled(&conn, id, false).unwrap();
+
+        let started =
+            AutoDiscoveryPoller::register_auto_collect_channels(&conn, &streams, 1).unwrap();
+
+        assert_eq!(started.len(), 1);
+        assert_eq!(started[0].id, Some(id));
+        assert_eq!(
+            ChannelRepository::get_registration_state(&conn, "twitch", "returning").unwrap(),
+            Some((id, true, true))
+        );
+
+        // 上限0では登録しない
+        assert!(AutoDiscoveryPoller::register_auto_collect_channels(
+            &conn,
+            &[discovered("other", 11)],
+            0
+        )
+        .unwrap()
+        .is_empty());
+    }
+}
@@ -0,0 +1,142 @@
+/// チャンネルアイコン（プロフィール画像）のローカルキャッシュ
+///
+/// `channels.profile_image_url` の画像を `<app_data_dir>/channel_icons/<channels.id>-<URLのハッシュ>.<拡張子>` に保存する。
+/// プロフィール画像は変更されるとURLも変わるため、ファイル名にURLのハッシュを含めて
+/// URLが変わったときだけ再取得し、同じチャンネルの古いファイルは削除する。
+/// オフライン時でもキャッシュ済みのアイコンを表示できるようにするためのもの。
+use crate::constants::channel_icon as icon_constants;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+/// 拡張子をそのまま使う画像形式
+const KNOWN_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp"];
+
+pub struct ChannelIconCache;
+
+impl ChannelIconCache {
+    /// キャッシュディレクトリのパスを取得
+    pub fn cache_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+        let app_data_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+        Ok(app_data_dir.join(icon_constants::CACHE_DIR_NAME))
+    }
+
+    /// チャンネルのキャッシュファイル名の接頭辞
+    fn file_prefix(channel_id: i64) -> String {
+        format!("{}-", channel_id)
+    }
+
+    /// チャンネルとアイコンURLに対応するキャッシュファイル名
+    fn file_name(channel_id: i64, url: &str) -> String {
+        let mut hasher = DefaultHasher::new();
+        url.hash(&mut hasher);
+
+        let extension = url
+            .split(['?', '#'])
+            .next()
+            .and_then(|path| path.rsplit_once('.'))
+            .map(|(_, ext)| ext.to_ascii_lowercase())
+            .filter(|ext| KNOWN_EXTENSIONS.contains(&ext.as_str()))
+            .unwrap_or_else(|| "png".to_string());
+
+        format!(
+            "{}{:016x}.{}",
+            Self::file_prefix(channel_id),
+            hasher.finish(),
+            extension
+        )
+    }
+
+    /// チャンネルとアイコンURLに対応するキャッシュファイルパスを取得
+    pub fn path_for(app_handle: &AppHandle, channel_id: i64, url: &str) -> Result<PathBuf, String> {
+        Ok(Self::cache_dir(app_handle)?.join(Self::file_name(channel_id, url)))
+    }
+
+    /// アイコンがキャッシュされていなければダウンロードして保存する
+    /// 戻り値: キャッシュファイルのパス
+    pub async fn refresh(
+        app_handle: &AppHandle,
+        channel_id: i64,
+        url: &str,
+    ) -> Result<PathBuf, String> {
+        let path = Self::path_for(app_handle, channel_id, url)?;
+        if path.exists() {
+            return Ok(path);
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(icon_constants::DOWNLOAD_TIMEOUT_SECS))
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+        let response = client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to download channel icon: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "Failed to download channel icon: HTTP {}",
+                response.status()
+            ));
+        }
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to read channel icon body: {}", e))?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create channel icon directory: {}", e))?;
+        }
+
+        // 書き込み途中のファイルを読まれないよう、一時ファイルに書いてからリネームする
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, &bytes)
+            .map_err(|e| format!("Failed to write channel icon: {}", e))?;
+        std::fs::rename(&tmp_path, &path)
+            .map_err(|e| format!("Failed to save channel icon: {}", e))?;
+
+        // 以前のURLでキャッシュしたアイコンは不要になるため削除する
+        Self::remove_files(app_handle, channel_id, Some(&path));
+
+        Ok(path)
+    }
+
+    /// チャンネルのキャッシュ済みアイコンをすべて削除する
+    pub fn remove(app_handle: &AppHandle, channel_id: i64) {
+        Self::remove_files(app_handle, channel_id, None);
+    }
+
+    /// チャンネルのキャッシュファイルを削除する（`keep` に指定したファイルは残す）
+    fn remove_files(app_handle: &AppHandle, channel_id: i64, keep: Option<&PathBuf>) {
+        let Ok(dir) = Self::cache_dir(app_handle) else {
+            return;
+        };
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            return;
+        };
+
+        let prefix = Self::file_prefix(channel_id);
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_target = entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.starts_with(&prefix));
+            if is_target && keep != Some(&path) {
+                if let Err(e) = std::fs::remove_file(&path) {
+                    eprintln!(
+                        "[ChannelIconCache] Failed to remove {}: {}",
+                        path.display(),
+                        e
+                    );
+                }
+            }
+        }
+    }
+}
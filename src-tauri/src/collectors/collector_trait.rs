@@ -1,4 +1,4 @@
-use crate::database::models::{Channel, StreamData};
+use crate::database::models::{Channel, ScheduledStreamData, StreamData};
 use async_trait::async_trait;
 
 #[async_trait]
@@ -11,4 +11,17 @@ pub trait Collector {
         &self,
         channel: &Channel,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// チャンネルの今後の配信予定を取得（スケジュール非対応のプラットフォームは空）
+    async fn fetch_schedule(
+        &self,
+        _channel: &Channel,
+    ) -> Result<Vec<ScheduledStreamData>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Vec::new())
+    }
+
+    /// ポーリング間隔に掛ける倍率（APIクォータの残量に応じて間隔を延ばすプラットフォーム用）
+    async fn poll_interval_multiplier(&self) -> u32 {
+        1
+    }
 }
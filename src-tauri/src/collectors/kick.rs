@@ -0,0 +1,145 @@
+use crate::api::rate_limiter::RetryableHttpError;
+use crate::collectors::collector_trait::Collector;
+use crate::constants::kick;
+use crate::database::models::{Channel, StreamData};
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, TimeZone, Utc};
+use reqwest::Client;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct KickChannelResponse {
+    followers_count: Option<i32>,
+    livestream: Option<KickLivestream>,
+    user: Option<KickUser>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KickUser {
+    username: Option<String>,
+    profile_pic: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KickLivestream {
+    id: i64,
+    session_title: Option<String>,
+    #[serde(default)]
+    is_live: bool,
+    viewer_count: Option<i32>,
+    /// "YYYY-MM-DD HH:MM:SS"（UTC）
+    start_time: Option<String>,
+    created_at: Option<String>,
+    #[serde(default)]
+    categories: Vec<KickCategory>,
+    thumbnail: Option<KickThumbnail>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KickCategory {
+    id: i64,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct KickThumbnail {
+    url: Option<String>,
+}
+
+/// Kick の公開API（認証不要）から配信状態を取得するコレクター
+///
+/// channels.channel_id にはチャンネルの slug（URLの末尾）を登録する。
+pub struct KickCollector {
+    http_client: Client,
+}
+
+impl KickCollector {
+    pub fn new() -> Self {
+        let http_client = Client::builder()
+            .user_agent(kick::USER_AGENT)
+            .timeout(std::time::Duration::from_secs(30))
+            .connect_timeout(std::time::Duration::from_secs(10))
+            .build()
+            .unwrap_or_else(|_| Client::new());
+
+        Self { http_client }
+    }
+
+    /// Kick の時刻表記（UTC・タイムゾーン無し）を RFC3339 に変換
+    fn parse_kick_time(value: &str) -> Option<String> {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+            .ok()
+            .map(|naive| Utc.from_utc_datetime(&naive).to_rfc3339())
+    }
+}
+
+impl Default for KickCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Collector for KickCollector {
+    async fn poll_channel(
+        &self,
+        channel: &Channel,
+    ) -> Result<Option<StreamData>, Box<dyn std::error::Error + Send + Sync>> {
+        let url = format!("{}/channels/{}", kick::API_BASE_URL, channel.channel_id);
+        let response = self.http_client.get(&url).send().await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            if let Some(retryable) = RetryableHttpError::from_response(response).await {
+                return Err(Box::new(retryable));
+            }
+            return Err(format!(
+                "Kick API request failed for channel {} ({})",
+                channel.channel_id, status
+            )
+            .into());
+        }
+
+        let channel_response: KickChannelResponse = response.json().await?;
+
+        let livestream = match channel_response.livestream {
+            Some(livestream) if livestream.is_live => livestream,
+            _ => return Ok(None),
+        };
+
+        let started_at = livestream
+            .start_time
+            .as_deref()
+            .or(livestream.created_at.as_deref())
+            .and_then(Self::parse_kick_time)
+            .unwrap_or_else(|| Utc::now().to_rfc3339());
+
+        let category = livestream.categories.first();
+        let (display_name, profile_image_url) = channel_response
+            .user
+            .map(|u| (u.username, u.profile_pic))
+            .unwrap_or_default();
+
+        Ok(Some(StreamData {
+            stream_id: livestream.id.to_string(),
+            title: livestream.session_title,
+            category: category.map(|c| c.name.clone()),
+            game_id: category.map(|c| c.id.to_string()),
+            thumbnail_url: livestream.thumbnail.and_then(|t| t.url),
+            started_at,
+            viewer_count: livestream.viewer_count,
+            follower_count: channel_response.followers_count,
+            comment_count: None,
+            display_name,
+            profile_image_url,
+        }))
+    }
+
+    async fn start_collection(
+        &self,
+        _channel: &Channel,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        // 公開APIのため認証は不要
+        Ok(())
+    }
+}
@@ -1,5 +1,9 @@
 pub mod auto_discovery;
+pub mod channel_icon_cache;
 pub mod collector_trait;
+pub mod kick;
+pub mod niconico;
 pub mod poller;
+pub mod thumbnail_cache;
 pub mod twitch;
 pub mod youtube;
@@ -0,0 +1,247 @@
+use crate::api::rate_limiter::RetryableHttpError;
+use crate::collectors::collector_trait::Collector;
+use crate::constants::niconico;
+use crate::database::models::{Channel, StreamData};
+use crate::database::DatabaseManager;
+use crate::logger::AppLogger;
+use crate::websocket::niconico_comment::NiconicoCommentSession;
+use async_trait::async_trait;
+use chrono::{TimeZone, Utc};
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// ニコニコ生放送のコレクター
+///
+/// channels.channel_id には番組ID（lv...）、コミュニティ／チャンネルID（co... / ch...）、
+/// またはユーザー番組の `user/<ユーザーID>` を登録する。視聴ページに埋め込まれた番組情報から
+/// 視聴者数・コメント数を取得し、放送中はコメントを WebSocket 経由で chat_messages に保存する。
+pub struct NiconicoCollector {
+    http_client: Client,
+    db_manager: Arc<DatabaseManager>,
+    logger: Arc<AppLogger>,
+    /// channels.id -> コメント受信セッション
+    comment_sessions: Mutex<HashMap<i64, NiconicoCommentSession>>,
+}
+
+/// 視聴ページの embedded-data から取り出した番組情報
+struct NiconicoProgram {
+    program_id: String,
+    title: Option<String>,
+    status: String,
+    begin_time: Option<i64>,
+    watch_count: Option<i32>,
+    /// 累計コメント数
+    comment_count: Option<i64>,
+    thumbnail_url: Option<String>,
+    web_socket_url: Option<String>,
+    /// 放送者名（ユーザー名 / チャンネル名）
+    supplier_name: Option<String>,
+    /// 放送者のアイコン画像URL
+    supplier_icon_url: Option<String>,
+}
+
+impl NiconicoCollector {
+    pub fn new(db_manager: Arc<DatabaseManager>, logger: Arc<AppLogger>) -> Self {
+        let http_client = Client::builder()
+            .user_agent(niconico::USER_AGENT)
+            .timeout(std::time::Duration::from_secs(30))
+            .connect_timeout(std::time::Duration::from_secs(10))
+            .build()
+            .unwrap_or_else(|_| Client::new());
+
+        Self {
+            http_client,
+            db_manager,
+            logger,
+            comment_sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    async fn fetch_program(
+        &self,
+        channel_id: &str,
+    ) -> Result<NiconicoProgram, Box<dyn std::error::Error + Send + Sync>> {
+        let url = format!("{}/{}", niconico::WATCH_PAGE_URL, channel_id);
+        let response = self.http_client.get(&url).send().await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            if let Some(retryable) = RetryableHttpError::from_response(response).await {
+                return Err(Box::new(retryable));
+            }
+            return Err(format!(
+                "Niconico watch page request failed for {} ({})",
+                channel_id, status
+            )
+            .into());
+        }
+
+        let html = response.text().await?;
+        let props = Self::extract_embedded_data(&html)
+            .ok_or_else(|| format!("embedded-data not found on watch page for {}", channel_id))?;
+        Self::parse_program(&props)
+    }
+
+    /// embedded-data の JSON から番組情報を取り出す
+    fn parse_program(
+        props: &Value,
+    ) -> Result<NiconicoProgram, Box<dyn std::error::Error + Send + Sync>> {
+        let program = &props["program"];
+        Ok(NiconicoProgram {
+            program_id: program["nicoliveProgramId"]
+                .as_str()
+                .ok_or("nicoliveProgramId not found in embedded-data")?
+                .to_string(),
+            title: program["title"].as_str().map(String::from),
+            status: program["status"].as_str().unwrap_or_default().to_string(),
+            begin_time: program["beginTime"].as_i64(),
+            watch_count: program["statistics"]["watchCount"]
+                .as_i64()
+                .map(|v| v as i32),
+            comment_count: program["statistics"]["commentCount"].as_i64(),
+            thumbnail_url: program["thumbnail"]["huge"]["s1280x720"]
+                .as_str()
+                .or_else(|| program["screenshot"]["urlSet"]["large"].as_str())
+                .map(String::from),
+            web_socket_url: props["site"]["relive"]["webSocketUrl"]
+                .as_str()
+                .filter(|url| !url.is_empty())
+                .map(String::from),
+            supplier_name: program["supplier"]["name"].as_str().map(String::from),
+            supplier_icon_url: program["supplier"]["icons"]["uri150x150"]
+                .as_str()
+                .filter(|url| !url.is_empty())
+                .map(String::from),
+        })
+    }
+
+    /// `<script id="embedded-data" data-props="...">` の JSON を取り出す
+    fn extract_embedded_data(html: &str) -> Option<Value> {
+        let marker = "id=\"embedded-data\" data-props=\"";
+        let start = html.find(marker)? + marker.len();
+        let end = start + html[start..].find('"')?;
+        let unescaped = html[start..end]
+            .replace("&quot;", "\"")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&#39;", "'")
+            .replace("&amp;", "&");
+        serde_json::from_str(&unescaped).ok()
+    }
+
+    /// 放送中の番組のコメント受信セッションを維持する（番組が変わった・切断された場合は張り直す）
+    async fn ensure_comment_session(&self, channel_db_id: i64, program: &NiconicoProgram) {
+        let Some(web_socket_url) = program.web_socket_url.clone() else {
+            return;
+        };
+
+        let mut sessions = self.comment_sessions.lock().await;
+        let needs_start = sessions
+            .get(&channel_db_id)
+            .map(|s| s.program_id != program.program_id || s.is_finished())
+            .unwrap_or(true);
+
+        if needs_start {
+            if let Some(old) = sessions.remove(&channel_db_id) {
+                old.stop();
+            }
+            sessions.insert(
+                channel_db_id,
+                NiconicoCommentSession::start(
+                    web_socket_url,
+                    channel_db_id,
+                    program.program_id.clone(),
+                    Arc::clone(&self.db_manager),
+                    Arc::clone(&self.logger),
+                ),
+            );
+        }
+    }
+
+    async fn stop_comment_session(&self, channel_db_id: i64) {
+        if let Some(session) = self.comment_sessions.lock().await.remove(&channel_db_id) {
+            session.stop();
+        }
+    }
+
+    /// コメント受信を停止（チャンネルの無効化・削除時にポーリング停止と併せて呼ぶ）
+    pub async fn stop_chat_collection(&self, channel_db_id: i64) {
+        self.stop_comment_session(channel_db_id).await;
+    }
+}
+
+#[async_trait]
+impl Collector for NiconicoCollector {
+    async fn poll_channel(
+        &self,
+        channel: &Channel,
+    ) -> Result<Option<StreamData>, Box<dyn std::error::Error + Send + Sync>> {
+        let channel_db_id = channel.id.unwrap_or_default();
+        let program = self.fetch_program(&channel.channel_id).await?;
+
+        if program.status != niconico::STATUS_ON_AIR {
+            self.stop_comment_session(channel_db_id).await;
+            return Ok(None);
+        }
+
+        // 手動登録チャンネルはコメントも収集する（チャット収集が無効なチャンネルは除く）
+        if channel.collect_chat && !channel.is_auto_discovered {
+            self.ensure_comment_session(channel_db_id, &program).await;
+        } else {
+            self.stop_comment_session(channel_db_id).await;
+        }
+
+        let started_at = program
+            .begin_time
+            .and_then(|secs| Utc.timestamp_opt(secs, 0).single())
+            .unwrap_or_else(Utc::now)
+            .to_rfc3339();
+
+        Ok(Some(StreamData {
+            stream_id: program.program_id,
+            title: program.title,
+            category: None,
+            game_id: None,
+            thumbnail_url: program.thumbnail_url,
+            started_at,
+            viewer_count: program.watch_count,
+            follower_count: None,
+            comment_count: program.comment_count,
+            display_name: program.supplier_name,
+            profile_image_url: program.supplier_icon_url,
+        }))
+    }
+
+    async fn start_collection(
+        &self,
+        _channel: &Channel,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        // コメント受信は放送中の poll_channel で開始する
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_program_statistics_from_embedded_data() {
+        let props = r#"{&quot;program&quot;:{&quot;nicoliveProgramId&quot;:&quot;lv1&quot;,&quot;status&quot;:&quot;ONAIR&quot;,&quot;beginTime&quot;:1704067200,&quot;statistics&quot;:{&quot;watchCount&quot;:120,&quot;commentCount&quot;:345}},&quot;site&quot;:{&quot;relive&quot;:{&quot;webSocketUrl&quot;:&quot;&quot;}}}"#;
+        let html = format!(
+            r#"<script id="embedded-data" data-props="{}"></script>"#,
+            props
+        );
+
+        let props = NiconicoCollector::extract_embedded_data(&html).unwrap();
+        let program = NiconicoCollector::parse_program(&props).unwrap();
+        assert_eq!(program.program_id, "lv1");
+        assert_eq!(program.status, "ONAIR");
+        assert_eq!(program.watch_count, Some(120));
+        assert_eq!(program.comment_count, Some(345));
+        assert_eq!(program.web_socket_url, None);
+    }
+}
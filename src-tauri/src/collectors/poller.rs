@@ -1,20 +1,28 @@
+use crate::api::rate_limiter::RateLimitedCollector;
+use crate::collectors::channel_icon_cache::ChannelIconCache;
 use crate::collectors::collector_trait::Collector;
+use crate::collectors::niconico::NiconicoCollector;
+use crate::collectors::thumbnail_cache::ThumbnailCache;
 use crate::collectors::twitch::TwitchCollector;
+use crate::collectors::youtube::YouTubeCollector;
 use crate::constants::database as db_constants;
+use crate::constants::poller as poller_constants;
+use crate::constants::youtube as youtube_constants;
 use crate::database::{
-    models::{Channel, ChannelStatsEvent, Stream, StreamData, StreamStats},
-    repositories::ChannelRepository,
+    models::{Channel, ChannelStatsEvent, Stream, StreamData, StreamLifecycleEvent, StreamStats},
+    repositories::{base::with_transaction, ChannelRepository, ScheduledStreamRepository},
     writer::DatabaseWriter,
     DatabaseManager,
 };
 use crate::logger::AppLogger;
-use chrono::Local;
+use chrono::Utc;
 use duckdb::Connection;
 use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 use tauri::{AppHandle, Emitter, Manager, State};
-use tokio::time::{interval, Duration, MissedTickBehavior};
+use tauri_plugin_notification::NotificationExt;
+use tokio::time::{interval, interval_at, Duration, Instant, MissedTickBehavior};
 
 #[derive(Debug, Clone, Serialize)]
 pub struct CollectorStatus {
@@ -27,11 +35,54 @@ pub struct CollectorStatus {
     pub last_error: Option<String>,
     pub poll_count: u64,
     pub error_count: u64,
+    /// 連続したポーリング失敗回数（成功するとリセット）
+    pub consecutive_failures: u32,
+    /// 連続失敗によりポーリングを一時停止している場合の再開予定時刻
+    pub paused_until: Option<String>,
+    /// 最後に発行したチャンネル統計（UI再接続時の状態復元用）
+    pub latest_stats: Option<ChannelStatsEvent>,
+    /// 視聴者数アラートを通知済みの配信（streams.id）。ポーリングを再開しても配信終了までは再通知しない
+    pub alerted_stream_id: Option<i64>,
+}
+
+/// ポーリングの連続失敗によりポーリングを一時停止した際のイベント（channel-poll-failed）
+#[derive(Debug, Clone, Serialize)]
+struct ChannelPollFailedEvent {
+    channel_id: i64,
+    channel_name: String,
+    platform: String,
+    consecutive_failures: u32,
+    last_error: String,
+    /// 一時停止の長さ（秒）。経過後に自動で再試行する
+    pause_secs: u64,
+}
+
+/// 視聴者数がチャンネルのアラート閾値を超えた際のイベント（viewer-threshold-exceeded）
+#[derive(Debug, Clone, Serialize)]
+struct ViewerThresholdEvent {
+    channel_id: i64,
+    channel_name: String,
+    /// streams.id
+    stream_id: i64,
+    viewer_count: i32,
+    threshold: i32,
+    title: Option<String>,
+}
+
+/// save_stream_data の保存結果
+struct SavedStream {
+    stream_db_id: i64,
+    /// 今回のポーリングで開始を検知した配信か
+    is_new: bool,
+    /// 新しい配信の開始に伴い終了済みにした配信
+    ended_streams: Vec<StreamLifecycleEvent>,
 }
 
 pub struct ChannelPoller {
     collectors: HashMap<String, Arc<dyn Collector + Send + Sync>>,
     twitch_collector: Option<Arc<TwitchCollector>>,
+    youtube_collector: Option<Arc<YouTubeCollector>>,
+    niconico_collector: Option<Arc<NiconicoCollector>>,
     tasks: HashMap<i64, tokio::task::JoinHandle<()>>,
     status_map: Arc<RwLock<HashMap<i64, CollectorStatus>>>,
 }
@@ -41,6 +92,8 @@ impl ChannelPoller {
         Self {
             collectors: HashMap::new(),
             twitch_collector: None,
+            youtube_collector: None,
+            niconico_collector: None,
             tasks: HashMap::new(),
             status_map: Arc::new(RwLock::new(HashMap::new())),
         }
@@ -51,14 +104,14 @@ impl ChannelPoller {
         platform: String,
         collector: Arc<dyn Collector + Send + Sync>,
     ) {
-        self.collectors.insert(platform.clone(), collector);
+        let limited = RateLimitedCollector::for_platform(&platform, collector);
+        self.collectors.insert(platform, Arc::new(limited));
     }
 
     /// Register Twitch collector specifically for token management
     pub fn register_twitch_collector(&mut self, collector: Arc<TwitchCollector>) {
         self.twitch_collector = Some(collector.clone());
-        self.collectors
-            .insert(db_constants::PLATFORM_TWITCH.to_string(), collector);
+        self.register_collector(db_constants::PLATFORM_TWITCH.to_string(), collector);
     }
 
     /// Get Twitch collector for rate limit tracking
@@ -66,6 +119,23 @@ impl ChannelPoller {
         self.twitch_collector.as_ref()
     }
 
+    /// Register YouTube collector specifically for quota tracking
+    pub fn register_youtube_collector(&mut self, collector: Arc<YouTubeCollector>) {
+        self.youtube_collector = Some(collector.clone());
+        self.register_collector(db_constants::PLATFORM_YOUTUBE.to_string(), collector);
+    }
+
+    /// Get YouTube collector for quota tracking
+    pub fn get_youtube_collector(&self) -> Option<&Arc<YouTubeCollector>> {
+        self.youtube_collector.as_ref()
+    }
+
+    /// Register Niconico collector specifically for stopping comment sessions
+    pub fn register_niconico_collector(&mut self, collector: Arc<NiconicoCollector>) {
+        self.niconico_collector = Some(collector.clone());
+        self.register_collector(db_constants::PLATFORM_NICONICO.to_string(), collector);
+    }
+
     pub fn start_polling(
         &mut self,
         channel: Channel,
@@ -96,8 +166,11 @@ impl ChannelPoller {
         );
         let db_manager = Arc::new(db_manager.inner().clone());
 
-        // Initialize status
+        // Initialize status（アラート通知済みの配信は再開後も引き継ぐ）
         if let Ok(mut status_map) = self.status_map.write() {
+            let alerted_stream_id = status_map
+                .get(&channel_id)
+                .and_then(|status| status.alerted_stream_id);
             status_map.insert(
                 channel_id,
                 CollectorStatus {
@@ -110,6 +183,10 @@ impl ChannelPoller {
                     last_error: None,
                     poll_count: 0,
                     error_count: 0,
+                    consecutive_failures: 0,
+                    paused_until: None,
+                    latest_stats: None,
+                    alerted_stream_id,
                 },
             );
         }
@@ -118,8 +195,11 @@ impl ChannelPoller {
         let twitch_collector_for_task = self.twitch_collector.clone();
 
         let task = tokio::spawn(async move {
-            // 手動登録チャンネルかつTwitchの場合、IRC接続を開始
-            if channel.platform == db_constants::PLATFORM_TWITCH && !channel.is_auto_discovered {
+            // 手動登録チャンネルかつTwitchの場合、IRC接続を開始（チャット収集が無効なチャンネルは接続しない）
+            if channel.platform == db_constants::PLATFORM_TWITCH
+                && !channel.is_auto_discovered
+                && channel.collect_chat
+            {
                 if let Some(ref twitch_collector) = &twitch_collector_for_task {
                     // IRC接続にはlogin name (channel_id)を使用、display name (channel_name)ではない
                     if let Err(e) = twitch_collector
@@ -142,7 +222,12 @@ impl ChannelPoller {
             // Get logger from app_handle
             let logger = app_handle.state::<AppLogger>();
 
+            let mut current_poll_interval = poll_interval;
             let mut interval = interval(poll_interval);
+            // 最後に配信予定を取得した時刻
+            let mut last_schedule_fetch: Option<Instant> = None;
+            // 連続失敗による一時停止の終了時刻
+            let mut paused_until: Option<Instant> = None;
             interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
             // 初回認証
@@ -164,8 +249,13 @@ impl ChannelPoller {
             loop {
                 interval.tick().await;
 
+                // 連続失敗で一時停止中は、停止明けまでポーリングしない
+                if paused_until.is_some_and(|until| Instant::now() < until) {
+                    continue;
+                }
+
                 // Update last poll time
-                let now = Local::now().to_rfc3339();
+                let now = Utc::now().to_rfc3339();
                 let poll_count = if let Ok(mut map) = status_map.write() {
                     if let Some(status) = map.get_mut(&channel_id) {
                         status.last_poll_at = Some(now.clone());
@@ -226,6 +316,26 @@ impl ChannelPoller {
                     break;
                 }
 
+                // poll_interval が変更されていれば、次回以降のポーリング間隔に反映
+                // （クォータ残量が少ないプラットフォームは倍率を掛けて間隔を延ばす）
+                let updated_poll_interval =
+                    Duration::from_secs((updated_channel.poll_interval.max(1)) as u64)
+                        * collector.poll_interval_multiplier().await;
+                if updated_poll_interval != current_poll_interval {
+                    logger.info(&format!(
+                        "Channel {} poll interval changed: {}s -> {}s",
+                        channel_id,
+                        current_poll_interval.as_secs(),
+                        updated_poll_interval.as_secs()
+                    ));
+                    current_poll_interval = updated_poll_interval;
+                    interval = interval_at(
+                        Instant::now() + current_poll_interval,
+                        current_poll_interval,
+                    );
+                    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+                }
+
                 // チャンネル情報を定期的に更新（Twitchの場合のみ）
                 // 注: 実際のダウンキャストは複雑なため、ここではスキップ
                 // 代わりに、start_polling時に一度だけ取得する方式を採用する必要がある
@@ -245,19 +355,24 @@ impl ChannelPoller {
                             .await
                             .map_err(|e| e.to_string());
                         match save_result {
-                            Ok(stream_db_id) => {
+                            Ok(saved) => {
+                                let stream_db_id = saved.stream_db_id;
                                 // Update status with success
-                                let now = Local::now().to_rfc3339();
+                                let now = Utc::now().to_rfc3339();
                                 if let Ok(mut map) = status_map.write() {
                                     if let Some(status) = map.get_mut(&channel_id) {
                                         status.last_success_at = Some(now);
                                         status.last_error = None;
+                                        status.consecutive_failures = 0;
+                                        status.paused_until = None;
                                     }
                                 }
+                                paused_until = None;
 
                                 // Twitch手動登録チャンネルの場合、IRC Managerにstream_idを通知
                                 if updated_channel.platform == db_constants::PLATFORM_TWITCH
                                     && !updated_channel.is_auto_discovered
+                                    && updated_channel.collect_chat
                                 {
                                     if let Some(ref twitch_collector) = twitch_collector_for_task {
                                         twitch_collector
@@ -266,6 +381,100 @@ impl ChannelPoller {
                                     }
                                 }
 
+                                // サムネイル画像のローカルキャッシュ（有効時のみ、ポーリングをブロックしない）
+                                if let Some(ref thumbnail_url) = stream_data.thumbnail_url {
+                                    if ThumbnailCache::is_enabled() {
+                                        let app_handle = app_handle.clone();
+                                        let thumbnail_url = thumbnail_url.clone();
+                                        tokio::spawn(async move {
+                                            if let Err(e) = ThumbnailCache::refresh(
+                                                &app_handle,
+                                                stream_db_id,
+                                                &thumbnail_url,
+                                            )
+                                            .await
+                                            {
+                                                eprintln!(
+                                                    "[Poller] Warning: Failed to cache thumbnail for stream {}: {}",
+                                                    stream_db_id, e
+                                                );
+                                            }
+                                        });
+                                    }
+                                }
+
+                                // チャンネルアイコンのローカルキャッシュ（未取得・URL変更時のみダウンロードする）
+                                if let Some(ref profile_image_url) = stream_data.profile_image_url {
+                                    let app_handle = app_handle.clone();
+                                    let profile_image_url = profile_image_url.clone();
+                                    tokio::spawn(async move {
+                                        if let Err(e) = ChannelIconCache::refresh(
+                                            &app_handle,
+                                            channel_id,
+                                            &profile_image_url,
+                                        )
+                                        .await
+                                        {
+                                            eprintln!(
+                                                "[Poller] Warning: Failed to cache channel icon for channel {}: {}",
+                                                channel_id, e
+                                            );
+                                        }
+                                    });
+                                }
+
+                                // イベント発行: 前の配信の終了・新しい配信の開始
+                                for ended in saved.ended_streams {
+                                    Self::spawn_vod_url_fetch(
+                                        &updated_channel,
+                                        &ended,
+                                        twitch_collector_for_task.clone(),
+                                        Arc::clone(&db_manager),
+                                    );
+                                    let _ = app_handle.emit("stream-ended", ended);
+                                }
+                                if saved.is_new {
+                                    logger.info(&format!(
+                                        "Channel {} started stream {}",
+                                        channel_id, stream_data.stream_id
+                                    ));
+                                    let _ = app_handle.emit(
+                                        "stream-started",
+                                        StreamLifecycleEvent {
+                                            channel_id,
+                                            stream_id: stream_db_id,
+                                            platform_stream_id: stream_data.stream_id.clone(),
+                                            title: stream_data.title.clone(),
+                                        },
+                                    );
+                                }
+
+                                // 視聴者数アラート（配信ごとに1回のみ）
+                                if let (Some(threshold), Some(viewer_count)) = (
+                                    updated_channel.alert_viewer_threshold,
+                                    stream_data.viewer_count,
+                                ) {
+                                    if viewer_count > threshold
+                                        && Self::mark_viewer_alerted(
+                                            &status_map,
+                                            channel_id,
+                                            stream_db_id,
+                                        )
+                                    {
+                                        Self::notify_viewer_threshold(
+                                            &app_handle,
+                                            ViewerThresholdEvent {
+                                                channel_id,
+                                                channel_name: updated_channel.channel_name.clone(),
+                                                stream_id: stream_db_id,
+                                                viewer_count,
+                                                threshold,
+                                                title: stream_data.title.clone(),
+                                            },
+                                        );
+                                    }
+                                }
+
                                 // イベント発行: チャンネルがライブ中
                                 let event = ChannelStatsEvent {
                                     channel_id,
@@ -273,6 +482,7 @@ impl ChannelPoller {
                                     viewer_count: stream_data.viewer_count,
                                     title: stream_data.title.clone(),
                                 };
+                                Self::record_latest_stats(&status_map, &event);
                                 let _ = app_handle.emit("channel-stats-updated", event);
                             }
                             Err(e) => {
@@ -294,11 +504,60 @@ impl ChannelPoller {
                     Ok(None) => {
                         // 配信していない - オフラインイベントを発行
                         // Update status with success (not live is valid state)
-                        let now = Local::now().to_rfc3339();
+                        let now = Utc::now().to_rfc3339();
                         if let Ok(mut map) = status_map.write() {
                             if let Some(status) = map.get_mut(&channel_id) {
-                                status.last_success_at = Some(now);
+                                status.last_success_at = Some(now.clone());
                                 status.last_error = None;
+                                status.consecutive_failures = 0;
+                                status.paused_until = None;
+                                status.alerted_stream_id = None;
+                            }
+                        }
+                        paused_until = None;
+
+                        // 進行中の配信があれば終了時刻を記録
+                        let close_result = db_manager
+                            .with_connection(|conn| {
+                                let open_streams =
+                                    DatabaseWriter::find_open_streams(conn, channel_id)?;
+                                if !open_streams.is_empty() {
+                                    DatabaseWriter::close_open_streams(
+                                        conn, channel_id, None, &now,
+                                    )?;
+                                }
+                                Ok::<_, duckdb::Error>(open_streams)
+                            })
+                            .await;
+                        match close_result {
+                            Ok(closed) if !closed.is_empty() => {
+                                logger.info(&format!(
+                                    "Channel {} went offline, closed {} stream(s)",
+                                    channel_id,
+                                    closed.len()
+                                ));
+                                for (stream_id, platform_stream_id, title) in closed {
+                                    let ended = StreamLifecycleEvent {
+                                        channel_id,
+                                        stream_id,
+                                        platform_stream_id,
+                                        title,
+                                    };
+                                    Self::spawn_vod_url_fetch(
+                                        &updated_channel,
+                                        &ended,
+                                        twitch_collector_for_task.clone(),
+                                        Arc::clone(&db_manager),
+                                    );
+                                    let _ = app_handle.emit("stream-ended", ended);
+                                }
+                            }
+                            Ok(_) => {}
+                            Err(e) => {
+                                logger.error(&format!(
+                                    "Failed to update ended_at for channel {}: {}",
+                                    channel_id, e
+                                ));
                             }
                         }
 
@@ -317,6 +576,7 @@ impl ChannelPoller {
                             viewer_count: None,
                             title: None,
                         };
+                        Self::record_latest_stats(&status_map, &event);
                         let _ = app_handle.emit("channel-stats-updated", event);
                     }
                     Err(e) => {
@@ -349,13 +609,76 @@ impl ChannelPoller {
                         }
 
                         // Update status with error
-                        if let Ok(mut map) = status_map.write() {
+                        let consecutive_failures = if let Ok(mut map) = status_map.write() {
                             if let Some(status) = map.get_mut(&channel_id) {
-                                status.last_error = Some(error_msg);
+                                status.last_error = Some(error_msg.clone());
                                 status.error_count += 1;
+                                status.consecutive_failures += 1;
+                                status.consecutive_failures
+                            } else {
+                                0
+                            }
+                        } else {
+                            0
+                        };
+
+                        // 連続失敗が上限に達したら一時停止し、停止明けに再試行する
+                        // （ネットワーク断・429/5xx 等の一時的な障害でも到達し得るため、チャンネルは無効化しない）
+                        if consecutive_failures >= poller_constants::MAX_CONSECUTIVE_POLL_FAILURES {
+                            let pause_secs = Self::poll_failure_pause_secs(consecutive_failures);
+                            paused_until = Some(Instant::now() + Duration::from_secs(pause_secs));
+                            logger.error(&format!(
+                                "Channel {} failed {} consecutive polls, pausing polling for {}s",
+                                channel_id, consecutive_failures, pause_secs
+                            ));
+
+                            if let Ok(mut map) = status_map.write() {
+                                if let Some(status) = map.get_mut(&channel_id) {
+                                    status.paused_until = Some(
+                                        (Utc::now() + chrono::Duration::seconds(pause_secs as i64))
+                                            .to_rfc3339(),
+                                    );
+                                }
                             }
+
+                            // 通知は一時停止に入った時のみ（停止明けの再試行の失敗では再通知しない）
+                            if consecutive_failures
+                                == poller_constants::MAX_CONSECUTIVE_POLL_FAILURES
+                            {
+                                let _ = app_handle.emit(
+                                    "channel-poll-failed",
+                                    ChannelPollFailedEvent {
+                                        channel_id,
+                                        channel_name: updated_channel.channel_name.clone(),
+                                        platform: updated_channel.platform.clone(),
+                                        consecutive_failures,
+                                        last_error: error_msg,
+                                        pause_secs,
+                                    },
+                                );
+                            }
+                        }
+                    }
+                }
+
+                // 配信予定は手動登録チャンネルのみ、一定間隔ごとに取得する
+                // （クォータ残量が少ない場合はポーリング間隔と同じ倍率で間隔を延ばす）
+                if !updated_channel.is_auto_discovered {
+                    let schedule_due = match last_schedule_fetch {
+                        Some(fetched_at) => {
+                            fetched_at.elapsed()
+                                >= Duration::from_secs(
+                                    poller_constants::SCHEDULE_REFRESH_INTERVAL_SECS,
+                                ) * collector.poll_interval_multiplier().await
                         }
+                        None => true,
+                    };
+                    if schedule_due {
+                        last_schedule_fetch = Some(Instant::now());
+                        Self::refresh_schedule(collector.as_ref(), &updated_channel, &db_manager)
+                            .await;
                     }
+                    Self::notify_due_schedules(&app_handle, &db_manager, channel_id).await;
                 }
             }
         });
@@ -382,6 +705,16 @@ impl ChannelPoller {
             }
         }
 
+        // ライブチャット収集を停止（YouTubeチャンネルの場合）
+        if let Some(ref youtube_collector) = self.youtube_collector {
+            youtube_collector.stop_chat_collection(channel_id).await;
+        }
+
+        // コメント受信を停止（ニコニコ生放送チャンネルの場合）
+        if let Some(ref niconico_collector) = self.niconico_collector {
+            niconico_collector.stop_chat_collection(channel_id).await;
+        }
+
         if let Some(task) = self.tasks.remove(&channel_id) {
             task.abort();
             println!("[ChannelPoller] Task aborted for channel {}", channel_id);
@@ -400,17 +733,304 @@ impl ChannelPoller {
         }
     }
 
+    /// 指定プラットフォームで実行中のポーリングとチャット接続をすべて停止する（トークン削除時など）
+    ///
+    /// 戻り値: 停止したチャンネルID
+    pub async fn stop_platform(&mut self, platform: &str) -> Vec<i64> {
+        let channel_ids: Vec<i64> = self
+            .status_map
+            .read()
+            .map(|map| {
+                map.values()
+                    .filter(|status| status.is_running && status.platform == platform)
+                    .map(|status| status.channel_id)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        for &channel_id in &channel_ids {
+            self.stop_polling(channel_id).await;
+        }
+        channel_ids
+    }
+
+    /// 指定プラットフォームの有効なチャンネルのポーリングを開始し直す（再ログイン時など）
+    ///
+    /// 実行中のタスクは古いコレクター・認証情報を保持しているため、一度停止してから開始する。
+    /// 戻り値: ポーリングを開始したチャンネル数
+    pub async fn restart_platform(
+        &mut self,
+        platform: &str,
+        db_manager: &State<'_, DatabaseManager>,
+        app_handle: &AppHandle,
+    ) -> Result<usize, String> {
+        self.stop_platform(platform).await;
+
+        let channels = db_manager
+            .with_connection(ChannelRepository::list_enabled)
+            .await
+            .map_err(|e| format!("Failed to list enabled channels: {}", e))?;
+
+        let mut started = 0;
+        for channel in channels.into_iter().filter(|c| c.platform == platform) {
+            let channel_id = channel.id.unwrap_or(-1);
+            match self.start_polling(channel, db_manager, app_handle.clone()) {
+                Ok(()) => started += 1,
+                Err(e) => eprintln!(
+                    "[ChannelPoller] Failed to restart polling for channel {}: {}",
+                    channel_id, e
+                ),
+            }
+        }
+        Ok(started)
+    }
+
+    /// 全チャンネルのポーリング状態を取得
+    pub fn get_statuses(&self) -> Vec<CollectorStatus> {
+        self.status_map
+            .read()
+            .map(|map| map.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// 終了した配信のアーカイブURLを取得して streams.vod_url に保存する
+    ///
+    /// YouTube は配信の動画IDがそのままアーカイブになる。Twitch は Get Videos から
+    /// stream_id が一致するVODを探す（ポーリングをブロックしないよう別タスクで実行）。
+    fn spawn_vod_url_fetch(
+        channel: &Channel,
+        ended: &StreamLifecycleEvent,
+        twitch_collector: Option<Arc<TwitchCollector>>,
+        db_manager: Arc<DatabaseManager>,
+    ) {
+        let platform = channel.platform.clone();
+        let twitch_user_id = channel.twitch_user_id;
+        let stream_db_id = ended.stream_id;
+        let platform_stream_id = ended.platform_stream_id.clone();
+
+        tokio::spawn(async move {
+            let vod_url = match platform.as_str() {
+                db_constants::PLATFORM_YOUTUBE => Some(format!(
+                    "{}{}",
+                    youtube_constants::WATCH_URL_BASE,
+                    platform_stream_id
+                )),
+                db_constants::PLATFORM_TWITCH => {
+                    let (Some(collector), Some(user_id)) = (twitch_collector, twitch_user_id)
+                    else {
+                        return;
+                    };
+                    match collector
+                        .find_vod_url(&user_id.to_string(), &platform_stream_id)
+                        .await
+                    {
+                        Ok(url) => url,
+                        Err(e) => {
+                            eprintln!(
+                                "[Poller] Warning: Failed to fetch VOD for stream {}: {}",
+                                stream_db_id, e
+                            );
+                            None
+                        }
+                    }
+                }
+                _ => None,
+            };
+
+            let Some(vod_url) = vod_url else {
+                return;
+            };
+            let result = db_manager
+                .with_connection(|conn| DatabaseWriter::set_vod_url(conn, stream_db_id, &vod_url))
+                .await;
+            if let Err(e) = result {
+                eprintln!(
+                    "[Poller] Warning: Failed to save VOD URL for stream {}: {}",
+                    stream_db_id, e
+                );
+            }
+        });
+    }
+
+    /// チャンネルの配信予定を取得して scheduled_streams を更新する
+    async fn refresh_schedule(
+        collector: &(dyn Collector + Send + Sync),
+        channel: &Channel,
+        db_manager: &Arc<DatabaseManager>,
+    ) {
+        let channel_id = channel.id.unwrap_or_default();
+        let schedules = match collector.fetch_schedule(channel).await {
+            Ok(schedules) => schedules,
+            Err(e) => {
+                eprintln!(
+                    "[Poller] Warning: Failed to fetch schedule for channel {}: {}",
+                    channel_id, e
+                );
+                return;
+            }
+        };
+
+        let result = db_manager
+            .with_connection(|conn| {
+                with_transaction(conn, |conn| {
+                    ScheduledStreamRepository::replace_for_channel(conn, channel_id, &schedules)
+                })
+            })
+            .await;
+        if let Err(e) = result {
+            eprintln!(
+                "[Poller] Warning: Failed to save schedule for channel {}: {}",
+                channel_id, e
+            );
+        }
+    }
+
+    /// 開始が近づいた配信予定のリマインダーを発行し、OS通知を表示する（予定ごとに1回のみ）
+    async fn notify_due_schedules(
+        app_handle: &AppHandle,
+        db_manager: &Arc<DatabaseManager>,
+        channel_id: i64,
+    ) {
+        let now = Utc::now();
+        let until =
+            now + chrono::Duration::minutes(poller_constants::SCHEDULE_REMINDER_LEAD_MINUTES);
+        let due = match db_manager
+            .with_connection(|conn| {
+                ScheduledStreamRepository::take_due_reminders(conn, channel_id, &now, &until)
+            })
+            .await
+        {
+            Ok(due) => due,
+            Err(e) => {
+                eprintln!(
+                    "[Poller] Warning: Failed to check schedule reminders for channel {}: {}",
+                    channel_id, e
+                );
+                return;
+            }
+        };
+
+        let logger = app_handle.state::<AppLogger>();
+        for schedule in due {
+            let name = schedule
+                .display_name
+                .clone()
+                .unwrap_or_else(|| schedule.channel_name.clone());
+            logger.info(&format!(
+                "Scheduled stream reminder: channel {} starts at {}",
+                schedule.channel_id, schedule.scheduled_start
+            ));
+
+            if let Err(e) = app_handle
+                .notification()
+                .builder()
+                .title(format!("{} の配信がまもなく始まります", name))
+                .body(
+                    schedule
+                        .title
+                        .clone()
+                        .unwrap_or_else(|| "配信予定".to_string()),
+                )
+                .show()
+            {
+                logger.error(&format!(
+                    "Failed to show scheduled stream notification: {}",
+                    e
+                ));
+            }
+
+            let _ = app_handle.emit("scheduled-stream-reminder", schedule);
+        }
+    }
+
+    /// 視聴者数アラートのイベントを発行し、OS通知を表示する
+    fn notify_viewer_threshold(app_handle: &AppHandle, event: ViewerThresholdEvent) {
+        let logger = app_handle.state::<AppLogger>();
+        logger.info(&format!(
+            "Channel {} exceeded viewer threshold: {} > {}",
+            event.channel_id, event.viewer_count, event.threshold
+        ));
+
+        if let Err(e) = app_handle
+            .notification()
+            .builder()
+            .title(format!(
+                "{} の視聴者数が {} 人を超えました",
+                event.channel_name, event.threshold
+            ))
+            .body(format!(
+                "現在 {} 人が視聴中{}",
+                event.viewer_count,
+                event
+                    .title
+                    .as_deref()
+                    .map(|title| format!(": {}", title))
+                    .unwrap_or_default()
+            ))
+            .show()
+        {
+            logger.error(&format!(
+                "Failed to show viewer threshold notification: {}",
+                e
+            ));
+        }
+
+        let _ = app_handle.emit("viewer-threshold-exceeded", event);
+    }
+
+    /// 連続失敗回数に応じた一時停止の長さ（秒）
+    ///
+    /// 上限回数で初めて停止した時は POLL_FAILURE_PAUSE_SECS、以降の再試行に失敗するたびに倍にする。
+    fn poll_failure_pause_secs(consecutive_failures: u32) -> u64 {
+        let retries = consecutive_failures
+            .saturating_sub(poller_constants::MAX_CONSECUTIVE_POLL_FAILURES)
+            .min(16);
+        poller_constants::POLL_FAILURE_PAUSE_SECS
+            .saturating_mul(1 << retries)
+            .min(poller_constants::MAX_POLL_FAILURE_PAUSE_SECS)
+    }
+
+    /// 視聴者数アラートの通知済み配信を記録する（未通知の配信なら true）
+    fn mark_viewer_alerted(
+        status_map: &Arc<RwLock<HashMap<i64, CollectorStatus>>>,
+        channel_id: i64,
+        stream_id: i64,
+    ) -> bool {
+        let Ok(mut map) = status_map.write() else {
+            return false;
+        };
+        match map.get_mut(&channel_id) {
+            Some(status) if status.alerted_stream_id != Some(stream_id) => {
+                status.alerted_stream_id = Some(stream_id);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 発行したチャンネル統計を状態マップに記録する
+    fn record_latest_stats(
+        status_map: &Arc<RwLock<HashMap<i64, CollectorStatus>>>,
+        event: &ChannelStatsEvent,
+    ) {
+        if let Ok(mut map) = status_map.write() {
+            if let Some(status) = map.get_mut(&event.channel_id) {
+                status.latest_stats = Some(event.clone());
+            }
+        }
+    }
+
     fn get_channel(conn: &Connection, channel_id: i64) -> Result<Option<Channel>, duckdb::Error> {
         ChannelRepository::get_by_id(conn, channel_id)
     }
 
     /// ストリーム統計情報をデータベースに保存する
-    /// 戻り値: データベース上のstream_id
+    /// 戻り値: 保存した配信の streams.id と、開始・終了を検知した配信
     fn save_stream_data(
         conn: &Connection,
         channel: &Channel,
         stream_data: &StreamData,
-    ) -> Result<i64, Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<SavedStream, Box<dyn std::error::Error + Send + Sync>> {
         let channel_id = channel.id.ok_or("Channel ID is required")?;
 
         // StreamDataから配信情報を含むStreamレコードを作成
@@ -425,9 +1045,33 @@ impl ChannelPoller {
             ended_at: None, // ライブ中なのでNone
         };
 
+        // 保存前に進行中だった配信（開始・終了イベントの判定用）
+        let open_streams = DatabaseWriter::find_open_streams(conn, channel_id)?;
+
         // ストリームを保存（同じstream_idの場合は更新）
         let stream_db_id = DatabaseWriter::insert_or_update_stream(conn, channel_id, &stream)?;
 
+        // ポーリングの合間に前の配信が終わり新しい配信が始まった場合、前の配信を終了済みにする
+        DatabaseWriter::close_open_streams(
+            conn,
+            channel_id,
+            Some(stream_db_id),
+            &stream_data.started_at,
+        )?;
+
+        // 保存前に進行中でなかった配信は新規開始として扱う（オフライン判定後の再開も含む）
+        let is_new = !open_streams.iter().any(|(id, _, _)| *id == stream_db_id);
+        let ended_streams = open_streams
+            .into_iter()
+            .filter(|(id, _, _)| *id != stream_db_id)
+            .map(|(id, platform_stream_id, title)| StreamLifecycleEvent {
+                channel_id,
+                stream_id: id,
+                platform_stream_id,
+                title,
+            })
+            .collect();
+
         // プラットフォーム別にtwitch_user_idを設定
         let twitch_user_id = if channel.platform == db_constants::PLATFORM_TWITCH {
             channel.twitch_user_id.map(|id| id.to_string()) // FIX: 正しいuser_idを使用
@@ -435,23 +1079,45 @@ impl ChannelPoller {
             None
         };
 
-        // StreamStatsを作成して保存
-        let stats = StreamStats {
-            id: None,
-            stream_id: stream_db_id,
-            collected_at: Local::now().to_rfc3339(),
-            viewer_count: stream_data.viewer_count,
-            chat_rate_1min: None, // Calculated dynamically when needed
-            category: stream_data.category.clone(),
-            game_id: stream_data.game_id.clone(),
-            title: stream_data.title.clone(),
-            follower_count: stream_data.follower_count,
-            twitch_user_id,
-            channel_name: Some(channel.channel_name.clone()),
-        };
+        // 統計収集が無効なチャンネルは配信レコードのみ保存する（チャットの紐付け用）
+        if channel.collect_stats {
+            // StreamStatsを作成して保存
+            let stats = StreamStats {
+                id: None,
+                stream_id: stream_db_id,
+                collected_at: Utc::now().to_rfc3339(),
+                viewer_count: stream_data.viewer_count,
+                chat_rate_1min: None, // Calculated dynamically when needed
+                category: stream_data.category.clone(),
+                game_id: stream_data.game_id.clone(),
+                title: stream_data.title.clone(),
+                follower_count: stream_data.follower_count,
+                twitch_user_id,
+                channel_name: Some(channel.channel_name.clone()),
+                comment_count: stream_data.comment_count,
+            };
+
+            // ストリーム統計を保存
+            DatabaseWriter::insert_stream_stats(conn, &stats)?;
+        }
+
+        // 表示名が変わっていれば保存（login名と表示名が異なるチャンネルの表示用）
+        if let Some(display_name) = stream_data
+            .display_name
+            .as_deref()
+            .filter(|name| !name.is_empty() && *name != channel.display_name)
+        {
+            ChannelRepository::update_display_name(conn, channel_id, display_name)?;
+        }
 
-        // ストリーム統計を保存
-        DatabaseWriter::insert_stream_stats(conn, &stats)?;
+        // プロフィール画像URLが変わっていれば保存（アイコンのキャッシュはポーリング側で更新する）
+        if let Some(profile_image_url) = stream_data
+            .profile_image_url
+            .as_deref()
+            .filter(|url| !url.is_empty() && *url != channel.profile_image_url)
+        {
+            ChannelRepository::update_profile_image_url(conn, channel_id, profile_image_url)?;
+        }
 
         // ゲームカテゴリをgame_categoriesテーブルに自動保存（ID->名前解決用）
         if let (Some(game_id), Some(game_name)) = (&stream_data.game_id, &stream_data.category) {
@@ -468,64 +1134,61 @@ impl ChannelPoller {
             }
         }
 
-        Ok(stream_db_id)
+        Ok(SavedStream {
+            stream_db_id,
+            is_new,
+            ended_streams,
+        })
     }
+}
 
-    /// チャット収集を開始する（ストリーム開始時に呼び出し）
-    /// TODO: チャット機能実装中
-    #[allow(dead_code)]
-    pub async fn start_chat_collection(
-        &self,
-        _channel: &Channel,
-        _stream_id: i64,
-        _video_id: Option<&str>,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        // チャット機能は現在無効化中
-        // match channel.platform.as_str() {
-        //     "twitch" => {
-        //         if let Some(twitch_collector) = self.collectors.get("twitch") {
-        //             if let Ok(twitch_collector) = twitch_collector.as_ref().downcast_ref::<crate::collectors::twitch::TwitchCollector>() {
-        //                 twitch_collector.start_chat_collection(stream_id, &channel.channel_name, "oauth_token_here").await?;
-        //             }
-        //         }
-        //     }
-        //     "youtube" => {
-        //         if let Some(youtube_collector) = self.collectors.get("youtube") {
-        //             if let Ok(youtube_collector) = youtube_collector.as_ref().downcast_ref::<crate::collectors::youtube::YouTubeCollector>() {
-        //                 if let Some(video_id) = video_id {
-        //                     youtube_collector.start_chat_collection(stream_id, video_id, 30).await?;
-        //                 }
-        //             }
-        //         }
-        //     }
-        //     _ => {}
-        // }
-        Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poll_failure_pause_backs_off_up_to_limit() {
+        let max = poller_constants::MAX_CONSECUTIVE_POLL_FAILURES;
+        assert_eq!(
+            ChannelPoller::poll_failure_pause_secs(max),
+            poller_constants::POLL_FAILURE_PAUSE_SECS
+        );
+        assert_eq!(
+            ChannelPoller::poll_failure_pause_secs(max + 1),
+            poller_constants::POLL_FAILURE_PAUSE_SECS * 2
+        );
+        assert_eq!(
+            ChannelPoller::poll_failure_pause_secs(max + 100),
+            poller_constants::MAX_POLL_FAILURE_PAUSE_SECS
+        );
     }
 
-    /// チャット収集を停止する（ストリーム終了時に呼び出し）
-    /// TODO: チャット機能実装中
-    #[allow(dead_code)]
-    pub async fn stop_chat_collection(&self, _channel: &Channel, _video_id: Option<&str>) {
-        // チャット機能は現在無効化中
-        // match channel.platform.as_str() {
-        //     "twitch" => {
-        //         if let Some(twitch_collector) = self.collectors.get("twitch") {
-        //             if let Ok(twitch_collector) = twitch_collector.as_ref().downcast_ref::<crate::collectors::twitch::TwitchCollector>() {
-        //                 twitch_collector.stop_chat_collection(&channel.channel_name).await;
-        //             }
-        //         }
-        //     }
-        //     "youtube" => {
-        //         if let Some(youtube_collector) = self.collectors.get("youtube") {
-        //             if let Ok(youtube_collector) = youtube_collector.as_ref().downcast_ref::<crate::collectors::youtube::YouTubeCollector>() {
-        //                 if let Some(video_id) = video_id {
-        //                     youtube_collector.stop_chat_collection(video_id).await;
-        //                 }
-        //             }
-        //         }
-        //     }
-        //     _ => {}
-        // }
+    #[test]
+    fn test_mark_viewer_alerted_once_per_stream() {
+        let status_map = Arc::new(RwLock::new(HashMap::new()));
+        status_map.write().unwrap().insert(
+            1,
+            CollectorStatus {
+                channel_id: 1,
+                channel_name: "foo".to_string(),
+                platform: db_constants::PLATFORM_TWITCH.to_string(),
+                is_running: true,
+                last_poll_at: None,
+                last_success_at: None,
+                last_error: None,
+                poll_count: 0,
+                error_count: 0,
+                consecutive_failures: 0,
+                paused_until: None,
+                latest_stats: None,
+                alerted_stream_id: None,
+            },
+        );
+
+        assert!(ChannelPoller::mark_viewer_alerted(&status_map, 1, 10));
+        assert!(!ChannelPoller::mark_viewer_alerted(&status_map, 1, 10));
+        assert!(ChannelPoller::mark_viewer_alerted(&status_map, 1, 11));
+        // 状態の無いチャンネルは通知しない
+        assert!(!ChannelPoller::mark_viewer_alerted(&status_map, 2, 10));
     }
 }
@@ -0,0 +1,102 @@
+/// 配信サムネイル画像のローカルキャッシュ
+///
+/// 設定の `cache_thumbnails` が有効な場合、ポーリングで取得したサムネイルURLの画像を
+/// `<app_data_dir>/thumbnails/<streams.id>.jpg` に保存する。
+/// ライブ中のサムネイルは随時更新されるため、一定間隔ごとに上書きする。
+use crate::constants::thumbnail as thumbnail_constants;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager};
+
+/// 設定の `cache_thumbnails`（ポーリングごとに設定ファイルを読まないよう起動時・変更時に更新する）
+static CACHE_ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub struct ThumbnailCache;
+
+impl ThumbnailCache {
+    /// キャッシュの有効/無効を設定する（AppSettings.cache_thumbnails）
+    pub fn set_enabled(enabled: bool) {
+        CACHE_ENABLED.store(enabled, Ordering::Relaxed);
+    }
+
+    /// キャッシュが有効か
+    pub fn is_enabled() -> bool {
+        CACHE_ENABLED.load(Ordering::Relaxed)
+    }
+
+    /// キャッシュディレクトリのパスを取得
+    pub fn cache_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+        let app_data_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+        Ok(app_data_dir.join(thumbnail_constants::CACHE_DIR_NAME))
+    }
+
+    /// 配信（streams.id）のキャッシュファイルパスを取得
+    pub fn path_for(app_handle: &AppHandle, stream_db_id: i64) -> Result<PathBuf, String> {
+        Ok(Self::cache_dir(app_handle)?.join(format!("{}.jpg", stream_db_id)))
+    }
+
+    /// キャッシュが存在しないか、更新間隔を過ぎている場合に true
+    fn needs_refresh(path: &Path) -> bool {
+        let modified = match std::fs::metadata(path).and_then(|m| m.modified()) {
+            Ok(modified) => modified,
+            Err(_) => return true,
+        };
+        SystemTime::now()
+            .duration_since(modified)
+            .map(|age| age >= Duration::from_secs(thumbnail_constants::CACHE_REFRESH_INTERVAL_SECS))
+            .unwrap_or(true)
+    }
+
+    /// 必要であればサムネイル画像をダウンロードしてキャッシュする
+    /// 戻り値: 画像を保存した場合は true
+    pub async fn refresh(
+        app_handle: &AppHandle,
+        stream_db_id: i64,
+        url: &str,
+    ) -> Result<bool, String> {
+        let path = Self::path_for(app_handle, stream_db_id)?;
+        if !Self::needs_refresh(&path) {
+            return Ok(false);
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(
+                thumbnail_constants::DOWNLOAD_TIMEOUT_SECS,
+            ))
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+        let response = client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to download thumbnail: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "Failed to download thumbnail: HTTP {}",
+                response.status()
+            ));
+        }
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to read thumbnail body: {}", e))?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create thumbnail directory: {}", e))?;
+        }
+
+        // 書き込み途中のファイルを読まれないよう、一時ファイルに書いてからリネームする
+        let tmp_path = path.with_extension("jpg.tmp");
+        std::fs::write(&tmp_path, &bytes)
+            .map_err(|e| format!("Failed to write thumbnail: {}", e))?;
+        std::fs::rename(&tmp_path, &path)
+            .map_err(|e| format!("Failed to save thumbnail: {}", e))?;
+
+        Ok(true)
+    }
+}
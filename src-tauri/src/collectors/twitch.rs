@@ -1,15 +1,79 @@
 use crate::api::twitch_api::TwitchApiClient;
 use crate::collectors::collector_trait::Collector;
-use crate::database::models::{Channel, StreamData};
+use crate::constants::twitch;
+use crate::database::models::{Channel, ScheduledStreamData, StreamData};
 use crate::database::DatabaseManager;
 use crate::logger::AppLogger;
-use crate::websocket::twitch_irc::TwitchIrcManager;
+use crate::websocket::twitch_irc::{
+    ChatConnectionStatus, IrcChannelStatus, IrcLoginCredentials, TwitchIrcManager,
+};
 use async_trait::async_trait;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use twitch_api::helix::streams::Stream;
+
+/// 監視中チャンネルの配信情報をまとめて取得するためのキャッシュ
+///
+/// 各チャンネルのポーリングタスクは独立して動くため、ここで取得要求を集約する。
+/// 直近のバッチ取得結果が「要求元チャンネルの poll_interval の半分」より新しければそれを返し、
+/// 古ければ監視中の全チャンネルを `GET /helix/streams` の一括取得（最大100件/リクエスト）で更新する。
+/// poll_interval がチャンネルごとに異なっていても、各チャンネルが受け取るデータの鮮度は
+/// 自身の間隔の半分以内に保たれる。
+#[derive(Default)]
+struct StreamBatchCache {
+    /// user_id -> (poll_interval, 最終要求時刻)
+    watched: HashMap<String, (Duration, Instant)>,
+    /// 直近のバッチ取得で対象にした user_id
+    fetched_ids: HashSet<String>,
+    /// 直近のバッチ取得で配信中だったストリーム
+    live_streams: HashMap<String, Stream>,
+    fetched_at: Option<Instant>,
+}
+
+impl StreamBatchCache {
+    fn is_fresh_for(&self, user_id: &str, max_age: Duration) -> bool {
+        self.fetched_ids.contains(user_id)
+            && self
+                .fetched_at
+                .is_some_and(|fetched_at| fetched_at.elapsed() < max_age)
+    }
+
+    /// 2回分のポーリング間隔以上要求のないチャンネル（停止・削除済み）を対象から外す
+    fn prune_stale(&mut self) {
+        self.watched
+            .retain(|_, (interval, last_requested)| last_requested.elapsed() < *interval * 2);
+    }
+
+    /// 要求を記録し、バッチ取得が必要なら取得対象の user_id を返す（キャッシュが新しければ None）
+    fn request(&mut self, user_id: &str, poll_interval: Duration) -> Option<Vec<String>> {
+        self.watched
+            .insert(user_id.to_string(), (poll_interval, Instant::now()));
+        if self.is_fresh_for(user_id, poll_interval / 2) {
+            return None;
+        }
+        self.prune_stale();
+        Some(self.watched.keys().cloned().collect())
+    }
+
+    /// バッチ取得の結果を保存する
+    fn store(&mut self, user_ids: Vec<String>, live_streams: HashMap<String, Stream>) {
+        self.fetched_ids = user_ids.into_iter().collect();
+        self.live_streams = live_streams;
+        self.fetched_at = Some(Instant::now());
+    }
+}
 
 pub struct TwitchCollector {
     api_client: Arc<TwitchApiClient>,
     irc_manager: Arc<TwitchIrcManager>,
+    irc_credentials: IrcLoginCredentials,
+    /// IRCに認証済みアカウントで接続するか（false の場合は匿名接続）
+    irc_authenticated: bool,
+    stream_cache: Mutex<StreamBatchCache>,
+    /// user_id -> (プロフィール画像URL, 取得時刻)
+    profile_images: Mutex<HashMap<String, (Option<String>, Instant)>>,
 }
 
 impl TwitchCollector {
@@ -20,14 +84,24 @@ impl TwitchCollector {
         app_handle: tauri::AppHandle,
         db_manager: Arc<DatabaseManager>,
         logger: Arc<AppLogger>,
+        irc_authenticated: bool,
     ) -> Self {
-        let irc_manager = Arc::new(TwitchIrcManager::new(db_manager, Arc::clone(&logger)));
+        let irc_credentials = IrcLoginCredentials::default();
+        let irc_manager = Arc::new(TwitchIrcManager::new(
+            db_manager,
+            Arc::clone(&logger),
+            irc_credentials.clone(),
+        ));
 
         Self {
             api_client: Arc::new(
                 TwitchApiClient::new(client_id, client_secret).with_app_handle(app_handle),
             ),
             irc_manager,
+            irc_credentials,
+            irc_authenticated,
+            stream_cache: Mutex::new(StreamBatchCache::default()),
+            profile_images: Mutex::new(HashMap::new()),
         }
     }
 
@@ -40,6 +114,83 @@ impl TwitchCollector {
     pub async fn initialize_irc(&self) {
         self.irc_manager.start_db_handler().await;
     }
+
+    /// 配信情報をバッチ取得キャッシュ経由で取得
+    ///
+    /// キャッシュのロックを取得処理中も保持するため、同時に期限切れになった複数チャンネルの
+    /// 要求は1回のバッチ取得にまとめられる。
+    async fn get_stream_batched(
+        &self,
+        user_id: &str,
+        poll_interval: Duration,
+    ) -> Result<Option<Stream>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut cache = self.stream_cache.lock().await;
+
+        if let Some(user_ids) = cache.request(user_id, poll_interval) {
+            let mut live_streams = HashMap::new();
+            for chunk in user_ids.chunks(twitch::MAX_STREAMS_PER_REQUEST) {
+                let refs: Vec<&str> = chunk.iter().map(String::as_str).collect();
+                for stream in self.api_client.get_streams_by_user_ids(&refs).await? {
+                    live_streams.insert(stream.user_id.to_string(), stream);
+                }
+            }
+            cache.store(user_ids, live_streams);
+        }
+
+        Ok(cache.live_streams.get(user_id).cloned())
+    }
+
+    /// プロフィール画像URLを取得（PROFILE_IMAGE_REFRESH_INTERVAL_SECS の間はキャッシュを返す）
+    ///
+    /// 取得に失敗した場合は None を返し、次回のポーリングで再取得する。
+    async fn get_profile_image_url(&self, user_id: &str) -> Option<String> {
+        let max_age = Duration::from_secs(twitch::PROFILE_IMAGE_REFRESH_INTERVAL_SECS);
+        if let Some((url, fetched_at)) = self.profile_images.lock().await.get(user_id) {
+            if fetched_at.elapsed() < max_age {
+                return url.clone();
+            }
+        }
+
+        match self.api_client.get_users_by_ids(&[user_id]).await {
+            Ok(users) => {
+                let url = users
+                    .into_iter()
+                    .next()
+                    .and_then(|user| user.profile_image_url)
+                    .filter(|url| !url.is_empty());
+                self.profile_images
+                    .lock()
+                    .await
+                    .insert(user_id.to_string(), (url.clone(), Instant::now()));
+                url
+            }
+            Err(e) => {
+                eprintln!(
+                    "[TwitchCollector] Failed to get profile image for {}: {}",
+                    user_id, e
+                );
+                None
+            }
+        }
+    }
+
+    /// 配信IDに対応するVODのURLを取得（VODが無効・削除済みの場合は None）
+    pub async fn find_vod_url(
+        &self,
+        user_id: &str,
+        platform_stream_id: &str,
+    ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+        let videos = self.api_client.get_archive_videos(user_id).await?;
+        Ok(videos
+            .into_iter()
+            .find(|video| {
+                video
+                    .stream_id
+                    .as_ref()
+                    .is_some_and(|id| id.as_str() == platform_stream_id)
+            })
+            .map(|video| video.url))
+    }
 }
 
 #[async_trait]
@@ -60,10 +211,10 @@ impl Collector for TwitchCollector {
             user.id.to_string()
         };
 
-        // 配信情報を取得
+        // 配信情報を取得（監視中の他チャンネルとまとめて一括取得）
+        let poll_interval = Duration::from_secs(channel.poll_interval.max(1) as u64);
         let stream_opt = self
-            .api_client
-            .get_stream_by_user_id(&user_id_string)
+            .get_stream_batched(&user_id_string, poll_interval)
             .await?;
 
         if let Some(stream) = stream_opt {
@@ -83,16 +234,29 @@ impl Collector for TwitchCollector {
                 }
             };
 
+            let profile_image_url = self.get_profile_image_url(&user_id_string).await;
+
             // Twitch APIから取得したストリーム情報を構造化して返す
             Ok(Some(StreamData {
                 stream_id: stream.id.to_string(),
                 title: Some(stream.title.to_string()),
                 category: Some(stream.game_name.to_string()),
                 game_id: Some(stream.game_id.to_string()),
-                thumbnail_url: Some(stream.thumbnail_url.to_string()),
+                // Get Streams のサムネイルは `{width}x{height}` プレースホルダー付きで返る
+                thumbnail_url: Some(stream.thumbnail_url.replace(
+                    "{width}x{height}",
+                    &format!(
+                        "{}x{}",
+                        twitch::STREAM_THUMBNAIL_WIDTH,
+                        twitch::STREAM_THUMBNAIL_HEIGHT
+                    ),
+                )),
                 started_at: stream.started_at.as_str().to_string(),
                 viewer_count: Some(stream.viewer_count as i32),
                 follower_count,
+                comment_count: None,
+                display_name: Some(stream.user_name.to_string()),
+                profile_image_url,
             }))
         } else {
             // 配信していない場合はNone
@@ -108,6 +272,35 @@ impl Collector for TwitchCollector {
         self.api_client.authenticate().await?;
         Ok(())
     }
+
+    async fn fetch_schedule(
+        &self,
+        channel: &Channel,
+    ) -> Result<Vec<ScheduledStreamData>, Box<dyn std::error::Error + Send + Sync>> {
+        let user_id_string = match channel.twitch_user_id {
+            Some(twitch_user_id) => twitch_user_id.to_string(),
+            None => self
+                .api_client
+                .get_user_by_login(&channel.channel_id)
+                .await?
+                .id
+                .to_string(),
+        };
+
+        let segments = self
+            .api_client
+            .get_channel_schedule(&user_id_string)
+            .await?;
+        Ok(segments
+            .into_iter()
+            .map(|segment| ScheduledStreamData {
+                scheduled_start: segment.start_time.as_str().to_string(),
+                scheduled_end: Some(segment.end_time.as_str().to_string()),
+                title: Some(segment.title).filter(|title| !title.is_empty()),
+                category: segment.category.map(|category| category.name),
+            })
+            .collect())
+    }
 }
 
 impl TwitchCollector {
@@ -138,15 +331,25 @@ impl TwitchCollector {
         channel_id: i64,
         channel_name: &str,
     ) -> Result<(), String> {
-        let access_token = self
-            .api_client
-            .get_access_token()
-            .await
-            .map_err(|e| e.to_string())?;
+        // 匿名モードではトークン無しで接続できる。認証モードは初回のみログイン名を解決する
+        if self.irc_authenticated && !self.irc_credentials.is_authenticated() {
+            match self.api_client.get_token_login().await {
+                Ok((login, token)) => {
+                    eprintln!("[TwitchCollector] IRC will connect as {}", login);
+                    self.irc_credentials.set_user(login, token);
+                }
+                Err(e) => {
+                    eprintln!(
+                        "[TwitchCollector] Failed to resolve IRC login, falling back to anonymous connection: {}",
+                        e
+                    );
+                }
+            }
+        }
+
         self.irc_manager
-            .start_channel_collection(channel_id, channel_name, &access_token)
+            .start_channel_collection(channel_id, channel_name)
             .await
-            .map_err(|e| e.to_string())
     }
 
     /// チャット収集を停止
@@ -160,4 +363,89 @@ impl TwitchCollector {
             .update_channel_stream(channel_id, stream_id)
             .await;
     }
+
+    /// IRC接続状態を取得
+    pub async fn get_irc_statuses(&self) -> Vec<IrcChannelStatus> {
+        self.irc_manager.get_channel_statuses().await
+    }
+
+    /// JOIN済みのIRCチャンネルと収集状況を取得
+    pub async fn get_active_chat_connections(&self) -> Vec<ChatConnectionStatus> {
+        self.irc_manager.get_active_connections().await
+    }
+
+    /// IRC受信を停止（アプリ終了時）
+    pub async fn shutdown_irc(&self) {
+        self.irc_manager.shutdown().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(mut ids: Vec<String>) -> Vec<String> {
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_stream_batch_cache_reuses_fresh_batch() {
+        let mut cache = StreamBatchCache::default();
+        let interval = Duration::from_secs(60);
+
+        // 未取得のチャンネルは取得が必要
+        let ids = cache.request("1", interval).unwrap();
+        assert_eq!(ids, vec!["1".to_string()]);
+        cache.store(ids, HashMap::new());
+
+        // 直近のバッチに含まれていれば再取得しない
+        assert!(cache.request("1", interval).is_none());
+        assert!(!cache.live_streams.contains_key("1"));
+
+        // バッチに含まれていないチャンネルは、監視中の全チャンネルをまとめて取得する
+        let ids = cache.request("2", interval).unwrap();
+        assert_eq!(sorted(ids), vec!["1".to_string(), "2".to_string()]);
+    }
+
+    #[test]
+    fn test_stream_batch_cache_freshness_follows_requester_interval() {
+        let mut cache = StreamBatchCache::default();
+        let ids = cache.request("fast", Duration::from_secs(10)).unwrap();
+        cache.store(ids, HashMap::new());
+        cache.request("slow", Duration::from_secs(60));
+        cache.fetched_ids.insert("slow".to_string());
+        cache.fetched_at = Instant::now().checked_sub(Duration::from_secs(6));
+
+        // 6秒前の取得結果は、60秒間隔には新しいが10秒間隔には古い
+        assert!(cache.request("slow", Duration::from_secs(60)).is_none());
+        assert!(cache.request("fast", Duration::from_secs(10)).is_some());
+    }
+
+    #[test]
+    fn test_stream_batch_cache_prunes_stopped_channels() {
+        let mut cache = StreamBatchCache::default();
+        let interval = Duration::from_secs(30);
+        cache.watched.insert(
+            "stopped".to_string(),
+            (
+                interval,
+                Instant::now().checked_sub(Duration::from_secs(61)).unwrap(),
+            ),
+        );
+        cache.watched.insert(
+            "waiting".to_string(),
+            (
+                interval,
+                Instant::now().checked_sub(Duration::from_secs(59)).unwrap(),
+            ),
+        );
+
+        let ids = cache.request("active", interval).unwrap();
+        assert_eq!(
+            sorted(ids),
+            vec!["active".to_string(), "waiting".to_string()]
+        );
+        assert!(!cache.watched.contains_key("stopped"));
+    }
 }
@@ -1,36 +1,51 @@
-use crate::api::youtube_api::YouTubeApiClient;
-use crate::api::youtube_live_chat::YouTubeLiveChatCollector;
+use crate::api::youtube_api::{YouTubeApiClient, YouTubeQuotaTracker};
+use crate::api::youtube_live_chat::{YouTubeHub, YouTubeLiveChatCollector};
 use crate::collectors::collector_trait::Collector;
-use crate::database::models::{Channel, StreamData};
+use crate::database::models::{Channel, ScheduledStreamData, StreamData};
 use crate::database::DatabaseManager;
 use async_trait::async_trait;
-use chrono::Local;
+use chrono::Utc;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
 
-#[allow(dead_code)]
 pub struct YouTubeCollector {
     api_client: Arc<Mutex<YouTubeApiClient>>,
-    chat_collectors: Arc<Mutex<HashMap<String, YouTubeLiveChatCollector>>>,
+    /// channels.id -> ライブチャット収集セッション
+    chat_sessions: Mutex<HashMap<i64, YouTubeLiveChatCollector>>,
     db_manager: Arc<DatabaseManager>,
+    /// APIクライアントとライブチャット収集で共有するクォータ使用量
+    quota: Arc<Mutex<YouTubeQuotaTracker>>,
 }
 
-#[allow(dead_code)]
 impl YouTubeCollector {
     pub async fn new(
         client_id: String,
         client_secret: String,
         redirect_uri: String,
         db_manager: Arc<DatabaseManager>,
+        daily_quota_limit: u32,
+        adaptive_quota: bool,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         let api_client = YouTubeApiClient::new(client_id, client_secret, redirect_uri).await?;
+        let quota = api_client.get_quota_tracker();
+        quota
+            .lock()
+            .await
+            .configure(daily_quota_limit, adaptive_quota);
         Ok(Self {
             api_client: Arc::new(Mutex::new(api_client)),
-            chat_collectors: Arc::new(Mutex::new(HashMap::new())),
+            chat_sessions: Mutex::new(HashMap::new()),
             db_manager,
+            quota,
         })
     }
+
+    /// クォータ使用量トラッカーを取得
+    pub fn get_quota_tracker(&self) -> Arc<Mutex<YouTubeQuotaTracker>> {
+        Arc::clone(&self.quota)
+    }
 }
 
 #[async_trait]
@@ -43,6 +58,7 @@ impl Collector for YouTubeCollector {
 
         // チャンネルIDからライブストリームを取得
         let stream_opt = client.get_live_stream(&channel.channel_id).await?;
+        let channel_db_id = channel.id.unwrap_or_default();
 
         if let Some(video) = stream_opt {
             // 視聴者数を取得（statisticsから）
@@ -58,10 +74,28 @@ impl Collector for YouTubeCollector {
                 .as_ref()
                 .and_then(|details| details.actual_start_time.as_ref())
                 .map(|dt| dt.to_rfc3339())
-                .unwrap_or_else(|| Local::now().to_rfc3339());
+                .unwrap_or_else(|| Utc::now().to_rfc3339());
+
+            // ストリームIDは動画IDを使用（streams.id への解決は保存時に UNIQUE(channel_id, stream_id) で行う）
+            // 動画IDが無いと全配信が同じ空IDに集約されてしまうため、エラーとして扱う
+            let stream_id = match video.id.as_deref() {
+                Some(id) if !id.is_empty() => id.to_string(),
+                _ => {
+                    return Err(format!(
+                        "YouTube live video without id for channel {}",
+                        channel.channel_id
+                    )
+                    .into())
+                }
+            };
 
-            // ストリームIDは動画IDを使用
-            let stream_id = video.id.as_ref().unwrap_or(&String::new()).clone();
+            // 手動登録チャンネルはライブチャットも収集する（チャット収集が無効なチャンネルは除く）
+            if channel.collect_chat && !channel.is_auto_discovered {
+                self.ensure_chat_session(channel, client.get_hub(), &stream_id)
+                    .await;
+            } else {
+                self.stop_chat_session(channel_db_id).await;
+            }
 
             // サムネイルURLを取得（高解像度優先）
             let thumbnail_url = video.snippet.as_ref().and_then(|snippet| {
@@ -75,6 +109,22 @@ impl Collector for YouTubeCollector {
                 })
             });
 
+            // 登録者数をフォロワー数として記録（非公開設定のチャンネルや取得失敗時は None）
+            let follower_count = match client.get_channel_by_id(&channel.channel_id).await {
+                Ok(channel_info) => channel_info
+                    .and_then(|c| c.statistics)
+                    .filter(|stats| stats.hidden_subscriber_count != Some(true))
+                    .and_then(|stats| stats.subscriber_count)
+                    .map(|count| count.min(i32::MAX as u64) as i32),
+                Err(e) => {
+                    eprintln!(
+                        "[YouTubeCollector] Failed to get subscriber count for {}: {}",
+                        channel.channel_id, e
+                    );
+                    None
+                }
+            };
+
             Ok(Some(StreamData {
                 stream_id,
                 title: video.snippet.as_ref().and_then(|s| s.title.clone()),
@@ -83,9 +133,14 @@ impl Collector for YouTubeCollector {
                 thumbnail_url,
                 started_at,
                 viewer_count,
-                follower_count: None, // YouTube APIではフォロワー数は取得していない
+                follower_count,
+                comment_count: None,
+                display_name: video.snippet.as_ref().and_then(|s| s.channel_title.clone()),
+                // チャンネルアイコンの取得には追加のクォータが必要なため取得しない
+                profile_image_url: None,
             }))
         } else {
+            self.stop_chat_session(channel_db_id).await;
             Ok(None)
         }
     }
@@ -97,37 +152,84 @@ impl Collector for YouTubeCollector {
         // 認証はOAuthモジュールで行われているため、ここでは確認のみ
         Ok(())
     }
+
+    async fn poll_interval_multiplier(&self) -> u32 {
+        self.quota.lock().await.poll_interval_multiplier()
+    }
+
+    async fn fetch_schedule(
+        &self,
+        channel: &Channel,
+    ) -> Result<Vec<ScheduledStreamData>, Box<dyn std::error::Error + Send + Sync>> {
+        let videos = self
+            .api_client
+            .lock()
+            .await
+            .get_upcoming_streams(&channel.channel_id)
+            .await?;
+
+        Ok(videos
+            .into_iter()
+            .filter_map(|video| {
+                let details = video.live_streaming_details?;
+                Some(ScheduledStreamData {
+                    scheduled_start: details.scheduled_start_time?.to_rfc3339(),
+                    scheduled_end: details.scheduled_end_time.map(|dt| dt.to_rfc3339()),
+                    title: video.snippet.and_then(|s| s.title),
+                    category: None,
+                })
+            })
+            .collect())
+    }
 }
 
 impl YouTubeCollector {
-    /// チャット収集を開始（ストリーム開始時に呼び出し）
-    /// TODO: チャット機能実装中
-    #[allow(dead_code)]
-    pub async fn start_chat_collection(
-        &self,
-        _stream_id: i64,
-        _video_id: &str,
-        _poll_interval_secs: u64,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        // チャット機能は現在無効化中
-        // let mut client = self.api_client.lock().await;
-        // let hub = client.get_hub().clone();
-        // let db_conn = Arc::clone(&self.db_conn);
-        // let chat_collector = YouTubeLiveChatCollector::new(hub, db_conn, stream_id);
-        // chat_collector.start_with_video_id(video_id, poll_interval_secs).await?;
-        // let mut chat_collectors = self.chat_collectors.lock().await;
-        // chat_collectors.insert(video_id.to_string(), chat_collector);
-        Ok(())
+    /// 配信中の動画のライブチャット収集セッションを維持する（動画が変わった・終了した場合は張り直す）
+    async fn ensure_chat_session(&self, channel: &Channel, hub: Arc<YouTubeHub>, video_id: &str) {
+        let channel_db_id = channel.id.unwrap_or_default();
+        let mut sessions = self.chat_sessions.lock().await;
+        let needs_start = sessions
+            .get(&channel_db_id)
+            .map(|s| s.video_id != video_id || s.is_finished())
+            .unwrap_or(true);
+        if !needs_start {
+            return;
+        }
+
+        if let Some(old) = sessions.remove(&channel_db_id) {
+            old.stop();
+        }
+        let min_poll_interval = Duration::from_secs(channel.poll_interval.max(1) as u64);
+        match YouTubeLiveChatCollector::start(
+            hub,
+            Arc::clone(&self.quota),
+            Arc::clone(&self.db_manager),
+            channel_db_id,
+            video_id,
+            min_poll_interval,
+        )
+        .await
+        {
+            Ok(session) => {
+                sessions.insert(channel_db_id, session);
+            }
+            Err(e) => {
+                eprintln!(
+                    "[YouTubeCollector] Failed to start live chat collection for {}: {}",
+                    video_id, e
+                );
+            }
+        }
     }
 
-    /// チャット収集を停止（ストリーム終了時に呼び出し）
-    /// TODO: チャット機能実装中
-    #[allow(dead_code)]
-    pub async fn stop_chat_collection(&self, _video_id: &str) {
-        // チャット機能は現在無効化中
-        // let mut chat_collectors = self.chat_collectors.lock().await;
-        // if let Some(collector) = chat_collectors.remove(video_id) {
-        //     collector.stop_collection().await;
-        // }
+    /// チャンネルのライブチャット収集を停止する（ポーリング停止時）
+    pub async fn stop_chat_collection(&self, channel_db_id: i64) {
+        self.stop_chat_session(channel_db_id).await;
+    }
+
+    async fn stop_chat_session(&self, channel_db_id: i64) {
+        if let Some(session) = self.chat_sessions.lock().await.remove(&channel_db_id) {
+            session.stop();
+        }
     }
 }
@@ -1,6 +1,45 @@
-use crate::database::{analytics, chat_analytics, DatabaseManager};
+use crate::config::settings::SettingsManager;
+use crate::database::{
+    analytics, chat_analytics,
+    repositories::{
+        BotFilter, ChatBucket, ChatMessageRepository, UniqueChattersBucket, UserStreamActivity,
+    },
+    DatabaseManager,
+};
 use crate::error::ResultExt;
-use tauri::State;
+use serde::Deserialize;
+use tauri::{AppHandle, State};
+
+/// チャット分析コマンド共通の絞り込み条件
+#[derive(Debug, Deserialize)]
+pub struct ChatAnalyticsQuery {
+    pub channel_id: Option<i64>,
+    pub stream_id: Option<i64>,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    /// true の場合、設定の bot 除外条件に一致するメッセージを集計から除く
+    pub exclude_bots: Option<bool>,
+}
+
+/// `exclude_bots` が true の場合、設定の bot 除外条件を読み込む
+fn load_bot_filter(
+    app_handle: &AppHandle,
+    exclude_bots: Option<bool>,
+) -> Result<Option<BotFilter>, String> {
+    if !exclude_bots.unwrap_or(false) {
+        return Ok(None);
+    }
+
+    let chat_filter = SettingsManager::load_settings(app_handle)
+        .config_context("load settings")
+        .map_err(|e| e.to_string())?
+        .chat_filter;
+    Ok(Some(BotFilter {
+        user_names: chat_filter.bot_user_names,
+        message_patterns: chat_filter.message_patterns,
+        exclude_commands: chat_filter.exclude_commands,
+    }))
+}
 
 #[tauri::command]
 pub async fn get_broadcaster_analytics(
@@ -104,26 +143,42 @@ pub async fn get_channel_daily_stats(
         .await
 }
 
+/// 配信終盤（直前5/10/15分）の平均視聴者数を配信全体と比較
+#[tauri::command]
+pub async fn get_stream_outro_metrics(
+    db_manager: State<'_, DatabaseManager>,
+    stream_id: i64,
+) -> Result<analytics::StreamOutroMetrics, String> {
+    db_manager
+        .with_connection(|conn| {
+            analytics::get_stream_outro_metrics(conn, stream_id)
+                .db_context("get stream outro metrics")
+                .map_err(|e| e.to_string())
+        })
+        .await
+}
+
 // Chat Analytics Commands
 
 #[tauri::command]
 pub async fn get_chat_engagement_timeline(
+    app_handle: AppHandle,
     db_manager: State<'_, DatabaseManager>,
-    channel_id: Option<i64>,
-    stream_id: Option<i64>,
-    start_time: Option<String>,
-    end_time: Option<String>,
+    query: ChatAnalyticsQuery,
     interval_minutes: Option<i32>,
 ) -> Result<Vec<chat_analytics::ChatEngagementStats>, String> {
+    let bot_filter = load_bot_filter(&app_handle, query.exclude_bots)?;
+
     db_manager
         .with_connection(|conn| {
             chat_analytics::get_chat_engagement_timeline(
                 conn,
-                channel_id,
-                stream_id,
-                start_time.as_deref(),
-                end_time.as_deref(),
+                query.channel_id,
+                query.stream_id,
+                query.start_time.as_deref(),
+                query.end_time.as_deref(),
                 interval_minutes.unwrap_or(5),
+                bot_filter.as_ref(),
             )
             .db_context("get chat engagement timeline")
             .map_err(|e| e.to_string())
@@ -131,24 +186,92 @@ pub async fn get_chat_engagement_timeline(
         .await
 }
 
+/// 配信の時間帯別ユニークチャッター推移を取得
+#[tauri::command]
+pub async fn get_unique_chatters_timeline(
+    db_manager: State<'_, DatabaseManager>,
+    stream_id: i64,
+    interval_minutes: Option<i32>,
+) -> Result<Vec<UniqueChattersBucket>, String> {
+    db_manager
+        .with_connection(|conn| {
+            ChatMessageRepository::get_unique_chatters_timeline(
+                conn,
+                stream_id,
+                interval_minutes.unwrap_or(5),
+            )
+            .db_context("get unique chatters timeline")
+            .map_err(|e| e.to_string())
+        })
+        .await
+}
+
+/// 配信のチャットを時間バケットごとに集計（メッセージ数・ユニークユーザー数・絵文字数・キーワード出現数）
+#[tauri::command]
+pub async fn get_chat_aggregates(
+    app_handle: AppHandle,
+    db_manager: State<'_, DatabaseManager>,
+    stream_id: i64,
+    bucket_seconds: Option<i64>,
+    keywords: Option<Vec<String>>,
+    exclude_bots: Option<bool>,
+) -> Result<Vec<ChatBucket>, String> {
+    let bot_filter = load_bot_filter(&app_handle, exclude_bots)?;
+    let keywords: Vec<String> = keywords
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|k| !k.trim().is_empty())
+        .collect();
+
+    db_manager
+        .with_connection(|conn| {
+            ChatMessageRepository::get_chat_aggregates(
+                conn,
+                stream_id,
+                bucket_seconds.unwrap_or(60),
+                &keywords,
+                bot_filter.as_ref(),
+            )
+            .db_context("get chat aggregates")
+            .map_err(|e| e.to_string())
+        })
+        .await
+}
+
+/// 配信のチャット盛り上がり度（1分ごとのz-score）を取得
+#[tauri::command]
+pub async fn get_chat_momentum(
+    db_manager: State<'_, DatabaseManager>,
+    stream_id: i64,
+) -> Result<Vec<chat_analytics::ChatMomentumPoint>, String> {
+    db_manager
+        .with_connection(|conn| {
+            chat_analytics::get_chat_momentum(conn, stream_id)
+                .db_context("get chat momentum")
+                .map_err(|e| e.to_string())
+        })
+        .await
+}
+
 #[tauri::command]
 pub async fn detect_chat_spikes(
+    app_handle: AppHandle,
     db_manager: State<'_, DatabaseManager>,
-    channel_id: Option<i64>,
-    stream_id: Option<i64>,
-    start_time: Option<String>,
-    end_time: Option<String>,
+    query: ChatAnalyticsQuery,
     min_spike_ratio: Option<f64>,
 ) -> Result<Vec<chat_analytics::ChatSpike>, String> {
+    let bot_filter = load_bot_filter(&app_handle, query.exclude_bots)?;
+
     db_manager
         .with_connection(|conn| {
             chat_analytics::detect_chat_spikes(
                 conn,
-                channel_id,
-                stream_id,
-                start_time.as_deref(),
-                end_time.as_deref(),
+                query.channel_id,
+                query.stream_id,
+                query.start_time.as_deref(),
+                query.end_time.as_deref(),
                 min_spike_ratio.unwrap_or(2.0),
+                bot_filter.as_ref(),
             )
             .db_context("detect chat spikes")
             .map_err(|e| e.to_string())
@@ -158,20 +281,21 @@ pub async fn detect_chat_spikes(
 
 #[tauri::command]
 pub async fn get_user_segment_stats(
+    app_handle: AppHandle,
     db_manager: State<'_, DatabaseManager>,
-    channel_id: Option<i64>,
-    stream_id: Option<i64>,
-    start_time: Option<String>,
-    end_time: Option<String>,
+    query: ChatAnalyticsQuery,
 ) -> Result<Vec<chat_analytics::UserSegmentStats>, String> {
+    let bot_filter = load_bot_filter(&app_handle, query.exclude_bots)?;
+
     db_manager
         .with_connection(|conn| {
             chat_analytics::get_user_segment_stats(
                 conn,
-                channel_id,
-                stream_id,
-                start_time.as_deref(),
-                end_time.as_deref(),
+                query.channel_id,
+                query.stream_id,
+                query.start_time.as_deref(),
+                query.end_time.as_deref(),
+                bot_filter.as_ref(),
             )
             .db_context("get user segment stats")
             .map_err(|e| e.to_string())
@@ -181,22 +305,23 @@ pub async fn get_user_segment_stats(
 
 #[tauri::command]
 pub async fn get_top_chatters(
+    app_handle: AppHandle,
     db_manager: State<'_, DatabaseManager>,
-    channel_id: Option<i64>,
-    stream_id: Option<i64>,
-    start_time: Option<String>,
-    end_time: Option<String>,
+    query: ChatAnalyticsQuery,
     limit: Option<i32>,
 ) -> Result<Vec<chat_analytics::TopChatter>, String> {
+    let bot_filter = load_bot_filter(&app_handle, query.exclude_bots)?;
+
     db_manager
         .with_connection(|conn| {
             chat_analytics::get_top_chatters(
                 conn,
-                channel_id,
-                stream_id,
-                start_time.as_deref(),
-                end_time.as_deref(),
+                query.channel_id,
+                query.stream_id,
+                query.start_time.as_deref(),
+                query.end_time.as_deref(),
                 limit.unwrap_or(50),
+                bot_filter.as_ref(),
             )
             .db_context("get top chatters")
             .map_err(|e| e.to_string())
@@ -204,14 +329,34 @@ pub async fn get_top_chatters(
         .await
 }
 
+/// ユーザー（user_id）の配信をまたいだチャット活動を取得
+#[tauri::command]
+pub async fn get_user_activity(
+    db_manager: State<'_, DatabaseManager>,
+    user_id: String,
+    limit: Option<i32>,
+) -> Result<Vec<UserStreamActivity>, String> {
+    db_manager
+        .with_connection(|conn| {
+            ChatMessageRepository::get_user_activity(conn, &user_id, limit.unwrap_or(50))
+                .db_context("get user activity")
+                .map_err(|e| e.to_string())
+        })
+        .await
+}
+
 #[tauri::command]
 pub async fn get_time_pattern_stats(
+    app_handle: AppHandle,
     db_manager: State<'_, DatabaseManager>,
     channel_id: Option<i64>,
     start_time: Option<String>,
     end_time: Option<String>,
     group_by_day: Option<bool>,
+    exclude_bots: Option<bool>,
 ) -> Result<Vec<chat_analytics::TimePatternStats>, String> {
+    let bot_filter = load_bot_filter(&app_handle, exclude_bots)?;
+
     db_manager
         .with_connection(|conn| {
             chat_analytics::get_time_pattern_stats(
@@ -220,6 +365,7 @@ pub async fn get_time_pattern_stats(
                 start_time.as_deref(),
                 end_time.as_deref(),
                 group_by_day.unwrap_or(false),
+                bot_filter.as_ref(),
             )
             .db_context("get time pattern stats")
             .map_err(|e| e.to_string())
@@ -229,11 +375,15 @@ pub async fn get_time_pattern_stats(
 
 #[tauri::command]
 pub async fn get_chatter_behavior_stats(
+    app_handle: AppHandle,
     db_manager: State<'_, DatabaseManager>,
     channel_id: Option<i64>,
     start_time: Option<String>,
     end_time: Option<String>,
+    exclude_bots: Option<bool>,
 ) -> Result<chat_analytics::ChatterBehaviorStats, String> {
+    let bot_filter = load_bot_filter(&app_handle, exclude_bots)?;
+
     db_manager
         .with_connection(|conn| {
             chat_analytics::get_chatter_behavior_stats(
@@ -241,6 +391,7 @@ pub async fn get_chatter_behavior_stats(
                 channel_id,
                 start_time.as_deref(),
                 end_time.as_deref(),
+                bot_filter.as_ref(),
             )
             .db_context("get chatter behavior stats")
             .map_err(|e| e.to_string())
@@ -1,13 +1,22 @@
+use crate::collectors::channel_icon_cache::ChannelIconCache;
 use crate::collectors::poller::ChannelPoller;
+use crate::constants::database as db_constants;
 use crate::database::{
     models::{Channel, ChannelWithStats},
-    repositories::{channel_repository::CreateChannelParams, ChannelRepository},
+    repositories::{
+        base,
+        channel_repository::{
+            ChannelExportEntry, ChannelImportOutcome, ChannelLiveState, CreateChannelParams,
+        },
+        ChannelRepository, ChannelTagRepository,
+    },
     DatabaseManager,
 };
-use crate::error::{OptionExt, ResultExt};
+use crate::error::{AppError, OptionExt, ResultExt};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
-use tauri::{AppHandle, Manager, State};
+use tauri::{AppHandle, Emitter, Manager, State};
 use tokio::sync::Mutex;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -21,6 +30,21 @@ pub struct AddChannelRequest {
     pub twitch_user_id: Option<i64>, // Twitchの不変なuser ID
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateChannelRequest {
+    pub id: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll_interval: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collect_chat: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collect_stats: Option<bool>,
+}
+
 #[tauri::command]
 pub async fn add_channel(
     app_handle: AppHandle,
@@ -71,12 +95,19 @@ pub async fn add_channel(
     Ok(channel)
 }
 
+/// チャンネルを削除
+///
+/// `delete_data` が false の場合は論理削除のみ行い、配信・統計・チャットの過去データを残す。
+/// 省略時は従来どおり関連データもすべて削除する。
 #[tauri::command]
 pub async fn remove_channel(
     app_handle: AppHandle,
     db_manager: State<'_, DatabaseManager>,
     id: i64,
+    delete_data: Option<bool>,
 ) -> Result<(), String> {
+    let delete_data = delete_data.unwrap_or(true);
+
     // 削除前にポーリングを停止
     if let Some(poller) = app_handle.try_state::<Arc<Mutex<ChannelPoller>>>() {
         let mut poller = poller.lock().await;
@@ -85,15 +116,29 @@ pub async fn remove_channel(
 
     db_manager
         .with_connection(|conn| {
-            ChannelRepository::delete_channel_and_related(conn, id)
-                .db_context("delete channel and related data")
-                .map_err(|e| e.to_string())
+            if delete_data {
+                ChannelRepository::delete_channel_and_related(conn, id)
+                    .db_context("delete channel and related data")
+                    .map_err(|e| e.to_string())
+            } else {
+                ChannelRepository::soft_delete(conn, id)
+                    .db_context("soft delete channel")
+                    .map_err(|e| e.to_string())
+            }
         })
         .await?;
-    eprintln!(
-        "[remove_channel] Successfully deleted channel {} and related data",
-        id
-    );
+    if delete_data {
+        ChannelIconCache::remove(&app_handle, id);
+        eprintln!(
+            "[remove_channel] Successfully deleted channel {} and related data",
+            id
+        );
+    } else {
+        eprintln!(
+            "[remove_channel] Soft deleted channel {} (historical data retained)",
+            id
+        );
+    }
     Ok(())
 }
 
@@ -101,11 +146,16 @@ pub async fn remove_channel(
 pub async fn update_channel(
     app_handle: AppHandle,
     db_manager: State<'_, DatabaseManager>,
-    id: i64,
-    channel_name: Option<String>,
-    poll_interval: Option<i32>,
-    enabled: Option<bool>,
+    request: UpdateChannelRequest,
 ) -> Result<Channel, String> {
+    let UpdateChannelRequest {
+        id,
+        channel_name,
+        poll_interval,
+        enabled,
+        collect_chat,
+        collect_stats,
+    } = request;
     let (old_channel, updated_channel) = db_manager
         .with_connection(|conn| {
             // 更新前の状態を取得（有効状態の変更を検知するため）
@@ -115,10 +165,23 @@ pub async fn update_channel(
                 .ok_or_not_found("Channel not found")
                 .map_err(|e| e.to_string())?;
 
-            if channel_name.is_some() || poll_interval.is_some() || enabled.is_some() {
-                ChannelRepository::update(conn, id, channel_name, poll_interval, enabled)
-                    .db_context("update channel")
-                    .map_err(|e| e.to_string())?;
+            if channel_name.is_some()
+                || poll_interval.is_some()
+                || enabled.is_some()
+                || collect_chat.is_some()
+                || collect_stats.is_some()
+            {
+                ChannelRepository::update(
+                    conn,
+                    id,
+                    channel_name,
+                    poll_interval,
+                    enabled,
+                    collect_chat,
+                    collect_stats,
+                )
+                .db_context("update channel")
+                .map_err(|e| e.to_string())?;
             }
 
             let updated_channel = ChannelRepository::get_by_id(conn, id)
@@ -149,6 +212,32 @@ pub async fn update_channel(
         }
     }
 
+    // 有効なままチャット収集の設定だけが変わった場合、IRC接続を開始/停止
+    // （collect_stats はポーリングごとにチャンネル情報を再取得するため次回から反映される）
+    let chat_toggled = old_channel.collect_chat != updated_channel.collect_chat;
+    if chat_toggled
+        && old_channel.enabled
+        && updated_channel.enabled
+        && updated_channel.platform == db_constants::PLATFORM_TWITCH
+        && !updated_channel.is_auto_discovered
+    {
+        if let Some(poller) = app_handle.try_state::<Arc<Mutex<ChannelPoller>>>() {
+            let poller = poller.lock().await;
+            if let Some(twitch_collector) = poller.get_twitch_collector() {
+                let result = if updated_channel.collect_chat {
+                    twitch_collector
+                        .start_chat_collection(id, &updated_channel.channel_id)
+                        .await
+                } else {
+                    twitch_collector.stop_chat_collection(id).await
+                };
+                if let Err(e) = result {
+                    eprintln!("Failed to update chat collection for channel {}: {}", id, e);
+                }
+            }
+        }
+    }
+
     Ok(updated_channel)
 }
 
@@ -158,20 +247,75 @@ pub async fn list_channels(
     db_manager: State<'_, DatabaseManager>,
 ) -> Result<Vec<ChannelWithStats>, String> {
     // DB接続とクエリをスコープ内で完了させる
-    let channels: Vec<Channel> = db_manager
+    let (channels, mut tags_by_channel) = db_manager
         .with_connection(|conn| {
-            ChannelRepository::list_all(conn)
+            let channels = ChannelRepository::list_all(conn)
                 .db_context("list all channels")
-                .map_err(|e| e.to_string())
+                .map_err(|e| e.to_string())?;
+            let tags = ChannelTagRepository::tags_by_channel(conn)
+                .db_context("list channel tags")
+                .map_err(|e| e.to_string())?;
+            Ok::<_, String>((channels, tags))
         })
         .await?;
 
     // Twitch API情報を取得して統合
-    let channels_with_stats = enrich_channels_with_twitch_info(channels, &app_handle).await;
+    let mut channels_with_stats = enrich_channels_with_twitch_info(channels, &app_handle).await;
+    for item in channels_with_stats.iter_mut() {
+        if let Some(tags) = item.channel.id.and_then(|id| tags_by_channel.remove(&id)) {
+            item.tags = tags;
+        }
+    }
 
     Ok(channels_with_stats)
 }
 
+/// DB上のライブ状態つきチャンネル一覧取得
+///
+/// - Twitch API にはアクセスせず、未終了の配信と最新の stream_stats から is_live / 視聴者数 / タイトルを埋める
+/// - 全プラットフォームの現況をダッシュボードで一覧する用途
+#[tauri::command]
+pub async fn list_channels_with_stats(
+    db_manager: State<'_, DatabaseManager>,
+) -> Result<Vec<ChannelWithStats>, String> {
+    db_manager
+        .with_connection(|conn| {
+            let channels = ChannelRepository::list_all(conn)
+                .db_context("list all channels")
+                .map_err(|e| e.to_string())?;
+            let mut tags_by_channel = ChannelTagRepository::tags_by_channel(conn)
+                .db_context("list channel tags")
+                .map_err(|e| e.to_string())?;
+            let live_states: HashMap<i64, ChannelLiveState> =
+                ChannelRepository::list_live_states(conn)
+                    .db_context("list channel live states")
+                    .map_err(|e| e.to_string())?
+                    .into_iter()
+                    .map(|state| (state.channel_id, state))
+                    .collect();
+
+            Ok(channels
+                .into_iter()
+                .map(|channel| {
+                    let live_state = channel.id.and_then(|id| live_states.get(&id));
+                    ChannelWithStats {
+                        is_live: live_state.is_some(),
+                        current_viewers: live_state.map(|s| s.current_viewers).unwrap_or(0),
+                        current_title: live_state
+                            .map(|s| s.current_title.clone())
+                            .unwrap_or_default(),
+                        tags: channel
+                            .id
+                            .and_then(|id| tags_by_channel.remove(&id))
+                            .unwrap_or_default(),
+                        channel,
+                    }
+                })
+                .collect())
+        })
+        .await
+}
+
 /// 軽量版チャンネル一覧取得
 ///
 /// - DBの `channels` テーブルのみを参照し、Twitch API にはアクセスしない
@@ -191,6 +335,171 @@ pub async fn list_channels_basic(
     Ok(channels)
 }
 
+/// タグ名の前後の空白を除去し、空・長すぎるタグを拒否する
+fn normalize_tag(tag: &str) -> Result<String, String> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Err("タグを入力してください".to_string());
+    }
+    if tag.chars().count() > db_constants::MAX_TAG_LENGTH {
+        return Err(format!(
+            "タグは{}文字以内で入力してください",
+            db_constants::MAX_TAG_LENGTH
+        ));
+    }
+    Ok(tag.to_string())
+}
+
+/// 視聴者数アラートの閾値を設定（None で解除）
+///
+/// ポーリング時に viewer_count が閾値を超えると viewer-threshold-exceeded を発行し、OS通知を表示する。
+/// 次回のポーリングから反映される。
+#[tauri::command]
+pub async fn set_channel_alert_threshold(
+    app_handle: AppHandle,
+    db_manager: State<'_, DatabaseManager>,
+    channel_id: i64,
+    threshold: Option<i32>,
+) -> Result<Channel, String> {
+    if matches!(threshold, Some(t) if t < 0) {
+        return Err("Alert threshold must be zero or greater".to_string());
+    }
+
+    let channel = db_manager
+        .with_connection(|conn| {
+            ChannelRepository::set_alert_viewer_threshold(conn, channel_id, threshold)
+                .db_context("set alert viewer threshold")
+                .map_err(|e| e.to_string())?;
+            ChannelRepository::get_by_id(conn, channel_id)
+                .db_context("get channel")
+                .map_err(|e| e.to_string())?
+                .ok_or_not_found("Channel not found")
+                .map_err(|e| e.to_string())
+        })
+        .await?;
+
+    let _ = app_handle.emit("channels-updated", ());
+    Ok(channel)
+}
+
+/// キャッシュ済みのチャンネルアイコン画像のローカルパスを取得
+///
+/// 未キャッシュの場合は `profile_image_url` からダウンロードしてキャッシュする。
+/// プロフィール画像URLが未登録、またはダウンロードできない（オフライン等）場合は None を返す。
+/// フロントエンドでは `convertFileSrc` で表示し、None の場合は `profile_image_url` を直接使う。
+#[tauri::command]
+pub async fn get_channel_icon_path(
+    app_handle: AppHandle,
+    db_manager: State<'_, DatabaseManager>,
+    channel_id: i64,
+) -> Result<Option<String>, String> {
+    let channel = db_manager
+        .with_connection(|conn| {
+            ChannelRepository::get_by_id(conn, channel_id)
+                .db_context("get channel")
+                .map_err(|e| e.to_string())?
+                .ok_or_not_found("Channel not found")
+                .map_err(|e| e.to_string())
+        })
+        .await?;
+    if channel.profile_image_url.is_empty() {
+        return Ok(None);
+    }
+
+    match ChannelIconCache::refresh(&app_handle, channel_id, &channel.profile_image_url).await {
+        Ok(path) => Ok(Some(path.to_string_lossy().to_string())),
+        Err(e) => {
+            eprintln!(
+                "[get_channel_icon_path] Failed to cache icon for channel {}: {}",
+                channel_id, e
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// チャンネルにタグを付与（付与済みの場合は何もしない）
+/// 戻り値: 付与後のチャンネルのタグ一覧
+#[tauri::command]
+pub async fn add_channel_tag(
+    app_handle: AppHandle,
+    db_manager: State<'_, DatabaseManager>,
+    channel_id: i64,
+    tag: String,
+) -> Result<Vec<String>, String> {
+    let tag = normalize_tag(&tag)?;
+    let tags = db_manager
+        .with_connection(|conn| {
+            ChannelRepository::get_by_id(conn, channel_id)
+                .db_context("get channel")
+                .map_err(|e| e.to_string())?
+                .ok_or_not_found("Channel not found")
+                .map_err(|e| e.to_string())?;
+            ChannelTagRepository::add_tag(conn, channel_id, &tag)
+                .db_context("add channel tag")
+                .map_err(|e| e.to_string())?;
+            ChannelTagRepository::tags_by_channel(conn)
+                .db_context("list channel tags")
+                .map_err(|e| e.to_string())
+        })
+        .await?;
+
+    let _ = app_handle.emit("channels-updated", ());
+    Ok(tags_for(tags, channel_id))
+}
+
+/// チャンネルからタグを外す
+/// 戻り値: 削除後のチャンネルのタグ一覧
+#[tauri::command]
+pub async fn remove_channel_tag(
+    app_handle: AppHandle,
+    db_manager: State<'_, DatabaseManager>,
+    channel_id: i64,
+    tag: String,
+) -> Result<Vec<String>, String> {
+    let tag = tag.trim().to_string();
+    let tags = db_manager
+        .with_connection(|conn| {
+            ChannelTagRepository::remove_tag(conn, channel_id, &tag)
+                .db_context("remove channel tag")
+                .map_err(|e| e.to_string())?;
+            ChannelTagRepository::tags_by_channel(conn)
+                .db_context("list channel tags")
+                .map_err(|e| e.to_string())
+        })
+        .await?;
+
+    let _ = app_handle.emit("channels-updated", ());
+    Ok(tags_for(tags, channel_id))
+}
+
+fn tags_for(mut tags_by_channel: HashMap<i64, Vec<String>>, channel_id: i64) -> Vec<String> {
+    tags_by_channel.remove(&channel_id).unwrap_or_default()
+}
+
+/// 指定タグが付いたチャンネル一覧を取得（作成日時降順）
+#[tauri::command]
+pub async fn list_channels_by_tag(
+    db_manager: State<'_, DatabaseManager>,
+    tag: String,
+) -> Result<Vec<Channel>, String> {
+    let tag = tag.trim().to_string();
+    db_manager
+        .with_connection(|conn| {
+            let channel_ids = ChannelTagRepository::list_channel_ids_by_tag(conn, &tag)
+                .db_context("list channels by tag")
+                .map_err(|e| e.to_string())?;
+            let channels = ChannelRepository::list_all(conn)
+                .db_context("list all channels")
+                .map_err(|e| e.to_string())?;
+            Ok(channels
+                .into_iter()
+                .filter(|c| c.id.is_some_and(|id| channel_ids.contains(&id)))
+                .collect())
+        })
+        .await
+}
+
 /// チャンネル情報にTwitch API情報を統合
 async fn enrich_channels_with_twitch_info(
     channels: Vec<Channel>,
@@ -379,6 +688,7 @@ async fn enrich_channels_with_twitch_info(
                 is_live,
                 current_viewers,
                 current_title,
+                tags: Vec::new(),
             }
         })
         .collect()
@@ -433,3 +743,251 @@ pub async fn toggle_channel(
 
     Ok(updated_channel)
 }
+
+/// 複数チャンネルの有効/無効を一括で切り替え、更新後のチャンネル一覧を返す
+///
+/// 更新は1トランザクションで行い、存在しないチャンネルが含まれる場合は何も変更しない。
+#[tauri::command]
+pub async fn set_channels_enabled(
+    app_handle: AppHandle,
+    db_manager: State<'_, DatabaseManager>,
+    channel_ids: Vec<i64>,
+    enabled: bool,
+) -> Result<Vec<Channel>, String> {
+    apply_channels_enabled(&app_handle, &db_manager, channel_ids, enabled).await
+}
+
+/// 指定タグが付いたチャンネルの有効/無効を一括で切り替え、更新後のチャンネル一覧を返す
+#[tauri::command]
+pub async fn set_tag_channels_enabled(
+    app_handle: AppHandle,
+    db_manager: State<'_, DatabaseManager>,
+    tag: String,
+    enabled: bool,
+) -> Result<Vec<Channel>, String> {
+    let tag = tag.trim().to_string();
+    let channel_ids = db_manager
+        .with_connection(|conn| {
+            ChannelTagRepository::list_channel_ids_by_tag(conn, &tag)
+                .db_context("list channels by tag")
+                .map_err(|e| e.to_string())
+        })
+        .await?;
+    apply_channels_enabled(&app_handle, &db_manager, channel_ids, enabled).await
+}
+
+async fn apply_channels_enabled(
+    app_handle: &AppHandle,
+    db_manager: &State<'_, DatabaseManager>,
+    mut channel_ids: Vec<i64>,
+    enabled: bool,
+) -> Result<Vec<Channel>, String> {
+    channel_ids.sort_unstable();
+    channel_ids.dedup();
+
+    // (更新後のチャンネル, 状態が変わったか)
+    let results = db_manager
+        .with_connection(|conn| {
+            base::with_transaction(conn, |conn| {
+                let mut results = Vec::with_capacity(channel_ids.len());
+                for &id in &channel_ids {
+                    let current = ChannelRepository::get_by_id(conn, id)
+                        .db_context("get channel")?
+                        .ok_or_not_found(&format!("Channel not found: {}", id))?;
+                    if current.enabled == enabled {
+                        results.push((current, false));
+                        continue;
+                    }
+
+                    ChannelRepository::update_enabled(conn, id, enabled)
+                        .db_context("update channel")?;
+                    let updated = ChannelRepository::get_by_id(conn, id)
+                        .db_context("get updated channel")?
+                        .ok_or_not_found("Channel not found")?;
+                    results.push((updated, true));
+                }
+                Ok::<_, AppError>(results)
+            })
+            .map_err(|e| e.to_string())
+        })
+        .await?;
+
+    // 状態が変わったチャンネルのみポーリングを開始/停止
+    if let Some(poller) = app_handle.try_state::<Arc<Mutex<ChannelPoller>>>() {
+        let mut poller = poller.lock().await;
+        for (channel, changed) in &results {
+            let Some(id) = channel.id.filter(|_| *changed) else {
+                continue;
+            };
+            if enabled {
+                if let Err(e) =
+                    poller.start_polling(channel.clone(), db_manager, app_handle.clone())
+                {
+                    eprintln!("Failed to start polling for channel {}: {}", id, e);
+                }
+            } else {
+                poller.stop_polling(id).await;
+            }
+        }
+    }
+
+    Ok(results.into_iter().map(|(channel, _)| channel).collect())
+}
+
+/// チャンネルリストのエクスポートファイル形式のバージョン
+const CHANNEL_EXPORT_VERSION: u32 = 1;
+
+/// チャンネルリストのエクスポートファイル
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChannelExportFile {
+    pub version: u32,
+    pub exported_at: String,
+    pub channels: Vec<ChannelExportEntry>,
+}
+
+/// チャンネルインポートの結果
+#[derive(Debug, Default, Serialize)]
+pub struct ChannelImportResult {
+    pub added: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    /// 未対応プラットフォームや空のチャンネルIDのため取り込まなかった件数
+    pub invalid: usize,
+}
+
+/// 監視チャンネルのリストをJSONファイルにエクスポート（エクスポート件数を返す）
+///
+/// 自動発見されたチャンネルは環境ごとに異なるため含めない。
+#[tauri::command]
+pub async fn export_channels(
+    db_manager: State<'_, DatabaseManager>,
+    file_path: String,
+) -> Result<usize, String> {
+    let channels = db_manager
+        .with_connection(|conn| {
+            ChannelRepository::list_for_export(conn)
+                .db_context("list channels for export")
+                .map_err(|e| e.to_string())
+        })
+        .await?;
+
+    let export = ChannelExportFile {
+        version: CHANNEL_EXPORT_VERSION,
+        exported_at: chrono::Local::now().to_rfc3339(),
+        channels,
+    };
+    let json = serde_json::to_string_pretty(&export)
+        .map_err(|e| format!("Failed to serialize channels: {}", e))?;
+    std::fs::write(&file_path, json)
+        .io_context("write channel export file")
+        .map_err(|e| e.to_string())?;
+
+    Ok(export.channels.len())
+}
+
+/// `export_channels` で書き出したJSONファイルからチャンネルを一括登録
+///
+/// 既に登録済みのチャンネルは `overwrite` が false ならスキップ、true なら設定を上書きする。
+/// 追加・有効化されたチャンネルはそのままポーリングを開始する。
+#[tauri::command]
+pub async fn import_channels(
+    app_handle: AppHandle,
+    db_manager: State<'_, DatabaseManager>,
+    path: String,
+    overwrite: bool,
+) -> Result<ChannelImportResult, String> {
+    let content = std::fs::read_to_string(&path)
+        .io_context("read channel import file")
+        .map_err(|e| e.to_string())?;
+    let import: ChannelExportFile = serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid channel import file: {}", e))?;
+    if import.version > CHANNEL_EXPORT_VERSION {
+        return Err(format!(
+            "Unsupported channel export version: {}",
+            import.version
+        ));
+    }
+
+    let supported_platforms = [
+        db_constants::PLATFORM_TWITCH,
+        db_constants::PLATFORM_YOUTUBE,
+        db_constants::PLATFORM_KICK,
+        db_constants::PLATFORM_NICONICO,
+    ];
+    let mut result = ChannelImportResult::default();
+    let entries: Vec<ChannelExportEntry> = import
+        .channels
+        .into_iter()
+        .filter(|entry| {
+            let valid = supported_platforms.contains(&entry.platform.as_str())
+                && !entry.channel_id.trim().is_empty();
+            if !valid {
+                result.invalid += 1;
+            }
+            valid
+        })
+        .map(|mut entry| {
+            if entry.poll_interval <= 0 {
+                entry.poll_interval = 60;
+            }
+            entry
+        })
+        .collect();
+
+    let outcomes = db_manager
+        .with_connection(|conn| {
+            base::with_transaction(conn, |conn| {
+                entries
+                    .iter()
+                    .map(|entry| ChannelRepository::import_entry(conn, entry, overwrite))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .db_context("import channels")
+            .map_err(|e| e.to_string())
+        })
+        .await?;
+
+    // ポーリングの開始/再起動（ポーリング間隔の変更も反映するため上書き分は再起動する）
+    let poller = app_handle.try_state::<Arc<Mutex<ChannelPoller>>>();
+    for outcome in outcomes {
+        let id = match outcome {
+            ChannelImportOutcome::Added(id) => {
+                result.added += 1;
+                id
+            }
+            ChannelImportOutcome::Updated(id, was_enabled) => {
+                result.updated += 1;
+                if was_enabled {
+                    if let Some(poller) = &poller {
+                        poller.lock().await.stop_polling(id).await;
+                    }
+                }
+                id
+            }
+            ChannelImportOutcome::Skipped => {
+                result.skipped += 1;
+                continue;
+            }
+        };
+
+        let Some(poller) = &poller else { continue };
+        let channel = db_manager
+            .with_connection(|conn| ChannelRepository::get_by_id(conn, id))
+            .await;
+        if let Ok(Some(channel)) = channel {
+            if channel.enabled {
+                let mut poller = poller.lock().await;
+                if let Err(e) = poller.start_polling(channel, &db_manager, app_handle.clone()) {
+                    eprintln!("Failed to start polling for imported channel {}: {}", id, e);
+                }
+            }
+        }
+    }
+
+    eprintln!(
+        "[import_channels] Imported from {}: {} added, {} updated, {} skipped, {} invalid",
+        path, result.added, result.updated, result.skipped, result.invalid
+    );
+
+    Ok(result)
+}
@@ -1,4 +1,6 @@
-use crate::database::{models::ChatMessage, utils, DatabaseManager};
+use crate::database::{
+    models::ChatMessage, repositories::ChatMessageRepository, utils, DatabaseManager,
+};
 use crate::error::ResultExt;
 use chrono::{Duration, Local, NaiveDateTime, TimeZone};
 use serde::{Deserialize, Serialize};
@@ -38,7 +40,7 @@ pub async fn get_chat_messages(
             CAST(cm.timestamp AS VARCHAR) as timestamp,
             cm.platform,
             cm.user_id, cm.user_name, cm.display_name, cm.message, cm.message_type,
-            CAST(cm.badges AS VARCHAR) as badges, cm.badge_info
+            CAST(cm.badges AS VARCHAR) as badges, cm.badge_info, cm.bits
         FROM chat_messages cm
         INNER JOIN streams s ON cm.stream_id = s.id
         WHERE 1=1
@@ -143,7 +145,7 @@ pub async fn get_chat_messages_around_timestamp(
             CAST(cm.timestamp AS VARCHAR) as timestamp,
             cm.platform,
             cm.user_id, cm.user_name, cm.display_name, cm.message, cm.message_type,
-            CAST(cm.badges AS VARCHAR) as badges, cm.badge_info
+            CAST(cm.badges AS VARCHAR) as badges, cm.badge_info, cm.bits
         FROM chat_messages cm
         WHERE cm.stream_id = ?
           AND cm.timestamp >= ?
@@ -169,3 +171,26 @@ pub async fn get_chat_messages_around_timestamp(
 
     Ok(messages)
 }
+
+/// チャットログをキーワード検索（FTSインデックスが使えない環境では LIKE 検索）
+#[tauri::command]
+pub async fn search_chat_messages(
+    db_manager: State<'_, DatabaseManager>,
+    stream_id: Option<i64>,
+    keyword: String,
+    limit: Option<i64>,
+) -> Result<Vec<ChatMessage>, String> {
+    let keyword = keyword.trim().to_string();
+    if keyword.is_empty() {
+        return Err("検索キーワードを入力してください".to_string());
+    }
+    let limit = limit.unwrap_or(100).clamp(1, 1000);
+
+    db_manager
+        .with_connection(|conn| {
+            ChatMessageRepository::search_messages(conn, stream_id, &keyword, limit)
+                .db_context("search chat messages")
+                .map_err(|e| e.to_string())
+        })
+        .await
+}
@@ -1,11 +1,16 @@
-use crate::config::keyring_store::KeyringStore;
-use crate::config::settings::SettingsManager;
+use crate::collectors::poller::ChannelPoller;
+use crate::collectors::thumbnail_cache::ThumbnailCache;
+use crate::config::keyring_store::{KeyringStore, TokenKind};
+use crate::config::settings::{ChatFilterSettings, SettingsManager};
 use crate::constants::database as db_constants;
 use crate::constants::youtube;
+use crate::database::repositories::ChatMessageRepository;
 use crate::database::DatabaseManager;
 use crate::error::ResultExt;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use tauri::{command, AppHandle, Manager, State};
+use tokio::sync::Mutex;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TokenResponse {
@@ -54,7 +59,7 @@ pub async fn save_token(
     platform: String,
     token: String,
 ) -> Result<TokenResponse, String> {
-    KeyringStore::save_token_with_app(&app_handle, &platform, &token)
+    KeyringStore::save_token_with_app(&app_handle, &platform, TokenKind::Access, &token)
         .config_context("save token")
         .map_err(|e| e.to_string())?;
 
@@ -69,10 +74,23 @@ pub async fn delete_token(
     app_handle: AppHandle,
     platform: String,
 ) -> Result<TokenResponse, String> {
-    KeyringStore::delete_token_with_app(&app_handle, &platform)
+    KeyringStore::delete_token_with_app(&app_handle, &platform, TokenKind::Access)
         .config_context("delete token")
         .map_err(|e| e.to_string())?;
 
+    // 古いトークンでの収集が401エラーを出し続けないよう、そのプラットフォームの収集を停止する
+    // （再ログイン時に reinitialize_twitch_collector / YouTube ログイン完了で再開する）
+    if let Some(poller) = app_handle.try_state::<Arc<Mutex<ChannelPoller>>>() {
+        let stopped = poller.lock().await.stop_platform(&platform).await;
+        if !stopped.is_empty() {
+            eprintln!(
+                "[Config] Stopped collection for {} {} channel(s) after token deletion",
+                stopped.len(),
+                platform
+            );
+        }
+    }
+
     Ok(TokenResponse {
         success: true,
         message: "Token deleted successfully".to_string(),
@@ -81,7 +99,14 @@ pub async fn delete_token(
 
 #[tauri::command]
 pub async fn verify_token(app_handle: AppHandle, platform: String) -> Result<bool, String> {
-    // TODO: 実際のAPIを呼び出してトークンを検証する
+    // Twitch は validate エンドポイントで実際に検証する
+    if platform == db_constants::PLATFORM_TWITCH {
+        return Ok(crate::commands::oauth::verify_twitch_token(app_handle)
+            .await
+            .is_ok());
+    }
+
+    // TODO: YouTube も実際のAPIを呼び出してトークンを検証する
     // ここでは一旦、トークンが存在するかどうかのみを確認
     Ok(KeyringStore::has_token_with_app(&app_handle, &platform))
 }
@@ -188,7 +213,7 @@ pub async fn delete_oauth_config(
 
     // YouTubeの場合のみClient SecretをKeyringから削除（TwitchはDevice Code FlowでClient Secret不要）
     if platform == youtube::PLATFORM_NAME {
-        KeyringStore::delete_token_with_app(&app_handle, &format!("{}_oauth_secret", platform))
+        KeyringStore::delete_token_with_app(&app_handle, &platform, TokenKind::OAuthSecret)
             .config_context("delete OAuth secret")
             .map_err(|e| e.to_string())?;
     }
@@ -225,6 +250,139 @@ pub async fn has_oauth_config(app_handle: AppHandle, platform: String) -> Result
     }
 }
 
+/// サムネイル画像のローカルキャッシュが有効か取得
+#[command]
+pub async fn get_cache_thumbnails(app_handle: AppHandle) -> Result<bool, String> {
+    let settings = SettingsManager::load_settings(&app_handle)
+        .config_context("load settings")
+        .map_err(|e| e.to_string())?;
+
+    Ok(settings.cache_thumbnails)
+}
+
+/// サムネイル画像のローカルキャッシュの有効/無効を保存（次回のポーリングから反映）
+#[command]
+pub async fn set_cache_thumbnails(app_handle: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = SettingsManager::load_settings(&app_handle)
+        .config_context("load settings")
+        .map_err(|e| e.to_string())?;
+
+    settings.cache_thumbnails = enabled;
+
+    SettingsManager::save_settings(&app_handle, &settings)
+        .config_context("save settings")
+        .map_err(|e| e.to_string())?;
+    ThumbnailCache::set_enabled(enabled);
+    Ok(())
+}
+
+/// Twitch IRC に認証済みアカウントで接続する設定か取得（false は justinfan による匿名接続）
+#[command]
+pub async fn get_twitch_irc_authenticated(app_handle: AppHandle) -> Result<bool, String> {
+    let settings = SettingsManager::load_settings(&app_handle)
+        .config_context("load settings")
+        .map_err(|e| e.to_string())?;
+
+    Ok(settings.twitch.irc_authenticated)
+}
+
+/// Twitch IRC の接続モードを保存（Twitchコレクターの次回初期化時から反映）
+#[command]
+pub async fn set_twitch_irc_authenticated(
+    app_handle: AppHandle,
+    authenticated: bool,
+) -> Result<(), String> {
+    let mut settings = SettingsManager::load_settings(&app_handle)
+        .config_context("load settings")
+        .map_err(|e| e.to_string())?;
+
+    settings.twitch.irc_authenticated = authenticated;
+
+    SettingsManager::save_settings(&app_handle, &settings)
+        .config_context("save settings")
+        .map_err(|e| e.to_string())
+}
+
+/// 設定ファイルの暗号化が有効か取得
+#[command]
+pub async fn get_settings_encryption(app_handle: AppHandle) -> Result<bool, String> {
+    let settings = SettingsManager::load_settings(&app_handle)
+        .config_context("load settings")
+        .map_err(|e| e.to_string())?;
+
+    Ok(settings.encrypt_settings)
+}
+
+/// 設定ファイルの暗号化の有効/無効を切り替え、その形式で保存し直す
+///
+/// 有効にすると平文の設定ファイルを暗号化し、無効にすると平文に戻す。
+#[command]
+pub async fn set_settings_encryption(app_handle: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = SettingsManager::load_settings(&app_handle)
+        .config_context("load settings")
+        .map_err(|e| e.to_string())?;
+
+    settings.encrypt_settings = enabled;
+
+    SettingsManager::save_settings(&app_handle, &settings)
+        .config_context("save settings")
+        .map_err(|e| e.to_string())
+}
+
+/// チャット集計時の bot 除外設定を取得
+#[command]
+pub async fn get_chat_filter_settings(app_handle: AppHandle) -> Result<ChatFilterSettings, String> {
+    let settings = SettingsManager::load_settings(&app_handle)
+        .config_context("load settings")
+        .map_err(|e| e.to_string())?;
+
+    Ok(settings.chat_filter)
+}
+
+/// チャット集計時の bot 除外設定を保存
+///
+/// ユーザー名は小文字に正規化して重複を除き、正規表現は DuckDB でコンパイルできるか検証する。
+#[command]
+pub async fn save_chat_filter_settings(
+    app_handle: AppHandle,
+    db_manager: State<'_, DatabaseManager>,
+    mut chat_filter: ChatFilterSettings,
+) -> Result<ChatFilterSettings, String> {
+    let mut user_names: Vec<String> = chat_filter
+        .bot_user_names
+        .iter()
+        .map(|name| name.trim().to_lowercase())
+        .filter(|name| !name.is_empty())
+        .collect();
+    user_names.sort();
+    user_names.dedup();
+    chat_filter.bot_user_names = user_names;
+    chat_filter
+        .message_patterns
+        .retain(|pattern| !pattern.trim().is_empty());
+
+    let patterns = chat_filter.message_patterns.clone();
+    db_manager
+        .with_connection(|conn| {
+            for pattern in &patterns {
+                ChatMessageRepository::validate_message_pattern(conn, pattern)
+                    .map_err(|e| format!("Invalid message pattern '{}': {}", pattern, e))?;
+            }
+            Ok::<_, String>(())
+        })
+        .await?;
+
+    let mut settings = SettingsManager::load_settings(&app_handle)
+        .config_context("load settings")
+        .map_err(|e| e.to_string())?;
+    settings.chat_filter = chat_filter.clone();
+    SettingsManager::save_settings(&app_handle, &settings)
+        .config_context("save settings")
+        .map_err(|e| e.to_string())?;
+
+    Ok(chat_filter)
+}
+
 #[command]
 pub async fn get_build_info() -> Result<BuildInfo, String> {
     Ok(BuildInfo {
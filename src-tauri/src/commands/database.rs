@@ -1,6 +1,19 @@
-use crate::database::DatabaseManager;
+use crate::config::settings::{DatabaseSettings, SettingsManager};
+use crate::constants::database as db_constants;
+use crate::database::{
+    default_db_path,
+    repositories::{
+        DeleteDataInRangeResult, RestoreMode, RestoreResult, RetentionRepository, RetentionResult,
+        StorageUsage, StreamMissingChat, StreamRepository, StreamStorageUsage,
+    },
+    validate_db_path,
+    writer::DatabaseWriter,
+    DatabaseManager,
+};
+use crate::error::ResultExt;
 use serde::Serialize;
-use tauri::{AppHandle, Manager};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, State};
 
 #[derive(Serialize)]
 pub struct DatabaseInfo {
@@ -8,11 +21,63 @@ pub struct DatabaseInfo {
     pub size_bytes: u64,
 }
 
+/// DB統計情報（蓄積データ量とストレージ・メモリ使用量）
+#[derive(Debug, Serialize)]
+pub struct DatabaseStats {
+    pub channels: i64,
+    pub streams: i64,
+    pub stream_stats_rows: i64,
+    pub chat_messages_rows: i64,
+    /// DBファイル・WAL・一時ファイルの合計サイズ
+    pub file_size_bytes: u64,
+    pub oldest_record: Option<String>,
+    pub newest_record: Option<String>,
+    /// DuckDB の使用量（取得できない場合は None）
+    pub storage_usage: Option<StorageUsage>,
+}
+
 #[tauri::command]
 pub async fn get_database_info(app_handle: AppHandle) -> Result<DatabaseInfo, String> {
     let db_manager: tauri::State<'_, DatabaseManager> = app_handle.state();
     let path = db_manager.get_db_path();
 
+    Ok(DatabaseInfo {
+        path: path.display().to_string(),
+        size_bytes: database_file_size(path),
+    })
+}
+
+/// DBに蓄積されたデータ量とサイズを取得（リテンション設定の判断材料）
+#[tauri::command]
+pub async fn get_database_stats(
+    db_manager: State<'_, DatabaseManager>,
+) -> Result<DatabaseStats, String> {
+    let (volume, storage_usage) = db_manager
+        .with_connection(|conn| {
+            let volume = RetentionRepository::get_data_volume(conn)
+                .db_context("get data volume")
+                .map_err(|e| e.to_string())?;
+            let storage_usage = RetentionRepository::get_storage_usage(conn)
+                .map_err(|e| eprintln!("[DB] Failed to get storage usage: {}", e))
+                .ok();
+            Ok::<_, String>((volume, storage_usage))
+        })
+        .await?;
+
+    Ok(DatabaseStats {
+        channels: volume.channels,
+        streams: volume.streams,
+        stream_stats_rows: volume.stream_stats_rows,
+        chat_messages_rows: volume.chat_messages_rows,
+        file_size_bytes: database_file_size(db_manager.get_db_path()),
+        oldest_record: volume.oldest_record,
+        newest_record: volume.newest_record,
+        storage_usage,
+    })
+}
+
+/// DBファイル・WAL・同じディレクトリの一時ファイル（*.tmp）の合計サイズ
+fn database_file_size(path: &Path) -> u64 {
     // Get main DB file size
     let mut total_size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
 
@@ -38,8 +103,254 @@ pub async fn get_database_info(app_handle: AppHandle) -> Result<DatabaseInfo, St
         }
     }
 
-    Ok(DatabaseInfo {
-        path: path.display().to_string(),
-        size_bytes: total_size,
-    })
+    total_size
+}
+
+/// データベース設定を取得
+#[tauri::command]
+pub async fn get_database_settings(app_handle: AppHandle) -> Result<DatabaseSettings, String> {
+    let settings = SettingsManager::load_settings(&app_handle)
+        .config_context("load settings")
+        .map_err(|e| e.to_string())?;
+
+    Ok(settings.database)
+}
+
+/// データベース設定を保存し、定期同期・リテンションタスクを新しい設定で再起動
+/// memory_limit / threads は稼働中の接続にも即時反映する
+#[tauri::command]
+pub async fn save_database_settings(
+    app_handle: AppHandle,
+    db_manager: State<'_, DatabaseManager>,
+    mut settings: DatabaseSettings,
+) -> Result<DatabaseSettings, String> {
+    // sync_intervalのバリデーション（0は無効化、それ以外は5-3600秒に制限）
+    if settings.sync_interval != 0 {
+        settings.sync_interval = settings.sync_interval.clamp(
+            db_constants::MIN_SYNC_INTERVAL_SECS,
+            db_constants::MAX_SYNC_INTERVAL_SECS,
+        );
+    }
+
+    settings.chat_dedupe_window_secs = settings
+        .chat_dedupe_window_secs
+        .min(db_constants::MAX_CHAT_DEDUPE_WINDOW_SECS);
+
+    // memory_limit / threads は範囲外の値を補正して保存する
+    settings.memory_limit_mb = settings.sanitized_memory_limit_mb();
+    settings.threads = settings.sanitized_threads();
+
+    let mut app_settings = SettingsManager::load_settings(&app_handle)
+        .config_context("load settings")
+        .map_err(|e| e.to_string())?;
+
+    // 保存先パスは set_database_path でのみ変更する
+    settings.db_path = app_settings.database.db_path.clone();
+    app_settings.database = settings.clone();
+
+    SettingsManager::save_settings(&app_handle, &app_settings)
+        .config_context("save settings")
+        .map_err(|e| e.to_string())?;
+
+    // 定期同期・リテンションタスクを再起動して新しい設定を反映
+    db_manager.start_periodic_sync(settings.sync_interval);
+    db_manager.start_retention_task(settings.retention_days, settings.retention_rollup);
+    db_manager.apply_resource_settings(&settings).await;
+    DatabaseWriter::set_chat_dedupe_window_secs(settings.chat_dedupe_window_secs);
+
+    Ok(settings)
+}
+
+/// 指定期間の stream_stats / chat_messages を削除
+///
+/// 障害時など不正なデータが記録された期間だけを取り除くためのコマンド。
+/// `tables` には "stream_stats" / "chat_messages" を指定する。
+/// 誤操作防止のため `confirm` が true でない場合は何もせずエラーを返す。
+#[tauri::command]
+pub async fn delete_data_in_range(
+    db_manager: State<'_, DatabaseManager>,
+    channel_id: Option<i64>,
+    from: String,
+    to: String,
+    tables: Vec<String>,
+    confirm: bool,
+) -> Result<DeleteDataInRangeResult, String> {
+    let result = db_manager
+        .with_connection(|conn| {
+            RetentionRepository::delete_data_in_range(
+                conn, channel_id, &from, &to, &tables, confirm,
+            )
+            .map_err(|e| e.to_string())
+        })
+        .await?;
+
+    eprintln!(
+        "[delete_data_in_range] Deleted {} stream_stats and {} chat_messages (channel: {:?}, {} - {})",
+        result.stream_stats_deleted, result.chat_messages_deleted, channel_id, from, to
+    );
+
+    Ok(result)
+}
+
+/// 配信ごとのストレージ使用量（推定）を大きい順に取得
+///
+/// リテンションやアーカイブ対象を決めるために、どの配信がDBを占有しているかを確認する。
+#[tauri::command]
+pub async fn get_storage_breakdown(
+    db_manager: State<'_, DatabaseManager>,
+    limit: Option<i32>,
+) -> Result<Vec<StreamStorageUsage>, String> {
+    db_manager
+        .with_connection(|conn| {
+            StreamRepository::get_storage_breakdown(conn, limit)
+                .db_context("get storage breakdown")
+                .map_err(|e| e.to_string())
+        })
+        .await
+}
+
+/// 統計はあるのにチャットが1件も無い配信を取得
+///
+/// IRC接続が黙って切れていた配信を特定するためのデータ品質診断。
+#[tauri::command]
+pub async fn get_streams_missing_chat(
+    db_manager: State<'_, DatabaseManager>,
+    channel_id: Option<i64>,
+) -> Result<Vec<StreamMissingChat>, String> {
+    db_manager
+        .with_connection(|conn| {
+            StreamRepository::get_streams_missing_chat(conn, channel_id)
+                .db_context("get streams missing chat")
+                .map_err(|e| e.to_string())
+        })
+        .await
+}
+
+/// 保存済みのリテンション設定で古いデータの削除を即時実行
+#[tauri::command]
+pub async fn apply_retention_policy(
+    app_handle: AppHandle,
+    db_manager: State<'_, DatabaseManager>,
+) -> Result<RetentionResult, String> {
+    let settings = SettingsManager::load_settings(&app_handle)
+        .config_context("load settings")
+        .map_err(|e| e.to_string())?
+        .database;
+
+    if settings.retention_days == 0 {
+        return Err("保持日数が設定されていません".to_string());
+    }
+
+    db_manager
+        .apply_retention(settings.retention_days, settings.retention_rollup)
+        .await
+        .db_context("apply retention policy")
+        .map_err(|e| e.to_string())
+}
+
+/// 現在のDBを指定パスの `.db` ファイルへバックアップ
+///
+/// 作成したファイルは別マシンでそのままDBとして使うことも、`restore_database` で取り込むこともできる。
+#[tauri::command]
+pub async fn backup_database(
+    db_manager: State<'_, DatabaseManager>,
+    path: String,
+) -> Result<(), String> {
+    let path = PathBuf::from(path);
+    db_manager
+        .backup_to(&path)
+        .await
+        .db_context("backup database")
+        .map_err(|e| e.to_string())?;
+
+    eprintln!("[backup_database] Database backed up to {}", path.display());
+    Ok(())
+}
+
+/// 外部の `.db` ファイルから復元
+///
+/// `mode` は "replace"（現在のデータを置き換え）または "merge"（未登録のデータのみ追加）。
+/// 実行前に現行DBを自動でバックアップし、そのパスを結果に含める。
+#[tauri::command]
+pub async fn restore_database(
+    db_manager: State<'_, DatabaseManager>,
+    path: String,
+    mode: RestoreMode,
+) -> Result<RestoreResult, String> {
+    let result = db_manager
+        .restore_from(&PathBuf::from(&path), mode)
+        .await
+        .db_context("restore database")
+        .map_err(|e| e.to_string())?;
+
+    eprintln!(
+        "[restore_database] Restored from {} ({:?}): {} channels, {} streams, {} stream_stats, {} chat_messages",
+        path, mode, result.channels, result.streams, result.stream_stats, result.chat_messages
+    );
+
+    Ok(result)
+}
+
+/// DBファイルの保存先を変更する（次回起動時から有効）
+///
+/// `path` が None または空文字の場合は既定のパス（app_data_dir/stream_stats.db）に戻す。
+/// `migrate` が true の場合は現在のDBを新しい保存先へコピーし、false の場合は
+/// 新しい保存先のDBをそのまま使用する（存在しなければ次回起動時に新規作成される）。
+/// 戻り値: 次回起動時に使用されるDBファイルパス
+#[tauri::command]
+pub async fn set_database_path(
+    app_handle: AppHandle,
+    db_manager: State<'_, DatabaseManager>,
+    path: Option<String>,
+    migrate: bool,
+) -> Result<String, String> {
+    let custom_path = path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+    let target_path = match &custom_path {
+        Some(p) => {
+            let target = PathBuf::from(p);
+            // 保存先の変更時のみ親ディレクトリを作成する（起動時の検証では作成しない）
+            if target.is_absolute() {
+                if let Some(parent) = target.parent() {
+                    std::fs::create_dir_all(parent).map_err(|e| {
+                        format!(
+                            "保存先ディレクトリを作成できません ({}): {}",
+                            parent.display(),
+                            e
+                        )
+                    })?;
+                }
+            }
+            validate_db_path(&target)?;
+            target
+        }
+        None => default_db_path(&app_handle)?,
+    };
+
+    if migrate {
+        if target_path.exists() {
+            return Err(format!(
+                "移行先に既にファイルが存在します: {}",
+                target_path.display()
+            ));
+        }
+        db_manager
+            .backup_to(&target_path)
+            .await
+            .db_context("migrate database")
+            .map_err(|e| e.to_string())?;
+        eprintln!(
+            "[set_database_path] Database copied to {}",
+            target_path.display()
+        );
+    }
+
+    let mut app_settings = SettingsManager::load_settings(&app_handle)
+        .config_context("load settings")
+        .map_err(|e| e.to_string())?;
+    app_settings.database.db_path = custom_path;
+    SettingsManager::save_settings(&app_handle, &app_settings)
+        .config_context("save settings")
+        .map_err(|e| e.to_string())?;
+
+    Ok(target_path.display().to_string())
 }
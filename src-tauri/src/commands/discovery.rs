@@ -1,6 +1,7 @@
 use crate::collectors::auto_discovery::AutoDiscoveryPoller;
+use crate::collectors::poller::ChannelPoller;
 use crate::config::settings::{AutoDiscoverySettings, SettingsManager};
-use crate::constants::database as db_constants;
+use crate::constants::{database as db_constants, twitch};
 use crate::database::{repositories::ChannelRepository, DatabaseManager};
 use crate::error::ResultExt;
 use serde::{Deserialize, Serialize};
@@ -34,6 +35,9 @@ pub async fn save_auto_discovery_settings(
     // max_streamsのバリデーション（1-500の範囲に制限）
     settings.max_streams = settings.max_streams.clamp(1, 500);
 
+    // max_auto_collectのバリデーション（1-100の範囲に制限）
+    settings.max_auto_collect = settings.max_auto_collect.clamp(1, 100);
+
     // game_idsのバリデーション（最大100件に制限）
     if settings.filters.game_ids.len() > 100 {
         return Err("ゲームIDは最大100件までです".to_string());
@@ -339,13 +343,42 @@ pub async fn search_twitch_games(
         .map(|cat| TwitchGame {
             id: cat.id.to_string(),
             name: cat.name.to_string(),
-            box_art_url: cat.box_art_url.to_string(),
+            box_art_url: sized_box_art_url(&cat.box_art_url),
         })
         .collect();
 
     Ok(games)
 }
 
+/// ボックスアートURLを固定サイズに置換
+///
+/// Get Games は `{width}x{height}` プレースホルダー、Search Categories は
+/// `-52x72.jpg` のようなサイズ付きURLを返すため、どちらも同じサイズに揃える。
+fn sized_box_art_url(url: &str) -> String {
+    let size = format!("{}x{}", twitch::BOX_ART_WIDTH, twitch::BOX_ART_HEIGHT);
+    if url.contains("{width}x{height}") {
+        return url.replace("{width}x{height}", &size);
+    }
+
+    // 末尾の "-<w>x<h>.<ext>" を置換
+    if let (Some(dash), Some(dot)) = (url.rfind('-'), url.rfind('.')) {
+        if dash < dot {
+            let dims = &url[dash + 1..dot];
+            let is_size = dims.split_once('x').is_some_and(|(w, h)| {
+                !w.is_empty()
+                    && !h.is_empty()
+                    && w.chars().all(|c| c.is_ascii_digit())
+                    && h.chars().all(|c| c.is_ascii_digit())
+            });
+            if is_size {
+                return format!("{}{}{}", &url[..dash + 1], size, &url[dot..]);
+            }
+        }
+    }
+
+    url.to_string()
+}
+
 /// ゲームIDからゲーム情報を取得（既存設定の表示用）
 #[tauri::command]
 pub async fn get_games_by_ids(
@@ -390,7 +423,7 @@ pub async fn get_games_by_ids(
         .map(|cat| TwitchGame {
             id: cat.id.to_string(),
             name: cat.name.to_string(),
-            box_art_url: cat.box_art_url.to_string(),
+            box_art_url: sized_box_art_url(&cat.box_art_url),
         })
         .collect();
 
@@ -445,7 +478,8 @@ pub async fn promote_discovered_channels(
 
         if already_exists {
             // 既に登録されている場合はis_auto_discoveredフラグを更新
-            if let Err(e) = db_manager
+            // 自動収集が解除されて無効化されていたチャンネルは有効に戻してポーリングを再開する
+            let reenabled = match db_manager
                 .with_connection(|conn| {
                     ChannelRepository::update_auto_discovered(
                         conn,
@@ -455,13 +489,42 @@ pub async fn promote_discovered_channels(
                         Some(stream_info.twitch_user_id),
                     )
                     .db_context("update channel")
-                    .map_err(|e| e.to_string())
+                    .map_err(|e| e.to_string())?;
+
+                    match ChannelRepository::get_registration_state(conn, "twitch", &login_name)
+                        .db_context("get channel state")
+                        .map_err(|e| e.to_string())?
+                    {
+                        Some((id, _, false)) => {
+                            ChannelRepository::update_enabled(conn, id, true)
+                                .db_context("enable channel")
+                                .map_err(|e| e.to_string())?;
+                            ChannelRepository::get_by_id(conn, id)
+                                .db_context("get channel")
+                                .map_err(|e| e.to_string())
+                        }
+                        _ => Ok(None),
+                    }
                 })
                 .await
                 .db_context("get connection")
             {
-                errors.push(format!("{}: {}", login_name, e));
-                continue;
+                Ok(reenabled) => reenabled,
+                Err(e) => {
+                    errors.push(format!("{}: {}", login_name, e));
+                    continue;
+                }
+            };
+            if let Some(channel) = reenabled {
+                if let Some(poller) = app_handle.try_state::<Arc<Mutex<ChannelPoller>>>() {
+                    let mut poller = poller.lock().await;
+                    if let Err(e) = poller.start_polling(channel, &db_manager, app_handle.clone()) {
+                        eprintln!(
+                            "[Discovery] Failed to restart polling for {}: {}",
+                            login_name, e
+                        );
+                    }
+                }
             }
             eprintln!(
                 "[Discovery] Updated existing channel {} (user_id: {}) to manual registration",
@@ -548,3 +611,44 @@ pub struct TwitchGame {
     pub name: String,
     pub box_art_url: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sized_box_art_url_replaces_placeholder() {
+        assert_eq!(
+            sized_box_art_url(
+                "https://static-cdn.jtvnw.net/ttv-boxart/509658-{width}x{height}.jpg"
+            ),
+            "https://static-cdn.jtvnw.net/ttv-boxart/509658-144x192.jpg"
+        );
+    }
+
+    #[test]
+    fn test_sized_box_art_url_resizes_sized_url() {
+        assert_eq!(
+            sized_box_art_url("https://static-cdn.jtvnw.net/ttv-boxart/509658-52x72.jpg"),
+            "https://static-cdn.jtvnw.net/ttv-boxart/509658-144x192.jpg"
+        );
+        assert_eq!(
+            sized_box_art_url("https://static-cdn.jtvnw.net/ttv-boxart/509658-144x192.jpg"),
+            "https://static-cdn.jtvnw.net/ttv-boxart/509658-144x192.jpg"
+        );
+    }
+
+    #[test]
+    fn test_sized_box_art_url_keeps_other_urls() {
+        assert_eq!(sized_box_art_url(""), "");
+        // ゲーム名に含まれる "-" やサイズ以外の部分は置換しない
+        assert_eq!(
+            sized_box_art_url("https://static-cdn.jtvnw.net/ttv-boxart/Just-Chatting.jpg"),
+            "https://static-cdn.jtvnw.net/ttv-boxart/Just-Chatting.jpg"
+        );
+        assert_eq!(
+            sized_box_art_url("https://example.com/box-x72.jpg"),
+            "https://example.com/box-x72.jpg"
+        );
+    }
+}
@@ -1,8 +1,25 @@
-use crate::database::{repositories::StreamStatsRepository, DatabaseManager};
+use crate::config::keyring_store::KeyringStore;
+use crate::config::settings::{S3ExportSettings, SettingsManager};
+use crate::constants::export as export_constants;
+use crate::database::{
+    models::{ChatMessage, StreamStats},
+    repositories::{
+        ChatMessageRepository, ExportRepository, ExportTable, S3SecretParams, StreamInfo,
+        StreamRepository, StreamStatsRepository, TimelinePoint,
+    },
+    DatabaseManager,
+};
 use crate::error::ResultExt;
 use chrono::{DateTime, FixedOffset, NaiveDateTime};
+use duckdb::Connection;
+use rust_xlsxwriter::Workbook;
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, State};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, State};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ExportQuery {
@@ -13,6 +30,221 @@ pub struct ExportQuery {
     pub delimiter: Option<String>,   // Custom delimiter (default: comma)
 }
 
+/// チャットエクスポート用クエリ
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChatExportQuery {
+    pub stream_id: Option<i64>,
+    pub channel_id: Option<i64>,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+}
+
+/// 配信サマリーエクスポート用クエリ（channel_id と日付範囲はどちらか一方以上を指定）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StreamSummaryExportQuery {
+    pub channel_id: Option<i64>,
+    pub date_from: Option<String>, // YYYY-MM-DD
+    pub date_to: Option<String>,   // YYYY-MM-DD
+}
+
+/// 配信サマリーJSONの1要素
+#[derive(Debug, Serialize)]
+struct StreamSummaryEntry {
+    #[serde(flatten)]
+    stream: StreamInfo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timeline: Option<Vec<TimelinePoint>>,
+}
+
+/// S3互換ストレージの認証情報（export_to_s3 で保存済み設定を上書きする場合に指定）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct S3Credentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub region: Option<String>,
+    pub endpoint: Option<String>,
+    pub url_style: Option<String>,
+    pub use_ssl: Option<bool>,
+}
+
+/// エクスポート進捗イベントのペイロード（export-progress）
+#[derive(Debug, Clone, Serialize)]
+struct ExportProgressEvent<'a> {
+    file_path: &'a str,
+    processed: usize,
+    total: usize,
+}
+
+fn emit_export_progress(app_handle: &AppHandle, file_path: &str, processed: usize, total: usize) {
+    let _ = app_handle.emit(
+        "export-progress",
+        ExportProgressEvent {
+            file_path,
+            processed,
+            total,
+        },
+    );
+}
+
+/// 出力先が ZIP アーカイブか（拡張子で判定）
+fn is_zip_path(file_path: &str) -> bool {
+    Path::new(file_path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+}
+
+/// ZIP アーカイブ内のエントリ名（`stats.zip` → `stats.csv`、`stats.csv.zip` → `stats.csv`）
+fn zip_entry_name(archive_path: &str, default_extension: &str) -> String {
+    let stem = Path::new(archive_path)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("export");
+    if Path::new(stem).extension().is_some() {
+        stem.to_string()
+    } else {
+        format!("{}.{}", stem, default_extension)
+    }
+}
+
+/// 単一ファイルのエクスポート先
+///
+/// 出力先が `.zip` の場合は常に ZIP 圧縮し、`compress` 指定時に `.zip` でなければ末尾に `.zip` を付ける。
+struct ExportOutput {
+    path: String,
+    /// ZIP 圧縮する場合のアーカイブ内のエントリ名
+    zip_entry: Option<String>,
+}
+
+impl ExportOutput {
+    fn new(file_path: &str, compress: bool, extension: &str) -> Self {
+        let path = if compress && !is_zip_path(file_path) {
+            format!("{}.zip", file_path)
+        } else {
+            file_path.to_string()
+        };
+        let zip_entry = is_zip_path(&path).then(|| zip_entry_name(&path, extension));
+        Self { path, zip_entry }
+    }
+
+    fn create(&self) -> io::Result<ExportWriter> {
+        let file = BufWriter::new(File::create(&self.path)?);
+        match &self.zip_entry {
+            Some(name) => {
+                let mut zip = ZipWriter::new(file);
+                zip.start_file(
+                    name.as_str(),
+                    SimpleFileOptions::default().compression_method(CompressionMethod::Deflated),
+                )
+                .map_err(io::Error::other)?;
+                Ok(ExportWriter::Zip(Box::new(zip)))
+            }
+            None => Ok(ExportWriter::Plain(file)),
+        }
+    }
+}
+
+/// エクスポートの書き込み先（通常のファイル / ZIP アーカイブ内のエントリ）
+enum ExportWriter {
+    Plain(BufWriter<File>),
+    Zip(Box<ZipWriter<BufWriter<File>>>),
+}
+
+impl ExportWriter {
+    /// 書き込みを完了する（ZIP の場合はセントラルディレクトリを書き込む）
+    fn finish(self) -> io::Result<()> {
+        match self {
+            Self::Plain(mut writer) => writer.flush(),
+            Self::Zip(zip) => zip.finish().map_err(io::Error::other)?.flush(),
+        }
+    }
+}
+
+impl Write for ExportWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(writer) => writer.write(buf),
+            Self::Zip(zip) => zip.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(writer) => writer.flush(),
+            Self::Zip(zip) => zip.flush(),
+        }
+    }
+}
+
+/// 区切り形式のファイルへ行を逐次書き込み、一定件数ごとに export-progress を発行する
+///
+/// 全行をメモリにためず、DBから取得したページごとに `write_rows` で書き込む。
+/// `header` と `format_row` の戻り値には行末の改行を含めること。
+struct DelimitedFileWriter<'a> {
+    app_handle: &'a AppHandle,
+    file_path: &'a str,
+    writer: ExportWriter,
+    processed: usize,
+    total: usize,
+}
+
+impl<'a> DelimitedFileWriter<'a> {
+    fn create(
+        app_handle: &'a AppHandle,
+        output: &'a ExportOutput,
+        include_bom: bool,
+        header: &str,
+        total: usize,
+    ) -> io::Result<Self> {
+        let mut writer = output.create()?;
+
+        // UTF-8 BOM（Excel に UTF-8 として認識させるため）
+        if include_bom {
+            writer.write_all("\u{FEFF}".as_bytes())?;
+        }
+        writer.write_all(header.as_bytes())?;
+        emit_export_progress(app_handle, &output.path, 0, total);
+
+        Ok(Self {
+            app_handle,
+            file_path: &output.path,
+            writer,
+            processed: 0,
+            total,
+        })
+    }
+
+    fn write_rows<T>(
+        &mut self,
+        rows: &[T],
+        mut format_row: impl FnMut(&T) -> String,
+    ) -> io::Result<()> {
+        for row in rows {
+            self.writer.write_all(format_row(row).as_bytes())?;
+            self.processed += 1;
+            if self
+                .processed
+                .is_multiple_of(export_constants::PROGRESS_EVENT_INTERVAL)
+                && self.processed < self.total
+            {
+                emit_export_progress(self.app_handle, self.file_path, self.processed, self.total);
+            }
+        }
+        Ok(())
+    }
+
+    /// 書き込みを完了し、書き出した行数を返す
+    fn finish(self) -> io::Result<usize> {
+        self.writer.finish()?;
+        emit_export_progress(
+            self.app_handle,
+            self.file_path,
+            self.processed,
+            self.processed,
+        );
+        Ok(self.processed)
+    }
+}
+
 fn normalize_timestamp(value: &str) -> String {
     // 1) RFC3339 (元の文字列形式を想定)
     if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
@@ -45,118 +277,453 @@ fn escape_field(value: &str, delimiter: &str) -> String {
     }
 }
 
-#[tauri::command]
-pub async fn export_to_delimited(
-    _app_handle: AppHandle,
-    db_manager: State<'_, DatabaseManager>,
-    query: ExportQuery,
-    file_path: String,
-    include_bom: Option<bool>,
-) -> Result<String, String> {
-    let ExportQuery {
-        channel_id,
+/// stream_stats をエクスポート用に取得する
+///
+/// `aggregation` が指定され、開始・終了時刻が揃っている場合は補間済みの集計値を返す。
+/// 集計単位（"1min" / "5min" / "1hour"）を補完間隔（分）に変換する。"raw" などは None
+fn aggregation_interval_minutes(aggregation: Option<&str>) -> Option<i64> {
+    match aggregation {
+        Some("1min") => Some(1),
+        Some("5min") => Some(5),
+        Some("1hour") => Some(60),
+        _ => None,
+    }
+}
+
+fn query_export_stats(
+    conn: &Connection,
+    channel_id: i64,
+    start_time: Option<&str>,
+    end_time: Option<&str>,
+    aggregation: Option<&str>,
+) -> Result<Vec<StreamStats>, String> {
+    let interval_minutes = aggregation_interval_minutes(aggregation);
+
+    if let (Some(st), Some(et), Some(interval)) = (start_time, end_time, interval_minutes) {
+        StreamStatsRepository::get_interpolated_stream_stats_for_export(
+            conn,
+            None,
+            Some(channel_id),
+            st,
+            et,
+            interval,
+        )
+        .db_context("query interpolated stats for export")
+        .map_err(|e| e.to_string())
+    } else {
+        StreamStatsRepository::get_stream_stats_filtered(
+            conn,
+            None,
+            Some(channel_id),
+            start_time,
+            end_time,
+            true, // ORDER BY collected_at ASC for export
+        )
+        .db_context("query stats")
+        .map_err(|e| e.to_string())
+    }
+}
+
+/// query_export_stats が返す行数をデータを読み込まずに算出する
+///
+/// 補完する場合は最初〜最後の収集時刻を補完間隔で区切った点の数になる。
+fn count_export_stats(
+    conn: &Connection,
+    channel_id: i64,
+    start_time: Option<&str>,
+    end_time: Option<&str>,
+    aggregation: Option<&str>,
+) -> Result<i64, String> {
+    let (rows, span_seconds) = StreamStatsRepository::count_stream_stats_filtered(
+        conn,
+        None,
+        Some(channel_id),
         start_time,
         end_time,
-        aggregation,
+    )
+    .db_context("count stats for export")
+    .map_err(|e| e.to_string())?;
+
+    let interval_minutes = aggregation_interval_minutes(aggregation);
+    match (start_time, end_time, interval_minutes) {
+        (Some(_), Some(_), Some(interval)) if rows > 0 => {
+            Ok((span_seconds / (interval * 60) as f64).floor() as i64 + 1)
+        }
+        _ => Ok(rows),
+    }
+}
+
+/// stream_stats の1行を区切り形式の文字列にする（行末の改行を含む）
+fn format_stats_row(stat: &StreamStats, delimiter: &str) -> String {
+    let collected_at = normalize_timestamp(&stat.collected_at);
+    let channel_name = stat.channel_name.as_deref().unwrap_or("");
+    let viewer_count = stat.viewer_count.unwrap_or(0).to_string();
+    let category = stat.category.as_deref().unwrap_or("");
+    let title = stat.title.as_deref().unwrap_or("");
+    let chat_rate = stat
+        .chat_rate_1min
+        .map(|c| c.to_string())
+        .unwrap_or_else(|| "0".to_string());
+
+    format!(
+        "{}{}{}{}{}{}{}{}{}{}{}\n",
+        escape_field(&collected_at, delimiter),
         delimiter,
-    } = query;
+        escape_field(channel_name, delimiter),
+        delimiter,
+        viewer_count,
+        delimiter,
+        escape_field(category, delimiter),
+        delimiter,
+        escape_field(title, delimiter),
+        delimiter,
+        chat_rate
+    )
+}
 
-    let stats = db_manager
-        .with_connection(|conn| {
-            let start_opt = start_time.as_deref();
-            let end_opt = end_time.as_deref();
-
-            let interval_minutes = match aggregation.as_deref() {
-                Some("1min") => Some(1),
-                Some("5min") => Some(5),
-                Some("1hour") => Some(60),
-                _ => None,
-            };
+/// stream_stats を区切り形式（CSV / TSV など）で書き出し、書き出した行数を返す
+///
+/// 生データは `PAGE_SIZE` 件ずつDBから取得して書き出す。補完（aggregation）する場合は
+/// 補完点の数が期間と間隔で決まるため、補完済みのデータをまとめて取得してから書き出す。
+async fn export_stats_delimited(
+    app_handle: &AppHandle,
+    db_manager: &DatabaseManager,
+    query: &ExportQuery,
+    output: &ExportOutput,
+    include_bom: bool,
+    delimiter: &str,
+) -> Result<usize, String> {
+    let channel_id = query.channel_id;
+    let start_time = query.start_time.as_deref();
+    let end_time = query.end_time.as_deref();
+    let aggregation = query.aggregation.as_deref();
+    let header = format!(
+        "collected_at{}channel_name{}viewer_count{}category{}title{}chat_rate_1min\n",
+        delimiter, delimiter, delimiter, delimiter, delimiter
+    );
 
-            if let (Some(st), Some(et), Some(interval)) = (start_opt, end_opt, interval_minutes) {
-                StreamStatsRepository::get_interpolated_stream_stats_for_export(
-                    conn,
-                    None,
-                    Some(channel_id),
-                    st,
-                    et,
-                    interval,
-                )
-                .db_context("query interpolated stats for export")
-                .map_err(|e| e.to_string())
-            } else {
-                StreamStatsRepository::get_stream_stats_filtered(
+    if start_time.is_some()
+        && end_time.is_some()
+        && aggregation_interval_minutes(aggregation).is_some()
+    {
+        let stats = db_manager
+            .with_connection(|conn| {
+                query_export_stats(conn, channel_id, start_time, end_time, aggregation)
+            })
+            .await?;
+        let mut writer =
+            DelimitedFileWriter::create(app_handle, output, include_bom, &header, stats.len())
+                .io_context("write file")
+                .map_err(|e| e.to_string())?;
+        writer
+            .write_rows(&stats, |stat| format_stats_row(stat, delimiter))
+            .io_context("write file")
+            .map_err(|e| e.to_string())?;
+        return writer
+            .finish()
+            .io_context("write file")
+            .map_err(|e| e.to_string());
+    }
+
+    let total = db_manager
+        .with_connection(|conn| count_export_stats(conn, channel_id, start_time, end_time, None))
+        .await?;
+    let mut writer =
+        DelimitedFileWriter::create(app_handle, output, include_bom, &header, total as usize)
+            .io_context("write file")
+            .map_err(|e| e.to_string())?;
+
+    let mut offset = 0;
+    loop {
+        let page = db_manager
+            .with_connection(|conn| {
+                StreamStatsRepository::get_stream_stats_page(
                     conn,
                     None,
                     Some(channel_id),
-                    start_opt,
-                    end_opt,
-                    true, // ORDER BY collected_at ASC for export
+                    start_time,
+                    end_time,
+                    export_constants::PAGE_SIZE,
+                    offset,
                 )
-                .db_context("query stats")
+                .db_context("query stats page for export")
                 .map_err(|e| e.to_string())
-            }
-        })
-        .await?;
+            })
+            .await?;
+        writer
+            .write_rows(&page, |stat| format_stats_row(stat, delimiter))
+            .io_context("write file")
+            .map_err(|e| e.to_string())?;
+        if (page.len() as i64) < export_constants::PAGE_SIZE {
+            break;
+        }
+        offset += export_constants::PAGE_SIZE;
+    }
 
-    let stats_len = stats.len();
+    writer
+        .finish()
+        .io_context("write file")
+        .map_err(|e| e.to_string())
+}
 
-    // Determine delimiter (default to comma)
-    let delimiter = delimiter.as_deref().unwrap_or(",");
+/// Excel の1シートに書き込める最大行数（ヘッダー行を除く）
+const XLSX_MAX_DATA_ROWS: usize = 1_048_575;
 
-    // Build delimited file content
-    let mut output = String::new();
+/// streams / stats / chat の3シートを持つ xlsx ブックを書き出す
+///
+/// 戻り値: 各シートに書き込んだ行数 (streams, stats, chat)
+fn write_stats_xlsx(
+    file_path: &str,
+    streams: &[StreamInfo],
+    stats: &[StreamStats],
+    messages: &[ChatMessage],
+) -> Result<(usize, usize, usize), rust_xlsxwriter::XlsxError> {
+    let mut workbook = Workbook::new();
 
-    // Add UTF-8 BOM if requested (helps Excel recognize UTF-8)
-    if include_bom.unwrap_or(false) {
-        output.push('\u{FEFF}');
+    let sheet = workbook.add_worksheet().set_name("streams")?;
+    let headers = [
+        "started_at",
+        "ended_at",
+        "channel_name",
+        "title",
+        "category",
+        "peak_viewers",
+        "avg_viewers",
+        "duration_minutes",
+        "minutes_watched",
+        "follower_gain",
+        "total_chat_messages",
+        "unique_chatters",
+    ];
+    for (col, header) in headers.iter().enumerate() {
+        sheet.write_string(0, col as u16, *header)?;
+    }
+    let streams = &streams[..streams.len().min(XLSX_MAX_DATA_ROWS)];
+    for (index, stream) in streams.iter().enumerate() {
+        let row = index as u32 + 1;
+        sheet.write_string(row, 0, normalize_timestamp(&stream.started_at))?;
+        sheet.write_string(row, 1, normalize_timestamp(&stream.ended_at))?;
+        sheet.write_string(row, 2, &stream.channel_name)?;
+        sheet.write_string(row, 3, &stream.title)?;
+        sheet.write_string(row, 4, &stream.category)?;
+        sheet.write_number(row, 5, stream.peak_viewers)?;
+        sheet.write_number(row, 6, stream.avg_viewers)?;
+        sheet.write_number(row, 7, stream.duration_minutes)?;
+        sheet.write_number(row, 8, stream.minutes_watched as f64)?;
+        sheet.write_number(row, 9, stream.follower_gain)?;
+        sheet.write_number(row, 10, stream.total_chat_messages as f64)?;
+        sheet.write_number(row, 11, stream.unique_chatters as f64)?;
     }
 
-    // Header row with full columns
-    output.push_str(&format!(
-        "collected_at{}channel_name{}viewer_count{}category{}title{}chat_rate_1min\n",
-        delimiter, delimiter, delimiter, delimiter, delimiter
-    ));
-
-    // Data rows
-    for stat in &stats {
-        let collected_at = normalize_timestamp(&stat.collected_at);
-        let channel_name = stat.channel_name.as_deref().unwrap_or("");
-        let viewer_count = stat.viewer_count.unwrap_or(0).to_string();
-        let category = stat.category.as_deref().unwrap_or("");
-        let title = stat.title.as_deref().unwrap_or("");
-        let chat_rate = stat
-            .chat_rate_1min
-            .map(|c| c.to_string())
-            .unwrap_or_else(|| "0".to_string());
+    let sheet = workbook.add_worksheet().set_name("stats")?;
+    let headers = [
+        "collected_at",
+        "channel_name",
+        "viewer_count",
+        "category",
+        "title",
+        "chat_rate_1min",
+    ];
+    for (col, header) in headers.iter().enumerate() {
+        sheet.write_string(0, col as u16, *header)?;
+    }
+    let stats = &stats[..stats.len().min(XLSX_MAX_DATA_ROWS)];
+    for (index, stat) in stats.iter().enumerate() {
+        let row = index as u32 + 1;
+        sheet.write_string(row, 0, normalize_timestamp(&stat.collected_at))?;
+        sheet.write_string(row, 1, stat.channel_name.as_deref().unwrap_or(""))?;
+        sheet.write_number(row, 2, stat.viewer_count.unwrap_or(0))?;
+        sheet.write_string(row, 3, stat.category.as_deref().unwrap_or(""))?;
+        sheet.write_string(row, 4, stat.title.as_deref().unwrap_or(""))?;
+        sheet.write_number(row, 5, stat.chat_rate_1min.unwrap_or(0) as f64)?;
+    }
 
-        output.push_str(&format!(
-            "{}{}{}{}{}{}{}{}{}{}{}\n",
-            escape_field(&collected_at, delimiter),
-            delimiter,
-            escape_field(channel_name, delimiter),
-            delimiter,
-            viewer_count,
-            delimiter,
-            escape_field(category, delimiter),
-            delimiter,
-            escape_field(title, delimiter),
-            delimiter,
-            chat_rate
-        ));
+    let sheet = workbook.add_worksheet().set_name("chat")?;
+    let headers = [
+        "timestamp",
+        "user_name",
+        "display_name",
+        "message",
+        "message_type",
+    ];
+    for (col, header) in headers.iter().enumerate() {
+        sheet.write_string(0, col as u16, *header)?;
+    }
+    let messages = &messages[..messages.len().min(XLSX_MAX_DATA_ROWS)];
+    for (index, msg) in messages.iter().enumerate() {
+        let row = index as u32 + 1;
+        sheet.write_string(row, 0, normalize_timestamp(&msg.timestamp))?;
+        sheet.write_string(row, 1, &msg.user_name)?;
+        sheet.write_string(row, 2, msg.display_name.as_deref().unwrap_or(""))?;
+        sheet.write_string(row, 3, &msg.message)?;
+        sheet.write_string(row, 4, &msg.message_type)?;
     }
 
-    // Write to file
-    std::fs::write(&file_path, output)
-        .io_context("write file")
-        .map_err(|e| e.to_string())?;
+    workbook.save(file_path)?;
+    Ok((streams.len(), stats.len(), messages.len()))
+}
+
+/// stream_stats を区切り形式でエクスポート
+///
+/// `compress` を指定するか出力先が `.zip` の場合は ZIP 圧縮して書き出す。
+#[tauri::command]
+pub async fn export_to_delimited(
+    app_handle: AppHandle,
+    db_manager: State<'_, DatabaseManager>,
+    query: ExportQuery,
+    file_path: String,
+    include_bom: Option<bool>,
+    compress: Option<bool>,
+) -> Result<String, String> {
+    // Determine delimiter (default to comma)
+    let delimiter = query.delimiter.as_deref().unwrap_or(",");
+    let extension = if delimiter == "\t" { "tsv" } else { "csv" };
+    let output = ExportOutput::new(&file_path, compress.unwrap_or(false), extension);
+
+    let rows = export_stats_delimited(
+        &app_handle,
+        &db_manager,
+        &query,
+        &output,
+        include_bom.unwrap_or(false),
+        delimiter,
+    )
+    .await?;
 
     Ok(format!(
         "Exported {} records to {} (delimiter: {:?})",
-        stats_len, file_path, delimiter
+        rows, output.path, delimiter
     ))
 }
 
+/// 統計データを指定フォーマットでエクスポート
+///
+/// `format` は "csv" / "tsv" / "xlsx"。
+/// csv / tsv は stream_stats のみを書き出し、`include_bom` で Excel 向けの UTF-8 BOM を付与できる。
+/// csv / tsv は `compress` を指定するか出力先が `.zip` の場合に ZIP 圧縮する。
+/// xlsx は streams / stats / chat の3シートに分けて書き出す（各シート最大 1,048,575 行）。
+#[tauri::command]
+pub async fn export_stats(
+    app_handle: AppHandle,
+    db_manager: State<'_, DatabaseManager>,
+    query: ExportQuery,
+    file_path: String,
+    format: String,
+    include_bom: Option<bool>,
+    compress: Option<bool>,
+) -> Result<String, String> {
+    match format.as_str() {
+        "csv" | "tsv" => {
+            let delimiter = if format == "tsv" {
+                "\t"
+            } else {
+                query.delimiter.as_deref().unwrap_or(",")
+            };
+            let output = ExportOutput::new(&file_path, compress.unwrap_or(false), &format);
+            let rows = export_stats_delimited(
+                &app_handle,
+                &db_manager,
+                &query,
+                &output,
+                include_bom.unwrap_or(false),
+                delimiter,
+            )
+            .await?;
+
+            Ok(format!(
+                "Exported {} records to {} ({})",
+                rows, output.path, format
+            ))
+        }
+        "xlsx" => {
+            let ExportQuery {
+                channel_id,
+                start_time,
+                end_time,
+                aggregation,
+                ..
+            } = query;
+            let (streams, stats, messages) = db_manager
+                .with_connection(|conn| {
+                    let stats = query_export_stats(
+                        conn,
+                        channel_id,
+                        start_time.as_deref(),
+                        end_time.as_deref(),
+                        aggregation.as_deref(),
+                    )?;
+
+                    // 配信は started_at の日付部分で期間を絞り込む
+                    let date_from = start_time.as_deref().and_then(|t| t.get(..10));
+                    let date_to = end_time.as_deref().and_then(|t| t.get(..10));
+                    let streams: Vec<StreamInfo> = StreamRepository::get_channel_streams(
+                        conn,
+                        channel_id,
+                        Some(i32::MAX),
+                        None,
+                    )
+                    .db_context("query streams for export")
+                    .map_err(|e| e.to_string())?
+                    .into_iter()
+                    .filter(|stream| {
+                        let date = stream.started_at.get(..10).unwrap_or(&stream.started_at);
+                        date_from.is_none_or(|from| date >= from)
+                            && date_to.is_none_or(|to| date <= to)
+                    })
+                    .collect();
+
+                    let messages = ChatMessageRepository::get_messages_for_export(
+                        conn,
+                        None,
+                        Some(channel_id),
+                        start_time.as_deref(),
+                        end_time.as_deref(),
+                    )
+                    .db_context("query chat messages for export")
+                    .map_err(|e| e.to_string())?;
+
+                    Ok::<_, String>((streams, stats, messages))
+                })
+                .await?;
+
+            let total = streams.len() + stats.len() + messages.len();
+            emit_export_progress(&app_handle, &file_path, 0, total);
+            let (stream_rows, stats_rows, chat_rows) =
+                write_stats_xlsx(&file_path, &streams, &stats, &messages)
+                    .map_err(|e| format!("Failed to write xlsx: {}", e))?;
+            emit_export_progress(&app_handle, &file_path, total, total);
+
+            Ok(format!(
+                "Exported {} streams, {} stats and {} chat messages to {}",
+                stream_rows, stats_rows, chat_rows, file_path
+            ))
+        }
+        _ => Err(format!(
+            "未対応のエクスポート形式です: {}（csv / tsv / xlsx）",
+            format
+        )),
+    }
+}
+
+/// エクスポートで出力される stream_stats の件数を取得（大量エクスポート前の確認用）
+#[tauri::command]
+pub async fn count_export_rows(
+    db_manager: State<'_, DatabaseManager>,
+    query: ExportQuery,
+) -> Result<i64, String> {
+    db_manager
+        .with_connection(|conn| {
+            count_export_stats(
+                conn,
+                query.channel_id,
+                query.start_time.as_deref(),
+                query.end_time.as_deref(),
+                query.aggregation.as_deref(),
+            )
+        })
+        .await
+}
+
 #[tauri::command]
 pub async fn preview_export_data(
     _app_handle: AppHandle,
@@ -174,39 +741,13 @@ pub async fn preview_export_data(
 
     let stats = db_manager
         .with_connection(|conn| {
-            let start_opt = start_time.as_deref();
-            let end_opt = end_time.as_deref();
-
-            let interval_minutes = match aggregation.as_deref() {
-                Some("1min") => Some(1),
-                Some("5min") => Some(5),
-                Some("1hour") => Some(60),
-                _ => None,
-            };
-
-            if let (Some(st), Some(et), Some(interval)) = (start_opt, end_opt, interval_minutes) {
-                StreamStatsRepository::get_interpolated_stream_stats_for_export(
-                    conn,
-                    None,
-                    Some(channel_id),
-                    st,
-                    et,
-                    interval,
-                )
-                .db_context("query interpolated stats for preview")
-                .map_err(|e| e.to_string())
-            } else {
-                StreamStatsRepository::get_stream_stats_filtered(
-                    conn,
-                    None,
-                    Some(channel_id),
-                    start_opt,
-                    end_opt,
-                    true, // ORDER BY collected_at ASC
-                )
-                .db_context("query stats")
-                .map_err(|e| e.to_string())
-            }
+            query_export_stats(
+                conn,
+                channel_id,
+                start_time.as_deref(),
+                end_time.as_deref(),
+                aggregation.as_deref(),
+            )
         })
         .await?;
 
@@ -256,3 +797,561 @@ pub async fn preview_export_data(
 
     Ok(output)
 }
+
+/// chat_messages を RFC4180 準拠の CSV としてエクスポート
+///
+/// メッセージにはカンマ・改行・ダブルクオートが頻出するため、全フィールドを escape_field に通し、
+/// レコード区切りは CRLF とする。`compress` を指定するか出力先が `.zip` の場合は ZIP 圧縮する。
+#[tauri::command]
+pub async fn export_chat_to_csv(
+    app_handle: AppHandle,
+    db_manager: State<'_, DatabaseManager>,
+    query: ChatExportQuery,
+    file_path: String,
+    include_bom: Option<bool>,
+    compress: Option<bool>,
+) -> Result<String, String> {
+    let ChatExportQuery {
+        stream_id,
+        channel_id,
+        start_time,
+        end_time,
+    } = query;
+
+    if stream_id.is_none() && channel_id.is_none() {
+        return Err("stream_id または channel_id を指定してください".to_string());
+    }
+
+    let start_time = start_time.as_deref();
+    let end_time = end_time.as_deref();
+    let total = db_manager
+        .with_connection(|conn| {
+            ChatMessageRepository::count_messages_for_export(
+                conn, stream_id, channel_id, start_time, end_time,
+            )
+            .db_context("count chat messages for export")
+            .map_err(|e| e.to_string())
+        })
+        .await?;
+
+    let output = ExportOutput::new(&file_path, compress.unwrap_or(false), "csv");
+    let mut writer = DelimitedFileWriter::create(
+        &app_handle,
+        &output,
+        include_bom.unwrap_or(false),
+        CHAT_CSV_HEADER,
+        total as usize,
+    )
+    .io_context("write file")
+    .map_err(|e| e.to_string())?;
+
+    // 全メッセージをメモリに載せず、ページ単位で取得して書き出す
+    let mut offset = 0;
+    loop {
+        let page = db_manager
+            .with_connection(|conn| {
+                ChatMessageRepository::get_messages_for_export_page(
+                    conn,
+                    stream_id,
+                    channel_id,
+                    start_time,
+                    end_time,
+                    export_constants::PAGE_SIZE,
+                    offset,
+                )
+                .db_context("query chat messages for export")
+                .map_err(|e| e.to_string())
+            })
+            .await?;
+        writer
+            .write_rows(&page, format_chat_row)
+            .io_context("write file")
+            .map_err(|e| e.to_string())?;
+        if (page.len() as i64) < export_constants::PAGE_SIZE {
+            break;
+        }
+        offset += export_constants::PAGE_SIZE;
+    }
+
+    let rows = writer
+        .finish()
+        .io_context("write file")
+        .map_err(|e| e.to_string())?;
+
+    Ok(format!(
+        "Exported {} chat messages to {}",
+        rows, output.path
+    ))
+}
+
+/// チャットCSVのヘッダー行（RFC4180 に合わせて CRLF）
+const CHAT_CSV_HEADER: &str = "timestamp,user_name,display_name,message,message_type\r\n";
+
+/// chat_messages の1行を RFC4180 準拠の CSV レコードにする（行末の CRLF を含む）
+fn format_chat_row(msg: &ChatMessage) -> String {
+    let delimiter = ",";
+    let timestamp = normalize_timestamp(&msg.timestamp);
+    let display_name = msg.display_name.as_deref().unwrap_or("");
+
+    format!(
+        "{},{},{},{},{}\r\n",
+        escape_field(&timestamp, delimiter),
+        escape_field(&msg.user_name, delimiter),
+        escape_field(display_name, delimiter),
+        escape_field(&msg.message, delimiter),
+        escape_field(&msg.message_type, delimiter),
+    )
+}
+
+/// 配信ごとのサマリー（StreamInfo）を JSON 配列としてエクスポート
+///
+/// `include_timeline` を指定すると各配信のタイムライン生データも `timeline` に含める。
+/// `compress` を指定するか出力先が `.zip` の場合は ZIP 圧縮する。
+#[tauri::command]
+pub async fn export_stream_summary_to_json(
+    db_manager: State<'_, DatabaseManager>,
+    query: StreamSummaryExportQuery,
+    file_path: String,
+    include_timeline: Option<bool>,
+    compress: Option<bool>,
+) -> Result<String, String> {
+    let StreamSummaryExportQuery {
+        channel_id,
+        date_from,
+        date_to,
+    } = query;
+
+    if channel_id.is_none() && date_from.is_none() && date_to.is_none() {
+        return Err("channel_id または日付範囲を指定してください".to_string());
+    }
+    let include_timeline = include_timeline.unwrap_or(false);
+
+    let entries = db_manager
+        .with_connection(|conn| {
+            let streams = match channel_id {
+                Some(channel_id) => {
+                    StreamRepository::get_channel_streams(conn, channel_id, Some(i32::MAX), None)
+                }
+                None => StreamRepository::get_streams_by_date_range(
+                    conn,
+                    date_from.as_deref().unwrap_or("1970-01-01"),
+                    date_to.as_deref().unwrap_or("9999-12-31"),
+                    Some(i32::MAX),
+                    None,
+                ),
+            }
+            .db_context("query streams for summary export")
+            .map_err(|e| e.to_string())?;
+
+            // channel_id 指定時の日付範囲は started_at の日付部分で絞り込む
+            let in_range = |stream: &StreamInfo| {
+                let date = stream.started_at.get(..10).unwrap_or(&stream.started_at);
+                date_from.as_deref().is_none_or(|from| date >= from)
+                    && date_to.as_deref().is_none_or(|to| date <= to)
+            };
+
+            streams
+                .into_iter()
+                .filter(in_range)
+                .map(|stream| {
+                    let timeline = if include_timeline {
+                        Some(
+                            StreamRepository::get_timeline_stats(conn, stream.id)
+                                .db_context("query stream timeline")
+                                .map_err(|e| e.to_string())?,
+                        )
+                    } else {
+                        None
+                    };
+                    Ok(StreamSummaryEntry { stream, timeline })
+                })
+                .collect::<Result<Vec<_>, String>>()
+        })
+        .await?;
+
+    let json = serde_json::to_string_pretty(&entries)
+        .map_err(|e| format!("Failed to serialize stream summary: {}", e))?;
+
+    let output = ExportOutput::new(&file_path, compress.unwrap_or(false), "json");
+    output
+        .create()
+        .and_then(|mut writer| {
+            writer.write_all(json.as_bytes())?;
+            writer.finish()
+        })
+        .io_context("write file")
+        .map_err(|e| e.to_string())?;
+
+    Ok(format!(
+        "Exported {} stream summaries to {}",
+        entries.len(),
+        output.path
+    ))
+}
+
+/// S3エクスポート設定を取得（シークレットは返さない）
+#[tauri::command]
+pub async fn get_s3_export_settings(
+    app_handle: AppHandle,
+) -> Result<Option<S3ExportSettings>, String> {
+    let settings = SettingsManager::load_settings(&app_handle)
+        .config_context("load settings")
+        .map_err(|e| e.to_string())?;
+
+    Ok(settings.s3_export)
+}
+
+/// S3エクスポート設定を保存（シークレットアクセスキーはKeyringに保存）
+#[tauri::command]
+pub async fn save_s3_export_settings(
+    app_handle: AppHandle,
+    settings: S3ExportSettings,
+    secret_access_key: Option<String>,
+) -> Result<(), String> {
+    if settings.access_key_id.trim().is_empty() {
+        return Err("アクセスキーIDを入力してください".to_string());
+    }
+    if let Some(url_style) = settings.url_style.as_deref() {
+        if url_style != "path" && url_style != "vhost" {
+            return Err(format!("Unsupported url_style: {}", url_style));
+        }
+    }
+
+    let mut app_settings = SettingsManager::load_settings(&app_handle)
+        .config_context("load settings")
+        .map_err(|e| e.to_string())?;
+
+    app_settings.s3_export = Some(settings);
+
+    SettingsManager::save_settings(&app_handle, &app_settings)
+        .config_context("save settings")
+        .map_err(|e| e.to_string())?;
+
+    if let Some(secret) = secret_access_key {
+        if !secret.trim().is_empty() {
+            KeyringStore::save_s3_secret_with_app(&app_handle, &secret)
+                .config_context("save S3 secret")
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// streams / stream_stats / chat_messages を Parquet 形式でローカルファイルへエクスポート
+///
+/// DuckDB の `COPY (SELECT ...) TO ... (FORMAT PARQUET)` で直接書き出す。
+/// `table` はホワイトリスト（streams / stream_stats / chat_messages）で検証する。
+/// 出力先が `.zip` の場合は各テーブルを `<table>.parquet` として1つのアーカイブにまとめ、
+/// `table` を省略すると全テーブルを含める。
+/// `query.aggregation` / `query.delimiter` は使用しない。
+#[tauri::command]
+pub async fn export_to_parquet(
+    db_manager: State<'_, DatabaseManager>,
+    query: ExportQuery,
+    file_path: String,
+    table: Option<String>,
+) -> Result<String, String> {
+    let export_table = table
+        .as_deref()
+        .map(|name| {
+            ExportTable::from_name(name)
+                .ok_or_else(|| format!("エクスポートできないテーブルです: {}", name))
+        })
+        .transpose()?;
+
+    let ExportQuery {
+        channel_id,
+        start_time,
+        end_time,
+        ..
+    } = query;
+
+    if is_zip_path(&file_path) {
+        let tables = export_table.map_or(ExportTable::ALL.to_vec(), |table| vec![table]);
+        return export_parquet_archive(
+            &db_manager,
+            &tables,
+            channel_id,
+            start_time.as_deref(),
+            end_time.as_deref(),
+            &file_path,
+        )
+        .await;
+    }
+
+    let export_table =
+        export_table.ok_or_else(|| "エクスポートするテーブルを指定してください".to_string())?;
+
+    let rows = db_manager
+        .with_connection(|conn| {
+            ExportRepository::copy_table_to_parquet(
+                conn,
+                export_table,
+                Some(channel_id),
+                start_time.as_deref(),
+                end_time.as_deref(),
+                &file_path,
+            )
+            .db_context("copy table to parquet")
+            .map_err(|e| e.to_string())
+        })
+        .await?;
+
+    Ok(format!(
+        "Exported {} records from {} to {}",
+        rows,
+        export_table.name(),
+        file_path
+    ))
+}
+
+/// 複数テーブルを一時ファイルへ Parquet で書き出し、1つの ZIP アーカイブにまとめる
+async fn export_parquet_archive(
+    db_manager: &DatabaseManager,
+    tables: &[ExportTable],
+    channel_id: i64,
+    start_time: Option<&str>,
+    end_time: Option<&str>,
+    archive_path: &str,
+) -> Result<String, String> {
+    let temp_dir = std::env::temp_dir();
+    let suffix = chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default();
+    let temp_files: Vec<(ExportTable, PathBuf)> = tables
+        .iter()
+        .map(|&table| {
+            let name = format!(
+                "stream_monitor_export_{}_{}_{}.parquet",
+                std::process::id(),
+                suffix,
+                table.name()
+            );
+            (table, temp_dir.join(name))
+        })
+        .collect();
+
+    let copied = db_manager
+        .with_connection(|conn| {
+            temp_files
+                .iter()
+                .map(|(table, path)| {
+                    ExportRepository::copy_table_to_parquet(
+                        conn,
+                        *table,
+                        Some(channel_id),
+                        start_time,
+                        end_time,
+                        &path.to_string_lossy(),
+                    )
+                    .db_context("copy table to parquet")
+                    .map_err(|e| e.to_string())
+                })
+                .collect::<Result<Vec<_>, String>>()
+        })
+        .await;
+    let result = copied.and_then(|rows| {
+        write_parquet_archive(archive_path, &temp_files)
+            .io_context("write zip archive")
+            .map_err(|e| e.to_string())?;
+        Ok(rows.iter().sum::<usize>())
+    });
+
+    for (_, path) in &temp_files {
+        let _ = std::fs::remove_file(path);
+    }
+    let rows = result?;
+
+    Ok(format!(
+        "Exported {} records from {} tables to {}",
+        rows,
+        tables.len(),
+        archive_path
+    ))
+}
+
+/// Parquet ファイルを `<table>.parquet` として ZIP アーカイブに格納する
+///
+/// Parquet は圧縮済みのため無圧縮（Stored）で格納する。
+fn write_parquet_archive(archive_path: &str, files: &[(ExportTable, PathBuf)]) -> io::Result<()> {
+    let mut zip = ZipWriter::new(BufWriter::new(File::create(archive_path)?));
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Stored)
+        .large_file(true);
+    for (table, path) in files {
+        zip.start_file(format!("{}.parquet", table.name()), options)
+            .map_err(io::Error::other)?;
+        io::copy(&mut File::open(path)?, &mut zip)?;
+    }
+    zip.finish().map_err(io::Error::other)?.flush()
+}
+
+/// stream_stats を Parquet 形式で S3 互換バケットへエクスポート
+///
+/// DuckDB の httpfs 拡張で `s3://bucket/key` へ直接 COPY する。
+/// `credentials` を省略した場合は保存済みのS3エクスポート設定とKeyringのシークレットを使用する。
+/// `bucket` を省略した場合は設定のデフォルトバケットを使用する。
+#[tauri::command]
+pub async fn export_to_s3(
+    app_handle: AppHandle,
+    db_manager: State<'_, DatabaseManager>,
+    query: ExportQuery,
+    bucket: Option<String>,
+    key: String,
+    credentials: Option<S3Credentials>,
+) -> Result<String, String> {
+    let saved_settings = SettingsManager::load_settings(&app_handle)
+        .config_context("load settings")
+        .map_err(|e| e.to_string())?
+        .s3_export;
+
+    let credentials = match credentials {
+        Some(c) => c,
+        None => {
+            let saved = saved_settings.clone().ok_or_else(|| {
+                "S3エクスポート設定がありません。設定画面で設定してください。".to_string()
+            })?;
+            let secret_access_key = KeyringStore::get_s3_secret_with_app(&app_handle)
+                .config_context("get S3 secret")
+                .map_err(|e| e.to_string())?;
+            S3Credentials {
+                access_key_id: saved.access_key_id,
+                secret_access_key,
+                region: saved.region,
+                endpoint: saved.endpoint,
+                url_style: saved.url_style,
+                use_ssl: saved.use_ssl,
+            }
+        }
+    };
+
+    let bucket = bucket
+        .or_else(|| saved_settings.and_then(|s| s.bucket))
+        .filter(|b| !b.trim().is_empty())
+        .ok_or_else(|| "バケット名を指定してください".to_string())?;
+    let key = key.trim_start_matches('/').to_string();
+    if key.is_empty() {
+        return Err("オブジェクトキーを指定してください".to_string());
+    }
+    let destination = format!("s3://{}/{}", bucket, key);
+
+    let ExportQuery {
+        channel_id,
+        start_time,
+        end_time,
+        ..
+    } = query;
+
+    let rows = db_manager
+        .with_connection(|conn| {
+            ExportRepository::load_httpfs(conn)
+                .db_context("load httpfs extension")
+                .map_err(|e| e.to_string())?;
+
+            ExportRepository::create_s3_secret(
+                conn,
+                &S3SecretParams {
+                    access_key_id: &credentials.access_key_id,
+                    secret_access_key: &credentials.secret_access_key,
+                    region: credentials.region.as_deref().unwrap_or("us-east-1"),
+                    endpoint: credentials.endpoint.as_deref(),
+                    url_style: credentials.url_style.as_deref(),
+                    use_ssl: credentials.use_ssl.unwrap_or(true),
+                },
+            )
+            .db_context("create S3 secret")
+            .map_err(|e| e.to_string())?;
+
+            let result = ExportRepository::copy_stream_stats_to_parquet(
+                conn,
+                Some(channel_id),
+                start_time.as_deref(),
+                end_time.as_deref(),
+                &destination,
+            )
+            .db_context("copy stats to S3")
+            .map_err(|e| e.to_string());
+
+            // 成否にかかわらずシークレットは削除する
+            if let Err(e) = ExportRepository::drop_s3_secret(conn) {
+                eprintln!("[export_to_s3] Failed to drop S3 secret: {}", e);
+            }
+
+            result
+        })
+        .await?;
+
+    Ok(format!("Exported {} records to {}", rows, destination))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_field_plain() {
+        assert_eq!(escape_field("hello", ","), "hello");
+    }
+
+    #[test]
+    fn test_escape_field_rfc4180() {
+        assert_eq!(escape_field("a,b", ","), "\"a,b\"");
+        assert_eq!(escape_field("say \"hi\"", ","), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape_field("line1\nline2", ","), "\"line1\nline2\"");
+        assert_eq!(escape_field("line1\r\nline2", ","), "\"line1\r\nline2\"");
+    }
+
+    fn chat_message(user_name: &str, display_name: Option<&str>, message: &str) -> ChatMessage {
+        ChatMessage {
+            id: None,
+            channel_id: Some(1),
+            stream_id: Some(1),
+            timestamp: "2024-01-01 12:00:00".to_string(),
+            platform: "twitch".to_string(),
+            user_id: None,
+            user_name: user_name.to_string(),
+            display_name: display_name.map(str::to_string),
+            message: message.to_string(),
+            message_type: "normal".to_string(),
+            badges: None,
+            badge_info: None,
+            bits: None,
+        }
+    }
+
+    #[test]
+    fn test_chat_csv_header_uses_crlf() {
+        assert_eq!(
+            CHAT_CSV_HEADER,
+            "timestamp,user_name,display_name,message,message_type\r\n"
+        );
+    }
+
+    #[test]
+    fn test_format_chat_row_plain() {
+        let msg = chat_message("alice", Some("Alice"), "hello");
+        assert_eq!(
+            format_chat_row(&msg),
+            "2024-01-01T12:00:00+00:00,alice,Alice,hello,normal\r\n"
+        );
+
+        // display_name が無い場合は空欄
+        let msg = chat_message("bob", None, "hi");
+        assert_eq!(
+            format_chat_row(&msg),
+            "2024-01-01T12:00:00+00:00,bob,,hi,normal\r\n"
+        );
+    }
+
+    #[test]
+    fn test_format_chat_row_quotes_message_rfc4180() {
+        let msg = chat_message(
+            "alice",
+            Some("Ali,ce"),
+            "he said \"gg\", then\r\nleft\nagain",
+        );
+        assert_eq!(
+            format_chat_row(&msg),
+            "2024-01-01T12:00:00+00:00,alice,\"Ali,ce\",\"he said \"\"gg\"\", then\r\nleft\nagain\",normal\r\n"
+        );
+    }
+}
@@ -15,3 +15,4 @@ pub mod system;
 pub mod timeline;
 pub mod twitch;
 pub mod window;
+pub mod youtube;
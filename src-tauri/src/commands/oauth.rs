@@ -1,7 +1,10 @@
+use crate::config::keyring_store::KeyringStore;
 use crate::config::settings::SettingsManager;
+use crate::constants::{twitch, youtube};
 use crate::error::ResultExt;
-use crate::oauth::twitch::{DeviceAuthStatus, TwitchOAuth};
-use tauri::{AppHandle, Manager};
+use crate::oauth::twitch::{DeviceAuthStatus, TwitchOAuth, TwitchTokenValidation};
+use crate::oauth::youtube::{YouTubeDeviceAuthStatus, YouTubeOAuth};
+use tauri::{AppHandle, Emitter, Manager};
 
 /// Twitch Device Code Grant Flow を開始
 #[tauri::command]
@@ -37,7 +40,9 @@ pub async fn poll_twitch_device_token(
     device_code: String,
     interval: u64,
     client_id: String,
+    expires_in: Option<u64>,
 ) -> Result<String, String> {
+    let expires_in = expires_in.unwrap_or(twitch::DEVICE_CODE_DEFAULT_EXPIRES_SECS);
     eprintln!("[Twitch Device Auth] Starting token polling");
     eprintln!("  - Device code length: {}", device_code.len());
     eprintln!("  - Polling interval: {} seconds", interval);
@@ -46,11 +51,112 @@ pub async fn poll_twitch_device_token(
     let oauth = TwitchOAuth::new(client_id, String::new()).with_app_handle(app_handle.clone());
 
     oauth
-        .poll_for_device_token(&device_code, interval, Some(app_handle))
+        .poll_for_device_token(&device_code, interval, expires_in, Some(app_handle))
         .await
         .map_err(|e| format!("Token polling failed: {}", e))
 }
 
+/// 保存済みの Twitch トークンを validate エンドポイントで検証
+///
+/// 無効な場合は自動でリフレッシュを試み、それも失敗した場合はエラーを返す。
+#[tauri::command]
+pub async fn verify_twitch_token(app_handle: AppHandle) -> Result<TwitchTokenValidation, String> {
+    let settings = SettingsManager::load_settings(&app_handle)
+        .config_context("load settings")
+        .map_err(|e| e.to_string())?;
+
+    let client_id = settings
+        .twitch
+        .client_id
+        .ok_or_else(|| "Twitch Client ID not configured".to_string())?;
+
+    TwitchOAuth::new(client_id, String::new())
+        .verify_twitch_token(&app_handle)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// YouTube Device Code Flow でログイン
+///
+/// user_code / verification_url を即座に返し、トークンのポーリングはバックグラウンドで行う。
+/// 完了時に `youtube-auth-success`、失敗時に `youtube-auth-error` イベントを送信する。
+#[tauri::command]
+pub async fn login_with_youtube_device(
+    app_handle: AppHandle,
+) -> Result<YouTubeDeviceAuthStatus, String> {
+    let settings = SettingsManager::load_settings(&app_handle)
+        .config_context("load settings")
+        .map_err(|e| e.to_string())?;
+
+    let client_id = settings.youtube.client_id.ok_or_else(|| {
+        "YouTube Client ID is not configured. Please set it in settings first.".to_string()
+    })?;
+    let client_secret =
+        KeyringStore::get_oauth_secret_with_app(&app_handle, youtube::PLATFORM_NAME)
+            .ok()
+            .or(settings.youtube.client_secret)
+            .ok_or_else(|| {
+                "YouTube Client Secret is not configured. Please set it in settings first."
+                    .to_string()
+            })?;
+
+    let oauth = YouTubeOAuth::new(client_id, client_secret);
+    let status = oauth
+        .start_device_flow(vec![youtube::SCOPE_YOUTUBE_READONLY])
+        .await
+        .map_err(|e| format!("Device flow initialization failed: {}", e))?;
+
+    let device_code = status.device_code.clone();
+    let interval = status.interval;
+    let expires_in = status.expires_in;
+    tauri::async_runtime::spawn(async move {
+        match oauth
+            .poll_for_device_token(&app_handle, &device_code, interval, expires_in)
+            .await
+        {
+            Ok(_) => resume_platform_collection(&app_handle, youtube::PLATFORM_NAME).await,
+            Err(e) => {
+                eprintln!("[YouTube Device Auth] Token polling failed: {}", e);
+                if let Err(emit_err) = app_handle.emit("youtube-auth-error", e.to_string()) {
+                    eprintln!(
+                        "[YouTube Device Auth] Failed to emit auth error event: {}",
+                        emit_err
+                    );
+                }
+            }
+        }
+    });
+
+    Ok(status)
+}
+
+/// 再ログイン後にプラットフォームの有効なチャンネルの収集を再開する
+async fn resume_platform_collection(app_handle: &AppHandle, platform: &str) {
+    use crate::collectors::poller::ChannelPoller;
+    use crate::database::DatabaseManager;
+    use std::sync::Arc;
+
+    let (Some(poller), Some(db_manager)) = (
+        app_handle.try_state::<Arc<tokio::sync::Mutex<ChannelPoller>>>(),
+        app_handle.try_state::<DatabaseManager>(),
+    ) else {
+        return;
+    };
+
+    let result = poller
+        .lock()
+        .await
+        .restart_platform(platform, &db_manager, app_handle)
+        .await;
+    match result {
+        Ok(count) => eprintln!(
+            "[OAuth] Resumed collection for {} {} channel(s)",
+            count, platform
+        ),
+        Err(e) => eprintln!("[OAuth] Failed to resume {} collection: {}", platform, e),
+    }
+}
+
 /// Twitch Collector を再初期化（トークン設定後に呼び出す）
 #[tauri::command]
 pub async fn reinitialize_twitch_collector(
@@ -87,6 +193,7 @@ pub async fn reinitialize_twitch_collector(
         app_handle.clone(),
         Arc::new(db_manager.inner().clone()),
         Arc::new(logger.inner().clone()),
+        settings.twitch.irc_authenticated,
     ));
 
     eprintln!("[Reinit] TwitchCollector created, initializing IRC...");
@@ -96,9 +203,18 @@ pub async fn reinitialize_twitch_collector(
 
     eprintln!("[Reinit] IRC initialized, registering collector...");
 
-    // ChannelPollerに登録（既存を上書き）
+    // 古いコレクターが持つIRC接続を閉じるため、登録の前に実行中の収集を停止する
     let mut poller_guard = poller.lock().await;
+    poller_guard
+        .stop_platform(crate::constants::database::PLATFORM_TWITCH)
+        .await;
+
+    // ChannelPollerに登録（既存を上書き）
     poller_guard.register_twitch_collector(collector);
+    drop(poller_guard);
+
+    // ログアウトで停止したチャンネルや古いコレクターで動いているタスクを新しいコレクターで再開
+    resume_platform_collection(&app_handle, crate::constants::database::PLATFORM_TWITCH).await;
 
     eprintln!("[Reinit] Twitch collector reinitialized successfully");
 
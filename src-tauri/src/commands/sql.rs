@@ -1,9 +1,14 @@
-use crate::database::repositories::SqlTemplateRepository;
+use crate::database::repositories::{
+    count_placeholders, is_valid_view_name, named_placeholders, rewrite_named_placeholders,
+    ExternalDataFormat, ExternalDataRepository, SqlTemplateRepository, TemplateParam,
+    TEMPLATE_PARAM_TYPES,
+};
 use crate::database::DatabaseManager;
 use crate::error::ResultExt;
 use chrono::{Local, TimeZone};
 use duckdb::{types::TimeUnit, types::ValueRef, Connection, Row};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::time::Instant;
 use tauri::State;
 
@@ -273,6 +278,559 @@ pub struct SaveTemplateRequest {
     pub name: String,
     pub description: String,
     pub query: String,
+    /// プレースホルダーのパラメータ定義
+    /// （省略時は `:name` の名前、または param1, param2, ... の text 型を自動生成）
+    #[serde(default)]
+    pub params: Option<Vec<TemplateParam>>,
+}
+
+/// テンプレートパラメータの検証結果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TemplateParamValidation {
+    pub expected: usize,
+    pub provided: usize,
+    pub params: Vec<TemplateParam>,
+}
+
+/// クエリのプレースホルダーとパラメータ定義を突き合わせる
+///
+/// `:name` 形式のクエリは名前で、`?` 形式のクエリは出現順で定義と対応付ける。
+fn resolve_template_params(
+    query: &str,
+    params: Option<Vec<TemplateParam>>,
+) -> Result<Vec<TemplateParam>, String> {
+    let expected = count_placeholders(query);
+    let names = named_placeholders(query);
+    if expected > 0 && !names.is_empty() {
+        return Err("`?` と `:name` のプレースホルダーは混在できません".to_string());
+    }
+
+    let params = match params {
+        Some(params) => params,
+        None if !names.is_empty() => {
+            return Ok(names
+                .into_iter()
+                .map(|name| TemplateParam {
+                    name,
+                    param_type: "text".to_string(),
+                    default: None,
+                })
+                .collect())
+        }
+        None => {
+            return Ok((1..=expected)
+                .map(|i| TemplateParam {
+                    name: format!("param{}", i),
+                    param_type: "text".to_string(),
+                    default: None,
+                })
+                .collect())
+        }
+    };
+
+    if names.is_empty() {
+        if params.len() != expected {
+            return Err(format!(
+                "パラメータ定義の数（{}）がクエリのプレースホルダー数（{}）と一致しません",
+                params.len(),
+                expected
+            ));
+        }
+    } else {
+        if let Some(missing) = names.iter().find(|n| !params.iter().any(|p| &p.name == *n)) {
+            return Err(format!(
+                "プレースホルダー ':{}' のパラメータ定義がありません",
+                missing
+            ));
+        }
+        if let Some(unused) = params.iter().find(|p| !names.contains(&p.name)) {
+            return Err(format!(
+                "パラメータ '{}' はクエリ内で使用されていません",
+                unused.name
+            ));
+        }
+        if params.len() != names.len() {
+            return Err("パラメータ定義の名前が重複しています".to_string());
+        }
+    }
+
+    for param in &params {
+        if param.name.trim().is_empty() {
+            return Err("パラメータ名が空です".to_string());
+        }
+        if !TEMPLATE_PARAM_TYPES.contains(&param.param_type.as_str()) {
+            return Err(format!(
+                "パラメータ '{}' の型 '{}' はサポートされていません",
+                param.name, param.param_type
+            ));
+        }
+        if let Some(default) = &param.default {
+            check_param_value(param, default)?;
+        }
+    }
+
+    Ok(params)
+}
+
+/// パラメータ値が定義された型に変換可能かチェック
+fn check_param_value(param: &TemplateParam, value: &serde_json::Value) -> Result<(), String> {
+    let ok = match (param.param_type.as_str(), value) {
+        (_, serde_json::Value::Null) => true,
+        ("text", serde_json::Value::String(_)) => true,
+        ("integer", serde_json::Value::Number(n)) => n.is_i64() || n.is_u64(),
+        ("integer", serde_json::Value::String(s)) => s.trim().parse::<i64>().is_ok(),
+        ("real", serde_json::Value::Number(_)) => true,
+        ("real", serde_json::Value::String(s)) => s.trim().parse::<f64>().is_ok(),
+        ("boolean", serde_json::Value::Bool(_)) => true,
+        ("timestamp", serde_json::Value::String(s)) => {
+            chrono::DateTime::parse_from_rfc3339(s).is_ok()
+                || chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").is_ok()
+        }
+        _ => false,
+    };
+
+    if ok {
+        Ok(())
+    } else {
+        Err(format!(
+            "パラメータ '{}' の値 {} は {} 型として解釈できません",
+            param.name, value, param.param_type
+        ))
+    }
+}
+
+/// パラメータ値をバインド用の DuckDB 値に変換（check_param_value で検証済みであること）
+///
+/// timestamp は文字列のままバインドし、DuckDB 側で比較対象の型にキャストさせる。
+fn param_to_sql_value(param: &TemplateParam, value: &serde_json::Value) -> duckdb::types::Value {
+    use duckdb::types::Value;
+
+    match (param.param_type.as_str(), value) {
+        (_, serde_json::Value::Null) => Value::Null,
+        ("integer", serde_json::Value::Number(n)) => Value::BigInt(n.as_i64().unwrap_or_default()),
+        ("integer", serde_json::Value::String(s)) => {
+            Value::BigInt(s.trim().parse().unwrap_or_default())
+        }
+        ("real", serde_json::Value::Number(n)) => Value::Double(n.as_f64().unwrap_or_default()),
+        ("real", serde_json::Value::String(s)) => {
+            Value::Double(s.trim().parse().unwrap_or_default())
+        }
+        ("boolean", serde_json::Value::Bool(b)) => Value::Boolean(*b),
+        (_, serde_json::Value::String(s)) => Value::Text(s.clone()),
+        (_, other) => Value::Text(other.to_string()),
+    }
+}
+
+/// 定義のないパラメータの型を値から推定する
+fn infer_template_param(name: &str, value: &serde_json::Value) -> TemplateParam {
+    let param_type = match value {
+        serde_json::Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        serde_json::Value::Number(_) => "real",
+        serde_json::Value::Bool(_) => "boolean",
+        _ => "text",
+    };
+    TemplateParam {
+        name: name.to_string(),
+        param_type: param_type.to_string(),
+        default: None,
+    }
+}
+
+/// クエリのプレースホルダーに値を割り当て、`?` 形式のクエリとバインド値を返す
+///
+/// 値は必ずバインド変数として渡し、クエリ文字列には埋め込まない。
+/// 値が指定されていないパラメータは定義のデフォルト値を使用する。
+fn bind_template_params(
+    query: &str,
+    definitions: &[TemplateParam],
+    values: &HashMap<String, serde_json::Value>,
+) -> Result<(String, Vec<duckdb::types::Value>), String> {
+    let (rewritten, mut names) = rewrite_named_placeholders(query);
+    let positional = count_placeholders(query);
+    if positional > 0 && !names.is_empty() {
+        return Err("`?` と `:name` のプレースホルダーは混在できません".to_string());
+    }
+    if names.is_empty() {
+        // `?` 形式は定義順に対応付ける（定義がない場合は param1, param2, ...）
+        names = (0..positional)
+            .map(|i| {
+                definitions
+                    .get(i)
+                    .map(|d| d.name.clone())
+                    .unwrap_or_else(|| format!("param{}", i + 1))
+            })
+            .collect();
+    }
+
+    let mut bound = Vec::with_capacity(names.len());
+    for name in &names {
+        let definition = definitions.iter().find(|d| &d.name == name);
+        let value = values
+            .get(name)
+            .or_else(|| definition.and_then(|d| d.default.as_ref()))
+            .ok_or_else(|| format!("パラメータ '{}' の値が指定されていません", name))?;
+        let definition = definition
+            .cloned()
+            .unwrap_or_else(|| infer_template_param(name, value));
+        check_param_value(&definition, value)?;
+        bound.push(param_to_sql_value(&definition, value));
+    }
+
+    Ok((rewritten, bound))
+}
+
+/// DuckDBの列値をJSONに変換
+fn value_to_json(row: &Row, i: usize) -> serde_json::Value {
+    match row.get_ref(i) {
+        Ok(ValueRef::Null) => serde_json::Value::Null,
+        Ok(ValueRef::Boolean(b)) => serde_json::Value::Bool(b),
+        Ok(ValueRef::TinyInt(i)) => serde_json::json!(i),
+        Ok(ValueRef::SmallInt(i)) => serde_json::json!(i),
+        Ok(ValueRef::Int(i)) => serde_json::json!(i),
+        Ok(ValueRef::BigInt(i)) => serde_json::json!(i),
+        Ok(ValueRef::HugeInt(i)) => serde_json::json!(i),
+        Ok(ValueRef::UTinyInt(i)) => serde_json::json!(i),
+        Ok(ValueRef::USmallInt(i)) => serde_json::json!(i),
+        Ok(ValueRef::UInt(i)) => serde_json::json!(i),
+        Ok(ValueRef::UBigInt(i)) => serde_json::json!(i),
+        Ok(ValueRef::Float(f)) => serde_json::json!(f),
+        Ok(ValueRef::Double(f)) => serde_json::json!(f),
+        Ok(ValueRef::Decimal(d)) => serde_json::json!(d.to_string()),
+        Ok(ValueRef::Timestamp(unit, value)) => {
+            // Timestampを文字列に変換
+            let datetime = match unit {
+                TimeUnit::Second => Local.timestamp_opt(value, 0).single(),
+                TimeUnit::Millisecond => Local.timestamp_millis_opt(value).single(),
+                TimeUnit::Microsecond => Local.timestamp_micros(value).single(),
+                TimeUnit::Nanosecond => {
+                    let secs = value / 1_000_000_000;
+                    let nsecs = (value % 1_000_000_000) as u32;
+                    Local.timestamp_opt(secs, nsecs).single()
+                }
+            };
+            match datetime {
+                Some(dt) => {
+                    serde_json::Value::String(dt.format("%Y-%m-%d %H:%M:%S%.6f").to_string())
+                }
+                None => serde_json::Value::String(format!("<Invalid Timestamp: {}>", value)),
+            }
+        }
+        Ok(ValueRef::Text(s)) => serde_json::Value::String(String::from_utf8_lossy(s).to_string()),
+        Ok(ValueRef::Blob(b)) => serde_json::Value::String(format!("<BLOB {} bytes>", b.len())),
+        Ok(ValueRef::Date32(_)) => {
+            // Dateを文字列に変換
+            match row.get::<_, String>(i) {
+                Ok(s) => serde_json::Value::String(s),
+                Err(_) => serde_json::Value::Null,
+            }
+        }
+        Ok(ValueRef::Time64(_, _)) => {
+            // Timeを文字列に変換
+            match row.get::<_, String>(i) {
+                Ok(s) => serde_json::Value::String(s),
+                Err(_) => serde_json::Value::Null,
+            }
+        }
+        Ok(ValueRef::Interval { .. }) => serde_json::Value::String("<INTERVAL>".to_string()),
+        Ok(ValueRef::List(_, _)) => {
+            // 専用の関数でListを処理
+            extract_list_from_row(row, i)
+        }
+        Ok(ValueRef::Enum(_, _)) => {
+            // Enumを文字列に変換
+            match row.get::<_, String>(i) {
+                Ok(s) => serde_json::Value::String(s),
+                Err(_) => serde_json::Value::String("<ENUM>".to_string()),
+            }
+        }
+        Ok(ValueRef::Struct(..)) => {
+            // Structを文字列に変換
+            match row.get::<_, String>(i) {
+                Ok(s) => serde_json::Value::String(s),
+                Err(_) => serde_json::Value::String("<STRUCT>".to_string()),
+            }
+        }
+        Ok(ValueRef::Union(_, _)) => serde_json::Value::String("<UNION>".to_string()),
+        Ok(ValueRef::Map(_, _)) => serde_json::Value::String("<MAP>".to_string()),
+        Ok(ValueRef::Array(_, _)) => serde_json::Value::String("<ARRAY>".to_string()),
+        Err(_) => serde_json::Value::Null,
+    }
+}
+
+/// SELECT系クエリを実行し、列名と行データを収集
+fn run_select_query(
+    conn: &Connection,
+    query: &str,
+    params: &[duckdb::types::Value],
+    start_time: Instant,
+) -> Result<SqlQueryResult, String> {
+    // LIST型カラムを自動変換するための前処理
+    let processed_query = match preprocess_query_for_list_columns(conn, query) {
+        Ok(q) => q,
+        Err(e) => {
+            eprintln!(
+                "[SQL WARN] Failed to preprocess query: {}, using original query",
+                e
+            );
+            query.to_string()
+        }
+    };
+
+    let mut stmt = match conn.prepare(&processed_query) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("[SQL ERROR] Failed to prepare: {}", e);
+            return Err(format!("SQL構文エラー: {}", e));
+        }
+    };
+
+    eprintln!("[SQL] Statement prepared successfully");
+
+    // クエリを実行してRowsを取得
+    let mut rows = match stmt.query(duckdb::params_from_iter(params.iter())) {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("[SQL ERROR] Failed to execute query: {}", e);
+            return Err(format!("クエリ実行エラー: {}", e));
+        }
+    };
+
+    eprintln!("[SQL] Query executed, collecting rows...");
+
+    // カラム情報と行データを収集（カラム情報は最初の行から取得）
+    let mut columns: Vec<String> = Vec::new();
+    let mut row_data = Vec::new();
+
+    loop {
+        let row = match rows.next() {
+            Ok(Some(r)) => r,
+            Ok(None) => break,
+            Err(e) => {
+                eprintln!("[SQL ERROR] Failed to fetch row: {}", e);
+                return Err(format!("行データ取得エラー: {}", e));
+            }
+        };
+
+        let column_count = row.as_ref().column_count();
+        if columns.is_empty() {
+            columns = (0..column_count)
+                .filter_map(|i| row.as_ref().column_name(i).ok().map(|s| s.to_string()))
+                .collect();
+            eprintln!("[SQL] Columns: {:?}", columns);
+        }
+
+        row_data.push((0..column_count).map(|i| value_to_json(row, i)).collect());
+    }
+
+    let execution_time = start_time.elapsed().as_millis();
+
+    eprintln!(
+        "[SQL] Query completed: {} columns, {} rows, {}ms",
+        columns.len(),
+        row_data.len(),
+        execution_time
+    );
+
+    Ok(SqlQueryResult {
+        columns,
+        rows: row_data,
+        affected_rows: 0,
+        execution_time_ms: execution_time,
+    })
+}
+
+/// 読み取り専用クエリで禁止するキーワード（書き込み・外部DB接続・設定変更）
+const READ_ONLY_FORBIDDEN_KEYWORDS: [&str; 16] = [
+    "INSERT", "UPDATE", "DELETE", "ATTACH", "DETACH", "PRAGMA", "COPY", "CREATE", "DROP", "ALTER",
+    "TRUNCATE", "INSTALL", "LOAD", "EXPORT", "IMPORT", "SET",
+];
+
+/// 読み取り専用クエリで禁止する関数（ローカルファイル・外部データ・環境変数の読み取り）
+const READ_ONLY_FORBIDDEN_FUNCTIONS: [&str; 24] = [
+    "READ_TEXT",
+    "READ_BLOB",
+    "READ_CSV",
+    "READ_CSV_AUTO",
+    "SNIFF_CSV",
+    "READ_PARQUET",
+    "PARQUET_SCAN",
+    "PARQUET_METADATA",
+    "PARQUET_SCHEMA",
+    "READ_JSON",
+    "READ_JSON_AUTO",
+    "READ_JSON_OBJECTS",
+    "READ_JSON_OBJECTS_AUTO",
+    "READ_NDJSON",
+    "READ_NDJSON_AUTO",
+    "READ_NDJSON_OBJECTS",
+    "READ_XLSX",
+    "GLOB",
+    "ST_READ",
+    "SQLITE_SCAN",
+    "POSTGRES_SCAN",
+    "MYSQL_SCAN",
+    "DELTA_SCAN",
+    "GETENV",
+];
+
+/// 文字列リテラルを除去した位置に置くトークン（`FROM 'file.csv'` の検出用）
+const STRING_LITERAL_TOKEN: &str = "__STRING_LITERAL__";
+
+/// ファイルパスのような引用符付き識別子を除去した位置に置くトークン（`FROM "file.csv"` の検出用）
+const PATH_IDENTIFIER_TOKEN: &str = "__PATH_IDENTIFIER__";
+
+/// 読み取り専用クエリとして実行可能かチェック
+///
+/// SELECT / WITH で始まる単一ステートメントのみ許可し、
+/// 書き込み・外部DB接続・設定変更につながるキーワードを含むものは拒否する。
+/// DuckDB はクエリからローカルファイルを読めるため、ファイル読み取り関数と
+/// `FROM 'path'` 形式のファイル参照も拒否する。
+fn validate_read_only_query(query: &str) -> Result<(), String> {
+    // 文字列リテラルとコメントを除去してからキーワードを判定する
+    let mut stripped = String::with_capacity(query.len());
+    let chars: Vec<char> = query.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\'' | '"' => {
+                let quote = chars[i];
+                let start = i + 1;
+                i += 1;
+                while i < chars.len() && chars[i] != quote {
+                    i += 1;
+                }
+                let content: String = chars[start..i.min(chars.len())].iter().collect();
+                let placeholder = if quote == '\'' {
+                    STRING_LITERAL_TOKEN
+                } else if content.contains(['.', '/', '\\']) {
+                    PATH_IDENTIFIER_TOKEN
+                } else {
+                    ""
+                };
+                stripped.push_str(&format!(" {} ", placeholder));
+            }
+            '-' if chars.get(i + 1) == Some(&'-') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                stripped.push(' ');
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i + 1 < chars.len() && !(chars[i] == '*' && chars[i + 1] == '/') {
+                    i += 1;
+                }
+                i += 1;
+                stripped.push(' ');
+            }
+            c => stripped.push(c),
+        }
+        i += 1;
+    }
+
+    let body = stripped.trim().trim_end_matches(';');
+    if body.contains(';') {
+        return Err("複数のステートメントは実行できません".to_string());
+    }
+
+    let tokens: Vec<String> = body
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_uppercase())
+        .collect();
+
+    match tokens.first().map(String::as_str) {
+        Some("SELECT") | Some("WITH") => {}
+        Some(other) => {
+            return Err(format!(
+                "読み取り専用モードでは SELECT / WITH のみ実行できます（'{}' は不可）",
+                other
+            ))
+        }
+        None => return Err("クエリが空です".to_string()),
+    }
+
+    if let Some(keyword) = tokens
+        .iter()
+        .find(|t| READ_ONLY_FORBIDDEN_KEYWORDS.contains(&t.as_str()))
+    {
+        return Err(format!(
+            "読み取り専用モードでは '{}' を含むクエリは実行できません",
+            keyword
+        ));
+    }
+
+    if let Some(function) = tokens
+        .iter()
+        .find(|t| READ_ONLY_FORBIDDEN_FUNCTIONS.contains(&t.as_str()))
+    {
+        return Err(format!(
+            "読み取り専用モードではファイルを読み取る関数 '{}' は使用できません",
+            function.to_lowercase()
+        ));
+    }
+
+    // FROM / JOIN 句内の文字列リテラルはファイル参照として扱う（副問い合わせや条件句に入ったら解除）
+    let mut in_from = false;
+    let mut reads_file = false;
+    for token in &tokens {
+        match token.as_str() {
+            "FROM" | "JOIN" => in_from = true,
+            "SELECT" | "VALUES" | "WHERE" | "ON" | "USING" | "GROUP" | "HAVING" | "QUALIFY"
+            | "WINDOW" | "ORDER" | "LIMIT" | "UNION" | "EXCEPT" | "INTERSECT" => in_from = false,
+            PATH_IDENTIFIER_TOKEN => reads_file = true,
+            STRING_LITERAL_TOKEN if in_from => reads_file = true,
+            _ => {}
+        }
+    }
+    if reads_file {
+        return Err(
+            "読み取り専用モードではファイルを直接参照するクエリは実行できません".to_string(),
+        );
+    }
+
+    Ok(())
+}
+
+/// 読み取り専用でSQLクエリを実行し、結果を返す（保存済みテンプレートの実行用）
+///
+/// クエリ内の `:name` / `?` プレースホルダーには `params` の値をバインドする。
+/// `template_id` を指定した場合はテンプレートのパラメータ定義（型・デフォルト値）を使用する。
+#[tauri::command]
+pub async fn execute_sql_query(
+    db_manager: State<'_, DatabaseManager>,
+    query: String,
+    template_id: Option<i64>,
+    params: Option<HashMap<String, serde_json::Value>>,
+) -> Result<SqlQueryResult, String> {
+    let start_time = Instant::now();
+
+    validate_read_only_query(&query)?;
+    let query = query.trim().trim_end_matches(';').to_string();
+    let values = params.unwrap_or_default();
+
+    db_manager
+        .with_connection(|conn| {
+            let definitions = match template_id {
+                Some(id) => {
+                    SqlTemplateRepository::get_by_id(conn, id)
+                        .db_context("get sql template")
+                        .map_err(|e| e.to_string())?
+                        .ok_or_else(|| "Template not found".to_string())?
+                        .params
+                }
+                None => Vec::new(),
+            };
+            let (query, bound) = bind_template_params(&query, &definitions, &values)?;
+
+            eprintln!(
+                "[SQL] Executing read-only query ({} params): {}",
+                bound.len(),
+                query
+            );
+            run_select_query(conn, &query, &bound, start_time)
+        })
+        .await
 }
 
 /// 任意のSQLクエリを実行し、結果を返す
@@ -339,278 +897,7 @@ pub async fn execute_sql(
         || query_type == "DESCRIBE"
         || query_type == "PRAGMA"
     {
-        // SELECT系クエリの処理
-        // LIST型カラムを自動変換するための前処理
-        let processed_query = match preprocess_query_for_list_columns(conn, query_to_execute) {
-            Ok(q) => q,
-            Err(e) => {
-                eprintln!(
-                    "[SQL WARN] Failed to preprocess query: {}, using original query",
-                    e
-                );
-                query_to_execute.to_string()
-            }
-        };
-
-        let mut stmt = match conn.prepare(&processed_query) {
-            Ok(s) => s,
-            Err(e) => {
-                eprintln!("[SQL ERROR] Failed to prepare: {}", e);
-                return Err(format!("SQL構文エラー: {}", e));
-            }
-        };
-
-        eprintln!("[SQL] Statement prepared successfully");
-
-        // クエリを実行してRowsを取得
-        let mut rows = match stmt.query([]) {
-            Ok(r) => r,
-            Err(e) => {
-                eprintln!("[SQL ERROR] Failed to execute query: {}", e);
-                return Err(format!("クエリ実行エラー: {}", e));
-            }
-        };
-
-        eprintln!("[SQL] Query executed, collecting rows...");
-
-        // カラム情報と行データを収集
-        let mut columns: Vec<String> = Vec::new();
-        let mut row_data = Vec::new();
-        let mut column_count = 0;
-
-        // 最初の行からカラム情報を取得
-        let first_row_result = match rows.next() {
-            Ok(r) => r,
-            Err(e) => {
-                eprintln!("[SQL ERROR] Failed to fetch first row: {}", e);
-                return Err(format!("行データ取得エラー: {}", e));
-            }
-        };
-
-        if let Some(first_row) = first_row_result {
-            column_count = first_row.as_ref().column_count();
-            eprintln!("[SQL] Column count: {}", column_count);
-
-            columns = (0..column_count)
-                .filter_map(|i| {
-                    first_row
-                        .as_ref()
-                        .column_name(i)
-                        .ok()
-                        .map(|s| s.to_string())
-                })
-                .collect();
-
-            eprintln!("[SQL] Columns: {:?}", columns);
-
-            // 最初の行のデータを処理
-            let mut row_values = Vec::new();
-            for i in 0..column_count {
-                let value = match first_row.get_ref(i) {
-                    Ok(ValueRef::Null) => serde_json::Value::Null,
-                    Ok(ValueRef::Boolean(b)) => serde_json::Value::Bool(b),
-                    Ok(ValueRef::TinyInt(i)) => serde_json::json!(i),
-                    Ok(ValueRef::SmallInt(i)) => serde_json::json!(i),
-                    Ok(ValueRef::Int(i)) => serde_json::json!(i),
-                    Ok(ValueRef::BigInt(i)) => serde_json::json!(i),
-                    Ok(ValueRef::HugeInt(i)) => serde_json::json!(i),
-                    Ok(ValueRef::UTinyInt(i)) => serde_json::json!(i),
-                    Ok(ValueRef::USmallInt(i)) => serde_json::json!(i),
-                    Ok(ValueRef::UInt(i)) => serde_json::json!(i),
-                    Ok(ValueRef::UBigInt(i)) => serde_json::json!(i),
-                    Ok(ValueRef::Float(f)) => serde_json::json!(f),
-                    Ok(ValueRef::Double(f)) => serde_json::json!(f),
-                    Ok(ValueRef::Decimal(d)) => serde_json::json!(d.to_string()),
-                    Ok(ValueRef::Timestamp(unit, value)) => {
-                        // Timestampを文字列に変換
-                        let datetime = match unit {
-                            TimeUnit::Second => Local.timestamp_opt(value, 0).single(),
-                            TimeUnit::Millisecond => Local.timestamp_millis_opt(value).single(),
-                            TimeUnit::Microsecond => Local.timestamp_micros(value).single(),
-                            TimeUnit::Nanosecond => {
-                                let secs = value / 1_000_000_000;
-                                let nsecs = (value % 1_000_000_000) as u32;
-                                Local.timestamp_opt(secs, nsecs).single()
-                            }
-                        };
-                        match datetime {
-                            Some(dt) => serde_json::Value::String(
-                                dt.format("%Y-%m-%d %H:%M:%S%.6f").to_string(),
-                            ),
-                            None => {
-                                serde_json::Value::String(format!("<Invalid Timestamp: {}>", value))
-                            }
-                        }
-                    }
-                    Ok(ValueRef::Text(s)) => {
-                        serde_json::Value::String(String::from_utf8_lossy(s).to_string())
-                    }
-                    Ok(ValueRef::Blob(b)) => {
-                        serde_json::Value::String(format!("<BLOB {} bytes>", b.len()))
-                    }
-                    Ok(ValueRef::Date32(_)) => {
-                        // Dateを文字列に変換
-                        match first_row.get::<_, String>(i) {
-                            Ok(s) => serde_json::Value::String(s),
-                            Err(_) => serde_json::Value::Null,
-                        }
-                    }
-                    Ok(ValueRef::Time64(_, _)) => {
-                        // Timeを文字列に変換
-                        match first_row.get::<_, String>(i) {
-                            Ok(s) => serde_json::Value::String(s),
-                            Err(_) => serde_json::Value::Null,
-                        }
-                    }
-                    Ok(ValueRef::Interval { .. }) => {
-                        serde_json::Value::String("<INTERVAL>".to_string())
-                    }
-                    Ok(ValueRef::List(_, _)) => {
-                        // 専用の関数でListを処理
-                        extract_list_from_row(first_row, i)
-                    }
-                    Ok(ValueRef::Enum(_, _)) => {
-                        // Enumを文字列に変換
-                        match first_row.get::<_, String>(i) {
-                            Ok(s) => serde_json::Value::String(s),
-                            Err(_) => serde_json::Value::String("<ENUM>".to_string()),
-                        }
-                    }
-                    Ok(ValueRef::Struct(..)) => {
-                        // Structを文字列に変換
-                        match first_row.get::<_, String>(i) {
-                            Ok(s) => serde_json::Value::String(s),
-                            Err(_) => serde_json::Value::String("<STRUCT>".to_string()),
-                        }
-                    }
-                    Ok(ValueRef::Union(_, _)) => serde_json::Value::String("<UNION>".to_string()),
-                    Ok(ValueRef::Map(_, _)) => serde_json::Value::String("<MAP>".to_string()),
-                    Ok(ValueRef::Array(_, _)) => serde_json::Value::String("<ARRAY>".to_string()),
-                    Err(_) => serde_json::Value::Null,
-                };
-                row_values.push(value);
-            }
-            row_data.push(row_values);
-        }
-
-        // 残りの行データを収集
-        loop {
-            let row_result = match rows.next() {
-                Ok(r) => r,
-                Err(e) => {
-                    eprintln!("[SQL ERROR] Failed to fetch row: {}", e);
-                    return Err(format!("行データ取得エラー: {}", e));
-                }
-            };
-
-            let row = match row_result {
-                Some(r) => r,
-                None => break,
-            };
-            let mut row_values = Vec::new();
-            for i in 0..column_count {
-                let value = match row.get_ref(i) {
-                    Ok(ValueRef::Null) => serde_json::Value::Null,
-                    Ok(ValueRef::Boolean(b)) => serde_json::Value::Bool(b),
-                    Ok(ValueRef::TinyInt(i)) => serde_json::json!(i),
-                    Ok(ValueRef::SmallInt(i)) => serde_json::json!(i),
-                    Ok(ValueRef::Int(i)) => serde_json::json!(i),
-                    Ok(ValueRef::BigInt(i)) => serde_json::json!(i),
-                    Ok(ValueRef::HugeInt(i)) => serde_json::json!(i),
-                    Ok(ValueRef::UTinyInt(i)) => serde_json::json!(i),
-                    Ok(ValueRef::USmallInt(i)) => serde_json::json!(i),
-                    Ok(ValueRef::UInt(i)) => serde_json::json!(i),
-                    Ok(ValueRef::UBigInt(i)) => serde_json::json!(i),
-                    Ok(ValueRef::Float(f)) => serde_json::json!(f),
-                    Ok(ValueRef::Double(f)) => serde_json::json!(f),
-                    Ok(ValueRef::Decimal(d)) => serde_json::json!(d.to_string()),
-                    Ok(ValueRef::Timestamp(unit, value)) => {
-                        // Timestampを文字列に変換
-                        let datetime = match unit {
-                            TimeUnit::Second => Local.timestamp_opt(value, 0).single(),
-                            TimeUnit::Millisecond => Local.timestamp_millis_opt(value).single(),
-                            TimeUnit::Microsecond => Local.timestamp_micros(value).single(),
-                            TimeUnit::Nanosecond => {
-                                let secs = value / 1_000_000_000;
-                                let nsecs = (value % 1_000_000_000) as u32;
-                                Local.timestamp_opt(secs, nsecs).single()
-                            }
-                        };
-                        match datetime {
-                            Some(dt) => serde_json::Value::String(
-                                dt.format("%Y-%m-%d %H:%M:%S%.6f").to_string(),
-                            ),
-                            None => {
-                                serde_json::Value::String(format!("<Invalid Timestamp: {}>", value))
-                            }
-                        }
-                    }
-                    Ok(ValueRef::Text(s)) => {
-                        serde_json::Value::String(String::from_utf8_lossy(s).to_string())
-                    }
-                    Ok(ValueRef::Blob(b)) => {
-                        serde_json::Value::String(format!("<BLOB {} bytes>", b.len()))
-                    }
-                    Ok(ValueRef::Date32(_)) => {
-                        // Dateを文字列に変換
-                        match row.get::<_, String>(i) {
-                            Ok(s) => serde_json::Value::String(s),
-                            Err(_) => serde_json::Value::Null,
-                        }
-                    }
-                    Ok(ValueRef::Time64(_, _)) => {
-                        // Timeを文字列に変換
-                        match row.get::<_, String>(i) {
-                            Ok(s) => serde_json::Value::String(s),
-                            Err(_) => serde_json::Value::Null,
-                        }
-                    }
-                    Ok(ValueRef::Interval { .. }) => {
-                        serde_json::Value::String("<INTERVAL>".to_string())
-                    }
-                    Ok(ValueRef::List(_, _)) => {
-                        // 専用の関数でListを処理
-                        extract_list_from_row(row, i)
-                    }
-                    Ok(ValueRef::Enum(_, _)) => {
-                        // Enumを文字列に変換
-                        match row.get::<_, String>(i) {
-                            Ok(s) => serde_json::Value::String(s),
-                            Err(_) => serde_json::Value::String("<ENUM>".to_string()),
-                        }
-                    }
-                    Ok(ValueRef::Struct(..)) => {
-                        // Structを文字列に変換
-                        match row.get::<_, String>(i) {
-                            Ok(s) => serde_json::Value::String(s),
-                            Err(_) => serde_json::Value::String("<STRUCT>".to_string()),
-                        }
-                    }
-                    Ok(ValueRef::Union(_, _)) => serde_json::Value::String("<UNION>".to_string()),
-                    Ok(ValueRef::Map(_, _)) => serde_json::Value::String("<MAP>".to_string()),
-                    Ok(ValueRef::Array(_, _)) => serde_json::Value::String("<ARRAY>".to_string()),
-                    Err(_) => serde_json::Value::Null,
-                };
-                row_values.push(value);
-            }
-            row_data.push(row_values);
-        }
-
-        let execution_time = start_time.elapsed().as_millis();
-
-        eprintln!(
-            "[SQL] Query completed: {} columns, {} rows, {}ms",
-            columns.len(),
-            row_data.len(),
-            execution_time
-        );
-
-        SqlQueryResult {
-            columns,
-            rows: row_data,
-            affected_rows: 0,
-            execution_time_ms: execution_time,
-        }
+        run_select_query(conn, query_to_execute, &[], start_time)?
     } else {
         // INSERT/UPDATE/DELETE/CREATE/DROP等の処理
         let affected = match conn.execute(query_to_execute, &[] as &[&dyn duckdb::ToSql]) {
@@ -636,6 +923,53 @@ pub async fn execute_sql(
         .await
 }
 
+/// 外部の Parquet / CSV ファイルを一時ビューとして登録する
+///
+/// 登録したビューは `execute_sql_query` / `execute_sql` から既存テーブルと同様に参照できる。
+/// 一時ビューのためアプリを再起動すると消える。
+#[tauri::command]
+pub async fn import_external_data(
+    db_manager: State<'_, DatabaseManager>,
+    path: String,
+    table_name: String,
+    format: String,
+) -> Result<String, String> {
+    let data_format = ExternalDataFormat::from_name(&format)
+        .ok_or_else(|| format!("未対応のファイル形式です: {}（parquet / csv）", format))?;
+    if !is_valid_view_name(&table_name) {
+        return Err(format!(
+            "無効なテーブル名です: '{}'（英数字とアンダースコアのみ使用できます）",
+            table_name
+        ));
+    }
+
+    db_manager
+        .with_connection(|conn| {
+            if ExternalDataRepository::table_exists(conn, &table_name)
+                .db_context("check table name")
+                .map_err(|e| e.to_string())?
+            {
+                return Err(format!(
+                    "テーブル '{}' は既に存在します。別の名前を指定してください",
+                    table_name
+                ));
+            }
+
+            let rows = ExternalDataRepository::register_view(conn, &path, &table_name, data_format)
+                .db_context("register external data")
+                .map_err(|e| e.to_string())?;
+            eprintln!(
+                "[SQL] Registered external data '{}' as view '{}' ({} rows)",
+                path, table_name, rows
+            );
+            Ok(format!(
+                "Registered {} rows from {} as {}",
+                rows, path, table_name
+            ))
+        })
+        .await
+}
+
 /// 全てのSQLテンプレートを取得
 #[tauri::command]
 pub async fn list_sql_templates(
@@ -652,6 +986,8 @@ pub async fn save_sql_template(
     db_manager: State<'_, DatabaseManager>,
     request: SaveTemplateRequest,
 ) -> Result<SqlTemplate, String> {
+    let params = resolve_template_params(&request.query, request.params)?;
+
     db_manager
         .with_connection(|conn| {
             SqlTemplateRepository::save(
@@ -660,12 +996,56 @@ pub async fn save_sql_template(
                 &request.name,
                 &request.description,
                 &request.query,
+                &params,
             )
             .map_err(|e| e.to_string())
         })
         .await
 }
 
+/// テンプレート実行前にパラメータの数と型を検証
+#[tauri::command]
+pub async fn validate_template_params(
+    db_manager: State<'_, DatabaseManager>,
+    id: i64,
+    params: Vec<serde_json::Value>,
+) -> Result<TemplateParamValidation, String> {
+    let template = db_manager
+        .with_connection(|conn| {
+            SqlTemplateRepository::get_by_id(conn, id)
+                .db_context("get sql template")
+                .map_err(|e| e.to_string())
+        })
+        .await?
+        .ok_or_else(|| "Template not found".to_string())?;
+
+    // params カラム追加前に保存されたテンプレートはクエリから定義を補完する
+    let definitions = if template.params.is_empty() {
+        resolve_template_params(&template.query, None)?
+    } else {
+        template.params
+    };
+
+    let expected = definitions.len();
+    if params.len() != expected {
+        return Err(format!(
+            "パラメータ数が一致しません: 必要 {} 個, 指定 {} 個",
+            expected,
+            params.len()
+        ));
+    }
+
+    for (definition, value) in definitions.iter().zip(params.iter()) {
+        check_param_value(definition, value)?;
+    }
+
+    Ok(TemplateParamValidation {
+        expected,
+        provided: params.len(),
+        params: definitions,
+    })
+}
+
 /// SQLテンプレートを削除
 #[tauri::command]
 pub async fn delete_sql_template(
@@ -759,3 +1139,46 @@ pub async fn list_database_tables(
         })
         .await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_read_only_query_allows_selects() {
+        assert!(validate_read_only_query("SELECT * FROM streams;").is_ok());
+        assert!(validate_read_only_query(
+            "WITH s AS (SELECT id FROM streams) SELECT * FROM s WHERE title = 'read_csv'"
+        )
+        .is_ok());
+        assert!(validate_read_only_query(r#"SELECT "channel_name" FROM "channels""#).is_ok());
+    }
+
+    #[test]
+    fn test_validate_read_only_query_rejects_writes() {
+        assert!(validate_read_only_query("DELETE FROM streams").is_err());
+        assert!(validate_read_only_query("SELECT 1; DROP TABLE streams").is_err());
+        assert!(validate_read_only_query(
+            "WITH x AS (SELECT 1) INSERT INTO streams SELECT * FROM x"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_validate_read_only_query_rejects_file_access() {
+        for query in [
+            "SELECT * FROM read_text('/etc/passwd')",
+            "SELECT * FROM read_csv('secrets.csv')",
+            "SELECT * FROM READ_PARQUET('data/*.parquet')",
+            "SELECT * FROM glob('/home/*')",
+            "SELECT content FROM streams, read_blob('key.pem')",
+            "SELECT * FROM 'secrets.csv'",
+            "SELECT * FROM streams JOIN 'other.parquet' USING (id)",
+            "SELECT * FROM streams s, 'other.csv' o",
+            r#"SELECT * FROM "secrets.csv""#,
+            "SELECT getenv('HOME')",
+        ] {
+            assert!(validate_read_only_query(query).is_err(), "{}", query);
+        }
+    }
+}
@@ -1,11 +1,14 @@
+use crate::constants::poller as poller_constants;
 use crate::database::{
     models::StreamStats,
     repositories::{
         chat_message_repository::ChatMessageRepository,
-        stream_stats_repository::StreamStatsRepository,
+        stream_stats_repository::StreamStatsRepository, ChannelRepository, ChannelSummary,
+        LiveChannel, ScheduledStream, ScheduledStreamRepository, StreamRepository,
     },
     DatabaseManager,
 };
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, State};
 
@@ -38,14 +41,73 @@ pub async fn get_stream_stats(
         .await
 }
 
+/// 直近1分間のチャットメッセージ数を取得（stream_id 指定時はその配信のみ）
 #[tauri::command]
 pub async fn get_realtime_chat_rate(
     _app_handle: AppHandle,
     db_manager: State<'_, DatabaseManager>,
+    stream_id: Option<i64>,
 ) -> Result<i64, String> {
     db_manager
         .with_connection(|conn| {
-            ChatMessageRepository::get_realtime_chat_rate(conn).map_err(|e| e.to_string())
+            ChatMessageRepository::get_realtime_chat_rate(conn, stream_id)
+                .map_err(|e| e.to_string())
+        })
+        .await
+}
+
+/// チャンネルの総合サマリーを取得
+///
+/// 全期間の総配信回数・総配信時間・平均/最高視聴者数・フォロワー純増に加え、
+/// 直近30日とその前の30日の集計、および前期間比を返す。
+#[tauri::command]
+pub async fn get_channel_stats(
+    db_manager: State<'_, DatabaseManager>,
+    channel_id: i64,
+) -> Result<ChannelSummary, String> {
+    let now = Utc::now().to_rfc3339();
+    db_manager
+        .with_connection(|conn| {
+            StreamRepository::get_channel_summary(conn, channel_id, &now)
+                .map_err(|e| format!("Failed to get channel stats: {}", e))
+        })
+        .await
+}
+
+/// 現在ライブ中のチャンネルを最新の視聴者数・タイトル・カテゴリ付きで取得
+///
+/// `stale_minutes`（省略時は LIVE_STREAM_STALE_MINUTES）以上 stream_stats が更新されていない
+/// 配信は、終了を検知できずに残ったゴースト配信とみなして除外する。
+#[tauri::command]
+pub async fn get_live_channels(
+    db_manager: State<'_, DatabaseManager>,
+    stale_minutes: Option<i64>,
+) -> Result<Vec<LiveChannel>, String> {
+    let stale_minutes = stale_minutes
+        .unwrap_or(poller_constants::LIVE_STREAM_STALE_MINUTES)
+        .max(1);
+    let stale_cutoff = (Utc::now() - chrono::Duration::minutes(stale_minutes)).to_rfc3339();
+
+    db_manager
+        .with_connection(|conn| {
+            ChannelRepository::list_live_channels(conn, &stale_cutoff).map_err(|e| e.to_string())
+        })
+        .await
+}
+
+/// 今後の配信予定を開始予定順に取得（channel_id 省略時は全チャンネル）
+///
+/// 配信予定はポーリング中の手動登録チャンネルについて定期的に取得・更新される。
+#[tauri::command]
+pub async fn get_scheduled_streams(
+    db_manager: State<'_, DatabaseManager>,
+    channel_id: Option<i64>,
+) -> Result<Vec<ScheduledStream>, String> {
+    let now = Utc::now();
+    db_manager
+        .with_connection(|conn| {
+            ScheduledStreamRepository::list_upcoming(conn, &now, channel_id)
+                .map_err(|e| e.to_string())
         })
         .await
 }
@@ -1,6 +1,9 @@
-use crate::collectors::poller::ChannelPoller;
+use crate::collectors::poller::{ChannelPoller, CollectorStatus};
+use crate::websocket::twitch_irc::{ChatConnectionStatus, IrcChannelStatus};
+use chrono::Local;
+use serde::Serialize;
 use std::sync::Arc;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Manager, State};
 use tokio::sync::Mutex;
 
 #[tauri::command]
@@ -16,3 +19,52 @@ pub async fn is_backend_ready(app_handle: AppHandle) -> Result<bool, String> {
         Ok(false) // ChannelPollerがまだ登録されていない
     }
 }
+
+/// UI再接続時に状態を復元するためのライブ状態スナップショット
+#[derive(Debug, Serialize)]
+pub struct LiveSnapshot {
+    pub channels: Vec<CollectorStatus>,
+    pub irc_channels: Vec<IrcChannelStatus>,
+    pub generated_at: String,
+}
+
+/// メモリ上のライブ状態（ポーラーの最新サンプル・IRC接続状態）を一括で取得
+///
+/// DBを参照せずに返すため、フロントエンドのリロード直後でも即座に状態を復元できる。
+#[tauri::command]
+pub async fn get_live_snapshot(
+    poller: State<'_, Arc<Mutex<ChannelPoller>>>,
+) -> Result<LiveSnapshot, String> {
+    // ポーラーのロックは状態のコピー中のみ保持する
+    let (channels, twitch_collector) = {
+        let poller = poller.lock().await;
+        (
+            poller.get_statuses(),
+            poller.get_twitch_collector().cloned(),
+        )
+    };
+
+    let irc_channels = match twitch_collector {
+        Some(collector) => collector.get_irc_statuses().await,
+        None => Vec::new(),
+    };
+
+    Ok(LiveSnapshot {
+        channels,
+        irc_channels,
+        generated_at: Local::now().to_rfc3339(),
+    })
+}
+
+/// JOIN済みのTwitch IRCチャンネル一覧と収集状況を取得（デバッグ・ダッシュボード用）
+#[tauri::command]
+pub async fn get_active_chat_connections(
+    poller: State<'_, Arc<Mutex<ChannelPoller>>>,
+) -> Result<Vec<ChatConnectionStatus>, String> {
+    let twitch_collector = poller.lock().await.get_twitch_collector().cloned();
+
+    match twitch_collector {
+        Some(collector) => Ok(collector.get_active_chat_connections().await),
+        None => Ok(Vec::new()),
+    }
+}
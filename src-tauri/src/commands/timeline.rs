@@ -1,7 +1,14 @@
-use crate::database::repositories::{StreamInfo, StreamRepository, TimelinePoint};
+use crate::collectors::thumbnail_cache::ThumbnailCache;
+use crate::constants::database as db_constants;
+use crate::database::aggregation::{parse_timeline_resolution, DataAggregator};
+use crate::database::repositories::{
+    AggregateViewerPoint, CategoryStat, FragmentedStreamGroup, HeatmapCell, PeakMoment,
+    RetentionBaseline, RetentionPoint, StreamInfo, StreamRepository, TimelinePoint,
+};
 use crate::database::DatabaseManager;
+use chrono::{Duration, NaiveDateTime};
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use tauri::{AppHandle, State};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CategoryChange {
@@ -17,6 +24,28 @@ pub struct TitleChange {
     pub to_title: String,
 }
 
+/// 視聴者数またはチャット速度が急増した区間
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Highlight {
+    /// 急増した指標（"viewer_count" / "chat_rate"）
+    pub metric: String,
+    pub start_time: String,
+    pub peak_time: String,
+    pub end_time: String,
+    /// 区間開始直前 N 分の平均値
+    pub baseline: f64,
+    pub peak_value: i32,
+    /// ピーク時点の直前平均に対する増加率（0.5 = +50%）
+    pub growth_rate: f64,
+}
+
+/// 配信中のカテゴリごとの滞在時間
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryDuration {
+    pub category: String,
+    pub minutes: f64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamTimelineData {
     pub stream_info: StreamInfo,
@@ -41,6 +70,19 @@ pub async fn get_channel_streams(
         .await
 }
 
+/// キャッシュ済みの配信サムネイル画像のローカルパスを取得
+///
+/// `cache_thumbnails` が無効、またはまだキャッシュされていない場合は None を返す。
+/// フロントエンドでは `convertFileSrc` で表示し、None の場合は `thumbnail_url` を直接使う。
+#[tauri::command]
+pub async fn get_cached_thumbnail_path(
+    app_handle: AppHandle,
+    stream_id: i64,
+) -> Result<Option<String>, String> {
+    let path = ThumbnailCache::path_for(&app_handle, stream_id)?;
+    Ok(path.exists().then(|| path.to_string_lossy().to_string()))
+}
+
 /// 日付範囲で配信一覧を取得（全チャンネル・カレンダー用）
 #[tauri::command]
 pub async fn get_streams_by_date_range(
@@ -59,40 +101,370 @@ pub async fn get_streams_by_date_range(
 }
 
 /// 比較用：基準配信と時間帯が重なる配信をサジェスト（全チャンネル・カテゴリ・時間帯）
+///
+/// `viewer_range` を指定すると peak_viewers が基準配信の (下限倍率, 上限倍率) の範囲内の配信に絞り込む（例: [0.5, 2.0]）。
 #[tauri::command]
 pub async fn get_suggested_streams_for_comparison(
     base_stream_id: i64,
     limit: Option<i32>,
+    viewer_range: Option<(f64, f64)>,
     db_manager: State<'_, DatabaseManager>,
 ) -> Result<Vec<StreamInfo>, String> {
+    if let Some((min_ratio, max_ratio)) = viewer_range {
+        if !(min_ratio.is_finite() && max_ratio.is_finite())
+            || min_ratio < 0.0
+            || min_ratio > max_ratio
+        {
+            return Err(format!(
+                "viewer_range must satisfy 0 <= min <= max: ({}, {})",
+                min_ratio, max_ratio
+            ));
+        }
+    }
+
     db_manager
         .with_connection(|conn| {
-            StreamRepository::get_suggested_streams_for_comparison(conn, base_stream_id, limit)
-                .map_err(|e| format!("Failed to get suggested streams: {}", e))
+            StreamRepository::get_suggested_streams_for_comparison(
+                conn,
+                base_stream_id,
+                limit,
+                viewer_range,
+            )
+            .map_err(|e| format!("Failed to get suggested streams: {}", e))
         })
         .await
 }
 
+/// 欠損補間の既定の間隔（秒）
+const DEFAULT_FILL_INTERVAL_SECONDS: i64 = 60;
+
+/// 欠損補間で指定できる間隔の範囲（秒）
+const MIN_FILL_INTERVAL_SECONDS: i64 = 10;
+const MAX_FILL_INTERVAL_SECONDS: i64 = 3600;
+
+/// `fill_gaps` / `interval_seconds` から補間の間隔（秒）を決める（補間しない場合は None）
+fn fill_interval_seconds(
+    fill_gaps: Option<bool>,
+    interval_seconds: Option<i64>,
+) -> Result<Option<i64>, String> {
+    if !fill_gaps.unwrap_or(false) {
+        return Ok(None);
+    }
+    let interval = interval_seconds.unwrap_or(DEFAULT_FILL_INTERVAL_SECONDS);
+    if !(MIN_FILL_INTERVAL_SECONDS..=MAX_FILL_INTERVAL_SECONDS).contains(&interval) {
+        return Err(format!(
+            "interval_seconds must be between {} and {}: {}",
+            MIN_FILL_INTERVAL_SECONDS, MAX_FILL_INTERVAL_SECONDS, interval
+        ));
+    }
+    Ok(Some(interval))
+}
+
 /// 特定配信のタイムラインデータを取得
+///
+/// `resolution` は "raw"（既定）/ "5m" / "15m"。長時間配信では集計済みの粒度を使うと点数を抑えられる。
+/// 集計済みの粒度は定期タスクで更新されるため、直近のバケットは最大で更新間隔分遅れる。
+/// `fill_gaps` を指定すると `interval_seconds`（既定60秒）間隔のグリッドに揃え、欠損は直前の値で補間する。
 #[tauri::command]
 pub async fn get_stream_timeline(
     stream_id: i64,
+    resolution: Option<String>,
+    fill_gaps: Option<bool>,
+    interval_seconds: Option<i64>,
     db_manager: State<'_, DatabaseManager>,
 ) -> Result<StreamTimelineData, String> {
+    let resolution_minutes = parse_timeline_resolution(resolution.as_deref())?;
+    let fill_interval = fill_interval_seconds(fill_gaps, interval_seconds)?;
+
     db_manager
         .with_connection(|conn| {
-            get_stream_timeline_internal(conn, stream_id)
+            get_stream_timeline_internal(conn, stream_id, resolution_minutes, fill_interval)
                 .map_err(|e| format!("Failed to get stream timeline: {}", e))
         })
         .await
 }
 
+/// 配信の視聴者維持率（リテンション）カーブを取得
+///
+/// `baseline` は "peak"（既定）/ "start"。複数配信を絶対数ではなく形で比較するために使う。
+#[tauri::command]
+pub async fn get_retention_curve(
+    stream_id: i64,
+    baseline: Option<RetentionBaseline>,
+    db_manager: State<'_, DatabaseManager>,
+) -> Result<Vec<RetentionPoint>, String> {
+    db_manager
+        .with_connection(|conn| {
+            StreamRepository::get_retention_curve(
+                conn,
+                stream_id,
+                baseline.unwrap_or(RetentionBaseline::Peak),
+            )
+            .map_err(|e| format!("Failed to get retention curve: {}", e))
+        })
+        .await
+}
+
+/// 配信の最大同時視聴者数を記録した時点（タイトル・カテゴリ付き）を取得
+#[tauri::command]
+pub async fn get_peak_moment(
+    stream_id: i64,
+    db_manager: State<'_, DatabaseManager>,
+) -> Result<Option<PeakMoment>, String> {
+    db_manager
+        .with_connection(|conn| {
+            StreamRepository::get_peak_moment(conn, stream_id)
+                .map_err(|e| format!("Failed to get peak moment: {}", e))
+        })
+        .await
+}
+
+/// 複数チャンネルの合計同時視聴者数の推移を取得（チャンネル横断ダッシュボード用）
+///
+/// `bucket_minutes` を省略した場合は5分単位で集計する。
+#[tauri::command]
+pub async fn get_aggregate_viewers(
+    channel_ids: Vec<i64>,
+    from: String,
+    to: String,
+    bucket_minutes: Option<i32>,
+    db_manager: State<'_, DatabaseManager>,
+) -> Result<Vec<AggregateViewerPoint>, String> {
+    db_manager
+        .with_connection(|conn| {
+            StreamRepository::get_aggregate_viewers(
+                conn,
+                &channel_ids,
+                &from,
+                &to,
+                bucket_minutes.unwrap_or(5),
+            )
+            .map_err(|e| format!("Failed to get aggregate viewers: {}", e))
+        })
+        .await
+}
+
+/// チャンネルのカテゴリ（ゲーム）別累計統計を取得
+#[tauri::command]
+pub async fn get_category_stats(
+    channel_id: i64,
+    db_manager: State<'_, DatabaseManager>,
+) -> Result<Vec<CategoryStat>, String> {
+    db_manager
+        .with_connection(|conn| {
+            StreamRepository::get_category_stats(conn, channel_id)
+                .map_err(|e| format!("Failed to get category stats: {}", e))
+        })
+        .await
+}
+
+/// 配信開始の曜日×時間帯ごとの平均視聴者数を取得（ローカル時刻基準の 7x24 グリッド）
+#[tauri::command]
+pub async fn get_viewer_heatmap(
+    channel_id: i64,
+    db_manager: State<'_, DatabaseManager>,
+) -> Result<Vec<HeatmapCell>, String> {
+    db_manager
+        .with_connection(|conn| {
+            StreamRepository::get_viewer_heatmap(conn, channel_id)
+                .map_err(|e| format!("Failed to get viewer heatmap: {}", e))
+        })
+        .await
+}
+
+/// 瞬断などで複数の配信レコードに分断されたとみられる配信のグループを取得
+///
+/// `max_gap_minutes` 省略時は `STREAM_MERGE_MAX_GAP_MINUTES` 分以内の間隔を分断とみなす。
+#[tauri::command]
+pub async fn get_fragmented_streams(
+    channel_id: i64,
+    max_gap_minutes: Option<i64>,
+    db_manager: State<'_, DatabaseManager>,
+) -> Result<Vec<FragmentedStreamGroup>, String> {
+    let max_gap_secs = max_gap_minutes
+        .unwrap_or(db_constants::STREAM_MERGE_MAX_GAP_MINUTES)
+        .max(0)
+        * 60;
+    db_manager
+        .with_connection(|conn| {
+            StreamRepository::find_fragmented_streams(conn, channel_id, max_gap_secs)
+                .map_err(|e| format!("Failed to find fragmented streams: {}", e))
+        })
+        .await
+}
+
+/// 同一チャンネルの複数の配信を1本に統合し、統合先の配信IDを返す
+///
+/// 統合先は最も遅く開始した配信とする。進行中の配信を含む場合でも、
+/// プラットフォームの配信IDが残るため以降の収集はそのまま統合先に記録される。
+#[tauri::command]
+pub async fn merge_streams(
+    stream_ids: Vec<i64>,
+    db_manager: State<'_, DatabaseManager>,
+) -> Result<i64, String> {
+    let mut stream_ids = stream_ids;
+    stream_ids.sort_unstable();
+    stream_ids.dedup();
+    if stream_ids.len() < 2 {
+        return Err("At least two streams are required to merge".to_string());
+    }
+
+    db_manager
+        .with_connection(|conn| {
+            let streams = StreamRepository::get_stream_channels(conn, &stream_ids)
+                .map_err(|e| format!("Failed to get streams: {}", e))?;
+            if streams.len() != stream_ids.len() {
+                return Err("Some streams were not found".to_string());
+            }
+            if streams
+                .iter()
+                .any(|&(_, channel_id)| channel_id != streams[0].1)
+            {
+                return Err("Only streams of the same channel can be merged".to_string());
+            }
+
+            // 開始時刻順に並んでいるため、最後の配信を統合先にする
+            let (target_id, _) = streams[streams.len() - 1];
+            let source_ids: Vec<i64> = streams[..streams.len() - 1]
+                .iter()
+                .map(|&(id, _)| id)
+                .collect();
+            StreamRepository::merge_streams(conn, target_id, &source_ids)
+                .map_err(|e| format!("Failed to merge streams: {}", e))?;
+            eprintln!(
+                "[merge_streams] Merged streams {:?} into {}",
+                source_ids, target_id
+            );
+            Ok(target_id)
+        })
+        .await
+}
+
+/// 複数配信のタイムラインを一括取得（比較表示用）
+///
+/// 各 TimelinePoint の `elapsed_minutes` を使うと、開始時刻の異なる配信を同じX軸で重ね描きできる。
+/// `fill_gaps` / `interval_seconds` は get_stream_timeline と同じ。
+#[tauri::command]
+pub async fn get_streams_comparison(
+    stream_ids: Vec<i64>,
+    fill_gaps: Option<bool>,
+    interval_seconds: Option<i64>,
+    db_manager: State<'_, DatabaseManager>,
+) -> Result<Vec<StreamTimelineData>, String> {
+    if stream_ids.is_empty() {
+        return Ok(vec![]);
+    }
+    let fill_interval = fill_interval_seconds(fill_gaps, interval_seconds)?;
+
+    db_manager
+        .with_connection(|conn| {
+            stream_ids
+                .iter()
+                .map(|&stream_id| {
+                    get_stream_timeline_internal(conn, stream_id, None, fill_interval).map_err(
+                        |e| format!("Failed to get stream timeline (id={}): {}", stream_id, e),
+                    )
+                })
+                .collect()
+        })
+        .await
+}
+
+/// 配信中のカテゴリ滞在時間の内訳を取得（滞在時間の長い順）
+///
+/// `detect_category_changes` の変化点で区間を区切り、最初の区間は配信開始から、
+/// 最後の区間は ended_at（配信中は最新の collected_at）までとして分数を合計する。
+#[tauri::command]
+pub async fn get_category_durations(
+    stream_id: i64,
+    db_manager: State<'_, DatabaseManager>,
+) -> Result<Vec<CategoryDuration>, String> {
+    db_manager
+        .with_connection(|conn| {
+            let stats = StreamRepository::get_timeline_stats(conn, stream_id)
+                .map_err(|e| format!("Failed to get stream timeline: {}", e))?;
+            let end_minutes = StreamRepository::get_stream_end_elapsed_minutes(conn, stream_id)
+                .map_err(|e| format!("Failed to get stream end time: {}", e))?
+                .unwrap_or_else(|| stats.last().map_or(0.0, |p| p.elapsed_minutes));
+            Ok(category_durations(&stats, end_minutes))
+        })
+        .await
+}
+
+/// 急増判定の既定しきい値（直前平均からの増加率）
+const DEFAULT_HIGHLIGHT_THRESHOLD: f64 = 0.5;
+
+/// 直前平均を取る既定の区間（分）
+const DEFAULT_HIGHLIGHT_WINDOW_MINUTES: f64 = 10.0;
+
+/// 配信の盛り上がり区間を検出
+///
+/// 各時点の視聴者数・chat_rate を直前 `window_minutes` 分の平均と比べ、
+/// 増加率が `threshold`（既定 0.5 = +50%）以上の連続区間を1つのハイライトとして返す。
+#[tauri::command]
+pub async fn detect_highlights(
+    stream_id: i64,
+    threshold: Option<f64>,
+    window_minutes: Option<f64>,
+    db_manager: State<'_, DatabaseManager>,
+) -> Result<Vec<Highlight>, String> {
+    let threshold = threshold
+        .filter(|t| *t > 0.0)
+        .unwrap_or(DEFAULT_HIGHLIGHT_THRESHOLD);
+    let window_minutes = window_minutes
+        .filter(|w| *w > 0.0)
+        .unwrap_or(DEFAULT_HIGHLIGHT_WINDOW_MINUTES);
+
+    let stats = db_manager
+        .with_connection(|conn| {
+            StreamRepository::get_timeline_stats(conn, stream_id)
+                .map_err(|e| format!("Failed to get stream timeline: {}", e))
+        })
+        .await?;
+
+    let mut highlights = detect_metric_spikes(
+        &stats,
+        "viewer_count",
+        |p| p.viewer_count,
+        threshold,
+        window_minutes,
+    );
+    highlights.extend(detect_metric_spikes(
+        &stats,
+        "chat_rate",
+        |p| p.chat_rate_1min,
+        threshold,
+        window_minutes,
+    ));
+    highlights.sort_by(|a, b| a.start_time.cmp(&b.start_time));
+
+    Ok(highlights)
+}
+
 fn get_stream_timeline_internal(
     conn: &duckdb::Connection,
     stream_id: i64,
+    resolution_minutes: Option<i32>,
+    fill_interval_seconds: Option<i64>,
 ) -> Result<StreamTimelineData, Box<dyn std::error::Error + Send + Sync>> {
     let stream_info = StreamRepository::get_stream_info_by_id(conn, stream_id)?;
-    let stats = StreamRepository::get_timeline_stats(conn, stream_id)?;
+    let mut stats = match resolution_minutes {
+        Some(resolution) => {
+            // 集計は定期タスクに任せ、読み取り時は既存のロールアップを返す。
+            // 定期更新前でまだ1件も集計されていない配信のみ、その場で対象配信を集計する
+            let rollup = StreamRepository::get_timeline_rollup(conn, stream_id, resolution)?;
+            if rollup.is_empty() {
+                DataAggregator::refresh_stream_stats_rollup(conn, resolution, Some(stream_id))?;
+                StreamRepository::get_timeline_rollup(conn, stream_id, resolution)?
+            } else {
+                rollup
+            }
+        }
+        None => StreamRepository::get_timeline_stats(conn, stream_id)?,
+    };
+    if let Some(interval) = fill_interval_seconds {
+        stats = fill_timeline_gaps(&stats, interval);
+    }
     let category_changes = detect_category_changes(&stats);
     let title_changes = detect_title_changes(&stats);
 
@@ -104,6 +476,54 @@ fn get_stream_timeline_internal(
     })
 }
 
+/// 前値補間を続ける最大の欠損（グリッド間隔の倍数）
+const MAX_FILL_GAP_INTERVALS: i32 = 2;
+
+/// タイムラインを最初の収集時刻から `interval_seconds` 間隔のグリッドに揃える
+///
+/// 各グリッド時刻には、その時刻以前で最も新しい収集値を入れる（前値補間）。
+/// 直前の収集から `MAX_FILL_GAP_INTERVALS` 間隔を超えた時刻は、収集が途切れた区間として数値の指標を None にする。
+/// 収集時刻を解釈できない点は除外し、1点も解釈できない場合は元の点をそのまま返す。
+fn fill_timeline_gaps(stats: &[TimelinePoint], interval_seconds: i64) -> Vec<TimelinePoint> {
+    let samples: Vec<(NaiveDateTime, &TimelinePoint)> = stats
+        .iter()
+        .filter_map(|p| {
+            NaiveDateTime::parse_from_str(&p.collected_at, "%Y-%m-%d %H:%M:%S%.f")
+                .ok()
+                .map(|t| (t, p))
+        })
+        .collect();
+    let (Some(&(start, _)), Some(&(end, _))) = (samples.first(), samples.last()) else {
+        return stats.to_vec();
+    };
+
+    let step = Duration::seconds(interval_seconds);
+    let max_gap = step * MAX_FILL_GAP_INTERVALS;
+    let mut filled = Vec::new();
+    let mut index = 0;
+    let mut time = start;
+    while time <= end {
+        while index + 1 < samples.len() && samples[index + 1].0 <= time {
+            index += 1;
+        }
+        let (sample_time, sample) = samples[index];
+        let mut point = TimelinePoint {
+            collected_at: time.format("%Y-%m-%d %H:%M:%S").to_string(),
+            elapsed_minutes: sample.elapsed_minutes
+                + (time - sample_time).num_milliseconds() as f64 / 60_000.0,
+            ..sample.clone()
+        };
+        if time - sample_time > max_gap {
+            point.viewer_count = None;
+            point.chat_rate_1min = None;
+            point.follower_count = None;
+        }
+        filled.push(point);
+        time += step;
+    }
+    filled
+}
+
 fn detect_category_changes(stats: &[TimelinePoint]) -> Vec<CategoryChange> {
     let mut changes = Vec::new();
     let mut prev_category: Option<String> = None;
@@ -126,6 +546,37 @@ fn detect_category_changes(stats: &[TimelinePoint]) -> Vec<CategoryChange> {
     changes
 }
 
+/// カテゴリ変化点で区切った区間の長さをカテゴリごとに合計する
+fn category_durations(stats: &[TimelinePoint], end_minutes: f64) -> Vec<CategoryDuration> {
+    let Some(first) = stats.iter().find(|p| !p.category.is_empty()) else {
+        return Vec::new();
+    };
+
+    // (カテゴリ, 区間開始の経過分数)
+    let mut segments = vec![(first.category.clone(), 0.0)];
+    for change in detect_category_changes(stats) {
+        if let Some(point) = stats.iter().find(|p| p.collected_at == change.timestamp) {
+            segments.push((change.to_category, point.elapsed_minutes));
+        }
+    }
+
+    let mut durations: Vec<CategoryDuration> = Vec::new();
+    for (i, (category, start)) in segments.iter().enumerate() {
+        let end = segments.get(i + 1).map_or(end_minutes, |(_, next)| *next);
+        let minutes = (end - start).max(0.0);
+        match durations.iter_mut().find(|d| &d.category == category) {
+            Some(duration) => duration.minutes += minutes,
+            None => durations.push(CategoryDuration {
+                category: category.clone(),
+                minutes,
+            }),
+        }
+    }
+
+    durations.sort_by(|a, b| b.minutes.total_cmp(&a.minutes));
+    durations
+}
+
 fn detect_title_changes(stats: &[TimelinePoint]) -> Vec<TitleChange> {
     let mut changes = Vec::new();
     let mut prev_title: Option<String> = None;
@@ -147,3 +598,168 @@ fn detect_title_changes(stats: &[TimelinePoint]) -> Vec<TitleChange> {
 
     changes
 }
+
+/// 1つの指標について、直前平均からの増加率がしきい値を超えた連続区間を検出する
+///
+/// `detect_category_changes` と同じく前後比較だが、比較対象を直前 `window_minutes` 分の平均にしている。
+/// 直前平均が1未満の時点（配信開始直後やチャットがほぼ無い時間帯）は誤検出を避けるため判定しない。
+/// 指標が None の時点は判定にも直前平均にも含めない。
+fn detect_metric_spikes(
+    stats: &[TimelinePoint],
+    metric: &str,
+    value: impl Fn(&TimelinePoint) -> Option<i32>,
+    threshold: f64,
+    window_minutes: f64,
+) -> Vec<Highlight> {
+    let samples: Vec<(&TimelinePoint, i32)> = stats
+        .iter()
+        .filter_map(|p| value(p).map(|v| (p, v)))
+        .collect();
+    let mut highlights = Vec::new();
+    let mut current: Option<Highlight> = None;
+    let mut window_start = 0;
+
+    for (i, &(point, current_value)) in samples.iter().enumerate() {
+        while window_start < i
+            && samples[window_start].0.elapsed_minutes < point.elapsed_minutes - window_minutes
+        {
+            window_start += 1;
+        }
+
+        let window = &samples[window_start..i];
+        let baseline = if window.is_empty() {
+            0.0
+        } else {
+            window.iter().map(|&(_, v)| v as f64).sum::<f64>() / window.len() as f64
+        };
+        let growth_rate = if baseline >= 1.0 {
+            current_value as f64 / baseline - 1.0
+        } else {
+            0.0
+        };
+
+        if growth_rate >= threshold {
+            let highlight = current.get_or_insert_with(|| Highlight {
+                metric: metric.to_string(),
+                start_time: point.collected_at.clone(),
+                peak_time: point.collected_at.clone(),
+                end_time: point.collected_at.clone(),
+                baseline,
+                peak_value: current_value,
+                growth_rate,
+            });
+            highlight.end_time = point.collected_at.clone();
+            if current_value > highlight.peak_value {
+                highlight.peak_time = point.collected_at.clone();
+                highlight.peak_value = current_value;
+                highlight.growth_rate = growth_rate;
+            }
+        } else if let Some(highlight) = current.take() {
+            highlights.push(highlight);
+        }
+    }
+
+    highlights.extend(current);
+    highlights
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(elapsed_minutes: f64, viewer_count: i32) -> TimelinePoint {
+        TimelinePoint {
+            collected_at: format!("t{}", elapsed_minutes),
+            viewer_count: Some(viewer_count),
+            chat_rate_1min: Some(0),
+            category: String::new(),
+            title: String::new(),
+            follower_count: Some(0),
+            elapsed_minutes,
+        }
+    }
+
+    #[test]
+    fn detects_consecutive_spike_as_one_highlight() {
+        let stats = vec![
+            point(0.0, 100),
+            point(1.0, 100),
+            point(2.0, 100),
+            point(3.0, 180),
+            point(4.0, 300),
+            point(5.0, 120),
+        ];
+
+        let highlights =
+            detect_metric_spikes(&stats, "viewer_count", |p| p.viewer_count, 0.5, 10.0);
+
+        assert_eq!(highlights.len(), 1);
+        assert_eq!(highlights[0].start_time, "t3");
+        assert_eq!(highlights[0].peak_time, "t4");
+        assert_eq!(highlights[0].end_time, "t4");
+        assert_eq!(highlights[0].peak_value, 300);
+    }
+
+    #[test]
+    fn fills_timeline_gaps_with_previous_value() {
+        let stats: Vec<TimelinePoint> = [
+            ("2024-01-01 12:00:00", 0.0, 100),
+            ("2024-01-01 12:01:10", 1.0 + 1.0 / 6.0, 120),
+            // 3分間の欠損
+            ("2024-01-01 12:05:00", 5.0, 90),
+        ]
+        .iter()
+        .map(|&(collected_at, elapsed, viewers)| TimelinePoint {
+            collected_at: collected_at.to_string(),
+            ..point(elapsed, viewers)
+        })
+        .collect();
+
+        let filled = fill_timeline_gaps(&stats, 60);
+
+        let viewers: Vec<Option<i32>> = filled.iter().map(|p| p.viewer_count).collect();
+        // 12:01:10 の値は 2 間隔（12:03:10）まで補間し、それ以降は欠損として None にする
+        assert_eq!(
+            viewers,
+            vec![Some(100), Some(100), Some(120), Some(120), None, Some(90)]
+        );
+        assert_eq!(filled[2].collected_at, "2024-01-01 12:02:00");
+        assert!((filled[2].elapsed_minutes - 2.0).abs() < 1e-9);
+        assert_eq!(filled[4].chat_rate_1min, None);
+        assert_eq!(filled[4].follower_count, None);
+    }
+
+    #[test]
+    fn skips_missing_values_when_detecting_spikes() {
+        let mut stats = vec![point(0.0, 100), point(1.0, 100), point(2.0, 100)];
+        stats.push(TimelinePoint {
+            viewer_count: None,
+            ..point(3.0, 0)
+        });
+        stats.push(point(4.0, 100));
+
+        let highlights =
+            detect_metric_spikes(&stats, "viewer_count", |p| p.viewer_count, 0.5, 10.0);
+
+        assert!(highlights.is_empty());
+    }
+
+    #[test]
+    fn sums_category_durations_across_switches() {
+        let mut stats: Vec<TimelinePoint> = [0.0, 10.0, 20.0, 30.0, 40.0]
+            .iter()
+            .map(|&m| point(m, 100))
+            .collect();
+        for (p, category) in stats.iter_mut().zip(["A", "A", "B", "B", "A"]) {
+            p.category = category.to_string();
+        }
+
+        // A: 0-20 + 40-50, B: 20-40
+        let durations = category_durations(&stats, 50.0);
+        assert_eq!(durations.len(), 2);
+        assert_eq!(durations[0].category, "A");
+        assert_eq!(durations[0].minutes, 30.0);
+        assert_eq!(durations[1].category, "B");
+        assert_eq!(durations[1].minutes, 20.0);
+    }
+}
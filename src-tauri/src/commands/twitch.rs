@@ -1,7 +1,8 @@
 use crate::api::twitch_api::TwitchRateLimitStatus;
 use crate::collectors::poller::ChannelPoller;
+use crate::config::keyring_store::{KeyringStore, TokenKind};
 use crate::config::settings::SettingsManager;
-use crate::constants::twitch;
+use crate::constants::{database as db_constants, twitch};
 use crate::error::ResultExt;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -27,7 +28,7 @@ pub struct TwitchChannelInfo {
 }
 
 /// Validate a Twitch channel by checking if it exists via Twitch API
-/// Note: access_token is passed from frontend (retrieved from Stronghold via JS API)
+/// access_token が渡されない場合は Keyring に保存済みのトークンを使用する
 #[tauri::command]
 pub async fn validate_twitch_channel(
     app_handle: AppHandle,
@@ -43,9 +44,16 @@ pub async fn validate_twitch_channel(
         "Twitch Client IDが設定されていません。設定画面からOAuth設定を行ってください。".to_string()
     })?;
 
-    // Check if access token is provided
-    let token_str = access_token
-        .ok_or_else(|| "Twitchの認証が必要です。設定画面から認証を行ってください。".to_string())?;
+    // 明示的に渡されたトークンを優先し、無ければ Keyring から取得する
+    let token_str = match access_token {
+        Some(token) => token,
+        None => KeyringStore::get_token_with_app(
+            &app_handle,
+            db_constants::PLATFORM_TWITCH,
+            TokenKind::Access,
+        )
+        .map_err(|_| "Twitchの認証が必要です。設定画面から認証を行ってください。".to_string())?,
+    };
 
     // Create Twitch API client
     let client: HelixClient<'static, reqwest::Client> = HelixClient::default();
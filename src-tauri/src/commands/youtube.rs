@@ -0,0 +1,77 @@
+use crate::api::youtube_api::{YouTubeQuotaStatus, YouTubeQuotaTracker};
+use crate::collectors::poller::ChannelPoller;
+use crate::config::settings::SettingsManager;
+use crate::constants::youtube;
+use crate::error::ResultExt;
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+use tokio::sync::Mutex;
+
+/// YouTube Data API のクォータ使用状況を取得
+#[tauri::command]
+pub async fn get_youtube_quota_usage(
+    app_handle: AppHandle,
+    poller: State<'_, Arc<Mutex<ChannelPoller>>>,
+) -> Result<YouTubeQuotaStatus, String> {
+    let poller_guard = poller.lock().await;
+
+    if let Some(youtube_collector) = poller_guard.get_youtube_collector() {
+        let quota = youtube_collector.get_quota_tracker();
+
+        // pollerのロックを早期に解放
+        drop(poller_guard);
+
+        let mut tracker = quota.lock().await;
+        Ok(tracker.get_status())
+    } else {
+        // YouTubeCollectorが初期化されていない場合、設定値から未使用の状態を返す
+        drop(poller_guard);
+        let settings = SettingsManager::load_settings(&app_handle)
+            .config_context("load settings")
+            .map_err(|e| e.to_string())?;
+        Ok(YouTubeQuotaTracker::new(
+            settings
+                .youtube
+                .daily_quota_limit
+                .unwrap_or(youtube::DEFAULT_DAILY_QUOTA_LIMIT),
+            settings.youtube.adaptive_quota,
+        )
+        .get_status())
+    }
+}
+
+/// クォータ上限とアダプティブモードの設定を保存し、収集中のトラッカーに反映する
+#[tauri::command]
+pub async fn set_youtube_quota_settings(
+    app_handle: AppHandle,
+    poller: State<'_, Arc<Mutex<ChannelPoller>>>,
+    daily_quota_limit: Option<u32>,
+    adaptive: bool,
+) -> Result<(), String> {
+    if daily_quota_limit == Some(0) {
+        return Err("daily_quota_limit must be greater than 0".to_string());
+    }
+
+    let mut settings = SettingsManager::load_settings(&app_handle)
+        .config_context("load settings")
+        .map_err(|e| e.to_string())?;
+    settings.youtube.daily_quota_limit = daily_quota_limit;
+    settings.youtube.adaptive_quota = adaptive;
+    SettingsManager::save_settings(&app_handle, &settings)
+        .config_context("save settings")
+        .map_err(|e| e.to_string())?;
+
+    let quota = poller
+        .lock()
+        .await
+        .get_youtube_collector()
+        .map(|collector| collector.get_quota_tracker());
+    if let Some(quota) = quota {
+        quota.lock().await.configure(
+            daily_quota_limit.unwrap_or(youtube::DEFAULT_DAILY_QUOTA_LIMIT),
+            adaptive,
+        );
+    }
+
+    Ok(())
+}
@@ -1,5 +1,11 @@
+/// KeyringStore - OS のキーチェーン（tauri-plugin-keyring）への資格情報の保存
+///
+/// アクセストークン・リフレッシュトークン・OAuth secret・S3 secret・設定ファイルの暗号化鍵の保存先はこのモジュールのみで、
+/// config コマンドや各 API クライアントはすべて KeyringStore を経由する（他の保存先は持たない）。
+use crate::config::settings_crypto;
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Runtime};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime};
 
 /// Token metadata for tracking expiration
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -8,60 +14,166 @@ pub struct TokenMetadata {
     pub obtained_at: String, // RFC3339 format
 }
 
+/// Keyring に保存する資格情報の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Access,
+    Refresh,
+    OAuthSecret,
+}
+
+impl TokenKind {
+    fn suffix(self) -> &'static str {
+        match self {
+            TokenKind::Access => "access_token",
+            TokenKind::Refresh => "refresh_token",
+            TokenKind::OAuthSecret => "oauth_secret",
+        }
+    }
+
+    /// 旧バージョンで使用していたキー名（読み取り時のフォールバック用）
+    ///
+    /// アクセストークンは `{platform}_token` で保存していた。
+    /// リフレッシュトークン（`{platform}_refresh_token`）と OAuth secret はキー名が変わらない。
+    fn legacy_key_name(self, platform: &str) -> Option<String> {
+        match self {
+            TokenKind::Access => Some(format!("{}_token", platform)),
+            TokenKind::Refresh | TokenKind::OAuthSecret => None,
+        }
+    }
+}
+
+/// Keyring のキー名を生成（`{platform}_{kind}`）
+pub fn key_name(platform: &str, kind: TokenKind) -> String {
+    format!("{}_{}", platform, kind.suffix())
+}
+
+/// デコード済みの設定ファイル暗号化鍵（managed state）
+///
+/// 設定の読み書きのたびにキーチェーンへ問い合わせないよう、初回に取得した鍵を保持する。
+#[derive(Default)]
+pub struct SettingsKeyCache(Mutex<Option<[u8; settings_crypto::KEY_LEN]>>);
+
+impl SettingsKeyCache {
+    fn get<R: Runtime>(app: &AppHandle<R>) -> Option<[u8; settings_crypto::KEY_LEN]> {
+        let cache = app.try_state::<SettingsKeyCache>()?;
+        let key = cache.0.lock().ok()?;
+        *key
+    }
+
+    fn set<R: Runtime>(app: &AppHandle<R>, key: [u8; settings_crypto::KEY_LEN]) {
+        if let Some(cache) = app.try_state::<SettingsKeyCache>() {
+            if let Ok(mut guard) = cache.0.lock() {
+                *guard = Some(key);
+            }
+        }
+    }
+}
+
 pub struct KeyringStore;
 
 impl KeyringStore {
     const SERVICE_NAME: &'static str = "stream-monitor";
+    const S3_SECRET_KEY: &'static str = "s3_export_secret_access_key";
+    const SETTINGS_KEY: &'static str = "settings_encryption_key";
 
     /// Save a token to OS keychain
     pub fn save_token_with_app<R: Runtime>(
         app: &AppHandle<R>,
         platform: &str,
+        kind: TokenKind,
         token: &str,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         use tauri_plugin_keyring::KeyringExt;
 
-        let key = format!("{}_token", platform);
         app.keyring()
-            .set_password(Self::SERVICE_NAME, &key, token)?;
+            .set_password(Self::SERVICE_NAME, &key_name(platform, kind), token)?;
 
-        eprintln!("[KeyringStore] Token saved for platform: '{}'", platform);
+        eprintln!(
+            "[KeyringStore] Token saved for platform: '{}' ({:?})",
+            platform, kind
+        );
         Ok(())
     }
 
     /// Get a token from OS keychain
+    ///
+    /// 新しいキー名で見つからない場合は旧キー名から読み取り、新しいキー名へ移行する。
     pub fn get_token_with_app<R: Runtime>(
         app: &AppHandle<R>,
         platform: &str,
+        kind: TokenKind,
     ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
         use tauri_plugin_keyring::KeyringExt;
 
-        let key = format!("{}_token", platform);
+        let key = key_name(platform, kind);
+        if let Some(token) = app.keyring().get_password(Self::SERVICE_NAME, &key)? {
+            return Ok(token);
+        }
+
+        let legacy_key = kind.legacy_key_name(platform).ok_or("Token not found")?;
         let token = app
             .keyring()
-            .get_password(Self::SERVICE_NAME, &key)?
+            .get_password(Self::SERVICE_NAME, &legacy_key)?
             .ok_or("Token not found")?;
 
+        // 旧キーから新キーへ移行（失敗しても読み取り結果は返す）
+        match app.keyring().set_password(Self::SERVICE_NAME, &key, &token) {
+            Ok(()) => {
+                let _ = app
+                    .keyring()
+                    .delete_password(Self::SERVICE_NAME, &legacy_key);
+                eprintln!(
+                    "[KeyringStore] Migrated token key '{}' -> '{}'",
+                    legacy_key, key
+                );
+            }
+            Err(e) => {
+                eprintln!(
+                    "[KeyringStore] Warning: Failed to migrate token key '{}': {}",
+                    legacy_key, e
+                );
+            }
+        }
+
         Ok(token)
     }
 
-    /// Delete a token from OS keychain
+    /// Delete a token from OS keychain (including its legacy key)
     pub fn delete_token_with_app<R: Runtime>(
         app: &AppHandle<R>,
         platform: &str,
+        kind: TokenKind,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         use tauri_plugin_keyring::KeyringExt;
 
-        let key = format!("{}_token", platform);
-        app.keyring().delete_password(Self::SERVICE_NAME, &key)?;
+        let result = app
+            .keyring()
+            .delete_password(Self::SERVICE_NAME, &key_name(platform, kind));
+        let legacy_deleted = kind
+            .legacy_key_name(platform)
+            .map(|legacy_key| {
+                app.keyring()
+                    .delete_password(Self::SERVICE_NAME, &legacy_key)
+                    .is_ok()
+            })
+            .unwrap_or(false);
 
-        eprintln!("[KeyringStore] Token deleted for platform: '{}'", platform);
+        // 旧キーのみ存在していた場合も削除成功として扱う
+        if !legacy_deleted {
+            result?;
+        }
+
+        eprintln!(
+            "[KeyringStore] Token deleted for platform: '{}' ({:?})",
+            platform, kind
+        );
         Ok(())
     }
 
-    /// Check if a token exists
+    /// Check if an access token exists
     pub fn has_token_with_app<R: Runtime>(app: &AppHandle<R>, platform: &str) -> bool {
-        Self::get_token_with_app(app, platform).is_ok()
+        Self::get_token_with_app(app, platform, TokenKind::Access).is_ok()
     }
 
     /// Save OAuth secret
@@ -72,9 +184,11 @@ impl KeyringStore {
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         use tauri_plugin_keyring::KeyringExt;
 
-        let key = format!("{}_oauth_secret", platform);
-        app.keyring()
-            .set_password(Self::SERVICE_NAME, &key, secret)?;
+        app.keyring().set_password(
+            Self::SERVICE_NAME,
+            &key_name(platform, TokenKind::OAuthSecret),
+            secret,
+        )?;
 
         eprintln!(
             "[KeyringStore] OAuth secret saved for platform: '{}'",
@@ -88,15 +202,8 @@ impl KeyringStore {
         app: &AppHandle<R>,
         platform: &str,
     ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        use tauri_plugin_keyring::KeyringExt;
-
-        let key = format!("{}_oauth_secret", platform);
-        let secret = app
-            .keyring()
-            .get_password(Self::SERVICE_NAME, &key)?
-            .ok_or("Secret not found")?;
-
-        Ok(secret)
+        Self::get_token_with_app(app, platform, TokenKind::OAuthSecret)
+            .map_err(|_| "Secret not found".into())
     }
 
     /// Save token metadata (expiration info)
@@ -153,4 +260,103 @@ impl KeyringStore {
         );
         Ok(())
     }
+
+    /// Save S3-compatible storage secret access key
+    pub fn save_s3_secret_with_app<R: Runtime>(
+        app: &AppHandle<R>,
+        secret: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        use tauri_plugin_keyring::KeyringExt;
+
+        app.keyring()
+            .set_password(Self::SERVICE_NAME, Self::S3_SECRET_KEY, secret)?;
+
+        eprintln!("[KeyringStore] S3 secret access key saved");
+        Ok(())
+    }
+
+    /// Get S3-compatible storage secret access key
+    pub fn get_s3_secret_with_app<R: Runtime>(
+        app: &AppHandle<R>,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        use tauri_plugin_keyring::KeyringExt;
+
+        let secret = app
+            .keyring()
+            .get_password(Self::SERVICE_NAME, Self::S3_SECRET_KEY)?
+            .ok_or("S3 secret access key not found")?;
+
+        Ok(secret)
+    }
+
+    /// 設定ファイルの暗号化鍵を取得（未作成の場合は None）
+    ///
+    /// 一度取得した鍵は `SettingsKeyCache` に保持し、以降はキーチェーンを読まない。
+    pub fn get_settings_key_with_app<R: Runtime>(
+        app: &AppHandle<R>,
+    ) -> Result<Option<[u8; settings_crypto::KEY_LEN]>, Box<dyn std::error::Error + Send + Sync>>
+    {
+        use tauri_plugin_keyring::KeyringExt;
+
+        if let Some(key) = SettingsKeyCache::get(app) {
+            return Ok(Some(key));
+        }
+
+        match app
+            .keyring()
+            .get_password(Self::SERVICE_NAME, Self::SETTINGS_KEY)?
+        {
+            Some(hex) => {
+                let key =
+                    settings_crypto::decode_key(&hex).ok_or("Invalid settings encryption key")?;
+                SettingsKeyCache::set(app, key);
+                Ok(Some(key))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// 設定ファイルの暗号化鍵を取得し、無ければ生成して保存する
+    pub fn get_or_create_settings_key_with_app<R: Runtime>(
+        app: &AppHandle<R>,
+    ) -> Result<[u8; settings_crypto::KEY_LEN], Box<dyn std::error::Error + Send + Sync>> {
+        use tauri_plugin_keyring::KeyringExt;
+
+        if let Some(key) = Self::get_settings_key_with_app(app)? {
+            return Ok(key);
+        }
+
+        let key = settings_crypto::generate_key();
+        app.keyring().set_password(
+            Self::SERVICE_NAME,
+            Self::SETTINGS_KEY,
+            &settings_crypto::encode_key(&key),
+        )?;
+        SettingsKeyCache::set(app, key);
+
+        eprintln!("[KeyringStore] Settings encryption key created");
+        Ok(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_name_per_kind() {
+        assert_eq!(key_name("twitch", TokenKind::Access), "twitch_access_token");
+        assert_eq!(
+            key_name("twitch", TokenKind::Refresh),
+            "twitch_refresh_token"
+        );
+        assert_eq!(
+            key_name("youtube", TokenKind::OAuthSecret),
+            "youtube_oauth_secret"
+        );
+        assert_eq!(
+            TokenKind::Access.legacy_key_name("twitch").as_deref(),
+            Some("twitch_token")
+        );
+    }
 }
@@ -1,2 +1,3 @@
 pub mod keyring_store;
 pub mod settings;
+pub mod settings_crypto;
@@ -1,3 +1,5 @@
+use crate::config::keyring_store::KeyringStore;
+use crate::config::settings_crypto;
 use crate::error::ResultExt;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -13,17 +15,41 @@ pub struct AppSettings {
     // Twitch自動発見機能設定
     #[serde(default)]
     pub auto_discovery: Option<AutoDiscoverySettings>,
+    // データベース設定
+    #[serde(default)]
+    pub database: DatabaseSettings,
+    // S3互換ストレージへのエクスポート設定（シークレットはKeyringに保存）
+    #[serde(default)]
+    pub s3_export: Option<S3ExportSettings>,
+    // 配信サムネイル画像をローカルにキャッシュするか（URLの保存は常に行う）
+    #[serde(default)]
+    pub cache_thumbnails: bool,
+    // チャット集計時に除外する bot の設定
+    #[serde(default)]
+    pub chat_filter: ChatFilterSettings,
+    // 設定ファイルをOSキーチェーンの鍵で暗号化して保存するか
+    #[serde(default)]
+    pub encrypt_settings: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TwitchSettings {
     pub client_id: Option<String>,
+    /// IRCに認証済みアカウントで接続するか（false の場合は justinfan による匿名接続）
+    #[serde(default)]
+    pub irc_authenticated: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct YouTubeSettings {
     pub client_id: Option<String>,
     pub client_secret: Option<String>,
+    /// 1日あたりのクォータ上限（未指定の場合は DEFAULT_DAILY_QUOTA_LIMIT）
+    #[serde(default)]
+    pub daily_quota_limit: Option<u32>,
+    /// クォータ残量が少ないときにポーリング間隔を自動で延ばすか
+    #[serde(default)]
+    pub adaptive_quota: bool,
 }
 
 /// 将来の機能: YouTubeスクレイピング設定
@@ -55,6 +81,127 @@ pub struct AutoDiscoverySettings {
     /// フィルター設定
     #[serde(default)]
     pub filters: AutoDiscoveryFilters,
+    /// 発見した配信を自動で一時的な収集対象にする（配信終了で収集を解除する）
+    #[serde(default)]
+    pub auto_collect: bool,
+    /// 自動収集するチャンネル数の上限
+    #[serde(default = "default_max_auto_collect")]
+    pub max_auto_collect: u32,
+}
+
+/// データベース設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseSettings {
+    /// 定期同期（CHECKPOINT）間隔（秒）。0の場合は定期同期を行わずシャットダウン時のみ同期する
+    #[serde(default = "default_sync_interval")]
+    pub sync_interval: u32,
+    /// stream_stats / chat_messages の保持日数。0の場合は自動削除しない
+    #[serde(default)]
+    pub retention_days: u32,
+    /// 削除前に配信単位の集計（peak/avg/minutes_watched）を stream_stats_archive に退避する
+    #[serde(default = "default_retention_rollup")]
+    pub retention_rollup: bool,
+    /// DuckDB の memory_limit（MB）。範囲外の値は実際の適用時に補正される
+    #[serde(default = "default_memory_limit_mb")]
+    pub memory_limit_mb: u32,
+    /// DuckDB のワーカースレッド数。0の場合はデフォルト値、CPUコア数を超える値はコア数に補正される
+    #[serde(default = "default_threads")]
+    pub threads: u32,
+    /// DBファイルの保存先。未指定の場合は app_data_dir/stream_stats.db を使用する
+    #[serde(default)]
+    pub db_path: Option<String>,
+    /// IRC再接続時の再送などで同一ユーザーの同一メッセージがこの秒数以内に続いた場合は保存しない。0の場合は重複除去しない
+    #[serde(default = "default_chat_dedupe_window_secs")]
+    pub chat_dedupe_window_secs: u32,
+}
+
+impl DatabaseSettings {
+    /// memory_limit を MIN_MEMORY_LIMIT_MB〜MAX_MEMORY_LIMIT_MB に補正した値（0はデフォルト値）
+    pub fn sanitized_memory_limit_mb(&self) -> u32 {
+        use crate::constants::database as db_constants;
+        if self.memory_limit_mb == 0 {
+            return db_constants::DEFAULT_MEMORY_LIMIT_MB;
+        }
+        self.memory_limit_mb.clamp(
+            db_constants::MIN_MEMORY_LIMIT_MB,
+            db_constants::MAX_MEMORY_LIMIT_MB,
+        )
+    }
+
+    /// threads を 1〜CPUコア数に補正した値（0はデフォルト値）
+    pub fn sanitized_threads(&self) -> u32 {
+        let max_threads = std::thread::available_parallelism()
+            .map(|n| n.get() as u32)
+            .unwrap_or(crate::constants::database::DEFAULT_THREADS);
+        let threads = if self.threads == 0 {
+            crate::constants::database::DEFAULT_THREADS
+        } else {
+            self.threads
+        };
+        threads.clamp(1, max_threads.max(1))
+    }
+}
+
+impl Default for DatabaseSettings {
+    fn default() -> Self {
+        Self {
+            sync_interval: default_sync_interval(),
+            retention_days: 0,
+            retention_rollup: default_retention_rollup(),
+            memory_limit_mb: default_memory_limit_mb(),
+            threads: default_threads(),
+            db_path: None,
+            chat_dedupe_window_secs: default_chat_dedupe_window_secs(),
+        }
+    }
+}
+
+/// チャット集計時の bot 除外設定
+/// 生ログは削除せず、exclude_bots を指定した集計でのみ除外する
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatFilterSettings {
+    /// 除外する bot のユーザー名（大文字小文字を区別しない）
+    #[serde(default = "default_bot_user_names")]
+    pub bot_user_names: Vec<String>,
+    /// 除外するメッセージの正規表現パターン（RE2 構文）
+    #[serde(default)]
+    pub message_patterns: Vec<String>,
+    /// `!` で始まる bot コマンドを除外するか
+    #[serde(default = "default_exclude_commands")]
+    pub exclude_commands: bool,
+}
+
+impl Default for ChatFilterSettings {
+    fn default() -> Self {
+        Self {
+            bot_user_names: default_bot_user_names(),
+            message_patterns: Vec::new(),
+            exclude_commands: default_exclude_commands(),
+        }
+    }
+}
+
+/// S3互換オブジェクトストレージへのエクスポート設定
+/// シークレットアクセスキーは設定ファイルではなくKeyringに保存する
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct S3ExportSettings {
+    /// アクセスキーID
+    pub access_key_id: String,
+    /// リージョン（未指定時は us-east-1）
+    #[serde(default)]
+    pub region: Option<String>,
+    /// カスタムエンドポイント（MinIO / Cloudflare R2 等、例: "xxxx.r2.cloudflarestorage.com"）
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// URLスタイル（"path" または "vhost"）
+    #[serde(default)]
+    pub url_style: Option<String>,
+    /// HTTPSを使用するか（未指定時は true）
+    #[serde(default)]
+    pub use_ssl: Option<bool>,
+    /// デフォルトのバケット名
+    #[serde(default)]
+    pub bucket: Option<String>,
 }
 
 fn deserialize_min_viewers<'de, D>(deserializer: D) -> Result<u32, D::Error>
@@ -85,6 +232,8 @@ impl Default for AutoDiscoverySettings {
             poll_interval: default_poll_interval(),
             max_streams: default_max_streams(),
             filters: AutoDiscoveryFilters::default(),
+            auto_collect: false,
+            max_auto_collect: default_max_auto_collect(),
         }
     }
 }
@@ -97,6 +246,50 @@ fn default_max_streams() -> u32 {
     20 // デフォルト20件
 }
 
+fn default_max_auto_collect() -> u32 {
+    10
+}
+
+fn default_sync_interval() -> u32 {
+    crate::constants::database::DEFAULT_SYNC_INTERVAL_SECS
+}
+
+fn default_retention_rollup() -> bool {
+    true
+}
+
+fn default_memory_limit_mb() -> u32 {
+    crate::constants::database::DEFAULT_MEMORY_LIMIT_MB
+}
+
+fn default_threads() -> u32 {
+    crate::constants::database::DEFAULT_THREADS
+}
+
+fn default_chat_dedupe_window_secs() -> u32 {
+    crate::constants::database::DEFAULT_CHAT_DEDUPE_WINDOW_SECS
+}
+
+fn default_bot_user_names() -> Vec<String> {
+    [
+        "nightbot",
+        "streamelements",
+        "streamlabs",
+        "moobot",
+        "fossabot",
+        "wizebot",
+        "sery_bot",
+        "soundalerts",
+    ]
+    .iter()
+    .map(|name| name.to_string())
+    .collect()
+}
+
+fn default_exclude_commands() -> bool {
+    true
+}
+
 fn default_scraping_settings() -> Option<YouTubeScrapingSettings> {
     None // デフォルトでは無効
 }
@@ -106,13 +299,21 @@ impl Default for AppSettings {
         Self {
             twitch: TwitchSettings {
                 client_id: Some("rxyno75ir81wkq3xck0bfeen1a0klh".to_string()),
+                irc_authenticated: false,
             },
             youtube: YouTubeSettings {
                 client_id: None,
                 client_secret: None,
+                daily_quota_limit: None,
+                adaptive_quota: false,
             },
             youtube_scraping: None,
             auto_discovery: None,
+            database: DatabaseSettings::default(),
+            s3_export: None,
+            cache_thumbnails: false,
+            chat_filter: ChatFilterSettings::default(),
+            encrypt_settings: false,
         }
     }
 }
@@ -149,9 +350,17 @@ impl SettingsManager {
             return Ok(AppSettings::default());
         }
 
-        let content = std::fs::read_to_string(&settings_path)?;
-        let settings: AppSettings = serde_json::from_str(&content)?;
-        Ok(settings)
+        let data = std::fs::read(&settings_path)?;
+        if !settings_crypto::is_encrypted(&data) {
+            // 平文の設定（暗号化を有効にすると次回の保存時に暗号化される）
+            return Ok(serde_json::from_slice(&data)?);
+        }
+
+        let key = KeyringStore::get_settings_key_with_app(app_handle)?.ok_or(
+            "Settings file is encrypted but the encryption key was not found in the keychain",
+        )?;
+        let content = settings_crypto::decrypt(&key, &data)?;
+        Ok(serde_json::from_slice(&content)?)
     }
 
     pub fn save_settings(
@@ -166,7 +375,15 @@ impl SettingsManager {
         }
 
         let content = serde_json::to_string_pretty(settings)?;
-        std::fs::write(&settings_path, content)?;
+        if settings.encrypt_settings {
+            let key = KeyringStore::get_or_create_settings_key_with_app(app_handle)?;
+            std::fs::write(
+                &settings_path,
+                settings_crypto::encrypt(&key, content.as_bytes())?,
+            )?;
+        } else {
+            std::fs::write(&settings_path, content)?;
+        }
         Ok(())
     }
 }
@@ -200,3 +417,48 @@ pub mod youtube_scraping {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::database as db_constants;
+
+    fn database_settings(memory_limit_mb: u32, threads: u32) -> DatabaseSettings {
+        DatabaseSettings {
+            memory_limit_mb,
+            threads,
+            ..DatabaseSettings::default()
+        }
+    }
+
+    #[test]
+    fn test_sanitized_memory_limit_mb() {
+        let sanitized = |mb| database_settings(mb, 0).sanitized_memory_limit_mb();
+
+        assert_eq!(sanitized(0), db_constants::DEFAULT_MEMORY_LIMIT_MB);
+        assert_eq!(sanitized(1), db_constants::MIN_MEMORY_LIMIT_MB);
+        assert_eq!(
+            sanitized(db_constants::MIN_MEMORY_LIMIT_MB),
+            db_constants::MIN_MEMORY_LIMIT_MB
+        );
+        assert_eq!(sanitized(2048), 2048);
+        assert_eq!(
+            sanitized(db_constants::MAX_MEMORY_LIMIT_MB),
+            db_constants::MAX_MEMORY_LIMIT_MB
+        );
+        assert_eq!(sanitized(u32::MAX), db_constants::MAX_MEMORY_LIMIT_MB);
+    }
+
+    #[test]
+    fn test_sanitized_threads() {
+        let cores = std::thread::available_parallelism()
+            .map(|n| n.get() as u32)
+            .unwrap_or(db_constants::DEFAULT_THREADS);
+        let sanitized = |threads| database_settings(0, threads).sanitized_threads();
+
+        assert_eq!(sanitized(0), db_constants::DEFAULT_THREADS.min(cores));
+        assert_eq!(sanitized(1), 1);
+        assert_eq!(sanitized(cores), cores);
+        assert_eq!(sanitized(u32::MAX), cores);
+    }
+}
@@ -0,0 +1,98 @@
+/// 設定ファイルの暗号化（AES-256-GCM）
+///
+/// 暗号化した設定ファイルは `MAGIC` + nonce(12バイト) + 暗号文 の形式で保存する。
+/// 鍵は初回の暗号化時に生成し、OS のキーチェーンに16進文字列で保存する（KeyringStore 参照）。
+/// 先頭が `MAGIC` でないファイルは従来の平文JSONとして読み込むため、平文設定からそのまま移行できる。
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+
+/// 暗号化済み設定ファイルの先頭に付与する識別子
+const MAGIC: &[u8] = b"SMENC1\n";
+
+/// AES-GCM の nonce 長（バイト）
+const NONCE_LEN: usize = 12;
+
+/// 鍵長（バイト）
+pub const KEY_LEN: usize = 32;
+
+/// 暗号化済みの設定ファイルか
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// 新しい鍵を生成する
+pub fn generate_key() -> [u8; KEY_LEN] {
+    Aes256Gcm::generate_key(OsRng).into()
+}
+
+/// 鍵をキーチェーン保存用の16進文字列に変換
+pub fn encode_key(key: &[u8; KEY_LEN]) -> String {
+    key.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// キーチェーンに保存した16進文字列の鍵を復元（形式が不正な場合は None）
+pub fn decode_key(hex: &str) -> Option<[u8; KEY_LEN]> {
+    let hex = hex.trim();
+    if hex.len() != KEY_LEN * 2 {
+        return None;
+    }
+    let mut key = [0u8; KEY_LEN];
+    for (i, byte) in key.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(hex.get(i * 2..i * 2 + 2)?, 16).ok()?;
+    }
+    Some(key)
+}
+
+/// 平文を暗号化して設定ファイルの形式にする
+pub fn encrypt(key: &[u8; KEY_LEN], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let cipher = Aes256Gcm::new(key.into());
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| "設定ファイルの暗号化に失敗しました".to_string())?;
+
+    let mut data = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+    data.extend_from_slice(MAGIC);
+    data.extend_from_slice(&nonce);
+    data.extend_from_slice(&ciphertext);
+    Ok(data)
+}
+
+/// 暗号化された設定ファイルを復号する
+pub fn decrypt(key: &[u8; KEY_LEN], data: &[u8]) -> Result<Vec<u8>, String> {
+    let body = data
+        .strip_prefix(MAGIC)
+        .ok_or_else(|| "暗号化された設定ファイルではありません".to_string())?;
+    if body.len() < NONCE_LEN {
+        return Err("暗号化された設定ファイルが壊れています".to_string());
+    }
+    let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+
+    Aes256Gcm::new(key.into())
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| {
+            "設定ファイルを復号できません（鍵が一致しないか、ファイルが壊れています）".to_string()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_roundtrip() {
+        let key = generate_key();
+        assert_eq!(decode_key(&encode_key(&key)), Some(key));
+
+        let data = encrypt(&key, br#"{"twitch":{"client_id":"abc"}}"#).unwrap();
+        assert!(is_encrypted(&data));
+        assert!(!is_encrypted(br#"{"twitch":{}}"#));
+        assert_eq!(
+            decrypt(&key, &data).unwrap(),
+            br#"{"twitch":{"client_id":"abc"}}"#.to_vec()
+        );
+
+        // 別の鍵では復号できない
+        assert!(decrypt(&generate_key(), &data).is_err());
+    }
+}
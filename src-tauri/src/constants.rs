@@ -4,6 +4,21 @@ pub mod twitch {
     /// トークンの有効期限チェック閾値（分）
     pub const TOKEN_EXPIRY_THRESHOLD_MINUTES: i64 = 30;
 
+    /// デバイスコードの有効期限が不明な場合に使用する既定値（秒）
+    pub const DEVICE_CODE_DEFAULT_EXPIRES_SECS: u64 = 1800;
+
+    /// ゲームのボックスアート画像の幅（px）
+    pub const BOX_ART_WIDTH: u32 = 144;
+
+    /// ゲームのボックスアート画像の高さ（px）
+    pub const BOX_ART_HEIGHT: u32 = 192;
+
+    /// 配信サムネイル画像の幅（px）
+    pub const STREAM_THUMBNAIL_WIDTH: u32 = 440;
+
+    /// 配信サムネイル画像の高さ（px）
+    pub const STREAM_THUMBNAIL_HEIGHT: u32 = 248;
+
     /// 1リクエストあたりの最大ストリーム数
     pub const MAX_STREAMS_PER_REQUEST: usize = 100;
 
@@ -16,11 +31,41 @@ pub mod twitch {
     /// レート制限ウィンドウ（秒）
     pub const RATE_LIMIT_WINDOW_SECS: u64 = 60;
 
+    /// プロフィール画像URLを再取得する間隔（秒）
+    pub const PROFILE_IMAGE_REFRESH_INTERVAL_SECS: u64 = 6 * 60 * 60;
+
     /// 401エラーステータスコード
     pub const ERROR_UNAUTHORIZED: &str = "401";
 
     /// Unauthorizedエラーテキスト
     pub const ERROR_UNAUTHORIZED_TEXT: &str = "Unauthorized";
+
+    /// 匿名IRC接続で使用するログイン名（トークン不要・読み取り専用）
+    pub const IRC_ANONYMOUS_LOGIN: &str = "justinfan12345";
+
+    /// IRCチャンネル参加状態の監視間隔（秒）
+    pub const IRC_WATCHDOG_INTERVAL_SECS: u64 = 1;
+
+    /// IRC再参加の初期バックオフ（秒）
+    pub const IRC_REJOIN_INITIAL_BACKOFF_SECS: u64 = 1;
+
+    /// IRC再参加の最大バックオフ（秒）
+    pub const IRC_REJOIN_MAX_BACKOFF_SECS: u64 = 30;
+
+    /// 自動収集チャンネルのポーリング間隔（秒）
+    pub const AUTO_COLLECT_POLL_INTERVAL_SECS: i32 = 60;
+
+    /// 自動収集開始後、配信が1件も記録されない場合に収集を解除するまでの猶予（秒）
+    pub const AUTO_COLLECT_GRACE_SECS: i64 = 30 * 60;
+
+    /// 配信終了時にVODを探す際に取得する最新アーカイブ数
+    pub const VOD_LOOKUP_COUNT: usize = 20;
+
+    /// 配信スケジュール取得時の最大セグメント数
+    pub const SCHEDULE_LOOKUP_COUNT: usize = 10;
+
+    /// 404エラーステータスコード（配信スケジュール未設定のチャンネルで返る）
+    pub const ERROR_NOT_FOUND: &str = "404";
 }
 
 pub mod youtube {
@@ -30,8 +75,13 @@ pub mod youtube {
     /// OAuthトークンURL
     pub const OAUTH_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
 
+    /// OAuthデバイスコードURL（Limited Input Device flow）
+    pub const OAUTH_DEVICE_CODE_URL: &str = "https://oauth2.googleapis.com/device/code";
+
+    /// デバイスフローのトークンポーリングで slow_down を受けた際に延長する秒数
+    pub const DEVICE_FLOW_SLOW_DOWN_SECS: u64 = 5;
+
     /// YouTube読み取り専用スコープ
-    #[allow(dead_code)]
     pub const SCOPE_YOUTUBE_READONLY: &str = "https://www.googleapis.com/auth/youtube.readonly";
 
     /// APIレスポンス部分: ID
@@ -49,6 +99,12 @@ pub mod youtube {
     /// イベントタイプ: ライブ
     pub const EVENT_TYPE_LIVE: &str = "live";
 
+    /// イベントタイプ: 配信予定
+    pub const EVENT_TYPE_UPCOMING: &str = "upcoming";
+
+    /// 配信予定を探すアップロード再生リストの取得件数（playlistItems.list の上限）
+    pub const UPCOMING_MAX_RESULTS: u32 = 50;
+
     /// タイプ: ビデオ
     pub const TYPE_VIDEO: &str = "video";
 
@@ -69,6 +125,118 @@ pub mod youtube {
 
     /// プラットフォーム名
     pub const PLATFORM_NAME: &str = "youtube";
+
+    /// liveChatMessages.list の1回あたりの最大取得件数（API上限）
+    pub const LIVE_CHAT_MAX_RESULTS: u32 = 2000;
+
+    /// ライブチャット取得エラー時の最大待機時間（秒）
+    pub const LIVE_CHAT_MAX_BACKOFF_SECS: u64 = 600;
+
+    /// 動画（ライブアーカイブ）の視聴URL。末尾に動画IDを付与する
+    pub const WATCH_URL_BASE: &str = "https://www.youtube.com/watch?v=";
+
+    /// YouTube Data API の1日あたりのクォータ上限（デフォルト割り当て）
+    pub const DEFAULT_DAILY_QUOTA_LIMIT: u32 = 10_000;
+
+    /// search.list 1回あたりのクォータ消費
+    pub const QUOTA_COST_SEARCH: u32 = 100;
+
+    /// videos.list / channels.list 1回あたりのクォータ消費
+    pub const QUOTA_COST_LIST: u32 = 1;
+
+    /// liveChatMessages.list 1回あたりのクォータ消費
+    pub const QUOTA_COST_LIVE_CHAT: u32 = 5;
+
+    /// アダプティブモードでポーリング間隔を2倍にするクォータ残量の割合
+    pub const ADAPTIVE_QUOTA_LOW_RATIO: f64 = 0.25;
+
+    /// アダプティブモードでポーリング間隔を4倍にするクォータ残量の割合
+    pub const ADAPTIVE_QUOTA_CRITICAL_RATIO: f64 = 0.1;
+}
+
+pub mod rate_limit {
+    /// 429/503 を受けた際の最大リトライ回数
+    pub const RETRY_MAX_ATTEMPTS: u32 = 3;
+
+    /// リトライの初期バックオフ（ミリ秒）
+    pub const RETRY_INITIAL_BACKOFF_MS: u64 = 1000;
+
+    /// リトライの最大バックオフ（秒）。Retry-After がこれより長い場合もこの値で打ち切る
+    pub const RETRY_MAX_BACKOFF_SECS: u64 = 60;
+
+    /// YouTube Data API のポーリング上限（回/分）。日次クォータを使い切らないよう控えめにする
+    pub const YOUTUBE_POLLS_PER_MINUTE: u32 = 30;
+
+    /// Kick / ニコニコ生放送など公開エンドポイントのポーリング上限（回/分）
+    pub const PUBLIC_API_POLLS_PER_MINUTE: u32 = 60;
+}
+
+pub mod poller {
+    /// この回数連続でポーリングに失敗したチャンネルはポーリングを一時停止する
+    pub const MAX_CONSECUTIVE_POLL_FAILURES: u32 = 10;
+
+    /// 連続失敗による一時停止の長さ（秒）。停止明けの再試行にも失敗するたびに倍にする
+    pub const POLL_FAILURE_PAUSE_SECS: u64 = 5 * 60;
+
+    /// 連続失敗による一時停止の上限（秒）
+    pub const MAX_POLL_FAILURE_PAUSE_SECS: u64 = 60 * 60;
+
+    /// 最後の stream_stats からこの時間（分）更新が無い進行中の配信はゴースト配信として扱う
+    pub const LIVE_STREAM_STALE_MINUTES: i64 = 30;
+
+    /// 配信予定（スケジュール）を再取得する間隔（秒）。クォータ残量が少ないプラットフォームはポーリング間隔と同じ倍率で延ばす
+    pub const SCHEDULE_REFRESH_INTERVAL_SECS: u64 = 60 * 60;
+
+    /// 配信予定の何分前にリマインダーを通知するか
+    pub const SCHEDULE_REMINDER_LEAD_MINUTES: i64 = 10;
+}
+
+pub mod kick {
+    /// Kick 公開APIのベースURL
+    pub const API_BASE_URL: &str = "https://kick.com/api/v2";
+
+    /// Kick APIはブラウザ以外のUAを弾くことがあるため、明示的に指定する
+    pub const USER_AGENT: &str = "Mozilla/5.0 (compatible; stream-monitor)";
+}
+
+pub mod niconico {
+    /// 視聴ページURL（/watch/{lvID | coID | chID | user/ユーザーID}）
+    pub const WATCH_PAGE_URL: &str = "https://live.nicovideo.jp/watch";
+
+    /// 放送中を表す program.status
+    pub const STATUS_ON_AIR: &str = "ON_AIR";
+
+    /// 座席維持（keepSeat）の既定間隔（秒）。seat メッセージで上書きされる
+    pub const DEFAULT_KEEP_SEAT_INTERVAL_SECS: u64 = 30;
+
+    /// 視聴ページ・WebSocket 接続で使用する User-Agent
+    pub const USER_AGENT: &str = "Mozilla/5.0 (compatible; stream-monitor)";
+}
+
+pub mod thumbnail {
+    /// サムネイルキャッシュのディレクトリ名（app_data_dir 配下）
+    pub const CACHE_DIR_NAME: &str = "thumbnails";
+
+    /// ライブ中のサムネイルを再取得する間隔（秒）
+    pub const CACHE_REFRESH_INTERVAL_SECS: u64 = 10 * 60;
+
+    /// サムネイル画像ダウンロードのタイムアウト（秒）
+    pub const DOWNLOAD_TIMEOUT_SECS: u64 = 15;
+}
+
+pub mod channel_icon {
+    /// チャンネルアイコンキャッシュのディレクトリ名（app_data_dir 配下）
+    pub const CACHE_DIR_NAME: &str = "channel_icons";
+
+    /// アイコン画像ダウンロードのタイムアウト（秒）
+    pub const DOWNLOAD_TIMEOUT_SECS: u64 = 15;
+}
+
+pub mod export {
+    /// export-progress イベントを発行する間隔（行数）
+    pub const PROGRESS_EVENT_INTERVAL: usize = 1000;
+    /// CSV / TSV エクスポートでDBから一度に取得する行数
+    pub const PAGE_SIZE: i64 = 10_000;
 }
 
 #[allow(dead_code)]
@@ -79,9 +247,60 @@ pub mod database {
     /// バッチフラッシュ間隔（秒）
     pub const BATCH_FLUSH_INTERVAL_SECS: u64 = 5;
 
+    /// DB書き込みが失敗し続けた場合に保持する未保存チャットの上限（超えた分は古いものから破棄）
+    pub const MAX_PENDING_CHAT_MESSAGES: usize = CHAT_BATCH_SIZE * 100;
+
+    /// 定期同期間隔のデフォルト値（秒）
+    pub const DEFAULT_SYNC_INTERVAL_SECS: u32 = 30;
+
+    /// 定期同期間隔の最小値（秒）
+    pub const MIN_SYNC_INTERVAL_SECS: u32 = 5;
+
+    /// 定期同期間隔の最大値（秒）
+    pub const MAX_SYNC_INTERVAL_SECS: u32 = 3600;
+
+    /// DuckDB の memory_limit のデフォルト値（MB）
+    pub const DEFAULT_MEMORY_LIMIT_MB: u32 = 1024;
+
+    /// DuckDB の memory_limit の最小値（MB）
+    pub const MIN_MEMORY_LIMIT_MB: u32 = 128;
+
+    /// DuckDB の memory_limit の最大値（MB）
+    pub const MAX_MEMORY_LIMIT_MB: u32 = 64 * 1024;
+
+    /// DuckDB の threads のデフォルト値
+    pub const DEFAULT_THREADS: u32 = 4;
+
+    /// 同一ユーザーの同一メッセージを重複とみなす時間幅のデフォルト値（秒）
+    pub const DEFAULT_CHAT_DEDUPE_WINDOW_SECS: u32 = 3;
+
+    /// 同一ユーザーの同一メッセージを重複とみなす時間幅の最大値（秒）
+    pub const MAX_CHAT_DEDUPE_WINDOW_SECS: u32 = 60;
+
+    /// リテンション（古いデータの自動削除）の実行間隔（秒）
+    pub const RETENTION_INTERVAL_SECS: u64 = 24 * 60 * 60;
+
+    /// 起動時に進行中の配信をオーファンとみなす、最後の統計からの経過時間（ポーリング間隔の倍数）
+    pub const ORPHAN_STREAM_POLL_MULTIPLIER: i64 = 3;
+
+    /// タイムライン用ロールアップ（stream_stats_rollup）の更新間隔（秒）
+    pub const ROLLUP_REFRESH_INTERVAL_SECS: u64 = 5 * 60;
+
     /// Twitchプラットフォーム名
     pub const PLATFORM_TWITCH: &str = "twitch";
 
     /// YouTubeプラットフォーム名
     pub const PLATFORM_YOUTUBE: &str = "youtube";
+
+    /// Kickプラットフォーム名
+    pub const PLATFORM_KICK: &str = "kick";
+
+    /// ニコニコ生放送プラットフォーム名
+    pub const PLATFORM_NICONICO: &str = "niconico";
+
+    /// チャンネルタグの最大文字数
+    pub const MAX_TAG_LENGTH: usize = 50;
+
+    /// 分断された配信とみなす、前の配信終了から次の配信開始までの間隔の既定値（分）
+    pub const STREAM_MERGE_MAX_GAP_MINUTES: i64 = 10;
 }
@@ -1,5 +1,7 @@
 use crate::database::models::{ChatMessage, StreamStats};
+use crate::database::utils;
 use chrono::TimeZone;
+use duckdb::Connection;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -27,6 +29,22 @@ pub struct AggregatedChatStats {
 
 pub struct DataAggregator;
 
+/// stream_stats_rollup に保持する集計粒度（分）
+pub const ROLLUP_RESOLUTIONS_MINUTES: [i32; 2] = [5, 15];
+
+/// タイムラインの粒度指定（"raw" / "5m" / "15m"）を分に変換する（raw は None）
+pub fn parse_timeline_resolution(resolution: Option<&str>) -> Result<Option<i32>, String> {
+    match resolution.unwrap_or("raw") {
+        "raw" => Ok(None),
+        "5m" => Ok(Some(5)),
+        "15m" => Ok(Some(15)),
+        other => Err(format!(
+            "Unsupported resolution: {} (expected raw, 5m or 15m)",
+            other
+        )),
+    }
+}
+
 impl DataAggregator {
     /// ストリーム統計データを指定した間隔で集計
     #[allow(dead_code)]
@@ -216,6 +234,97 @@ impl DataAggregator {
         Self::aggregate_stream_stats(stats, 60)
     }
 
+    /// stream_stats_rollup を更新し、書き込んだ行数を返す
+    ///
+    /// 最新の collected_at がロールアップに反映されていない配信のみ再集計する。
+    /// stream_id を指定した場合はその配信のみ対象とする。
+    pub fn refresh_stream_stats_rollup(
+        conn: &Connection,
+        resolution_minutes: i32,
+        stream_id: Option<i64>,
+    ) -> Result<usize, duckdb::Error> {
+        // プレースホルダーの出現順: latest の stream_id（任意）→ rolled の resolution_minutes
+        let mut params: Vec<String> = Vec::new();
+        let stream_filter = match stream_id {
+            Some(id) => {
+                params.push(id.to_string());
+                " AND stream_id = ?"
+            }
+            None => "",
+        };
+        params.push(resolution_minutes.to_string());
+
+        let sql = format!(
+            r#"
+            INSERT OR REPLACE INTO stream_stats_rollup (
+                stream_id, resolution_minutes, bucket_start, avg_viewer_count, max_viewer_count,
+                chat_messages, category, title, follower_count, data_points, last_collected_at
+            )
+            WITH latest AS (
+                SELECT stream_id, MAX(collected_at) AS last_collected_at
+                FROM stream_stats
+                WHERE stream_id IS NOT NULL{stream_filter}
+                GROUP BY stream_id
+            ),
+            rolled AS (
+                SELECT stream_id, MAX(last_collected_at) AS last_collected_at
+                FROM stream_stats_rollup
+                WHERE resolution_minutes = ?
+                GROUP BY stream_id
+            ),
+            target AS (
+                SELECT l.stream_id
+                FROM latest l
+                LEFT JOIN rolled r ON l.stream_id = r.stream_id
+                WHERE r.last_collected_at IS NULL OR r.last_collected_at < l.last_collected_at
+            ),
+            stats AS (
+                SELECT
+                    ss.stream_id,
+                    time_bucket(INTERVAL '{resolution} minutes', ss.collected_at) AS bucket_start,
+                    AVG(ss.viewer_count) AS avg_viewer_count,
+                    MAX(ss.viewer_count) AS max_viewer_count,
+                    arg_max(ss.category, ss.collected_at) AS category,
+                    arg_max(ss.title, ss.collected_at) AS title,
+                    arg_max(ss.follower_count, ss.collected_at) AS follower_count,
+                    COUNT(*) AS data_points,
+                    MAX(ss.collected_at) AS last_collected_at
+                FROM stream_stats ss
+                WHERE ss.stream_id IN (SELECT stream_id FROM target)
+                GROUP BY ss.stream_id, bucket_start
+            ),
+            chat AS (
+                SELECT
+                    cm.stream_id,
+                    time_bucket(INTERVAL '{resolution} minutes', cm.timestamp) AS bucket_start,
+                    COUNT(*) AS chat_messages
+                FROM chat_messages cm
+                WHERE cm.stream_id IN (SELECT stream_id FROM target)
+                GROUP BY cm.stream_id, bucket_start
+            )
+            SELECT
+                s.stream_id, {resolution}, s.bucket_start, s.avg_viewer_count, s.max_viewer_count,
+                COALESCE(c.chat_messages, 0), s.category, s.title, s.follower_count,
+                s.data_points, s.last_collected_at
+            FROM stats s
+            LEFT JOIN chat c ON s.stream_id = c.stream_id AND s.bucket_start = c.bucket_start
+            "#,
+            stream_filter = stream_filter,
+            resolution = resolution_minutes
+        );
+
+        utils::execute_with_params(conn, &sql, &params)
+    }
+
+    /// 全粒度の stream_stats_rollup を更新（定期タスク用）
+    pub fn refresh_all_rollups(conn: &Connection) -> Result<usize, duckdb::Error> {
+        let mut total = 0;
+        for resolution in ROLLUP_RESOLUTIONS_MINUTES {
+            total += Self::refresh_stream_stats_rollup(conn, resolution, None)?;
+        }
+        Ok(total)
+    }
+
     /// チャットメッセージを1分間隔で集計（便利関数）
     ///
     /// # 注意
@@ -270,6 +379,7 @@ mod tests {
             follower_count: None,
             twitch_user_id: None,
             channel_name: None,
+            comment_count: None,
         }];
 
         let result = DataAggregator::aggregate_stream_stats(&stats, 1);
@@ -296,6 +406,7 @@ mod tests {
                 follower_count: None,
                 twitch_user_id: None,
                 channel_name: None,
+                comment_count: None,
             },
             StreamStats {
                 id: Some(2),
@@ -309,6 +420,7 @@ mod tests {
                 follower_count: None,
                 twitch_user_id: None,
                 channel_name: None,
+                comment_count: None,
             },
         ];
 
@@ -337,6 +449,7 @@ mod tests {
                 message_type: "normal".to_string(),
                 badges: Some(vec!["broadcaster".to_string()]),
                 badge_info: None,
+                bits: None,
             },
             ChatMessage {
                 id: Some(2),
@@ -351,6 +464,7 @@ mod tests {
                 message_type: "normal".to_string(),
                 badges: None,
                 badge_info: None,
+                bits: None,
             },
         ];
 
@@ -403,4 +517,111 @@ mod tests {
             .unwrap();
         assert_eq!(result_60min, expected_60min.to_rfc3339());
     }
+
+    #[test]
+    fn test_parse_timeline_resolution() {
+        assert_eq!(parse_timeline_resolution(None), Ok(None));
+        assert_eq!(parse_timeline_resolution(Some("raw")), Ok(None));
+        assert_eq!(parse_timeline_resolution(Some("5m")), Ok(Some(5)));
+        assert_eq!(parse_timeline_resolution(Some("15m")), Ok(Some(15)));
+        assert!(parse_timeline_resolution(Some("1m")).is_err());
+        assert!(parse_timeline_resolution(Some("")).is_err());
+    }
+
+    #[test]
+    #[cfg_attr(
+        target_os = "windows",
+        ignore = "Database tests are unstable on Windows local environment"
+    )]
+    fn test_refresh_stream_stats_rollup_buckets() {
+        use crate::database::test_support::{init_test_db, insert_channel, insert_stream};
+
+        let conn = init_test_db();
+        insert_channel(&conn, 1, "twitch", "foo");
+        insert_stream(&conn, 1, 1, "2024-01-01 12:00:00", None);
+        conn.execute_batch(
+            r#"
+            INSERT INTO stream_stats (stream_id, collected_at, viewer_count, category) VALUES
+                (1, '2024-01-01 12:00:00', 100, 'A'),
+                (1, '2024-01-01 12:04:59', 200, 'B'),
+                (1, '2024-01-01 12:05:00', 300, 'C');
+            INSERT INTO chat_messages (stream_id, timestamp, platform, user_name, message) VALUES
+                (1, '2024-01-01 12:04:59', 'twitch', 'viewer', 'a'),
+                (1, '2024-01-01 12:05:00', 'twitch', 'viewer', 'b'),
+                (1, '2024-01-01 12:09:59', 'twitch', 'viewer', 'c');
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            DataAggregator::refresh_stream_stats_rollup(&conn, 5, None).unwrap(),
+            2
+        );
+        // バケット境界: 12:04:59 は 12:00 のバケット、12:05:00 は 12:05 のバケットに入る
+        let buckets: Vec<(String, f64, i32, i64, String, i32)> = conn
+            .prepare(
+                r#"
+                SELECT CAST(bucket_start AS VARCHAR), avg_viewer_count, max_viewer_count,
+                       chat_messages, category, data_points
+                FROM stream_stats_rollup
+                WHERE stream_id = 1 AND resolution_minutes = 5
+                ORDER BY bucket_start
+                "#,
+            )
+            .unwrap()
+            .query_map([], |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                    row.get(5)?,
+                ))
+            })
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            buckets,
+            vec![
+                (
+                    "2024-01-01 12:00:00".to_string(),
+                    150.0,
+                    200,
+                    1,
+                    "B".to_string(),
+                    2
+                ),
+                (
+                    "2024-01-01 12:05:00".to_string(),
+                    300.0,
+                    300,
+                    2,
+                    "C".to_string(),
+                    1
+                ),
+            ]
+        );
+
+        // 15分粒度では同じデータが1バケットにまとまる
+        assert_eq!(
+            DataAggregator::refresh_stream_stats_rollup(&conn, 15, Some(1)).unwrap(),
+            1
+        );
+
+        // 新しい統計がなければ再集計しない
+        assert_eq!(DataAggregator::refresh_all_rollups(&conn).unwrap(), 0);
+
+        // 新しい統計が入った配信は最新バケットを含めて再集計する
+        conn.execute(
+            "INSERT INTO stream_stats (stream_id, collected_at, viewer_count) VALUES (1, '2024-01-01 12:10:00', 400)",
+            [],
+        )
+        .unwrap();
+        assert_eq!(
+            DataAggregator::refresh_stream_stats_rollup(&conn, 5, None).unwrap(),
+            3
+        );
+    }
 }
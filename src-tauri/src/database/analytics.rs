@@ -1,7 +1,8 @@
 use crate::database::{
-    repositories::{AggregationRepository, StreamStatsRepository},
+    repositories::{AggregationRepository, StatsWithInterval, StreamStatsRepository},
     utils,
 };
+use chrono::NaiveDateTime;
 use duckdb::Connection;
 use serde::{Deserialize, Serialize};
 
@@ -643,6 +644,88 @@ pub fn get_channel_daily_stats(
     StreamStatsRepository::get_channel_daily_stats(conn, channel_id, start_time, end_time)
 }
 
+/// 配信終盤（アウトロ）の視聴者推移
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamOutroMetrics {
+    pub stream_id: i64,
+    /// 配信全体の平均視聴者数（インターバル加重）
+    pub stream_avg_viewers: f64,
+    /// 最後に記録された視聴者数
+    pub ending_viewer_count: Option<i32>,
+    pub ending_collected_at: Option<String>,
+    pub last_5min_avg: Option<f64>,
+    pub last_10min_avg: Option<f64>,
+    pub last_15min_avg: Option<f64>,
+    /// 終盤15分平均 / 配信全体平均（1.0未満なら終盤で視聴者が離脱）
+    pub last_15min_retention: Option<f64>,
+    pub data_points: usize,
+}
+
+fn parse_collected_at(ts: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(ts, "%Y-%m-%d %H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(ts, "%Y-%m-%d %H:%M:%S"))
+        .ok()
+}
+
+/// インターバル加重平均（LEAD で求めた間隔、最後の行は1分として扱う）
+fn weighted_avg_viewers<'a>(stats: impl Iterator<Item = &'a StatsWithInterval>) -> Option<f64> {
+    let (weighted_sum, total_minutes) = stats.fold((0.0, 0.0), |(sum, minutes), s| {
+        (
+            sum + s.viewer_count as f64 * s.interval_minutes,
+            minutes + s.interval_minutes,
+        )
+    });
+
+    if total_minutes > 0.0 {
+        Some(weighted_sum / total_minutes)
+    } else {
+        None
+    }
+}
+
+/// 配信終盤の平均視聴者数を配信全体と比較する
+///
+/// 最後の stream_stats レコードを終了時刻とみなし、直前 5/10/15 分の
+/// インターバル加重平均を算出します。
+pub fn get_stream_outro_metrics(
+    conn: &Connection,
+    stream_id: i64,
+) -> Result<StreamOutroMetrics, duckdb::Error> {
+    let stats =
+        StreamStatsRepository::get_stats_with_interval(conn, None, Some(stream_id), None, None)?;
+
+    let last = stats.last();
+    let ending_at = last.and_then(|s| parse_collected_at(&s.collected_at));
+
+    let window_avg = |minutes: i64| -> Option<f64> {
+        let ending_at = ending_at?;
+        let window_start = ending_at - chrono::Duration::minutes(minutes);
+        weighted_avg_viewers(stats.iter().filter(|s| {
+            parse_collected_at(&s.collected_at)
+                .map(|at| at >= window_start)
+                .unwrap_or(false)
+        }))
+    };
+
+    let stream_avg_viewers = weighted_avg_viewers(stats.iter()).unwrap_or(0.0);
+    let last_15min_avg = window_avg(15);
+    let last_15min_retention = last_15min_avg
+        .filter(|_| stream_avg_viewers > 0.0)
+        .map(|avg| avg / stream_avg_viewers);
+
+    Ok(StreamOutroMetrics {
+        stream_id,
+        stream_avg_viewers,
+        ending_viewer_count: last.map(|s| s.viewer_count),
+        ending_collected_at: last.map(|s| s.collected_at.clone()),
+        last_5min_avg: window_avg(5),
+        last_10min_avg: window_avg(10),
+        last_15min_avg,
+        last_15min_retention,
+        data_points: stats.len(),
+    })
+}
+
 /// チャンネル別日次統計を取得（旧実装 - 使用しない）
 #[allow(dead_code)]
 fn get_channel_daily_stats_old(
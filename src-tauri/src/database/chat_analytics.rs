@@ -1,4 +1,4 @@
-use crate::database::repositories::ChatMessageRepository;
+use crate::database::repositories::{BotFilter, ChatMessageRepository};
 use duckdb::Connection;
 use serde::{Deserialize, Serialize};
 
@@ -23,6 +23,16 @@ pub struct ChatSpike {
     pub prev_count: i64,
 }
 
+/// チャット盛り上がり度（1分ごと）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatMomentumPoint {
+    pub timestamp: String,
+    pub chat_count: i64,
+    /// 配信全体の平均チャット数からの偏差を標準偏差で割った値（z-score）
+    pub score: f64,
+}
+
 /// ユーザーセグメント統計
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -78,6 +88,7 @@ pub fn get_chat_engagement_timeline(
     start_time: Option<&str>,
     end_time: Option<&str>,
     interval_minutes: i32,
+    bot_filter: Option<&BotFilter>,
 ) -> Result<Vec<ChatEngagementStats>, duckdb::Error> {
     use crate::database::repositories::StreamStatsRepository;
 
@@ -89,6 +100,7 @@ pub fn get_chat_engagement_timeline(
         stream_id,
         start_time,
         end_time,
+        bot_filter,
     )?;
 
     // channel_idからchannel_nameを取得（エラーハンドリングを改善）
@@ -165,10 +177,11 @@ pub fn detect_chat_spikes(
     start_time: Option<&str>,
     end_time: Option<&str>,
     min_spike_ratio: f64,
+    bot_filter: Option<&BotFilter>,
 ) -> Result<Vec<ChatSpike>, duckdb::Error> {
     // 5分間隔でバケット取得
     let buckets = ChatMessageRepository::count_by_time_bucket(
-        conn, 5, channel_id, stream_id, start_time, end_time,
+        conn, 5, channel_id, stream_id, start_time, end_time, bot_filter,
     )?;
 
     // 前のバケットとの比較でスパイクを検出
@@ -197,6 +210,46 @@ pub fn detect_chat_spikes(
     Ok(spikes)
 }
 
+/// 配信のチャット盛り上がり度を計算
+///
+/// 1分ごとのチャット数を配信全体の平均・標準偏差で正規化するため、
+/// 視聴者数の規模が異なる配信同士でも相対的な盛り上がりを比較できる。
+pub fn get_chat_momentum(
+    conn: &Connection,
+    stream_id: i64,
+) -> Result<Vec<ChatMomentumPoint>, duckdb::Error> {
+    let counts = ChatMessageRepository::get_chat_count_per_minute(conn, stream_id)?;
+    if counts.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let n = counts.len() as f64;
+    let mean = counts.iter().map(|(_, c)| *c as f64).sum::<f64>() / n;
+    let variance = counts
+        .iter()
+        .map(|(_, c)| (*c as f64 - mean).powi(2))
+        .sum::<f64>()
+        / n;
+    let std_dev = variance.sqrt();
+
+    Ok(counts
+        .into_iter()
+        .map(|(timestamp, chat_count)| {
+            // チャット数が一定の場合は偏差なしとして扱う
+            let score = if std_dev > 0.0 {
+                (chat_count as f64 - mean) / std_dev
+            } else {
+                0.0
+            };
+            ChatMomentumPoint {
+                timestamp,
+                chat_count,
+                score,
+            }
+        })
+        .collect())
+}
+
 /// ユーザーセグメント別統計を取得
 ///
 /// ChatMessageRepositoryを使用して、badges直接SELECT問題を回避します。
@@ -206,10 +259,11 @@ pub fn get_user_segment_stats(
     stream_id: Option<i64>,
     start_time: Option<&str>,
     end_time: Option<&str>,
+    bot_filter: Option<&BotFilter>,
 ) -> Result<Vec<UserSegmentStats>, duckdb::Error> {
     // ChatMessageRepositoryを使用して安全にセグメント別統計を取得
     let segment_stats = ChatMessageRepository::count_by_user_segment(
-        conn, channel_id, stream_id, start_time, end_time,
+        conn, channel_id, stream_id, start_time, end_time, bot_filter,
     )?;
 
     // パーセンテージと平均メッセージ数を計算
@@ -253,9 +307,10 @@ pub fn get_top_chatters(
     start_time: Option<&str>,
     end_time: Option<&str>,
     limit: i32,
+    bot_filter: Option<&BotFilter>,
 ) -> Result<Vec<TopChatter>, duckdb::Error> {
     let chatters = ChatMessageRepository::get_top_chatters(
-        conn, channel_id, stream_id, start_time, end_time, limit,
+        conn, channel_id, stream_id, start_time, end_time, limit, bot_filter,
     )?;
 
     // ChatterWithBadgesからTopChatterに変換
@@ -280,6 +335,7 @@ pub fn get_time_pattern_stats(
     start_time: Option<&str>,
     end_time: Option<&str>,
     group_by_day: bool,
+    bot_filter: Option<&BotFilter>,
 ) -> Result<Vec<TimePatternStats>, duckdb::Error> {
     let results = ChatMessageRepository::get_time_pattern_stats(
         conn,
@@ -287,6 +343,7 @@ pub fn get_time_pattern_stats(
         start_time,
         end_time,
         group_by_day,
+        bot_filter,
     )?;
 
     Ok(results
@@ -308,9 +365,12 @@ pub fn get_chatter_behavior_stats(
     channel_id: Option<i64>,
     start_time: Option<&str>,
     end_time: Option<&str>,
+    bot_filter: Option<&BotFilter>,
 ) -> Result<ChatterBehaviorStats, duckdb::Error> {
     let (total_unique, repeater, new_chatter, avg_participation) =
-        ChatMessageRepository::get_chatter_behavior_stats(conn, channel_id, start_time, end_time)?;
+        ChatMessageRepository::get_chatter_behavior_stats(
+            conn, channel_id, start_time, end_time, bot_filter,
+        )?;
 
     let repeater_percentage = if total_unique > 0 {
         (repeater as f64 / total_unique as f64) * 100.0
@@ -438,7 +438,7 @@ pub fn get_word_frequency_analysis(
 
     // Sort and limit
     let mut word_vec: Vec<(String, i64)> = word_counts.into_iter().collect();
-    word_vec.sort_by(|a, b| b.1.cmp(&a.1));
+    word_vec.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
     word_vec.truncate(limit as usize);
 
     let unique_words = word_vec.len() as i64;
@@ -561,7 +561,7 @@ pub fn get_emote_analysis(
 
     // Sort emotes by count
     let mut emote_vec: Vec<(String, i64)> = emote_counts.into_iter().collect();
-    emote_vec.sort_by(|a, b| b.1.cmp(&a.1));
+    emote_vec.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
     emote_vec.truncate(100);
 
     let emotes: Vec<EmoteUsage> = emote_vec
@@ -6,15 +6,26 @@ pub mod models;
 pub mod query_helpers;
 pub mod repositories;
 pub mod schema;
+#[cfg(test)]
+pub mod test_support;
 pub mod utils;
 pub mod writer;
 
+use crate::config::settings::DatabaseSettings;
+use crate::constants::database as db_constants;
 use crate::error::ResultExt;
 use duckdb::Connection;
+use repositories::{
+    BackupRepository, RestoreMode, RestoreResult, RetentionRepository, RetentionResult,
+};
+use serde::Serialize;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tauri::{AppHandle, Manager};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter, Manager};
 use tokio::sync::Mutex;
+use tokio::time::{interval, Duration, MissedTickBehavior};
 
 /// DuckDB の WAL ファイルパス（DB が stream_stats.db のとき stream_stats.db.wal）
 fn wal_path(db_path: &Path) -> PathBuf {
@@ -74,72 +85,515 @@ fn recover_from_corrupted_wal(db_path: &Path) -> Result<(), Box<dyn std::error::
     Ok(())
 }
 
+/// 他プロセスがDBファイルをロックしているために開けなかったか
+///
+/// この場合はファイル自体は正常なため、退避せずにエラーとする。
+fn is_lock_conflict(error_msg: &str) -> bool {
+    let lower = error_msg.to_lowercase();
+    lower.contains("could not set lock")
+        || lower.contains("conflicting lock")
+        || lower.contains("being used by another process")
+}
+
+/// DuckDB の open エラーがファイル破損（ヘッダー不正・チェックサム不一致）によるものか
+///
+/// 権限不足やディスクの取り外しなど一時的な要因で退避すると正常なDBを捨ててしまうため、
+/// 破損と判別できるエラーのみを対象にする。
+fn is_corruption_error(error_msg: &str) -> bool {
+    let lower = error_msg.to_lowercase();
+    lower.contains("not a valid duckdb database file")
+        || lower.contains("invalid header")
+        || lower.contains("checksum")
+        || lower.contains("corrupt")
+}
+
+/// 破損したDBファイルを `<ファイル名>.corrupt.<timestamp>` にリネームして退避する
+/// 戻り値: 退避先のパス
+fn quarantine_corrupted_db(db_path: &Path) -> Result<PathBuf, std::io::Error> {
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let file_name = db_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "stream_stats.db".to_string());
+    let quarantine_path = db_path.with_file_name(format!("{}.corrupt.{}", file_name, timestamp));
+
+    std::fs::rename(db_path, &quarantine_path)?;
+
+    // 退避したDBに対応する WAL が残っていると新規DBの open 時に再生されるため一緒に退避する
+    let wal = wal_path(db_path);
+    if wal.exists() {
+        let quarantine_wal = quarantine_path.with_file_name(format!(
+            "{}.wal",
+            quarantine_path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default()
+        ));
+        if let Err(e) = std::fs::rename(&wal, &quarantine_wal) {
+            eprintln!("[DB Recovery] Failed to quarantine WAL file: {}", e);
+        }
+    }
+
+    Ok(quarantine_path)
+}
+
+/// DBファイルを開く。破損していて開けない場合はファイルを退避して新規DBとして開き直す
+/// 破損以外のエラー（ロック競合・権限不足など）はそのまま返す
+/// 戻り値: (接続, 退避した場合は退避先のパス)
+fn open_or_quarantine(db_path: &Path) -> Result<(Connection, Option<PathBuf>), String> {
+    // 1プロセス内で2回 open すると、初回失敗時に DuckDB がファイルを握ったまま Err を返すため
+    // 同じファイルの2回目の open は「使用中」で失敗する。退避（リネーム）後の新規ファイルのみ開き直す。
+    let error_msg = match Connection::open(db_path) {
+        Ok(conn) => return Ok((conn, None)),
+        Err(e) => format!("{:?}", e),
+    };
+
+    if error_msg.contains("WAL file") || error_msg.contains("replaying WAL") {
+        eprintln!(
+            "[DB Recovery] Database open failed (WAL). WAL was already backed up before open. Please restart the application once."
+        );
+        return Err(format!("Database error: {}", error_msg));
+    }
+    if !db_path.exists() || is_lock_conflict(&error_msg) || !is_corruption_error(&error_msg) {
+        return Err(format!("Database error: {}", error_msg));
+    }
+
+    eprintln!(
+        "[DB Recovery] Failed to open database, quarantining corrupted file: {}",
+        error_msg
+    );
+    let quarantine_path = quarantine_corrupted_db(db_path).map_err(|e| {
+        format!(
+            "Database error: {} (破損ファイルの退避にも失敗しました: {})",
+            error_msg, e
+        )
+    })?;
+    eprintln!(
+        "[DB Recovery] Corrupted database moved to {}, starting with a new database",
+        quarantine_path.display()
+    );
+
+    let conn = Connection::open(db_path)
+        .map_err(|e| format!("Database error after quarantine: {:?}", e))?;
+    Ok((conn, Some(quarantine_path)))
+}
+
+/// 既定のDBファイルパス（app_data_dir/stream_stats.db）
+pub fn default_db_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    match app_handle.path().app_data_dir() {
+        Ok(app_data_dir) => {
+            std::fs::create_dir_all(&app_data_dir)
+                .io_context("create app data directory")
+                .map_err(|e| e.to_string())?;
+            Ok(app_data_dir.join("stream_stats.db"))
+        }
+        Err(_) => {
+            eprintln!("Warning: Using current directory for database");
+            Ok(PathBuf::from("stream_stats.db"))
+        }
+    }
+}
+
+/// DBファイルの保存先として使用できるか検証する
+///
+/// 親ディレクトリが存在し、実際に一時ファイルを書き込めることを確認する。
+/// 起動時にも呼ばれるため、外付けドライブが外れている場合などに空のディレクトリを作らないよう
+/// ここではディレクトリを作成しない（作成は保存先の変更時に `set_database_path` で行う）。
+pub fn validate_db_path(path: &Path) -> Result<(), String> {
+    if !path.is_absolute() {
+        return Err(format!("絶対パスを指定してください: {}", path.display()));
+    }
+    if path.is_dir() {
+        return Err(format!(
+            "ディレクトリではなくファイルパスを指定してください: {}",
+            path.display()
+        ));
+    }
+    let parent = path
+        .parent()
+        .ok_or_else(|| format!("保存先ディレクトリを特定できません: {}", path.display()))?;
+    if !parent.is_dir() {
+        return Err(format!(
+            "保存先ディレクトリが存在しません: {}",
+            parent.display()
+        ));
+    }
+
+    let probe_path = parent.join(".stream_monitor_write_test");
+    std::fs::write(&probe_path, b"")
+        .map_err(|e| format!("保存先に書き込めません ({}): {}", parent.display(), e))?;
+    let _ = std::fs::remove_file(&probe_path);
+    Ok(())
+}
+
+/// 設定の db_path を解決する
+///
+/// 指定パスが使用できない場合は既定のパスにフォールバックし、警告メッセージを併せて返す。
+fn resolve_db_path(
+    app_handle: &AppHandle,
+    settings: &DatabaseSettings,
+) -> Result<(PathBuf, Option<String>), String> {
+    let custom_path = match settings.db_path.as_deref().map(str::trim) {
+        Some(path) if !path.is_empty() => PathBuf::from(path),
+        _ => return Ok((default_db_path(app_handle)?, None)),
+    };
+
+    match validate_db_path(&custom_path) {
+        Ok(()) => Ok((custom_path, None)),
+        Err(e) => {
+            let default_path = default_db_path(app_handle)?;
+            let warning = format!(
+                "設定されたDBファイルパスを使用できないため、既定のパス（{}）を使用します: {}",
+                default_path.display(),
+                e
+            );
+            eprintln!("[DB Path] {}", warning);
+            Ok((default_path, Some(warning)))
+        }
+    }
+}
+
 /// データベース接続を共有するための管理構造体
 #[derive(Clone)]
 pub struct DatabaseManager {
     conn: Arc<Mutex<Connection>>,
     db_path: PathBuf,
+    /// 設定の db_path を使用できず既定のパスにフォールバックした場合の警告
+    path_warning: Option<String>,
+    /// 起動時に破損したDBファイルを退避して新規作成した場合の退避先
+    quarantined_path: Option<PathBuf>,
+    sync_task: Arc<std::sync::Mutex<Option<JoinHandle<()>>>>,
+    retention_task: Arc<std::sync::Mutex<Option<JoinHandle<()>>>>,
+    rollup_task: Arc<std::sync::Mutex<Option<JoinHandle<()>>>>,
+    /// shutdown 済みか（終了経路が複数あっても最終同期は1回だけ行う）
+    is_shut_down: Arc<AtomicBool>,
+    /// 同期失敗をフロントエンドへ通知するためのハンドル（テストでは None）
+    app_handle: Option<AppHandle>,
+}
+
+/// 定期同期（CHECKPOINT）失敗時のイベント（database-sync-failed）
+#[derive(Debug, Clone, Serialize)]
+struct DatabaseSyncFailedEvent {
+    error: String,
+    /// 連続した同期失敗回数（成功するとリセット）
+    consecutive_failures: u32,
 }
 
 impl DatabaseManager {
-    pub fn new(app_handle: &AppHandle) -> Result<Self, Box<dyn std::error::Error>> {
-        // データベースファイルパスの取得
-        let db_path = if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
-            std::fs::create_dir_all(&app_data_dir)
-                .io_context("create app data directory")
-                .map_err(|e| e.to_string())?;
-            app_data_dir.join("stream_stats.db")
-        } else {
-            eprintln!("Warning: Using current directory for database");
-            PathBuf::from("stream_stats.db")
-        };
+    /// `settings` の定期同期間隔・リテンション設定に従ってバックグラウンドタスクを起動する
+    pub fn new(
+        app_handle: &AppHandle,
+        settings: &DatabaseSettings,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        // データベースファイルパスの取得（設定で上書き可能）
+        let (db_path, path_warning) = resolve_db_path(app_handle, settings)?;
 
         // 起動時のリカバリ処理
         cleanup_stale_files(&db_path);
 
         // 開発環境と本番環境で統一してファイルベースDBを使用
         eprintln!("Opening DuckDB at: {}", db_path.display());
-        // 1プロセス内で2回 open すると、初回失敗時に DuckDB がファイルを握ったまま Err を返すため
-        // 2回目の open が「使用中」で必ず失敗する。よって open は1回だけ行い、失敗時は再起動を促す。
-        let conn = match Connection::open(&db_path) {
-            Ok(conn) => conn,
-            Err(e) => {
-                let error_msg = format!("{:?}", e);
-                if error_msg.contains("WAL file") || error_msg.contains("replaying WAL") {
-                    eprintln!(
-                        "[DB Recovery] Database open failed (WAL). WAL was already backed up before open. Please restart the application once."
-                    );
-                }
-                return Err(format!("Database error: {}", error_msg).into());
-            }
-        };
+        let (conn, quarantined_path) = open_or_quarantine(&db_path)?;
 
         // DuckDBの設定
-        conn.execute("PRAGMA memory_limit='1GB'", []).ok();
-        conn.execute("PRAGMA threads=4", []).ok();
+        Self::apply_pragmas(&conn, settings);
         conn.execute("PRAGMA wal_autocheckpoint='1000'", []).ok(); // 1000ページごとに自動チェックポイント
 
         // スキーマ初期化
         schema::init_database(&conn)?;
+        // CURRENT_TIMESTAMP を TIMESTAMP 列に保存する際もUTCになるようにする
+        // （既存データの補正は init_database 内のマイグレーションで行う）
+        Self::use_utc_time_zone(&conn);
+
+        // クラッシュ等で終了記録されずに残った配信を、最後に収集した時刻で終了済みにする
+        match writer::DatabaseWriter::close_orphan_streams(
+            &conn,
+            &chrono::Utc::now().to_rfc3339(),
+            db_constants::ORPHAN_STREAM_POLL_MULTIPLIER,
+        ) {
+            Ok(0) => {}
+            Ok(closed) => eprintln!("[DB] Closed {} orphan streams left open", closed),
+            Err(e) => eprintln!("[DB] Failed to close orphan streams: {}", e),
+        }
 
         eprintln!("Database initialized successfully");
 
-        Ok(DatabaseManager {
+        let manager = DatabaseManager {
             conn: Arc::new(Mutex::new(conn)),
             db_path,
+            path_warning,
+            quarantined_path,
+            sync_task: Arc::new(std::sync::Mutex::new(None)),
+            retention_task: Arc::new(std::sync::Mutex::new(None)),
+            rollup_task: Arc::new(std::sync::Mutex::new(None)),
+            is_shut_down: Arc::new(AtomicBool::new(false)),
+            app_handle: Some(app_handle.clone()),
+        };
+        manager.start_periodic_sync(settings.sync_interval);
+        manager.start_retention_task(settings.retention_days, settings.retention_rollup);
+        manager.start_rollup_task();
+        manager.start_fts_index_build();
+        writer::DatabaseWriter::set_chat_dedupe_window_secs(settings.chat_dedupe_window_secs);
+
+        Ok(manager)
+    }
+
+    /// セッションのタイムゾーンをUTCにする
+    ///
+    /// ICU 拡張が読み込まれていない場合は TimeZone 設定が存在しないが、その場合は常にUTCで扱われる。
+    fn use_utc_time_zone(conn: &Connection) {
+        if let Err(e) = conn.execute("SET TimeZone = 'UTC'", []) {
+            eprintln!("[DB] TimeZone setting unavailable, using UTC: {}", e);
+        }
+    }
+
+    /// 設定の memory_limit / threads を補正して PRAGMA に反映する
+    fn apply_pragmas(conn: &Connection, settings: &DatabaseSettings) {
+        let memory_limit_mb = settings.sanitized_memory_limit_mb();
+        let threads = settings.sanitized_threads();
+        if let Err(e) = conn.execute(&format!("PRAGMA memory_limit='{}MB'", memory_limit_mb), []) {
+            eprintln!(
+                "[DB] Failed to set memory_limit to {}MB: {}",
+                memory_limit_mb, e
+            );
+        }
+        if let Err(e) = conn.execute(&format!("PRAGMA threads={}", threads), []) {
+            eprintln!("[DB] Failed to set threads to {}: {}", threads, e);
+        }
+        eprintln!(
+            "[DB] memory_limit={}MB, threads={}",
+            memory_limit_mb, threads
+        );
+    }
+
+    /// memory_limit / threads の設定を稼働中の接続に反映する
+    pub async fn apply_resource_settings(&self, settings: &DatabaseSettings) {
+        self.with_connection(|conn| Self::apply_pragmas(conn, settings))
+            .await;
+    }
+
+    /// 定期同期タスクを（再）起動する
+    /// 既存のタスクは停止され、新しい間隔で起動し直す。
+    /// 0を指定した場合はタスクを起動せず、シャットダウン時のみ同期する。
+    /// 0以外の値は MIN_SYNC_INTERVAL_SECS〜MAX_SYNC_INTERVAL_SECS にクランプされる。
+    pub fn start_periodic_sync(&self, interval_secs: u32) {
+        self.stop_periodic_sync();
+
+        if interval_secs == 0 {
+            eprintln!("[DB Sync] Periodic sync disabled (sync only on shutdown)");
+            return;
+        }
+
+        let interval_secs = interval_secs.clamp(
+            db_constants::MIN_SYNC_INTERVAL_SECS,
+            db_constants::MAX_SYNC_INTERVAL_SECS,
+        );
+        eprintln!("[DB Sync] Starting periodic sync every {}s", interval_secs);
+
+        let manager = self.clone();
+        let handle = tauri::async_runtime::spawn(async move {
+            let mut ticker = interval(Duration::from_secs(interval_secs as u64));
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            // 初回の tick は即座に完了するため読み捨てる
+            ticker.tick().await;
+
+            let mut consecutive_failures: u32 = 0;
+            loop {
+                ticker.tick().await;
+                match manager.checkpoint().await {
+                    Ok(()) => {
+                        if consecutive_failures > 0 {
+                            eprintln!(
+                                "[DB Sync] Periodic CHECKPOINT recovered after {} failures",
+                                consecutive_failures
+                            );
+                            consecutive_failures = 0;
+                        }
+                    }
+                    Err(e) => {
+                        consecutive_failures += 1;
+                        eprintln!(
+                            "[DB Sync] Periodic CHECKPOINT failed ({} consecutive): {}",
+                            consecutive_failures, e
+                        );
+                        // ディスクフル等で永続化が止まっていることにユーザーが気づけるよう通知する
+                        if let Some(app_handle) = &manager.app_handle {
+                            let _ = app_handle.emit(
+                                "database-sync-failed",
+                                DatabaseSyncFailedEvent {
+                                    error: e.to_string(),
+                                    consecutive_failures,
+                                },
+                            );
+                        }
+                    }
+                }
+            }
+        });
+
+        if let Ok(mut guard) = self.sync_task.lock() {
+            *guard = Some(handle);
+        }
+    }
+
+    /// 定期同期タスクを停止する
+    pub fn stop_periodic_sync(&self) {
+        if let Ok(mut guard) = self.sync_task.lock() {
+            if let Some(handle) = guard.take() {
+                handle.abort();
+                eprintln!("[DB Sync] Periodic sync stopped");
+            }
+        }
+    }
+
+    /// リテンションタスクを（再）起動する
+    /// 起動直後に1回、その後 RETENTION_INTERVAL_SECS ごとに古いデータを削除する。
+    /// 0日を指定した場合はタスクを起動しない。
+    pub fn start_retention_task(&self, retention_days: u32, rollup: bool) {
+        self.stop_retention_task();
+
+        if retention_days == 0 {
+            return;
+        }
+
+        eprintln!(
+            "[DB Retention] Starting retention task (keep {} days, rollup: {})",
+            retention_days, rollup
+        );
+
+        let manager = self.clone();
+        let handle = tauri::async_runtime::spawn(async move {
+            let mut ticker = interval(Duration::from_secs(db_constants::RETENTION_INTERVAL_SECS));
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                ticker.tick().await;
+                match manager.apply_retention(retention_days, rollup).await {
+                    Ok(result) => eprintln!(
+                        "[DB Retention] Archived {} streams, deleted {} stream_stats and {} chat_messages older than {}",
+                        result.streams_archived,
+                        result.stream_stats_deleted,
+                        result.chat_messages_deleted,
+                        result.cutoff
+                    ),
+                    Err(e) => eprintln!("[DB Retention] Retention failed: {}", e),
+                }
+            }
+        });
+
+        if let Ok(mut guard) = self.retention_task.lock() {
+            *guard = Some(handle);
+        }
+    }
+
+    /// リテンションタスクを停止する
+    pub fn stop_retention_task(&self) {
+        if let Ok(mut guard) = self.retention_task.lock() {
+            if let Some(handle) = guard.take() {
+                handle.abort();
+                eprintln!("[DB Retention] Retention task stopped");
+            }
+        }
+    }
+
+    /// タイムライン用ロールアップの定期更新タスクを起動する
+    fn start_rollup_task(&self) {
+        let manager = self.clone();
+        let handle = tauri::async_runtime::spawn(async move {
+            let mut ticker = interval(Duration::from_secs(
+                db_constants::ROLLUP_REFRESH_INTERVAL_SECS,
+            ));
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                ticker.tick().await;
+                let result = manager
+                    .with_connection(aggregation::DataAggregator::refresh_all_rollups)
+                    .await;
+                if let Err(e) = result {
+                    eprintln!("[DB Rollup] Failed to refresh stream_stats_rollup: {}", e);
+                }
+            }
+        });
+
+        if let Ok(mut guard) = self.rollup_task.lock() {
+            *guard = Some(handle);
+        }
+    }
+
+    /// チャット全文検索インデックスをバックグラウンドで構築する
+    ///
+    /// メッセージ数に比例して時間がかかるため起動処理（init_database）では行わない。
+    /// 構築が終わるまでの検索は LIKE にフォールバックする。
+    fn start_fts_index_build(&self) {
+        let manager = self.clone();
+        tauri::async_runtime::spawn(async move {
+            let available = manager
+                .with_connection(repositories::ChatMessageRepository::build_fts_index)
+                .await;
+            if !available {
+                eprintln!("[ChatSearch] FTS unavailable, chat search falls back to LIKE");
+            }
+        });
+    }
+
+    /// `retention_days` 日より古い配信のデータを削除する
+    /// `rollup` が true の場合は削除前に配信単位の集計を stream_stats_archive に退避する
+    pub async fn apply_retention(
+        &self,
+        retention_days: u32,
+        rollup: bool,
+    ) -> Result<RetentionResult, duckdb::Error> {
+        let cutoff =
+            (chrono::Utc::now() - chrono::Duration::days(retention_days as i64)).to_rfc3339();
+
+        self.with_connection(|conn| {
+            repositories::base::with_transaction(conn, |conn| {
+                let streams_archived = if rollup {
+                    RetentionRepository::archive_expired_streams(conn, &cutoff)?
+                } else {
+                    0
+                };
+                // stream_stats で期限切れ配信を判定するため、チャットを先に削除する
+                let chat_messages_deleted =
+                    RetentionRepository::delete_expired_chat_messages(conn, &cutoff)?;
+                let stream_stats_deleted =
+                    RetentionRepository::delete_expired_stream_stats(conn, &cutoff)?;
+
+                Ok(RetentionResult {
+                    cutoff: cutoff.clone(),
+                    streams_archived,
+                    stream_stats_deleted,
+                    chat_messages_deleted,
+                })
+            })
         })
+        .await
     }
 
     /// Exclusive access to database connection via closure.
     /// The lock is held only for the duration of the closure execution.
     /// Connection reference cannot escape the closure scope.
+    ///
+    /// ネットワークI/Oなど時間のかかる処理はクロージャの外で行い、ロック保持時間を最小にすること。
+    /// クロージャ内でパニックした場合は開きっぱなしのトランザクションをロールバックしてから
+    /// パニックを再送出する（tokio の Mutex はポイズンしないため、以降の呼び出しはそのまま使える）。
     pub async fn with_connection<F, R>(&self, f: F) -> R
     where
         F: FnOnce(&Connection) -> R + Send,
         R: Send,
     {
         let guard = self.conn.lock().await;
-        f(&guard)
+        match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| f(&guard))) {
+            Ok(result) => result,
+            Err(payload) => {
+                eprintln!("[DB] Panic inside with_connection, rolling back open transaction");
+                // トランザクション外でのパニックでは ROLLBACK がエラーになるが、無視してよい
+                let _ = guard.execute_batch("ROLLBACK");
+                drop(guard);
+                std::panic::resume_unwind(payload)
+            }
+        }
     }
 
     /// データベースファイルのパスを取得
@@ -147,10 +601,33 @@ impl DatabaseManager {
         &self.db_path
     }
 
+    /// 設定の db_path を使用できなかった場合の警告メッセージ
+    pub fn path_warning(&self) -> Option<&str> {
+        self.path_warning.as_deref()
+    }
+
+    /// 起動時に破損したDBファイルを退避した場合の退避先パス
+    pub fn quarantined_path(&self) -> Option<&Path> {
+        self.quarantined_path.as_deref()
+    }
+
     /// グレースフルシャットダウン - WALをフラッシュ
+    ///
+    /// アプリ終了時（RunEvent::Exit / シグナル）に明示的に呼び出す。2回目以降の呼び出しは何もしない。
     pub async fn shutdown(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.is_shut_down.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
         eprintln!("[DB Shutdown] Starting graceful shutdown...");
 
+        self.stop_periodic_sync();
+        self.stop_retention_task();
+        if let Ok(mut guard) = self.rollup_task.lock() {
+            if let Some(handle) = guard.take() {
+                handle.abort();
+            }
+        }
+
         let conn = self.conn.lock().await;
 
         // WALチェックポイントを強制実行（全データをメインDBにフラッシュ）
@@ -163,8 +640,101 @@ impl DatabaseManager {
         Ok(())
     }
 
+    /// 現在のDBを `path` の `.db` ファイルへバックアップする
+    pub async fn backup_to(
+        &self,
+        path: &Path,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.ensure_not_live_db(path)?;
+        self.with_connection(|conn| BackupRepository::backup_to_file(conn, path))
+            .await
+    }
+
+    /// `path` の `.db` ファイルから復元する
+    ///
+    /// 復元に失敗しても元に戻せるよう、実行前に現行DBを DB と同じディレクトリへ自動バックアップする。
+    /// 置換モードで復元に失敗した場合は、このバックアップから自動的に元の状態へ戻す。
+    /// バックアップから復元まで同じロック内で行うため、その間にポーリング等の書き込みは入らない。
+    pub async fn restore_from(
+        &self,
+        path: &Path,
+        mode: RestoreMode,
+    ) -> Result<RestoreResult, Box<dyn std::error::Error + Send + Sync>> {
+        self.ensure_not_live_db(path)?;
+        if !path.exists() {
+            return Err(format!("Backup file not found: {}", path.display()).into());
+        }
+
+        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+        let pre_restore_path = self
+            .db_path
+            .with_extension(format!("pre_restore.{}.db", timestamp));
+
+        self.with_connection(|conn| {
+            BackupRepository::backup_to_file(conn, &pre_restore_path)?;
+            eprintln!(
+                "[DB Restore] Current database backed up to {}",
+                pre_restore_path.display()
+            );
+
+            let mut result = match BackupRepository::restore_from_file(conn, path, mode) {
+                Ok(result) => result,
+                Err(e) if mode == RestoreMode::Replace => {
+                    // 置換モードは既存データの削除後にコピーが失敗し得るため、復元前の状態に戻す
+                    eprintln!(
+                        "[DB Restore] Restore failed, rolling back from {}: {}",
+                        pre_restore_path.display(),
+                        e
+                    );
+                    if let Err(rollback_err) = BackupRepository::restore_from_file(
+                        conn,
+                        &pre_restore_path,
+                        RestoreMode::Replace,
+                    ) {
+                        return Err(format!(
+                            "復元に失敗し、復元前の状態にも戻せませんでした: {}（ロールバック: {}、バックアップ: {}）",
+                            e,
+                            rollback_err,
+                            pre_restore_path.display()
+                        )
+                        .into());
+                    }
+                    return Err(e);
+                }
+                Err(e) => return Err(e),
+            };
+            result.pre_restore_backup_path = Some(pre_restore_path.display().to_string());
+
+            // 派生データ（ロールアップ・全文検索インデックス）は復元後のデータから作り直す
+            let rollup_result = conn
+                .execute("DELETE FROM stream_stats_rollup", [])
+                .and_then(|_| aggregation::DataAggregator::refresh_all_rollups(conn));
+            if let Err(e) = rollup_result {
+                eprintln!("[DB Restore] Failed to rebuild stream_stats_rollup: {}", e);
+            }
+            repositories::ChatMessageRepository::rebuild_fts_index(conn);
+
+            Ok(result)
+        })
+        .await
+    }
+
+    /// 稼働中のDBファイル自身をバックアップ先・復元元に指定していないか確認する
+    fn ensure_not_live_db(
+        &self,
+        path: &Path,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let same = match (path.canonicalize(), self.db_path.canonicalize()) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => path == self.db_path,
+        };
+        if same {
+            return Err("稼働中のデータベースファイルは指定できません".into());
+        }
+        Ok(())
+    }
+
     /// 定期的なチェックポイント（データ安全性向上）
-    #[allow(dead_code)]
     pub async fn checkpoint(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         self.with_connection(|conn| {
             conn.execute("CHECKPOINT", [])?;
@@ -180,6 +750,20 @@ mod tests {
     use tempfile::TempDir;
 
     // テスト用のヘルパー関数
+    fn manager_with_connection(conn: Connection, db_path: PathBuf) -> DatabaseManager {
+        DatabaseManager {
+            conn: Arc::new(Mutex::new(conn)),
+            db_path,
+            path_warning: None,
+            quarantined_path: None,
+            sync_task: Arc::new(std::sync::Mutex::new(None)),
+            retention_task: Arc::new(std::sync::Mutex::new(None)),
+            rollup_task: Arc::new(std::sync::Mutex::new(None)),
+            is_shut_down: Arc::new(AtomicBool::new(false)),
+            app_handle: None,
+        }
+    }
+
     fn get_connection_with_path(
         db_path: PathBuf,
     ) -> Result<Connection, Box<dyn std::error::Error>> {
@@ -189,6 +773,40 @@ mod tests {
         Ok(conn)
     }
 
+    #[tokio::test]
+    #[cfg_attr(
+        target_os = "windows",
+        ignore = "Database tests are unstable on Windows local environment"
+    )]
+    async fn test_with_connection_recovers_after_panic() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let conn = get_connection_with_path(db_path.clone()).unwrap();
+        conn.execute_batch("CREATE TABLE t (v INTEGER)").unwrap();
+        let manager = manager_with_connection(conn, db_path);
+
+        let result = futures_util::FutureExt::catch_unwind(std::panic::AssertUnwindSafe(
+            manager.with_connection(|conn| {
+                conn.execute_batch("BEGIN TRANSACTION; INSERT INTO t VALUES (1);")
+                    .unwrap();
+                panic!("boom");
+            }),
+        ))
+        .await;
+        assert!(result.is_err());
+
+        // ロックが解放され、途中のトランザクションはロールバックされている
+        let count: i64 = manager
+            .with_connection(|conn| conn.query_row("SELECT COUNT(*) FROM t", [], |r| r.get(0)))
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+        manager
+            .with_connection(|conn| conn.execute_batch("BEGIN TRANSACTION; COMMIT;"))
+            .await
+            .unwrap();
+    }
+
     #[test]
     #[cfg_attr(
         target_os = "windows",
@@ -239,4 +857,103 @@ mod tests {
 
         assert!(db_path.exists());
     }
+
+    #[tokio::test]
+    #[cfg_attr(
+        target_os = "windows",
+        ignore = "Database tests are unstable on Windows local environment"
+    )]
+    async fn test_failed_replace_restore_rolls_back() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("stream_stats.db");
+        let conn = get_connection_with_path(db_path.clone()).unwrap();
+        schema::init_database(&conn).unwrap();
+        test_support::insert_channel(&conn, 1, "twitch", "foo");
+        let manager = manager_with_connection(conn, db_path);
+
+        // channels.platform の CHECK 制約に違反する、互換性の無いバックアップ
+        let backup_path = temp_dir.path().join("incompatible.db");
+        let backup = get_connection_with_path(backup_path.clone()).unwrap();
+        backup
+            .execute_batch(
+                r#"
+                CREATE TABLE channels (id BIGINT, platform TEXT, channel_id TEXT, channel_name TEXT);
+                INSERT INTO channels VALUES (1, 'unknown', 'bar', 'bar');
+                "#,
+            )
+            .unwrap();
+        drop(backup);
+
+        assert!(manager
+            .restore_from(&backup_path, RestoreMode::Replace)
+            .await
+            .is_err());
+
+        // 削除済みのデータは復元前のバックアップから戻っている
+        let names: Vec<String> = manager
+            .with_connection(|conn| {
+                let mut stmt = conn.prepare("SELECT channel_name FROM channels")?;
+                let names = stmt
+                    .query_map([], |row| row.get(0))?
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok::<_, duckdb::Error>(names)
+            })
+            .await
+            .unwrap();
+        assert_eq!(names, vec!["foo".to_string()]);
+    }
+
+    #[test]
+    fn test_validate_db_path() {
+        let temp_dir = TempDir::new().unwrap();
+
+        assert!(validate_db_path(&temp_dir.path().join("stream_stats.db")).is_ok());
+
+        // 存在しない親ディレクトリは作成せずにエラーとする
+        let nested = temp_dir.path().join("external").join("stream_stats.db");
+        assert!(validate_db_path(&nested).is_err());
+        assert!(!nested.parent().unwrap().exists());
+
+        assert!(validate_db_path(Path::new("relative/stream_stats.db")).is_err());
+        assert!(validate_db_path(temp_dir.path()).is_err());
+    }
+
+    #[test]
+    #[cfg_attr(
+        target_os = "windows",
+        ignore = "Database tests are unstable on Windows local environment"
+    )]
+    fn test_open_or_quarantine_corrupted_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("stream_stats.db");
+        std::fs::write(&db_path, b"this is not a duckdb database file").unwrap();
+
+        let (conn, quarantined) = open_or_quarantine(&db_path).unwrap();
+        let quarantined = quarantined.expect("corrupted file should be quarantined");
+        assert!(quarantined.exists());
+        assert!(quarantined
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("stream_stats.db.corrupt."));
+
+        // 新規DBとしてスキーマを作成できる
+        schema::init_database(&conn).unwrap();
+    }
+
+    #[test]
+    fn test_is_corruption_error() {
+        assert!(is_corruption_error(
+            "IO Error: The file \"stream_stats.db\" exists, but it is not a valid DuckDB database file!"
+        ));
+        assert!(is_corruption_error(
+            "IO Error: Corrupt database file: computed checksum 1 does not match stored checksum 2"
+        ));
+        assert!(!is_corruption_error(
+            "IO Error: Cannot open file \"stream_stats.db\": Permission denied"
+        ));
+        assert!(!is_corruption_error(
+            "IO Error: Could not set lock on file \"stream_stats.db\": Conflicting lock is held"
+        ));
+    }
 }
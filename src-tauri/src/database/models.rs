@@ -18,6 +18,19 @@ pub struct Channel {
     pub twitch_user_id: Option<i64>, // Twitchの不変なuser ID（内部識別子）
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
+    /// チャットを収集するか（false の場合は IRC 等に接続しない）
+    #[serde(default = "default_true")]
+    pub collect_chat: bool,
+    /// 視聴者数などの統計（stream_stats）を収集するか
+    #[serde(default = "default_true")]
+    pub collect_stats: bool,
+    /// この視聴者数を超えたら通知する（None の場合は通知しない）
+    #[serde(default)]
+    pub alert_viewer_threshold: Option<i32>,
+}
+
+fn default_true() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +58,8 @@ pub struct StreamStats {
     pub follower_count: Option<i32>,
     pub twitch_user_id: Option<String>,
     pub channel_name: Option<String>,
+    /// 累計コメント数（ニコニコ生放送など、プラットフォームが提供する場合のみ）
+    pub comment_count: Option<i64>,
 }
 
 /// Combined stream data returned by collectors
@@ -58,6 +73,23 @@ pub struct StreamData {
     pub started_at: String,
     pub viewer_count: Option<i32>,
     pub follower_count: Option<i32>,
+    /// 累計コメント数（ニコニコ生放送など、プラットフォームが提供する場合のみ）
+    pub comment_count: Option<i64>,
+    /// 配信者の表示名（取得できたプラットフォームのみ）
+    pub display_name: Option<String>,
+    /// 配信者のプロフィール画像URL（取得できたプラットフォームのみ）
+    pub profile_image_url: Option<String>,
+}
+
+/// 配信予定（collectors が取得し scheduled_streams に保存する）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledStreamData {
+    /// 開始予定時刻（RFC3339）
+    pub scheduled_start: String,
+    /// 終了予定時刻（RFC3339、プラットフォームが提供する場合のみ）
+    pub scheduled_end: Option<String>,
+    pub title: Option<String>,
+    pub category: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,6 +99,9 @@ pub struct ChannelWithStats {
     pub is_live: bool,
     pub current_viewers: i32,
     pub current_title: String,
+    /// チャンネルに付与されたタグ（グループ表示用）
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 /// Event payload for channel stats updates
@@ -78,6 +113,17 @@ pub struct ChannelStatsEvent {
     pub title: Option<String>,
 }
 
+/// Event payload for stream lifecycle notifications (stream-started / stream-ended)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamLifecycleEvent {
+    pub channel_id: i64,
+    /// streams.id
+    pub stream_id: i64,
+    /// プラットフォーム固有の配信ID（Twitch stream ID / YouTube video ID）
+    pub platform_stream_id: String,
+    pub title: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
     pub id: Option<i64>,
@@ -92,6 +138,8 @@ pub struct ChatMessage {
     pub message_type: String,
     pub badges: Option<Vec<String>>,
     pub badge_info: Option<String>, // サブスク月数等の詳細情報 (例: "subscriber:24")
+    #[serde(default)]
+    pub bits: Option<i64>, // cheer で送られたBits数（message_type = "cheer" の場合）
 }
 
 /// ゲームカテゴリ（Twitch game/category）
@@ -127,6 +175,9 @@ mod tests {
             poll_interval: 60,
             created_at: Some("2024-01-01T00:00:00Z".to_string()),
             updated_at: Some("2024-01-01T00:00:00Z".to_string()),
+            collect_chat: true,
+            collect_stats: false,
+            alert_viewer_threshold: Some(1000),
         };
 
         let json = serde_json::to_string(&channel).unwrap();
@@ -153,6 +204,7 @@ mod tests {
             follower_count: Some(5000),
             twitch_user_id: Some("123456789".to_string()),
             channel_name: Some("test_channel".to_string()),
+            comment_count: None,
         };
 
         let json = serde_json::to_string(&stats).unwrap();
@@ -0,0 +1,575 @@
+/// BackupRepository - DBファイルへのバックアップと外部DBファイルからの復元
+///
+/// 外部ファイルは ATTACH して扱います。復元元は古いバージョンのアプリで作成された
+/// DBの可能性があるため、テーブルごとに両側に存在する列だけをコピーします。
+use crate::database::utils::quote_literal;
+use duckdb::Connection;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 復元元DBをアタッチする際のエイリアス
+const RESTORE_SOURCE: &str = "restore_source";
+
+/// バックアップ先DBをアタッチする際のエイリアス
+const BACKUP_TARGET: &str = "backup_target";
+
+/// 復元モード
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RestoreMode {
+    /// 現在のデータをすべて削除し、復元元の内容で置き換える
+    Replace,
+    /// 現在のデータを残したまま、復元元にしか存在しないデータを追加する
+    Merge,
+}
+
+/// 復元結果（テーブルごとの追加件数）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RestoreResult {
+    pub channels: usize,
+    pub streams: usize,
+    pub stream_stats: usize,
+    pub chat_messages: usize,
+    /// 復元前に自動作成したバックアップのパス
+    pub pre_restore_backup_path: Option<String>,
+}
+
+pub struct BackupRepository;
+
+impl BackupRepository {
+    /// 現在のDBの内容を `path` の新しいDBファイルへコピーする
+    ///
+    /// 途中で失敗しても既存ファイルを壊さないよう、一時ファイルに書き出してから置き換える。
+    pub fn backup_to_file(
+        conn: &Connection,
+        path: &Path,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let tmp_path = path.with_extension("db.tmp");
+        if tmp_path.exists() {
+            std::fs::remove_file(&tmp_path)?;
+        }
+
+        // WALの内容をメインDBへ反映してからコピーする
+        conn.execute("CHECKPOINT", [])?;
+        let current_db: String =
+            conn.query_row("SELECT current_database()", [], |row| row.get(0))?;
+
+        conn.execute_batch(&format!(
+            "ATTACH {} AS {}",
+            quote_literal(&tmp_path.to_string_lossy()),
+            BACKUP_TARGET
+        ))?;
+        let copy_result = conn.execute_batch(&format!(
+            "COPY FROM DATABASE {} TO {}",
+            quote_identifier(&current_db),
+            BACKUP_TARGET
+        ));
+        let detach_result = conn.execute_batch(&format!("DETACH {}", BACKUP_TARGET));
+
+        if let Err(e) = copy_result.and(detach_result) {
+            let _ = std::fs::remove_file(&tmp_path);
+            return Err(e.into());
+        }
+
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// `path` のDBファイルを読み込み、`mode` に従って現在のDBへ復元する
+    pub fn restore_from_file(
+        conn: &Connection,
+        path: &Path,
+        mode: RestoreMode,
+    ) -> Result<RestoreResult, Box<dyn std::error::Error + Send + Sync>> {
+        conn.execute_batch(&format!(
+            "ATTACH {} AS {} (READ_ONLY)",
+            quote_literal(&path.to_string_lossy()),
+            RESTORE_SOURCE
+        ))?;
+
+        let result = match mode {
+            RestoreMode::Replace => Self::restore_replace(conn),
+            RestoreMode::Merge => Self::restore_merge(conn),
+        };
+
+        let _ = conn.execute_batch("DROP TABLE IF EXISTS restore_channel_map");
+        let _ = conn.execute_batch("DROP TABLE IF EXISTS restore_stream_map");
+        conn.execute_batch(&format!("DETACH {}", RESTORE_SOURCE))?;
+
+        Ok(result?)
+    }
+
+    /// 置換モード: 全データ削除後に復元元の行をIDごとコピーする
+    ///
+    /// 削除とコピーは別トランザクションのため、コピーに失敗すると空のDBが残る。
+    /// 呼び出し側（DatabaseManager::restore_from）が復元前のバックアップから元に戻す。
+    fn restore_replace(conn: &Connection) -> Result<RestoreResult, duckdb::Error> {
+        // DuckDB は同一トランザクション内の参照元削除を FK チェックで認識しないため、
+        // 参照元 → streams → channels の順に別トランザクションで削除する
+        // 配信予定は次回のスケジュール取得で再作成されるため復元しない
+        for statements in [
+            "DELETE FROM chat_messages; DELETE FROM stream_stats; \
+             DELETE FROM stream_stats_rollup; DELETE FROM stream_stats_archive; \
+             DELETE FROM channel_tags; DELETE FROM scheduled_streams;",
+            "DELETE FROM streams;",
+            "DELETE FROM channels; DELETE FROM sql_templates; DELETE FROM game_categories;",
+        ] {
+            conn.execute_batch(&format!("BEGIN TRANSACTION; {} COMMIT;", statements))
+                .inspect_err(|_| {
+                    let _ = conn.execute("ROLLBACK", []);
+                })?;
+        }
+
+        let result = super::base::with_transaction(conn, |conn| {
+            let result = RestoreResult {
+                channels: Self::copy_table(conn, "channels")?,
+                streams: Self::copy_table(conn, "streams")?,
+                stream_stats: Self::copy_table(conn, "stream_stats")?,
+                chat_messages: Self::copy_table(conn, "chat_messages")?,
+                pre_restore_backup_path: None,
+            };
+            Self::copy_table(conn, "stream_stats_archive")?;
+            Self::copy_table(conn, "channel_tags")?;
+            Self::copy_table(conn, "sql_templates")?;
+            Self::copy_table(conn, "game_categories")?;
+            Ok::<_, duckdb::Error>(result)
+        })?;
+
+        // 明示的なIDで挿入したため、シーケンスを最大IDの先まで進める
+        for (sequence, table) in [
+            ("channels_id_seq", "channels"),
+            ("streams_id_seq", "streams"),
+            ("stream_stats_id_seq", "stream_stats"),
+            ("chat_messages_id_seq", "chat_messages"),
+            ("sql_templates_id_seq", "sql_templates"),
+        ] {
+            Self::advance_sequence(conn, sequence, table)?;
+        }
+
+        Ok(result)
+    }
+
+    /// マージモード: 自然キーで既存行と突き合わせ、未登録の行だけを新しいIDで追加する
+    ///
+    /// - channels: (platform, channel_id)
+    /// - streams: (channel, stream_id)
+    /// - stream_stats: (stream, collected_at)
+    /// - chat_messages: (channel, timestamp, user_name, message)
+    fn restore_merge(conn: &Connection) -> Result<RestoreResult, duckdb::Error> {
+        super::base::with_transaction(conn, |conn| {
+            let columns = Self::common_columns(conn, "channels", &["id"])?;
+            let channels = conn.execute(
+                &format!(
+                    r#"
+                    INSERT INTO channels ({cols})
+                    SELECT {src_cols} FROM {src}.main.channels s
+                    WHERE NOT EXISTS (
+                        SELECT 1 FROM channels d
+                        WHERE d.platform = s.platform AND d.channel_id = s.channel_id
+                    )
+                    "#,
+                    cols = columns.join(", "),
+                    src_cols = prefixed(&columns, "s"),
+                    src = RESTORE_SOURCE,
+                ),
+                [],
+            )?;
+            conn.execute_batch(&format!(
+                r#"
+                CREATE TEMP TABLE restore_channel_map AS
+                SELECT s.id AS src_id, d.id AS dst_id
+                FROM {src}.main.channels s
+                JOIN channels d ON d.platform = s.platform AND d.channel_id = s.channel_id
+                "#,
+                src = RESTORE_SOURCE,
+            ))?;
+
+            // 復元元にタグがある場合（タグ機能追加後のバックアップ）のみ取り込む
+            if !Self::common_columns(conn, "channel_tags", &[])?.is_empty() {
+                conn.execute(
+                    &format!(
+                        r#"
+                        INSERT OR IGNORE INTO channel_tags (channel_id, tag)
+                        SELECT m.dst_id, s.tag
+                        FROM {src}.main.channel_tags s
+                        JOIN restore_channel_map m ON s.channel_id = m.src_id
+                        "#,
+                        src = RESTORE_SOURCE,
+                    ),
+                    [],
+                )?;
+            }
+
+            let columns = Self::common_columns(conn, "streams", &["id", "channel_id"])?;
+            let streams = conn.execute(
+                &format!(
+                    r#"
+                    INSERT INTO streams (channel_id, {cols})
+                    SELECT m.dst_id, {src_cols}
+                    FROM {src}.main.streams s
+                    JOIN restore_channel_map m ON s.channel_id = m.src_id
+                    WHERE NOT EXISTS (
+                        SELECT 1 FROM streams d
+                        WHERE d.channel_id = m.dst_id AND d.stream_id = s.stream_id
+                    )
+                    "#,
+                    cols = columns.join(", "),
+                    src_cols = prefixed(&columns, "s"),
+                    src = RESTORE_SOURCE,
+                ),
+                [],
+            )?;
+            conn.execute_batch(&format!(
+                r#"
+                CREATE TEMP TABLE restore_stream_map AS
+                SELECT s.id AS src_id, d.id AS dst_id
+                FROM {src}.main.streams s
+                JOIN restore_channel_map m ON s.channel_id = m.src_id
+                JOIN streams d ON d.channel_id = m.dst_id AND d.stream_id = s.stream_id
+                "#,
+                src = RESTORE_SOURCE,
+            ))?;
+
+            let columns = Self::common_columns(conn, "stream_stats", &["id", "stream_id"])?;
+            let stream_stats = conn.execute(
+                &format!(
+                    r#"
+                    INSERT INTO stream_stats (stream_id, {cols})
+                    SELECT m.dst_id, {src_cols}
+                    FROM {src}.main.stream_stats s
+                    JOIN restore_stream_map m ON s.stream_id = m.src_id
+                    WHERE NOT EXISTS (
+                        SELECT 1 FROM stream_stats d
+                        WHERE d.stream_id = m.dst_id AND d.collected_at = s.collected_at
+                    )
+                    "#,
+                    cols = columns.join(", "),
+                    src_cols = prefixed(&columns, "s"),
+                    src = RESTORE_SOURCE,
+                ),
+                [],
+            )?;
+
+            let columns =
+                Self::common_columns(conn, "chat_messages", &["id", "channel_id", "stream_id"])?;
+            let chat_messages = conn.execute(
+                &format!(
+                    r#"
+                    INSERT INTO chat_messages (channel_id, stream_id, {cols})
+                    SELECT cm.dst_id, sm.dst_id, {src_cols}
+                    FROM {src}.main.chat_messages s
+                    LEFT JOIN restore_channel_map cm ON s.channel_id = cm.src_id
+                    LEFT JOIN restore_stream_map sm ON s.stream_id = sm.src_id
+                    WHERE NOT EXISTS (
+                        SELECT 1 FROM chat_messages d
+                        WHERE d.channel_id IS NOT DISTINCT FROM cm.dst_id
+                          AND d.timestamp = s.timestamp
+                          AND d.user_name = s.user_name
+                          AND d.message = s.message
+                    )
+                    "#,
+                    cols = columns.join(", "),
+                    src_cols = prefixed(&columns, "s"),
+                    src = RESTORE_SOURCE,
+                ),
+                [],
+            )?;
+
+            // 以下の補助テーブルは古いバージョンのDBには存在しない場合がある
+            let columns =
+                Self::common_columns(conn, "stream_stats_archive", &["stream_id", "channel_id"])?;
+            if !columns.is_empty() {
+                conn.execute(
+                    &format!(
+                        r#"
+                        INSERT OR IGNORE INTO stream_stats_archive (stream_id, channel_id, {cols})
+                        SELECT sm.dst_id, cm.dst_id, {src_cols}
+                        FROM {src}.main.stream_stats_archive s
+                        JOIN restore_stream_map sm ON s.stream_id = sm.src_id
+                        LEFT JOIN restore_channel_map cm ON s.channel_id = cm.src_id
+                        "#,
+                        cols = columns.join(", "),
+                        src_cols = prefixed(&columns, "s"),
+                        src = RESTORE_SOURCE,
+                    ),
+                    [],
+                )?;
+            }
+
+            let columns = Self::common_columns(conn, "sql_templates", &["id"])?;
+            if !columns.is_empty() {
+                conn.execute(
+                    &format!(
+                        r#"
+                        INSERT INTO sql_templates ({cols})
+                        SELECT {src_cols} FROM {src}.main.sql_templates s
+                        WHERE NOT EXISTS (SELECT 1 FROM sql_templates d WHERE d.name = s.name)
+                        "#,
+                        cols = columns.join(", "),
+                        src_cols = prefixed(&columns, "s"),
+                        src = RESTORE_SOURCE,
+                    ),
+                    [],
+                )?;
+            }
+
+            let columns = Self::common_columns(conn, "game_categories", &[])?;
+            if !columns.is_empty() {
+                conn.execute(
+                    &format!(
+                        "INSERT OR IGNORE INTO game_categories ({cols}) \
+                         SELECT {cols} FROM {src}.main.game_categories",
+                        cols = columns.join(", "),
+                        src = RESTORE_SOURCE,
+                    ),
+                    [],
+                )?;
+            }
+
+            Ok(RestoreResult {
+                channels,
+                streams,
+                stream_stats,
+                chat_messages,
+                pre_restore_backup_path: None,
+            })
+        })
+    }
+
+    /// 復元元の `table` の行を、両側に存在する列だけでそのままコピーする
+    fn copy_table(conn: &Connection, table: &str) -> Result<usize, duckdb::Error> {
+        let columns = Self::common_columns(conn, table, &[])?;
+        if columns.is_empty() {
+            return Ok(0);
+        }
+        let cols = columns.join(", ");
+        // UNIQUE(stream_id, collected_at) 追加前のバックアップには重複した stream_stats が
+        // 含まれ得るため、制約に違反する行はスキップする
+        conn.execute(
+            &format!(
+                "INSERT INTO {table} ({cols}) SELECT {cols} FROM {src}.main.{table} ON CONFLICT DO NOTHING",
+                src = RESTORE_SOURCE,
+            ),
+            [],
+        )
+    }
+
+    /// 現在のDBと復元元の両方に存在する `table` の列（`exclude` を除く）
+    ///
+    /// 復元元にテーブル自体が無い場合は空になる。
+    fn common_columns(
+        conn: &Connection,
+        table: &str,
+        exclude: &[&str],
+    ) -> Result<Vec<String>, duckdb::Error> {
+        let mut stmt = conn.prepare(
+            r#"
+                SELECT t.column_name
+                FROM information_schema.columns t
+                JOIN information_schema.columns s
+                  ON s.table_name = t.table_name AND s.column_name = t.column_name
+                WHERE t.table_catalog = current_database() AND t.table_schema = 'main'
+                  AND s.table_catalog = ? AND s.table_schema = 'main'
+                  AND t.table_name = ?
+                ORDER BY t.ordinal_position
+                "#,
+        )?;
+        let columns = stmt
+            .query_map([RESTORE_SOURCE, table], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(columns
+            .into_iter()
+            .filter(|c| !exclude.contains(&c.as_str()))
+            .map(|c| quote_identifier(&c))
+            .collect())
+    }
+
+    /// `sequence` の次の値が `table` の最大IDより大きくなるまで進める
+    fn advance_sequence(
+        conn: &Connection,
+        sequence: &str,
+        table: &str,
+    ) -> Result<(), duckdb::Error> {
+        let next: i64 = conn.query_row(&format!("SELECT nextval('{}')", sequence), [], |row| {
+            row.get(0)
+        })?;
+        let max_id: i64 = conn.query_row(
+            &format!("SELECT COALESCE(MAX(id), 0) FROM {}", table),
+            [],
+            |row| row.get(0),
+        )?;
+        if max_id >= next {
+            conn.query_row(
+                &format!(
+                    "SELECT MAX(nextval('{}')) FROM range({})",
+                    sequence,
+                    max_id - next + 1
+                ),
+                [],
+                |row| row.get::<_, i64>(0),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn quote_identifier(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+/// 列リストにテーブルエイリアスを付与する
+fn prefixed(columns: &[String], alias: &str) -> String {
+    columns
+        .iter()
+        .map(|c| format!("{}.{}", alias, c))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{init_test_db, insert_channel, insert_stream};
+    use tempfile::TempDir;
+
+    fn count(conn: &Connection, sql: &str) -> i64 {
+        conn.query_row(sql, [], |row| row.get(0)).unwrap()
+    }
+
+    /// チャンネル foo の配信 s1 に統計2件・チャット1件を持つバックアップを作成する
+    fn create_backup(temp_dir: &TempDir) -> std::path::PathBuf {
+        let conn = init_test_db();
+        insert_channel(&conn, 1, "twitch", "foo");
+        insert_stream(&conn, 1, 1, "2024-01-01 12:00:00", None);
+        conn.execute_batch(
+            r#"
+            INSERT INTO stream_stats (id, stream_id, collected_at, viewer_count) VALUES
+                (1, 1, '2024-01-01 12:01:00', 10),
+                (2, 1, '2024-01-01 12:02:00', 20);
+            INSERT INTO chat_messages (id, channel_id, stream_id, timestamp, platform, user_name, message)
+            VALUES (1, 1, 1, '2024-01-01 12:01:30', 'twitch', 'alice', 'hi');
+            "#,
+        )
+        .unwrap();
+
+        let path = temp_dir.path().join("backup.db");
+        BackupRepository::backup_to_file(&conn, &path).unwrap();
+        path
+    }
+
+    #[test]
+    #[cfg_attr(
+        target_os = "windows",
+        ignore = "Database tests are unstable on Windows local environment"
+    )]
+    fn test_backup_and_replace_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let backup_path = create_backup(&temp_dir);
+
+        let conn = init_test_db();
+        insert_channel(&conn, 7, "youtube", "other");
+
+        let result =
+            BackupRepository::restore_from_file(&conn, &backup_path, RestoreMode::Replace).unwrap();
+        assert_eq!(
+            (
+                result.channels,
+                result.streams,
+                result.stream_stats,
+                result.chat_messages
+            ),
+            (1, 1, 2, 1)
+        );
+        assert_eq!(
+            count(
+                &conn,
+                "SELECT COUNT(*) FROM channels WHERE channel_name = 'other'"
+            ),
+            0
+        );
+
+        // 明示IDで復元した後も、シーケンスによる新しいIDは既存IDと衝突しない
+        let next_id: i64 = conn
+            .query_row(
+                "INSERT INTO stream_stats (stream_id, collected_at) VALUES (1, '2024-01-01 12:03:00') RETURNING id",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(next_id > 2);
+    }
+
+    #[test]
+    #[cfg_attr(
+        target_os = "windows",
+        ignore = "Database tests are unstable on Windows local environment"
+    )]
+    fn test_merge_adds_only_missing_rows() {
+        let temp_dir = TempDir::new().unwrap();
+        let backup_path = create_backup(&temp_dir);
+
+        // 同じ配信（IDは異なる）に統計1件を持つDBと、別チャンネルを持つDB
+        let conn = init_test_db();
+        insert_channel(&conn, 5, "youtube", "bar");
+        insert_channel(&conn, 6, "twitch", "foo");
+        insert_stream(&conn, 9, 6, "2024-01-01 12:00:00", None);
+        conn.execute("UPDATE streams SET stream_id = 's1' WHERE id = 9", [])
+            .unwrap();
+        conn.execute(
+            "INSERT INTO stream_stats (stream_id, collected_at, viewer_count) VALUES (9, '2024-01-01 12:01:00', 10)",
+            [],
+        )
+        .unwrap();
+
+        let result =
+            BackupRepository::restore_from_file(&conn, &backup_path, RestoreMode::Merge).unwrap();
+        assert_eq!(
+            (
+                result.channels,
+                result.streams,
+                result.stream_stats,
+                result.chat_messages
+            ),
+            (0, 0, 1, 1)
+        );
+        assert_eq!(
+            count(
+                &conn,
+                "SELECT COUNT(*) FROM stream_stats WHERE stream_id = 9"
+            ),
+            2
+        );
+        assert_eq!(
+            count(
+                &conn,
+                "SELECT COUNT(*) FROM chat_messages WHERE channel_id = 6 AND stream_id = 9"
+            ),
+            1
+        );
+
+        // 同じファイルを再度マージしても重複しない
+        let again =
+            BackupRepository::restore_from_file(&conn, &backup_path, RestoreMode::Merge).unwrap();
+        assert_eq!((again.stream_stats, again.chat_messages), (0, 0));
+    }
+
+    #[test]
+    fn test_advance_sequence_skips_existing_ids() {
+        let conn = init_test_db();
+        insert_channel(&conn, 10, "twitch", "foo");
+
+        BackupRepository::advance_sequence(&conn, "channels_id_seq", "channels").unwrap();
+        let first: i64 = conn
+            .query_row("SELECT nextval('channels_id_seq')", [], |row| row.get(0))
+            .unwrap();
+        assert!(first > 10);
+
+        // 既に最大IDを超えている場合は進めない（advance_sequence 自身が1つ消費する）
+        BackupRepository::advance_sequence(&conn, "channels_id_seq", "channels").unwrap();
+        let second: i64 = conn
+            .query_row("SELECT nextval('channels_id_seq')", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(second, first + 2);
+    }
+}
@@ -2,7 +2,8 @@
 ///
 /// チャンネルテーブルへのアクセスを抽象化
 use crate::database::models::Channel;
-use duckdb::Connection;
+use duckdb::{Connection, OptionalExt};
+use serde::{Deserialize, Serialize};
 
 pub struct ChannelRepository;
 
@@ -15,6 +16,52 @@ pub struct CreateChannelParams {
     pub twitch_user_id: Option<i64>,
 }
 
+/// DB上のライブ状態（未終了の配信と最新の stream_stats から算出）
+pub struct ChannelLiveState {
+    pub channel_id: i64,
+    pub current_viewers: i32,
+    pub current_title: String,
+}
+
+/// 現在ライブ中のチャンネル（進行中の配信と最新の stream_stats）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveChannel {
+    pub channel_id: i64,
+    pub platform: String,
+    pub channel_name: String,
+    pub display_name: Option<String>,
+    /// 進行中の配信（streams.id）
+    pub stream_id: i64,
+    pub started_at: String,
+    /// 統計がまだ1件も無い場合は None
+    pub viewer_count: Option<i32>,
+    pub title: Option<String>,
+    pub category: Option<String>,
+    pub last_collected_at: Option<String>,
+}
+
+/// チャンネルのエクスポート/インポート用エントリ（環境に依存するIDや統計は含めない）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelExportEntry {
+    pub platform: String,
+    pub channel_id: String,
+    pub channel_name: String,
+    pub enabled: bool,
+    pub poll_interval: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub twitch_user_id: Option<i64>,
+}
+
+/// 1件のインポート結果
+pub enum ChannelImportOutcome {
+    /// 新規追加された（DB上のID）
+    Added(i64),
+    /// 既存チャンネルを上書きした（DB上のID, 上書き前の有効状態）
+    Updated(i64, bool),
+    /// 既存チャンネルのためスキップした
+    Skipped,
+}
+
 impl ChannelRepository {
     /// IDでチャンネルを取得
     pub fn get_by_id(conn: &Connection, id: i64) -> Result<Option<Channel>, duckdb::Error> {
@@ -24,7 +71,7 @@ impl ChannelRepository {
                 platform, 
                 channel_id, 
                 channel_name, 
-                COALESCE(display_name, channel_name) as display_name, 
+                COALESCE(NULLIF(display_name, ''), channel_name) as display_name, 
                 COALESCE(profile_image_url, '') as profile_image_url, 
                 enabled, 
                 poll_interval, 
@@ -35,7 +82,10 @@ impl ChannelRepository {
                 COALESCE(discovered_at, '') as discovered_at, 
                 twitch_user_id, 
                 CAST(created_at AS VARCHAR) as created_at, 
-                CAST(updated_at AS VARCHAR) as updated_at 
+                CAST(updated_at AS VARCHAR) as updated_at, 
+                COALESCE(collect_chat, true) as collect_chat, 
+                COALESCE(collect_stats, true) as collect_stats, 
+                alert_viewer_threshold 
             FROM channels 
             WHERE id = ?",
         )?;
@@ -59,13 +109,16 @@ impl ChannelRepository {
                 twitch_user_id: row.get(13)?,
                 created_at: Some(row.get(14)?),
                 updated_at: Some(row.get(15)?),
+                collect_chat: row.get(16)?,
+                collect_stats: row.get(17)?,
+                alert_viewer_threshold: row.get(18)?,
             })
         })?;
 
         rows.next().transpose()
     }
 
-    /// 全チャンネルを取得（作成日時降順）
+    /// 全チャンネルを取得（作成日時降順、論理削除済みは除く）
     pub fn list_all(conn: &Connection) -> Result<Vec<Channel>, duckdb::Error> {
         let mut stmt = conn.prepare(
             "SELECT 
@@ -73,7 +126,7 @@ impl ChannelRepository {
                 platform, 
                 channel_id, 
                 channel_name, 
-                COALESCE(display_name, channel_name) as display_name, 
+                COALESCE(NULLIF(display_name, ''), channel_name) as display_name, 
                 COALESCE(profile_image_url, '') as profile_image_url, 
                 enabled, 
                 poll_interval, 
@@ -84,8 +137,12 @@ impl ChannelRepository {
                 COALESCE(discovered_at, '') as discovered_at, 
                 twitch_user_id, 
                 CAST(created_at AS VARCHAR) as created_at, 
-                CAST(updated_at AS VARCHAR) as updated_at 
+                CAST(updated_at AS VARCHAR) as updated_at, 
+                COALESCE(collect_chat, true) as collect_chat, 
+                COALESCE(collect_stats, true) as collect_stats, 
+                alert_viewer_threshold 
             FROM channels 
+            WHERE deleted_at IS NULL
             ORDER BY created_at DESC",
         )?;
 
@@ -108,6 +165,9 @@ impl ChannelRepository {
                     twitch_user_id: row.get(13)?,
                     created_at: Some(row.get(14)?),
                     updated_at: Some(row.get(15)?),
+                    collect_chat: row.get(16)?,
+                    collect_stats: row.get(17)?,
+                    alert_viewer_threshold: row.get(18)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -127,7 +187,7 @@ impl ChannelRepository {
                 platform, 
                 channel_id, 
                 channel_name, 
-                COALESCE(display_name, channel_name) as display_name, 
+                COALESCE(NULLIF(display_name, ''), channel_name) as display_name, 
                 COALESCE(profile_image_url, '') as profile_image_url, 
                 enabled, 
                 poll_interval, 
@@ -138,9 +198,12 @@ impl ChannelRepository {
                 COALESCE(discovered_at, '') as discovered_at, 
                 twitch_user_id, 
                 CAST(created_at AS VARCHAR) as created_at, 
-                CAST(updated_at AS VARCHAR) as updated_at 
+                CAST(updated_at AS VARCHAR) as updated_at, 
+                COALESCE(collect_chat, true) as collect_chat, 
+                COALESCE(collect_stats, true) as collect_stats, 
+                alert_viewer_threshold 
             FROM channels 
-            WHERE platform = ?
+            WHERE platform = ? AND deleted_at IS NULL
             ORDER BY created_at DESC",
         )?;
 
@@ -163,6 +226,9 @@ impl ChannelRepository {
                     twitch_user_id: row.get(13)?,
                     created_at: Some(row.get(14)?),
                     updated_at: Some(row.get(15)?),
+                    collect_chat: row.get(16)?,
+                    collect_stats: row.get(17)?,
+                    alert_viewer_threshold: row.get(18)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -179,7 +245,7 @@ impl ChannelRepository {
                 platform, 
                 channel_id, 
                 channel_name, 
-                COALESCE(display_name, channel_name) as display_name, 
+                COALESCE(NULLIF(display_name, ''), channel_name) as display_name, 
                 COALESCE(profile_image_url, '') as profile_image_url, 
                 enabled, 
                 poll_interval, 
@@ -190,9 +256,12 @@ impl ChannelRepository {
                 COALESCE(discovered_at, '') as discovered_at, 
                 twitch_user_id, 
                 CAST(created_at AS VARCHAR) as created_at, 
-                CAST(updated_at AS VARCHAR) as updated_at 
+                CAST(updated_at AS VARCHAR) as updated_at, 
+                COALESCE(collect_chat, true) as collect_chat, 
+                COALESCE(collect_stats, true) as collect_stats, 
+                alert_viewer_threshold 
             FROM channels 
-            WHERE enabled = true
+            WHERE enabled = true AND deleted_at IS NULL
             ORDER BY created_at DESC",
         )?;
 
@@ -215,6 +284,9 @@ impl ChannelRepository {
                     twitch_user_id: row.get(13)?,
                     created_at: Some(row.get(14)?),
                     updated_at: Some(row.get(15)?),
+                    collect_chat: row.get(16)?,
+                    collect_stats: row.get(17)?,
+                    alert_viewer_threshold: row.get(18)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -223,7 +295,23 @@ impl ChannelRepository {
     }
 
     /// チャンネルを作成（IDを返す）
+    ///
+    /// 同じ (platform, channel_id) の論理削除済みチャンネルがある場合は、
+    /// 過去のデータを引き継ぐため新規作成せずに復元する。
     pub fn create(conn: &Connection, params: CreateChannelParams) -> Result<i64, duckdb::Error> {
+        if let Some(id) = Self::restore_deleted(conn, &params.platform, &params.channel_id)? {
+            conn.execute(
+                "UPDATE channels SET channel_name = ?, poll_interval = ?, twitch_user_id = COALESCE(?, twitch_user_id), enabled = true, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+                duckdb::params![
+                    &params.channel_name,
+                    params.poll_interval,
+                    params.twitch_user_id,
+                    id,
+                ],
+            )?;
+            return Ok(id);
+        }
+
         let channel_id: i64 = conn.query_row(
             "INSERT INTO channels (platform, channel_id, channel_name, poll_interval, twitch_user_id) 
              VALUES (?, ?, ?, ?, ?) RETURNING id",
@@ -247,6 +335,34 @@ impl ChannelRepository {
         Ok(())
     }
 
+    /// チャンネルを論理削除（無効化して deleted_at を設定）
+    ///
+    /// 配信・統計・チャットなどの過去データは残し、チャンネル一覧やポーリング対象から外す。
+    /// 配信予定はリマインダー通知の対象から外すため削除する。
+    pub fn soft_delete(conn: &Connection, id: i64) -> Result<(), duckdb::Error> {
+        conn.execute(
+            "UPDATE channels SET enabled = false, deleted_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+            duckdb::params![id],
+        )?;
+        super::ScheduledStreamRepository::delete_by_channel(conn, id)?;
+        Ok(())
+    }
+
+    /// 論理削除済みのチャンネルを復元する（無効状態のまま deleted_at のみ解除）
+    /// 戻り値: 復元したチャンネルのID（該当なしは None）
+    fn restore_deleted(
+        conn: &Connection,
+        platform: &str,
+        channel_id: &str,
+    ) -> Result<Option<i64>, duckdb::Error> {
+        conn.query_row(
+            "UPDATE channels SET deleted_at = NULL, updated_at = CURRENT_TIMESTAMP WHERE platform = ? AND channel_id = ? AND deleted_at IS NOT NULL RETURNING id",
+            [platform, channel_id],
+            |row| row.get(0),
+        )
+        .optional()
+    }
+
     /// チャンネルと関連データを削除。DuckDB は同一トランザクション内で FK 参照先の削除を認識しないため、参照元削除と streams/channels 削除を別トランザクションで実行する。
     pub fn delete_channel_and_related(conn: &Connection, id: i64) -> Result<(), duckdb::Error> {
         let stream_ids: Vec<i64> = conn
@@ -269,6 +385,8 @@ impl ChannelRepository {
                 "DELETE FROM chat_messages WHERE channel_id = ?",
                 duckdb::params![id],
             )?;
+            super::ChannelTagRepository::delete_by_channel(conn, id)?;
+            super::ScheduledStreamRepository::delete_by_channel(conn, id)?;
             Ok(())
         })();
         match r1 {
@@ -329,6 +447,32 @@ impl ChannelRepository {
         Ok(())
     }
 
+    /// プラットフォームから取得した表示名を保存する
+    pub fn update_display_name(
+        conn: &Connection,
+        id: i64,
+        display_name: &str,
+    ) -> Result<(), duckdb::Error> {
+        conn.execute(
+            "UPDATE channels SET display_name = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+            duckdb::params![display_name, id],
+        )?;
+        Ok(())
+    }
+
+    /// プラットフォームから取得したプロフィール画像URLを保存する
+    pub fn update_profile_image_url(
+        conn: &Connection,
+        id: i64,
+        profile_image_url: &str,
+    ) -> Result<(), duckdb::Error> {
+        conn.execute(
+            "UPDATE channels SET profile_image_url = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+            duckdb::params![profile_image_url, id],
+        )?;
+        Ok(())
+    }
+
     /// チャンネル情報を更新（指定したフィールドのみ）
     pub fn update(
         conn: &Connection,
@@ -336,6 +480,8 @@ impl ChannelRepository {
         channel_name: Option<String>,
         poll_interval: Option<i32>,
         enabled: Option<bool>,
+        collect_chat: Option<bool>,
+        collect_stats: Option<bool>,
     ) -> Result<(), duckdb::Error> {
         use crate::database::utils;
         let mut updates = Vec::new();
@@ -353,6 +499,14 @@ impl ChannelRepository {
             updates.push("enabled = ?");
             params.push(en.to_string());
         }
+        if let Some(chat) = collect_chat {
+            updates.push("collect_chat = ?");
+            params.push(chat.to_string());
+        }
+        if let Some(stats) = collect_stats {
+            updates.push("collect_stats = ?");
+            params.push(stats.to_string());
+        }
         if updates.is_empty() {
             return Ok(());
         }
@@ -365,10 +519,22 @@ impl ChannelRepository {
         Ok(())
     }
 
+    /// 視聴者数アラートの閾値を設定（None で解除）
+    pub fn set_alert_viewer_threshold(
+        conn: &Connection,
+        id: i64,
+        threshold: Option<i32>,
+    ) -> Result<usize, duckdb::Error> {
+        conn.execute(
+            "UPDATE channels SET alert_viewer_threshold = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+            duckdb::params![threshold, id],
+        )
+    }
+
     /// Twitchの全ユーザーIDを取得（自動発見用）
     pub fn get_all_twitch_user_ids(conn: &Connection) -> Result<Vec<i64>, duckdb::Error> {
         let mut stmt = conn.prepare(
-            "SELECT twitch_user_id FROM channels WHERE platform = 'twitch' AND twitch_user_id IS NOT NULL"
+            "SELECT twitch_user_id FROM channels WHERE platform = 'twitch' AND twitch_user_id IS NOT NULL AND deleted_at IS NULL"
         )?;
 
         let user_ids = stmt
@@ -385,11 +551,34 @@ impl ChannelRepository {
         channel_id: &str,
     ) -> Result<bool, duckdb::Error> {
         let mut stmt =
-            conn.prepare("SELECT COUNT(*) FROM channels WHERE platform = ? AND channel_id = ?")?;
+            conn.prepare("SELECT COUNT(*) FROM channels WHERE platform = ? AND channel_id = ? AND deleted_at IS NULL")?;
         let count: i64 = stmt.query_row([platform, channel_id], |row| row.get(0))?;
         Ok(count > 0)
     }
 
+    /// プラットフォームとチャンネルIDから登録状態（DB上のID, 自動発見か, 有効か）を取得
+    pub fn get_registration_state(
+        conn: &Connection,
+        platform: &str,
+        channel_id: &str,
+    ) -> Result<Option<(i64, bool, bool)>, duckdb::Error> {
+        conn.query_row(
+            "SELECT id, COALESCE(is_auto_discovered, false), enabled FROM channels WHERE platform = ? AND channel_id = ? AND deleted_at IS NULL",
+            [platform, channel_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()
+    }
+
+    /// 自動収集中（有効な自動発見チャンネル）の数
+    pub fn count_enabled_auto_discovered(conn: &Connection) -> Result<i64, duckdb::Error> {
+        conn.query_row(
+            "SELECT COUNT(*) FROM channels WHERE is_auto_discovered = true AND enabled = true",
+            [],
+            |row| row.get(0),
+        )
+    }
+
     /// 自動発見フラグとtwitch_user_idを更新
     pub fn update_auto_discovered(
         conn: &Connection,
@@ -423,27 +612,37 @@ impl ChannelRepository {
         )
     }
 
-    /// 自動発見されたチャンネルのうち、最新の配信が終了しているものを取得（クリーンアップ対象）
+    /// 自動収集中のチャンネルのうち、最新の配信が終了しているものを取得（収集解除の対象）
+    ///
+    /// 発見から `grace_secs` 秒経っても配信が1件も記録されていないチャンネルも対象にする。
     pub fn get_offline_auto_discovered_channels(
         conn: &Connection,
+        grace_secs: i64,
     ) -> Result<Vec<(i64, String)>, duckdb::Error> {
         let mut stmt = conn.prepare(
             r#"
             SELECT c.id, c.channel_name
             FROM channels c
             WHERE c.is_auto_discovered = true
+            AND c.enabled = true
             AND NOT EXISTS (
                 SELECT 1 FROM streams s
                 WHERE s.channel_id = c.id
                 AND s.ended_at IS NULL
             )
-            AND EXISTS (
-                SELECT 1 FROM streams s
-                WHERE s.channel_id = c.id
+            AND (
+                EXISTS (
+                    SELECT 1 FROM streams s
+                    WHERE s.channel_id = c.id
+                )
+                -- 発見後に一度も配信を記録できなかったチャンネル（発見直後に配信終了した等）
+                -- TIMESTAMPTZ の減算は ICU 拡張が必要なため、UTC の TIMESTAMP にそろえて比較する
+                OR CAST(TRY_CAST(c.discovered_at AS TIMESTAMPTZ) AS TIMESTAMP)
+                    < CAST(CURRENT_TIMESTAMP AS TIMESTAMP) - to_seconds(?)
             )
             "#,
         )?;
-        let rows = stmt.query_map([], |row| {
+        let rows = stmt.query_map([grace_secs], |row| {
             Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
         })?;
         rows.collect::<Result<Vec<_>, _>>()
@@ -457,4 +656,302 @@ impl ChannelRepository {
         let exists: bool = stmt.query_row([channel_id], |row| row.get(0))?;
         Ok(exists)
     }
+
+    /// 未終了の配信（ended_at IS NULL）があるチャンネルのライブ状態を取得
+    ///
+    /// 視聴者数・タイトルは最新の stream_stats から取り、統計がまだ無い場合は配信のタイトルを使う。
+    pub fn list_live_states(conn: &Connection) -> Result<Vec<ChannelLiveState>, duckdb::Error> {
+        let mut stmt = conn.prepare(
+            r#"
+            WITH open_streams AS (
+                SELECT id, channel_id, title,
+                    ROW_NUMBER() OVER (PARTITION BY channel_id ORDER BY started_at DESC) as rn
+                FROM streams
+                WHERE ended_at IS NULL
+            ),
+            latest_stats AS (
+                SELECT os.channel_id, os.title as stream_title, ss.viewer_count, ss.title,
+                    ROW_NUMBER() OVER (PARTITION BY os.channel_id ORDER BY ss.collected_at DESC) as rn
+                FROM open_streams os
+                LEFT JOIN stream_stats ss ON ss.stream_id = os.id
+                WHERE os.rn = 1
+            )
+            SELECT channel_id, COALESCE(viewer_count, 0), COALESCE(title, stream_title, '')
+            FROM latest_stats
+            WHERE rn = 1
+            "#,
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(ChannelLiveState {
+                channel_id: row.get(0)?,
+                current_viewers: row.get(1)?,
+                current_title: row.get(2)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>()
+    }
+
+    /// 現在ライブ中のチャンネルを最新の視聴者数・タイトル・カテゴリ付きで取得（視聴者数降順）
+    ///
+    /// 終了検知に失敗して ended_at が NULL のまま残った「ゴースト配信」を除外するため、
+    /// 最後の stream_stats（統計が無い場合は配信開始時刻）が `stale_cutoff` より古い配信は含めない。
+    /// タイトル・カテゴリは最新の stream_stats を優先し、記録が無い場合は streams の値を使う。
+    pub fn list_live_channels(
+        conn: &Connection,
+        stale_cutoff: &str,
+    ) -> Result<Vec<LiveChannel>, duckdb::Error> {
+        let mut stmt = conn.prepare(
+            r#"
+            WITH open_streams AS (
+                SELECT id, channel_id, started_at, title, category,
+                    ROW_NUMBER() OVER (PARTITION BY channel_id ORDER BY started_at DESC) as rn
+                FROM streams
+                WHERE ended_at IS NULL
+            ),
+            latest_stats AS (
+                SELECT ss.stream_id, ss.collected_at, ss.viewer_count,
+                    NULLIF(ss.title, '') as title, NULLIF(ss.category, '') as category,
+                    ROW_NUMBER() OVER (PARTITION BY ss.stream_id ORDER BY ss.collected_at DESC) as rn
+                FROM stream_stats ss
+                INNER JOIN open_streams os ON ss.stream_id = os.id AND os.rn = 1
+            )
+            SELECT
+                c.id,
+                c.platform,
+                c.channel_name,
+                NULLIF(c.display_name, ''),
+                os.id,
+                os.started_at::VARCHAR,
+                ls.viewer_count,
+                COALESCE(ls.title, NULLIF(os.title, '')),
+                COALESCE(ls.category, NULLIF(os.category, '')),
+                ls.collected_at::VARCHAR
+            FROM open_streams os
+            INNER JOIN channels c ON c.id = os.channel_id
+            LEFT JOIN latest_stats ls ON ls.stream_id = os.id AND ls.rn = 1
+            WHERE os.rn = 1
+              AND c.deleted_at IS NULL
+              AND COALESCE(ls.collected_at, os.started_at) >= ?::TIMESTAMP
+            ORDER BY ls.viewer_count DESC NULLS LAST, c.channel_name ASC
+            "#,
+        )?;
+        let rows = stmt.query_map([stale_cutoff], |row| {
+            Ok(LiveChannel {
+                channel_id: row.get(0)?,
+                platform: row.get(1)?,
+                channel_name: row.get(2)?,
+                display_name: row.get(3)?,
+                stream_id: row.get(4)?,
+                started_at: row.get(5)?,
+                viewer_count: row.get(6)?,
+                title: row.get(7)?,
+                category: row.get(8)?,
+                last_collected_at: row.get(9)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>()
+    }
+
+    /// エクスポート用に手動登録チャンネルを取得（自動発見チャンネルは環境固有のため除外）
+    pub fn list_for_export(conn: &Connection) -> Result<Vec<ChannelExportEntry>, duckdb::Error> {
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT platform, channel_id, channel_name, enabled, poll_interval, twitch_user_id
+            FROM channels
+            WHERE COALESCE(is_auto_discovered, false) = false
+              AND deleted_at IS NULL
+            ORDER BY platform, channel_id
+            "#,
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(ChannelExportEntry {
+                platform: row.get(0)?,
+                channel_id: row.get(1)?,
+                channel_name: row.get(2)?,
+                enabled: row.get(3)?,
+                poll_interval: row.get(4)?,
+                twitch_user_id: row.get(5)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>()
+    }
+
+    /// エクスポートしたエントリを1件インポートする
+    ///
+    /// (platform, channel_id) の UNIQUE 制約で重複を判定し、既存の場合は
+    /// `overwrite` が true ならチャンネル名・有効状態・ポーリング間隔を上書きする。
+    pub fn import_entry(
+        conn: &Connection,
+        entry: &ChannelExportEntry,
+        overwrite: bool,
+    ) -> Result<ChannelImportOutcome, duckdb::Error> {
+        // 論理削除済みのチャンネルは復元して新規追加として扱う
+        if let Some(id) = Self::restore_deleted(conn, &entry.platform, &entry.channel_id)? {
+            Self::update(
+                conn,
+                id,
+                Some(entry.channel_name.clone()),
+                Some(entry.poll_interval),
+                Some(entry.enabled),
+                None,
+                None,
+            )?;
+            return Ok(ChannelImportOutcome::Added(id));
+        }
+
+        let inserted: Option<i64> = conn
+            .query_row(
+                r#"
+                INSERT INTO channels (platform, channel_id, channel_name, enabled, poll_interval, twitch_user_id)
+                VALUES (?, ?, ?, ?, ?, ?)
+                ON CONFLICT (platform, channel_id) DO NOTHING
+                RETURNING id
+                "#,
+                duckdb::params![
+                    &entry.platform,
+                    &entry.channel_id,
+                    &entry.channel_name,
+                    entry.enabled,
+                    entry.poll_interval,
+                    entry.twitch_user_id,
+                ],
+                |row| row.get(0),
+            )
+            .map(Some)
+            .or_else(|e| match e {
+                duckdb::Error::QueryReturnedNoRows => Ok(None),
+                e => Err(e),
+            })?;
+
+        if let Some(id) = inserted {
+            return Ok(ChannelImportOutcome::Added(id));
+        }
+        if !overwrite {
+            return Ok(ChannelImportOutcome::Skipped);
+        }
+
+        let (id, was_enabled): (i64, bool) = conn.query_row(
+            "SELECT id, enabled FROM channels WHERE platform = ? AND channel_id = ?",
+            [&entry.platform, &entry.channel_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        Self::update(
+            conn,
+            id,
+            Some(entry.channel_name.clone()),
+            Some(entry.poll_interval),
+            Some(entry.enabled),
+            None,
+            None,
+        )?;
+        Ok(ChannelImportOutcome::Updated(id, was_enabled))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{init_test_db, insert_channel, insert_stream};
+
+    #[test]
+    fn test_get_registration_state() {
+        let conn = init_test_db();
+        insert_channel(&conn, 1, "twitch", "manual");
+        insert_channel(&conn, 2, "twitch", "auto");
+        ChannelRepository::update_auto_discovered(&conn, "twitch", "auto", true, Some(200))
+            .unwrap();
+
+        let state = |platform: &str, channel_id: &str| {
+            ChannelRepository::get_registration_state(&conn, platform, channel_id).unwrap()
+        };
+        assert_eq!(state("twitch", "manual"), Some((1, false, true)));
+        assert_eq!(state("twitch", "auto"), Some((2, true, true)));
+        assert_eq!(state("twitch", "unknown"), None);
+        assert_eq!(state("youtube", "manual"), None);
+
+        // 自動収集を解除したチャンネルは無効として返す
+        ChannelRepository::update_enabled(&conn, 2, false).unwrap();
+        assert_eq!(state("twitch", "auto"), Some((2, true, false)));
+
+        // 削除済みのチャンネルは未登録として扱う
+        ChannelRepository::soft_delete(&conn, 1).unwrap();
+        assert_eq!(state("twitch", "manual"), None);
+    }
+
+    #[test]
+    fn test_count_enabled_auto_discovered() {
+        let conn = init_test_db();
+        assert_eq!(
+            ChannelRepository::count_enabled_auto_discovered(&conn).unwrap(),
+            0
+        );
+
+        insert_channel(&conn, 1, "twitch", "manual");
+        for (id, name) in [(2, "auto1"), (3, "auto2"), (4, "released")] {
+            insert_channel(&conn, id, "twitch", name);
+            ChannelRepository::update_auto_discovered(&conn, "twitch", name, true, None).unwrap();
+        }
+        ChannelRepository::update_enabled(&conn, 4, false).unwrap();
+
+        assert_eq!(
+            ChannelRepository::count_enabled_auto_discovered(&conn).unwrap(),
+            2
+        );
+
+        // 手動登録に昇格したチャンネルは数えない
+        ChannelRepository::update_auto_discovered(&conn, "twitch", "auto2", false, None).unwrap();
+        assert_eq!(
+            ChannelRepository::count_enabled_auto_discovered(&conn).unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_get_offline_auto_discovered_channels_grace() {
+        let conn = init_test_db();
+        for (id, name) in [(1, "ended"), (2, "live"), (3, "new"), (4, "stale")] {
+            insert_channel(&conn, id, "twitch", name);
+            ChannelRepository::update_auto_discovered(&conn, "twitch", name, true, None).unwrap();
+        }
+        insert_channel(&conn, 5, "twitch", "manual");
+        insert_stream(
+            &conn,
+            1,
+            1,
+            "2024-01-01 12:00:00",
+            Some("2024-01-01 13:00:00"),
+        );
+        insert_stream(&conn, 2, 2, "2024-01-01 12:00:00", None);
+        insert_stream(
+            &conn,
+            3,
+            5,
+            "2024-01-01 12:00:00",
+            Some("2024-01-01 13:00:00"),
+        );
+        // 発見から猶予時間を過ぎても配信を記録できなかったチャンネル
+        conn.execute(
+            "UPDATE channels SET discovered_at = '2024-01-01 00:00:00+00' WHERE id = 4",
+            [],
+        )
+        .unwrap();
+
+        let mut offline =
+            ChannelRepository::get_offline_auto_discovered_channels(&conn, 600).unwrap();
+        offline.sort();
+        assert_eq!(
+            offline,
+            vec![(1, "ended".to_string()), (4, "stale".to_string())]
+        );
+
+        // 猶予0秒なら発見直後で配信の無いチャンネルも対象になる
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        let ids: Vec<i64> = ChannelRepository::get_offline_auto_discovered_channels(&conn, 0)
+            .unwrap()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert!(ids.contains(&3));
+        assert!(!ids.contains(&2));
+    }
 }
@@ -0,0 +1,101 @@
+/// ChannelTagRepository - チャンネルのタグ付け・グルーピング
+///
+/// channel_tags テーブル（channel_id, tag）へのアクセスを提供します。
+/// channels はマイグレーションで再作成されることがあるため外部キーは張らず、
+/// チャンネル削除時は ChannelRepository::delete_channel_and_related で併せて削除します。
+use duckdb::Connection;
+use std::collections::HashMap;
+
+pub struct ChannelTagRepository;
+
+impl ChannelTagRepository {
+    /// タグを付与する（付与済みの場合は何もしない）
+    pub fn add_tag(conn: &Connection, channel_id: i64, tag: &str) -> Result<(), duckdb::Error> {
+        conn.execute(
+            "INSERT OR IGNORE INTO channel_tags (channel_id, tag) VALUES (?, ?)",
+            duckdb::params![channel_id, tag],
+        )?;
+        Ok(())
+    }
+
+    /// タグを外す
+    /// 戻り値: 削除した件数（付与されていなかった場合は0）
+    pub fn remove_tag(
+        conn: &Connection,
+        channel_id: i64,
+        tag: &str,
+    ) -> Result<usize, duckdb::Error> {
+        conn.execute(
+            "DELETE FROM channel_tags WHERE channel_id = ? AND tag = ?",
+            duckdb::params![channel_id, tag],
+        )
+    }
+
+    /// チャンネルのタグをすべて削除
+    pub fn delete_by_channel(conn: &Connection, channel_id: i64) -> Result<usize, duckdb::Error> {
+        conn.execute(
+            "DELETE FROM channel_tags WHERE channel_id = ?",
+            duckdb::params![channel_id],
+        )
+    }
+
+    /// タグが付いたチャンネルIDを取得
+    pub fn list_channel_ids_by_tag(
+        conn: &Connection,
+        tag: &str,
+    ) -> Result<Vec<i64>, duckdb::Error> {
+        let mut stmt =
+            conn.prepare("SELECT channel_id FROM channel_tags WHERE tag = ? ORDER BY channel_id")?;
+        let rows = stmt.query_map([tag], |row| row.get(0))?;
+        rows.collect()
+    }
+
+    /// 全チャンネルのタグを channel_id ごとにまとめて取得（タグ名順）
+    pub fn tags_by_channel(conn: &Connection) -> Result<HashMap<i64, Vec<String>>, duckdb::Error> {
+        let mut stmt = conn.prepare("SELECT channel_id, tag FROM channel_tags ORDER BY tag")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get(1)?)))?;
+
+        let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+        for row in rows {
+            let (channel_id, tag) = row?;
+            tags.entry(channel_id).or_default().push(tag);
+        }
+        Ok(tags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::init_test_db;
+
+    #[test]
+    fn test_channel_tags() {
+        let conn = init_test_db();
+
+        ChannelTagRepository::add_tag(&conn, 1, "fps").unwrap();
+        ChannelTagRepository::add_tag(&conn, 1, "fps").unwrap();
+        ChannelTagRepository::add_tag(&conn, 1, "jp").unwrap();
+        ChannelTagRepository::add_tag(&conn, 2, "fps").unwrap();
+
+        assert_eq!(
+            ChannelTagRepository::list_channel_ids_by_tag(&conn, "fps").unwrap(),
+            vec![1, 2]
+        );
+        let tags = ChannelTagRepository::tags_by_channel(&conn).unwrap();
+        assert_eq!(tags[&1], vec!["fps".to_string(), "jp".to_string()]);
+
+        assert_eq!(
+            ChannelTagRepository::remove_tag(&conn, 2, "fps").unwrap(),
+            1
+        );
+        assert_eq!(
+            ChannelTagRepository::remove_tag(&conn, 2, "fps").unwrap(),
+            0
+        );
+        assert_eq!(
+            ChannelTagRepository::list_channel_ids_by_tag(&conn, "fps").unwrap(),
+            vec![1]
+        );
+    }
+}
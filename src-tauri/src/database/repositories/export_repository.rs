@@ -0,0 +1,291 @@
+/// ExportRepository - DuckDB の COPY 文によるエクスポート専用レポジトリ
+///
+/// COPY 文ではパラメータバインドが使えないため、値は utils::quote_literal で
+/// エスケープしてから SQL に埋め込みます。
+use crate::database::utils::quote_literal;
+use duckdb::Connection;
+
+/// DuckDB に登録する S3 シークレット名（エクスポート完了後に削除する）
+const S3_SECRET_NAME: &str = "stream_monitor_s3_export";
+
+/// S3互換ストレージの接続パラメータ
+pub struct S3SecretParams<'a> {
+    pub access_key_id: &'a str,
+    pub secret_access_key: &'a str,
+    pub region: &'a str,
+    pub endpoint: Option<&'a str>,
+    pub url_style: Option<&'a str>,
+    pub use_ssl: bool,
+}
+
+/// ローカルファイルへ Parquet エクスポートできるテーブル（ホワイトリスト）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportTable {
+    Streams,
+    StreamStats,
+    ChatMessages,
+}
+
+impl ExportTable {
+    /// エクスポート可能な全テーブル
+    pub const ALL: [Self; 3] = [Self::Streams, Self::StreamStats, Self::ChatMessages];
+
+    /// テーブル名
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Streams => "streams",
+            Self::StreamStats => "stream_stats",
+            Self::ChatMessages => "chat_messages",
+        }
+    }
+
+    /// テーブル名を検証して変換する。ホワイトリスト外の名前は None
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "streams" => Some(Self::Streams),
+            "stream_stats" => Some(Self::StreamStats),
+            "chat_messages" => Some(Self::ChatMessages),
+            _ => None,
+        }
+    }
+}
+
+pub struct ExportRepository;
+
+impl ExportRepository {
+    /// エクスポート用の stream_stats SELECT 文を生成
+    fn stream_stats_select(
+        channel_id: Option<i64>,
+        start_time: Option<&str>,
+        end_time: Option<&str>,
+    ) -> String {
+        let mut sql = String::from(
+            r#"
+            SELECT
+                ss.collected_at,
+                s.channel_id,
+                ss.stream_id,
+                ss.channel_name,
+                ss.viewer_count,
+                COALESCE((
+                    SELECT COUNT(*)
+                    FROM chat_messages cm
+                    WHERE cm.stream_id = ss.stream_id
+                      AND cm.timestamp >= ss.collected_at - INTERVAL '1 minute'
+                      AND cm.timestamp < ss.collected_at
+                ), 0) AS chat_rate_1min,
+                ss.category,
+                ss.game_id,
+                ss.title,
+                ss.follower_count
+            FROM stream_stats ss
+            INNER JOIN streams s ON ss.stream_id = s.id
+            WHERE 1=1
+            "#,
+        );
+
+        if let Some(cid) = channel_id {
+            sql.push_str(&format!(" AND s.channel_id = {}", cid));
+        }
+        if let Some(st) = start_time {
+            sql.push_str(&format!(" AND ss.collected_at >= {}", quote_literal(st)));
+        }
+        if let Some(et) = end_time {
+            sql.push_str(&format!(" AND ss.collected_at <= {}", quote_literal(et)));
+        }
+
+        sql.push_str(" ORDER BY ss.collected_at ASC");
+        sql
+    }
+
+    /// エクスポート用の streams SELECT 文を生成（期間は started_at で絞り込む）
+    fn streams_select(
+        channel_id: Option<i64>,
+        start_time: Option<&str>,
+        end_time: Option<&str>,
+    ) -> String {
+        let mut sql = String::from(
+            r#"
+            SELECT
+                s.id,
+                s.channel_id,
+                c.platform,
+                c.channel_name,
+                s.stream_id,
+                s.title,
+                s.category,
+                s.thumbnail_url,
+                s.started_at,
+                s.ended_at
+            FROM streams s
+            INNER JOIN channels c ON s.channel_id = c.id
+            WHERE 1=1
+            "#,
+        );
+
+        if let Some(cid) = channel_id {
+            sql.push_str(&format!(" AND s.channel_id = {}", cid));
+        }
+        if let Some(st) = start_time {
+            sql.push_str(&format!(" AND s.started_at >= {}", quote_literal(st)));
+        }
+        if let Some(et) = end_time {
+            sql.push_str(&format!(" AND s.started_at <= {}", quote_literal(et)));
+        }
+
+        sql.push_str(" ORDER BY s.started_at ASC");
+        sql
+    }
+
+    /// エクスポート用の chat_messages SELECT 文を生成
+    fn chat_messages_select(
+        channel_id: Option<i64>,
+        start_time: Option<&str>,
+        end_time: Option<&str>,
+    ) -> String {
+        // channel_id が未設定の古いメッセージは配信側のチャンネルで補完する
+        let mut sql = String::from(
+            r#"
+            SELECT
+                cm.id,
+                COALESCE(cm.channel_id, s.channel_id) AS channel_id,
+                cm.stream_id,
+                cm.timestamp,
+                cm.platform,
+                cm.user_id,
+                cm.user_name,
+                cm.message,
+                cm.message_type
+            FROM chat_messages cm
+            LEFT JOIN streams s ON cm.stream_id = s.id
+            WHERE 1=1
+            "#,
+        );
+
+        if let Some(cid) = channel_id {
+            sql.push_str(&format!(
+                " AND COALESCE(cm.channel_id, s.channel_id) = {}",
+                cid
+            ));
+        }
+        if let Some(st) = start_time {
+            sql.push_str(&format!(" AND cm.timestamp >= {}", quote_literal(st)));
+        }
+        if let Some(et) = end_time {
+            sql.push_str(&format!(" AND cm.timestamp <= {}", quote_literal(et)));
+        }
+
+        sql.push_str(" ORDER BY cm.timestamp ASC");
+        sql
+    }
+
+    /// 指定テーブルを Parquet 形式で指定先にコピーする
+    ///
+    /// 戻り値: 書き出した行数
+    pub fn copy_table_to_parquet(
+        conn: &Connection,
+        table: ExportTable,
+        channel_id: Option<i64>,
+        start_time: Option<&str>,
+        end_time: Option<&str>,
+        destination: &str,
+    ) -> Result<usize, duckdb::Error> {
+        let select = match table {
+            ExportTable::Streams => Self::streams_select(channel_id, start_time, end_time),
+            ExportTable::StreamStats => Self::stream_stats_select(channel_id, start_time, end_time),
+            ExportTable::ChatMessages => {
+                Self::chat_messages_select(channel_id, start_time, end_time)
+            }
+        };
+        let sql = format!(
+            "COPY ({}) TO {} (FORMAT PARQUET)",
+            select,
+            quote_literal(destination)
+        );
+        conn.execute(&sql, [])
+    }
+
+    /// stream_stats を Parquet 形式で指定先（ローカルパス / s3:// URL）にコピーする
+    ///
+    /// 戻り値: 書き出した行数
+    pub fn copy_stream_stats_to_parquet(
+        conn: &Connection,
+        channel_id: Option<i64>,
+        start_time: Option<&str>,
+        end_time: Option<&str>,
+        destination: &str,
+    ) -> Result<usize, duckdb::Error> {
+        Self::copy_table_to_parquet(
+            conn,
+            ExportTable::StreamStats,
+            channel_id,
+            start_time,
+            end_time,
+            destination,
+        )
+    }
+
+    /// httpfs 拡張をインストールしてロードする（初回のみダウンロードが発生）
+    pub fn load_httpfs(conn: &Connection) -> Result<(), duckdb::Error> {
+        conn.execute_batch("INSTALL httpfs; LOAD httpfs;")
+    }
+
+    /// S3 シークレットを（一時的に）登録する
+    pub fn create_s3_secret(
+        conn: &Connection,
+        params: &S3SecretParams<'_>,
+    ) -> Result<(), duckdb::Error> {
+        let mut options = vec![
+            "TYPE S3".to_string(),
+            format!("KEY_ID {}", quote_literal(params.access_key_id)),
+            format!("SECRET {}", quote_literal(params.secret_access_key)),
+            format!("REGION {}", quote_literal(params.region)),
+            format!("USE_SSL {}", params.use_ssl),
+        ];
+        if let Some(endpoint) = params.endpoint {
+            options.push(format!("ENDPOINT {}", quote_literal(endpoint)));
+        }
+        if let Some(url_style) = params.url_style {
+            options.push(format!("URL_STYLE {}", quote_literal(url_style)));
+        }
+
+        let sql = format!(
+            "CREATE OR REPLACE TEMPORARY SECRET {} ({})",
+            S3_SECRET_NAME,
+            options.join(", ")
+        );
+        conn.execute(&sql, [])?;
+        Ok(())
+    }
+
+    /// 登録した S3 シークレットを削除する
+    pub fn drop_s3_secret(conn: &Connection) -> Result<(), duckdb::Error> {
+        conn.execute(
+            &format!("DROP TEMPORARY SECRET IF EXISTS {}", S3_SECRET_NAME),
+            [],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_table_whitelist() {
+        assert_eq!(
+            ExportTable::from_name("streams"),
+            Some(ExportTable::Streams)
+        );
+        assert_eq!(
+            ExportTable::from_name("chat_messages"),
+            Some(ExportTable::ChatMessages)
+        );
+        assert_eq!(ExportTable::from_name("channels"), None);
+        assert_eq!(
+            ExportTable::from_name("stream_stats; DROP TABLE streams"),
+            None
+        );
+    }
+}
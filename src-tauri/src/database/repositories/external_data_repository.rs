@@ -0,0 +1,122 @@
+/// ExternalDataRepository - 外部 Parquet / CSV ファイルの取り込み
+///
+/// 外部ファイルを DuckDB の `read_parquet` / `read_csv_auto` で読み込む一時ビューとして登録し、
+/// SQLビューアから既存テーブルと組み合わせて分析できるようにします。
+/// 一時ビューは接続が閉じられる（アプリ終了）まで有効です。
+use crate::database::utils::quote_literal;
+use duckdb::Connection;
+
+/// 取り込み可能な外部ファイル形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExternalDataFormat {
+    Parquet,
+    Csv,
+}
+
+impl ExternalDataFormat {
+    /// 形式名を検証して変換する。未対応の形式は None
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "parquet" => Some(Self::Parquet),
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+
+    fn reader(self, path: &str) -> String {
+        match self {
+            Self::Parquet => format!("read_parquet({})", quote_literal(path)),
+            Self::Csv => format!("read_csv_auto({})", quote_literal(path)),
+        }
+    }
+}
+
+/// ビュー名として使用できるか（英字またはアンダースコアで始まる英数字・アンダースコア）
+pub fn is_valid_view_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    name.len() <= 64 && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+pub struct ExternalDataRepository;
+
+impl ExternalDataRepository {
+    /// 一時ビュー以外の同名テーブル・ビューが存在するか
+    pub fn table_exists(conn: &Connection, name: &str) -> Result<bool, duckdb::Error> {
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM information_schema.tables WHERE table_catalog <> 'temp' AND lower(table_name) = lower(?)",
+            [name],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    /// 外部ファイルを一時ビューとして登録する（同名の一時ビューは置き換える）
+    ///
+    /// `view_name` は呼び出し側で `is_valid_view_name` と `table_exists` により検証済みであること。
+    /// 戻り値: 読み込んだ行数
+    pub fn register_view(
+        conn: &Connection,
+        path: &str,
+        view_name: &str,
+        format: ExternalDataFormat,
+    ) -> Result<i64, duckdb::Error> {
+        // CREATE VIEW ではパラメータバインドが使えないため、パスはリテラルとして埋め込む
+        conn.execute(
+            &format!(
+                "CREATE OR REPLACE TEMP VIEW {} AS SELECT * FROM {}",
+                view_name,
+                format.reader(path)
+            ),
+            [],
+        )?;
+
+        conn.query_row(&format!("SELECT COUNT(*) FROM {}", view_name), [], |row| {
+            row.get(0)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::init_test_db;
+
+    #[test]
+    fn test_register_csv_view() {
+        let conn = init_test_db();
+
+        let path = std::env::temp_dir().join(format!(
+            "stream_monitor_external_{}.csv",
+            std::process::id()
+        ));
+        std::fs::write(&path, "channel,viewers\nfoo,10\nbar,20\n").unwrap();
+        let path_str = path.to_string_lossy().to_string();
+
+        let rows = ExternalDataRepository::register_view(
+            &conn,
+            &path_str,
+            "legacy_stats",
+            ExternalDataFormat::Csv,
+        )
+        .unwrap();
+        assert_eq!(rows, 2);
+
+        let total: i64 = conn
+            .query_row("SELECT SUM(viewers) FROM legacy_stats", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(total, 30);
+
+        // 登録した一時ビューは既存テーブル扱いしない
+        assert!(ExternalDataRepository::table_exists(&conn, "streams").unwrap());
+        assert!(!ExternalDataRepository::table_exists(&conn, "legacy_stats").unwrap());
+        assert!(!is_valid_view_name("x; DROP TABLE streams"));
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -2,7 +2,7 @@
 ///
 /// Twitchゲームカテゴリの管理を行います。
 use crate::database::models::GameCategory;
-use chrono::Local;
+use chrono::Utc;
 use duckdb::Connection;
 
 pub struct GameCategoryRepository;
@@ -17,7 +17,7 @@ impl GameCategoryRepository {
         game_name: &str,
         box_art_url: Option<&str>,
     ) -> Result<(), duckdb::Error> {
-        let now = Local::now().to_rfc3339();
+        let now = Utc::now().to_rfc3339();
         conn.execute(
             r#"
             INSERT INTO game_categories (game_id, game_name, box_art_url, last_updated)
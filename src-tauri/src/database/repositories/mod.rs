@@ -2,19 +2,44 @@ pub mod aggregation_repository;
 /// レポジトリパターンモジュール
 ///
 /// データベースアクセスを抽象化し、型変換ロジックを統一します。
+pub mod backup_repository;
 pub mod base;
 pub mod channel_repository;
+pub mod channel_tag_repository;
 pub mod chat_message_repository;
+pub mod export_repository;
+pub mod external_data_repository;
 pub mod game_category_repository;
+pub mod retention_repository;
+pub mod scheduled_stream_repository;
 pub mod sql_template_repository;
 pub mod stream_repository;
 pub mod stream_stats_repository;
 
 // Re-exports
 pub use aggregation_repository::AggregationRepository;
-pub use channel_repository::ChannelRepository;
-pub use chat_message_repository::ChatMessageRepository;
+pub use backup_repository::{BackupRepository, RestoreMode, RestoreResult};
+pub use channel_repository::{ChannelRepository, LiveChannel};
+pub use channel_tag_repository::ChannelTagRepository;
+pub use chat_message_repository::{
+    BotFilter, ChatBucket, ChatMessageRepository, UniqueChattersBucket, UserStreamActivity,
+};
+pub use export_repository::{ExportRepository, ExportTable, S3SecretParams};
+pub use external_data_repository::{
+    is_valid_view_name, ExternalDataFormat, ExternalDataRepository,
+};
 pub use game_category_repository::GameCategoryRepository;
-pub use sql_template_repository::{SqlTemplate, SqlTemplateRepository};
-pub use stream_repository::{StreamInfo, StreamRepository, TimelinePoint};
-pub use stream_stats_repository::StreamStatsRepository;
+pub use retention_repository::{
+    DataVolume, DeleteDataInRangeResult, RetentionRepository, RetentionResult, StorageUsage,
+};
+pub use scheduled_stream_repository::{ScheduledStream, ScheduledStreamRepository};
+pub use sql_template_repository::{
+    count_placeholders, named_placeholders, rewrite_named_placeholders, SqlTemplate,
+    SqlTemplateRepository, TemplateParam, TEMPLATE_PARAM_TYPES,
+};
+pub use stream_repository::{
+    AggregateViewerPoint, CategoryStat, ChannelSummary, FragmentedStreamGroup, HeatmapCell,
+    PeakMoment, RetentionBaseline, RetentionPoint, StreamInfo, StreamMissingChat, StreamRepository,
+    StreamStorageUsage, TimelinePoint,
+};
+pub use stream_stats_repository::{StatsWithInterval, StreamStatsRepository};
@@ -0,0 +1,430 @@
+/// RetentionRepository - 古い stream_stats / chat_messages の削除とロールアップ
+///
+/// 配信単位で判定し、最後の統計が保持期限より古い配信のデータのみ削除します。
+/// 配信途中で期限をまたぐデータが部分的に削除されて集計が崩れるのを防ぐためです。
+use crate::database::repositories::{base, ChatMessageRepository, StreamStatsRepository};
+use crate::database::utils;
+use chrono::DateTime;
+use duckdb::Connection;
+use serde::{Deserialize, Serialize};
+
+/// リテンション実行結果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RetentionResult {
+    pub cutoff: String,
+    pub streams_archived: usize,
+    pub stream_stats_deleted: usize,
+    pub chat_messages_deleted: usize,
+}
+
+/// 蓄積データ量（行数と記録期間）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DataVolume {
+    pub channels: i64,
+    pub streams: i64,
+    pub stream_stats_rows: i64,
+    pub chat_messages_rows: i64,
+    /// stream_stats / chat_messages で最も古い記録の時刻（UTC, RFC3339）
+    pub oldest_record: Option<String>,
+    /// stream_stats / chat_messages で最も新しい記録の時刻（UTC, RFC3339）
+    pub newest_record: Option<String>,
+}
+
+/// DuckDB のストレージ・メモリ使用量（バイト）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StorageUsage {
+    /// 使用中ブロックの合計サイズ
+    pub used_block_bytes: i64,
+    /// バッファマネージャのメモリ使用量
+    pub memory_usage_bytes: i64,
+    /// memory_limit を超えて一時ファイルに退避した量
+    pub temporary_storage_bytes: i64,
+}
+
+/// 最後の統計が cutoff より古い配信ID（stream_stats ベース）
+const EXPIRED_STREAMS_SUBQUERY: &str = r#"
+    SELECT stream_id FROM stream_stats
+    WHERE stream_id IS NOT NULL
+    GROUP BY stream_id
+    HAVING MAX(collected_at) < ?
+"#;
+
+/// 期間指定削除の結果（テーブルごとの削除件数）
+#[derive(Debug, Serialize)]
+pub struct DeleteDataInRangeResult {
+    pub stream_stats_deleted: usize,
+    pub chat_messages_deleted: usize,
+}
+
+pub struct RetentionRepository;
+
+impl RetentionRepository {
+    /// 期限切れ配信の集計値を stream_stats_archive に退避し、退避した配信数を返す
+    ///
+    /// minutes_watched は LEAD による収集間隔で重み付けする（最後の行は1分扱い）。
+    pub fn archive_expired_streams(
+        conn: &Connection,
+        cutoff: &str,
+    ) -> Result<usize, duckdb::Error> {
+        let sql = format!(
+            r#"
+            INSERT OR REPLACE INTO stream_stats_archive (
+                stream_id, channel_id, peak_viewers, avg_viewers, minutes_watched,
+                stats_rows, chat_messages, first_collected_at, last_collected_at
+            )
+            WITH expired AS ({}),
+            stats_with_interval AS (
+                SELECT
+                    ss.stream_id,
+                    ss.viewer_count,
+                    ss.collected_at,
+                    EXTRACT(EPOCH FROM (
+                        LEAD(ss.collected_at) OVER (PARTITION BY ss.stream_id ORDER BY ss.collected_at)
+                        - ss.collected_at
+                    )) / 60.0 AS interval_minutes
+                FROM stream_stats ss
+                WHERE ss.stream_id IN (SELECT stream_id FROM expired)
+            ),
+            chat_counts AS (
+                SELECT stream_id, COUNT(*) AS chat_messages
+                FROM chat_messages
+                WHERE stream_id IN (SELECT stream_id FROM expired)
+                GROUP BY stream_id
+            )
+            SELECT
+                swi.stream_id,
+                s.channel_id,
+                MAX(swi.viewer_count),
+                AVG(swi.viewer_count),
+                COALESCE(SUM(swi.viewer_count * COALESCE(swi.interval_minutes, 1)), 0)::BIGINT,
+                COUNT(*)::BIGINT,
+                COALESCE(MAX(cc.chat_messages), 0)::BIGINT,
+                MIN(swi.collected_at),
+                MAX(swi.collected_at)
+            FROM stats_with_interval swi
+            LEFT JOIN streams s ON swi.stream_id = s.id
+            LEFT JOIN chat_counts cc ON swi.stream_id = cc.stream_id
+            GROUP BY swi.stream_id, s.channel_id
+            "#,
+            EXPIRED_STREAMS_SUBQUERY
+        );
+
+        utils::execute_with_params(conn, &sql, &[cutoff.to_string()])
+    }
+
+    /// 期限切れ配信の chat_messages と、配信に紐づかない古いチャットを削除
+    pub fn delete_expired_chat_messages(
+        conn: &Connection,
+        cutoff: &str,
+    ) -> Result<usize, duckdb::Error> {
+        let sql = format!(
+            "DELETE FROM chat_messages WHERE stream_id IN ({}) OR (stream_id IS NULL AND timestamp < ?)",
+            EXPIRED_STREAMS_SUBQUERY
+        );
+        utils::execute_with_params(conn, &sql, &[cutoff.to_string(), cutoff.to_string()])
+    }
+
+    /// 期限切れ配信の stream_stats と、配信に紐づかない古い統計を削除
+    ///
+    /// 期限切れ配信の判定に stream_stats を使うため、chat_messages より後に実行すること。
+    pub fn delete_expired_stream_stats(
+        conn: &Connection,
+        cutoff: &str,
+    ) -> Result<usize, duckdb::Error> {
+        let sql = format!(
+            "DELETE FROM stream_stats WHERE stream_id IN ({}) OR (stream_id IS NULL AND collected_at < ?)",
+            EXPIRED_STREAMS_SUBQUERY
+        );
+        utils::execute_with_params(conn, &sql, &[cutoff.to_string(), cutoff.to_string()])
+    }
+
+    /// 指定期間の stream_stats / chat_messages を1トランザクションで削除
+    ///
+    /// `tables` には "stream_stats" / "chat_messages" を指定する。
+    /// 誤操作防止のため `confirm` が true でない場合、未対応のテーブル名や
+    /// RFC3339 として解釈できない期間が渡された場合は何も削除せずエラーを返す。
+    pub fn delete_data_in_range(
+        conn: &Connection,
+        channel_id: Option<i64>,
+        from: &str,
+        to: &str,
+        tables: &[String],
+        confirm: bool,
+    ) -> Result<DeleteDataInRangeResult, Box<dyn std::error::Error + Send + Sync>> {
+        if !confirm {
+            return Err("削除を実行するには confirm を true にしてください".into());
+        }
+
+        if tables.is_empty() {
+            return Err("削除対象のテーブルを指定してください".into());
+        }
+
+        let mut delete_stream_stats = false;
+        let mut delete_chat_messages = false;
+        for table in tables {
+            match table.as_str() {
+                "stream_stats" => delete_stream_stats = true,
+                "chat_messages" => delete_chat_messages = true,
+                other => return Err(format!("Unsupported table: {}", other).into()),
+            }
+        }
+
+        let from_dt = DateTime::parse_from_rfc3339(from)
+            .map_err(|e| format!("Invalid 'from' timestamp: {}", e))?;
+        let to_dt = DateTime::parse_from_rfc3339(to)
+            .map_err(|e| format!("Invalid 'to' timestamp: {}", e))?;
+        if from_dt > to_dt {
+            return Err("'from' must be earlier than or equal to 'to'".into());
+        }
+
+        let result = base::with_transaction(conn, |conn| {
+            let stream_stats_deleted = if delete_stream_stats {
+                StreamStatsRepository::delete_in_range(conn, channel_id, from, to)?
+            } else {
+                0
+            };
+            let chat_messages_deleted = if delete_chat_messages {
+                ChatMessageRepository::delete_in_range(conn, channel_id, from, to)?
+            } else {
+                0
+            };
+            Ok::<_, duckdb::Error>(DeleteDataInRangeResult {
+                stream_stats_deleted,
+                chat_messages_deleted,
+            })
+        })?;
+
+        Ok(result)
+    }
+
+    /// テーブルごとの行数と、統計・チャットの記録期間を取得
+    pub fn get_data_volume(conn: &Connection) -> Result<DataVolume, duckdb::Error> {
+        conn.query_row(
+            r#"
+            WITH ranges AS (
+                SELECT MIN(collected_at) AS oldest, MAX(collected_at) AS newest FROM stream_stats
+                UNION ALL
+                SELECT MIN(timestamp), MAX(timestamp) FROM chat_messages
+            )
+            SELECT
+                (SELECT COUNT(*) FROM channels),
+                (SELECT COUNT(*) FROM streams),
+                (SELECT COUNT(*) FROM stream_stats),
+                (SELECT COUNT(*) FROM chat_messages),
+                strftime(MIN(oldest), '%Y-%m-%dT%H:%M:%SZ'),
+                strftime(MAX(newest), '%Y-%m-%dT%H:%M:%SZ')
+            FROM ranges
+            "#,
+            [],
+            |row| {
+                Ok(DataVolume {
+                    channels: row.get(0)?,
+                    streams: row.get(1)?,
+                    stream_stats_rows: row.get(2)?,
+                    chat_messages_rows: row.get(3)?,
+                    oldest_record: row.get(4)?,
+                    newest_record: row.get(5)?,
+                })
+            },
+        )
+    }
+
+    /// DuckDB のブロック使用量とメモリ使用量を取得（pragma_database_size / duckdb_memory）
+    pub fn get_storage_usage(conn: &Connection) -> Result<StorageUsage, duckdb::Error> {
+        conn.query_row(
+            r#"
+            SELECT
+                (SELECT COALESCE(SUM(used_blocks * block_size), 0)::BIGINT
+                 FROM pragma_database_size() WHERE database_name = current_database()),
+                COALESCE(SUM(memory_usage_bytes), 0)::BIGINT,
+                COALESCE(SUM(temporary_storage_bytes), 0)::BIGINT
+            FROM duckdb_memory()
+            "#,
+            [],
+            |row| {
+                Ok(StorageUsage {
+                    used_block_bytes: row.get(0)?,
+                    memory_usage_bytes: row.get(1)?,
+                    temporary_storage_bytes: row.get(2)?,
+                })
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{init_test_db, insert_channel, insert_stream};
+
+    #[test]
+    fn test_get_data_volume() {
+        let conn = init_test_db();
+        insert_channel(&conn, 1, "twitch", "foo");
+        insert_stream(&conn, 1, 1, "2024-01-02 00:00:00", None);
+        insert_stream(&conn, 2, 1, "2024-01-03 00:00:00", None);
+        conn.execute_batch(
+            r#"
+            INSERT INTO stream_stats (stream_id, collected_at) VALUES
+                (1, '2024-01-02 00:00:00'),
+                (2, '2024-01-03 00:00:00');
+            INSERT INTO chat_messages (timestamp, platform, user_name, message)
+            VALUES ('2024-01-01 12:00:00', 'twitch', 'alice', 'hi');
+            "#,
+        )
+        .unwrap();
+
+        let volume = RetentionRepository::get_data_volume(&conn).unwrap();
+        assert_eq!(
+            (
+                volume.channels,
+                volume.streams,
+                volume.stream_stats_rows,
+                volume.chat_messages_rows
+            ),
+            (1, 2, 2, 1)
+        );
+        assert_eq!(
+            volume.oldest_record.as_deref(),
+            Some("2024-01-01T12:00:00Z")
+        );
+        assert_eq!(
+            volume.newest_record.as_deref(),
+            Some("2024-01-03T00:00:00Z")
+        );
+
+        // 空のDBでは記録期間は None
+        conn.execute_batch("DELETE FROM stream_stats; DELETE FROM chat_messages;")
+            .unwrap();
+        let volume = RetentionRepository::get_data_volume(&conn).unwrap();
+        assert_eq!(volume.oldest_record, None);
+    }
+
+    fn count(conn: &Connection, table: &str) -> i64 {
+        conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+            row.get(0)
+        })
+        .unwrap()
+    }
+
+    fn seed_range_data(conn: &Connection) {
+        insert_channel(conn, 1, "twitch", "foo");
+        insert_stream(conn, 1, 1, "2024-01-01 00:00:00", None);
+        conn.execute_batch(
+            r#"
+            INSERT INTO stream_stats (stream_id, collected_at) VALUES
+                (1, '2024-01-01 00:00:00'),
+                (1, '2024-01-01 01:00:00'),
+                (1, '2024-01-01 02:00:00');
+            INSERT INTO chat_messages (channel_id, stream_id, timestamp, platform, user_name, message)
+            VALUES
+                (1, 1, '2024-01-01 00:30:00', 'twitch', 'alice', 'before'),
+                (1, 1, '2024-01-01 01:30:00', 'twitch', 'bob', 'inside'),
+                (1, 1, '2024-01-01 02:30:00', 'twitch', 'carol', 'after');
+            "#,
+        )
+        .unwrap();
+    }
+
+    fn all_tables() -> Vec<String> {
+        vec!["stream_stats".to_string(), "chat_messages".to_string()]
+    }
+
+    #[test]
+    fn delete_data_in_range_requires_confirm() {
+        let conn = init_test_db();
+        seed_range_data(&conn);
+
+        let result = RetentionRepository::delete_data_in_range(
+            &conn,
+            None,
+            "2024-01-01T00:00:00Z",
+            "2024-01-01T03:00:00Z",
+            &all_tables(),
+            false,
+        );
+
+        assert!(result.is_err());
+        assert_eq!(count(&conn, "stream_stats"), 3);
+        assert_eq!(count(&conn, "chat_messages"), 3);
+    }
+
+    #[test]
+    fn delete_data_in_range_rejects_unsupported_table() {
+        let conn = init_test_db();
+        seed_range_data(&conn);
+
+        let err = RetentionRepository::delete_data_in_range(
+            &conn,
+            None,
+            "2024-01-01T00:00:00Z",
+            "2024-01-01T03:00:00Z",
+            &["stream_stats".to_string(), "channels".to_string()],
+            true,
+        )
+        .unwrap_err();
+
+        assert!(err.to_string().contains("Unsupported table: channels"));
+        // 対応テーブルが含まれていても検証前に拒否される
+        assert_eq!(count(&conn, "stream_stats"), 3);
+        assert_eq!(count(&conn, "channels"), 1);
+    }
+
+    #[test]
+    fn delete_data_in_range_rejects_malformed_timestamp() {
+        let conn = init_test_db();
+        seed_range_data(&conn);
+
+        let err = RetentionRepository::delete_data_in_range(
+            &conn,
+            None,
+            "2024-01-01 00:00:00",
+            "2024-01-01T03:00:00Z",
+            &all_tables(),
+            true,
+        )
+        .unwrap_err();
+
+        assert!(err.to_string().contains("Invalid 'from' timestamp"));
+        assert_eq!(count(&conn, "stream_stats"), 3);
+        assert_eq!(count(&conn, "chat_messages"), 3);
+    }
+
+    #[test]
+    fn delete_data_in_range_deletes_only_rows_inside_range() {
+        let conn = init_test_db();
+        seed_range_data(&conn);
+
+        let result = RetentionRepository::delete_data_in_range(
+            &conn,
+            Some(1),
+            "2024-01-01T00:45:00Z",
+            "2024-01-01T02:00:00Z",
+            &all_tables(),
+            true,
+        )
+        .unwrap();
+
+        assert_eq!(result.stream_stats_deleted, 2);
+        assert_eq!(result.chat_messages_deleted, 1);
+
+        let remaining_stats: String = conn
+            .query_row(
+                "SELECT CAST(collected_at AS VARCHAR) FROM stream_stats",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(remaining_stats.starts_with("2024-01-01 00:00:00"));
+
+        let mut stmt = conn
+            .prepare("SELECT message FROM chat_messages ORDER BY timestamp")
+            .unwrap();
+        let remaining_messages: Vec<String> = stmt
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(remaining_messages, vec!["before", "after"]);
+    }
+}
@@ -0,0 +1,240 @@
+/// ScheduledStreamRepository - 配信予定（スケジュール）
+///
+/// scheduled_streams テーブル（channel_id, scheduled_start）へのアクセスを提供します。
+/// 時刻はすべてUTCで保存し、読み出し時は `YYYY-MM-DDTHH:MM:SSZ` 形式で返します。
+/// channels はマイグレーションで再作成されることがあるため外部キーは張らず、
+/// チャンネル削除時は ChannelRepository::delete_channel_and_related で併せて削除します。
+use crate::database::models::ScheduledStreamData;
+use chrono::{DateTime, Utc};
+use duckdb::Connection;
+use serde::{Deserialize, Serialize};
+
+/// 配信予定（チャンネル情報付き）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledStream {
+    pub channel_id: i64,
+    pub platform: String,
+    pub channel_name: String,
+    pub display_name: Option<String>,
+    pub scheduled_start: String,
+    pub scheduled_end: Option<String>,
+    pub title: Option<String>,
+    pub category: Option<String>,
+}
+
+/// RFC3339 の時刻を scheduled_streams に保存するUTC形式へ変換（解釈できない場合は None）
+pub fn to_utc_timestamp(value: &str) -> Option<String> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|dt| format_utc(&dt.with_timezone(&Utc)))
+}
+
+/// UTC時刻を scheduled_streams の比較・保存用の文字列に変換
+pub fn format_utc(dt: &DateTime<Utc>) -> String {
+    dt.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+const SELECT_SCHEDULED: &str = r#"
+    SELECT
+        s.channel_id,
+        c.platform,
+        c.channel_name,
+        c.display_name,
+        strftime(s.scheduled_start, '%Y-%m-%dT%H:%M:%SZ'),
+        strftime(s.scheduled_end, '%Y-%m-%dT%H:%M:%SZ'),
+        s.title,
+        s.category
+    FROM scheduled_streams s
+    INNER JOIN channels c ON c.id = s.channel_id
+"#;
+
+fn map_scheduled(row: &duckdb::Row) -> Result<ScheduledStream, duckdb::Error> {
+    Ok(ScheduledStream {
+        channel_id: row.get(0)?,
+        platform: row.get(1)?,
+        channel_name: row.get(2)?,
+        display_name: row.get(3)?,
+        scheduled_start: row.get(4)?,
+        scheduled_end: row.get(5)?,
+        title: row.get(6)?,
+        category: row.get(7)?,
+    })
+}
+
+pub struct ScheduledStreamRepository;
+
+impl ScheduledStreamRepository {
+    /// チャンネルの配信予定を取得結果で置き換える
+    ///
+    /// 既存の予定は内容のみ更新し、リマインダー通知済みの状態は保持する。
+    /// 取得結果に含まれない予定（キャンセル・終了済み）は削除する。
+    /// 戻り値: 保存した予定の件数（時刻を解釈できない予定は除く）
+    pub fn replace_for_channel(
+        conn: &Connection,
+        channel_id: i64,
+        schedules: &[ScheduledStreamData],
+    ) -> Result<usize, duckdb::Error> {
+        let mut starts = Vec::with_capacity(schedules.len());
+        {
+            let mut upsert = conn.prepare(
+                r#"
+                INSERT INTO scheduled_streams (channel_id, scheduled_start, scheduled_end, title, category, fetched_at)
+                VALUES (?, ?, ?, ?, ?, CURRENT_TIMESTAMP)
+                ON CONFLICT (channel_id, scheduled_start) DO UPDATE SET
+                    scheduled_end = excluded.scheduled_end,
+                    title = excluded.title,
+                    category = excluded.category,
+                    fetched_at = excluded.fetched_at
+                "#,
+            )?;
+            for schedule in schedules {
+                let Some(start) = to_utc_timestamp(&schedule.scheduled_start) else {
+                    eprintln!(
+                        "[ScheduledStream] Skipping schedule with invalid start time: {}",
+                        schedule.scheduled_start
+                    );
+                    continue;
+                };
+                let end = schedule.scheduled_end.as_deref().and_then(to_utc_timestamp);
+                upsert.execute(duckdb::params![
+                    channel_id,
+                    &start,
+                    end,
+                    schedule.title.as_deref(),
+                    schedule.category.as_deref(),
+                ])?;
+                starts.push(start);
+            }
+        }
+
+        let mut sql = String::from("DELETE FROM scheduled_streams WHERE channel_id = ?");
+        let mut params: Vec<String> = vec![channel_id.to_string()];
+        if !starts.is_empty() {
+            let placeholders = vec!["CAST(? AS TIMESTAMP)"; starts.len()].join(", ");
+            sql.push_str(&format!(" AND scheduled_start NOT IN ({})", placeholders));
+            params.extend(starts.iter().cloned());
+        }
+        conn.execute(&sql, duckdb::params_from_iter(params.iter()))?;
+
+        Ok(starts.len())
+    }
+
+    /// 指定時刻以降の配信予定を開始予定順に取得（channel_id 指定時はそのチャンネルのみ）
+    pub fn list_upcoming(
+        conn: &Connection,
+        from: &DateTime<Utc>,
+        channel_id: Option<i64>,
+    ) -> Result<Vec<ScheduledStream>, duckdb::Error> {
+        let mut sql = format!("{} WHERE s.scheduled_start >= ?", SELECT_SCHEDULED);
+        let mut params: Vec<String> = vec![format_utc(from)];
+        if let Some(channel_id) = channel_id {
+            sql.push_str(" AND s.channel_id = ?");
+            params.push(channel_id.to_string());
+        }
+        sql.push_str(" ORDER BY s.scheduled_start, s.channel_id");
+
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(duckdb::params_from_iter(params.iter()), map_scheduled)?;
+        rows.collect()
+    }
+
+    /// 開始予定が `now`〜`until` の範囲で未通知の予定を取得し、通知済みにする
+    pub fn take_due_reminders(
+        conn: &Connection,
+        channel_id: i64,
+        now: &DateTime<Utc>,
+        until: &DateTime<Utc>,
+    ) -> Result<Vec<ScheduledStream>, duckdb::Error> {
+        let now = format_utc(now);
+        let until = format_utc(until);
+        let due: Vec<ScheduledStream> = {
+            let mut stmt = conn.prepare(&format!(
+                "{} WHERE s.channel_id = ? AND NOT COALESCE(s.reminded, FALSE) AND s.scheduled_start >= ? AND s.scheduled_start <= ? ORDER BY s.scheduled_start",
+                SELECT_SCHEDULED
+            ))?;
+            let rows = stmt.query_map(duckdb::params![channel_id, &now, &until], map_scheduled)?;
+            rows.collect::<Result<_, _>>()?
+        };
+
+        if !due.is_empty() {
+            conn.execute(
+                "UPDATE scheduled_streams SET reminded = TRUE WHERE channel_id = ? AND scheduled_start >= ? AND scheduled_start <= ?",
+                duckdb::params![channel_id, &now, &until],
+            )?;
+        }
+        Ok(due)
+    }
+
+    /// チャンネルの配信予定をすべて削除
+    pub fn delete_by_channel(conn: &Connection, channel_id: i64) -> Result<usize, duckdb::Error> {
+        conn.execute(
+            "DELETE FROM scheduled_streams WHERE channel_id = ?",
+            duckdb::params![channel_id],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{init_test_db, insert_channel};
+    use chrono::TimeZone;
+
+    fn schedule(start: &str, title: &str) -> ScheduledStreamData {
+        ScheduledStreamData {
+            scheduled_start: start.to_string(),
+            scheduled_end: None,
+            title: Some(title.to_string()),
+            category: None,
+        }
+    }
+
+    #[test]
+    fn test_replace_and_remind() {
+        let conn = init_test_db();
+        insert_channel(&conn, 1, "twitch", "foo");
+
+        // +09:00 の時刻はUTCに正規化して保存される
+        let saved = ScheduledStreamRepository::replace_for_channel(
+            &conn,
+            1,
+            &[
+                schedule("2026-01-01T21:00:00+09:00", "a"),
+                schedule("2026-01-02T12:00:00Z", "b"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(saved, 2);
+
+        let from = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let upcoming = ScheduledStreamRepository::list_upcoming(&conn, &from, None).unwrap();
+        assert_eq!(upcoming.len(), 2);
+        assert_eq!(upcoming[0].scheduled_start, "2026-01-01T12:00:00Z");
+
+        let now = Utc.with_ymd_and_hms(2026, 1, 1, 11, 55, 0).unwrap();
+        let until = Utc.with_ymd_and_hms(2026, 1, 1, 12, 5, 0).unwrap();
+        let due = ScheduledStreamRepository::take_due_reminders(&conn, 1, &now, &until).unwrap();
+        assert_eq!(due.len(), 1);
+        assert!(
+            ScheduledStreamRepository::take_due_reminders(&conn, 1, &now, &until)
+                .unwrap()
+                .is_empty()
+        );
+
+        // 再取得で消えた予定は削除され、残った予定の通知済み状態は保持される
+        ScheduledStreamRepository::replace_for_channel(
+            &conn,
+            1,
+            &[schedule("2026-01-01T12:00:00Z", "a2")],
+        )
+        .unwrap();
+        let upcoming = ScheduledStreamRepository::list_upcoming(&conn, &from, Some(1)).unwrap();
+        assert_eq!(upcoming.len(), 1);
+        assert_eq!(upcoming[0].title.as_deref(), Some("a2"));
+        assert!(
+            ScheduledStreamRepository::take_due_reminders(&conn, 1, &now, &until)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
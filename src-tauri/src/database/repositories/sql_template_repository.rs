@@ -2,21 +2,161 @@
 use duckdb::{params, Connection};
 use serde::{Deserialize, Serialize};
 
+/// テンプレートのプレースホルダー（`?` または `:name`）に対応するパラメータ定義
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TemplateParam {
+    pub name: String,
+    /// "text" | "integer" | "real" | "boolean" | "timestamp"
+    #[serde(rename = "type")]
+    pub param_type: String,
+    /// 実行時に値が指定されなかった場合に使用する値
+    #[serde(default)]
+    pub default: Option<serde_json::Value>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SqlTemplate {
     pub id: i64,
     pub name: String,
     pub description: String,
     pub query: String,
+    #[serde(default)]
+    pub params: Vec<TemplateParam>,
     pub created_at: String,
     pub updated_at: String,
 }
 
 pub struct SqlTemplateRepository;
 
+/// パラメータ型として受け付ける値
+pub const TEMPLATE_PARAM_TYPES: [&str; 5] = ["text", "integer", "real", "boolean", "timestamp"];
+
 const SELECT_COLUMNS: &str = "SELECT id, name, description, query, 
      CAST(created_at AS VARCHAR) as created_at, 
-     CAST(updated_at AS VARCHAR) as updated_at";
+     CAST(updated_at AS VARCHAR) as updated_at,
+     params";
+
+fn row_to_template(row: &duckdb::Row) -> Result<SqlTemplate, duckdb::Error> {
+    let params_json: Option<String> = row.get(6).unwrap_or(None);
+    let params = params_json
+        .and_then(|json| serde_json::from_str::<Vec<TemplateParam>>(&json).ok())
+        .unwrap_or_default();
+
+    Ok(SqlTemplate {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2).unwrap_or_else(|_| String::new()),
+        query: row.get(3)?,
+        params,
+        created_at: row.get(4).unwrap_or_else(|_| String::new()),
+        updated_at: row.get(5).unwrap_or_else(|_| String::new()),
+    })
+}
+
+/// クエリ内の `?` プレースホルダー数を数える
+///
+/// 文字列リテラル（'...'）・引用識別子（"..."）・コメント（`--` / `/* */`）内の `?` は除外する。
+pub fn count_placeholders(query: &str) -> usize {
+    let mut count = 0;
+    scan_query(query, |chars, i, _| {
+        if chars[i] == '?' {
+            count += 1;
+        }
+        None
+    });
+    count
+}
+
+/// 名前付きプレースホルダー（`:channel_id` など）を `?` に置き換える
+///
+/// 戻り値: (置換後のクエリ, 出現順のパラメータ名)。同じ名前が複数回出現する場合はその回数分含まれる。
+/// リテラル・コメント内や型キャスト（`::`）は置き換えない。
+pub fn rewrite_named_placeholders(query: &str) -> (String, Vec<String>) {
+    let mut names = Vec::new();
+    let rewritten = scan_query(query, |chars, i, prev| {
+        if chars[i] != ':' || prev == Some(':') || chars.get(i + 1) == Some(&':') {
+            return None;
+        }
+        match chars.get(i + 1) {
+            Some(c) if c.is_ascii_alphabetic() || *c == '_' => {}
+            _ => return None,
+        }
+        let end = (i + 1..chars.len())
+            .find(|&j| !(chars[j].is_ascii_alphanumeric() || chars[j] == '_'))
+            .unwrap_or(chars.len());
+        names.push(chars[i + 1..end].iter().collect());
+        Some(end - i)
+    });
+    (rewritten, names)
+}
+
+/// 名前付きプレースホルダーの一覧（重複を除き、最初の出現順）
+pub fn named_placeholders(query: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for name in rewrite_named_placeholders(query).1 {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// リテラル・引用識別子・コメントを除いた位置ごとに `on_code` を呼び出し、クエリを再構築する
+///
+/// `on_code` が `Some(len)` を返した場合、その位置から `len` 文字を `?` に置き換える。
+fn scan_query<F>(query: &str, mut on_code: F) -> String
+where
+    F: FnMut(&[char], usize, Option<char>) -> Option<usize>,
+{
+    let chars: Vec<char> = query.chars().collect();
+    let mut out = String::with_capacity(query.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let start = i;
+        match chars[i] {
+            quote @ ('\'' | '"') => {
+                i += 1;
+                while i < chars.len() {
+                    if chars[i] == quote {
+                        // '' / "" はエスケープされた引用符
+                        if i + 1 < chars.len() && chars[i + 1] == quote {
+                            i += 2;
+                            continue;
+                        }
+                        break;
+                    }
+                    i += 1;
+                }
+            }
+            '-' if i + 1 < chars.len() && chars[i + 1] == '-' => {
+                while i + 1 < chars.len() && chars[i + 1] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if i + 1 < chars.len() && chars[i + 1] == '*' => {
+                i += 2;
+                while i + 1 < chars.len() && !(chars[i] == '*' && chars[i + 1] == '/') {
+                    i += 1;
+                }
+                i += 1;
+            }
+            _ => {
+                let prev = if i > 0 { Some(chars[i - 1]) } else { None };
+                if let Some(len) = on_code(&chars, i, prev) {
+                    out.push('?');
+                    i += len;
+                    continue;
+                }
+            }
+        }
+        let end = (i + 1).min(chars.len());
+        out.extend(&chars[start..end]);
+        i += 1;
+    }
+
+    out
+}
 
 impl SqlTemplateRepository {
     /// 全SQLテンプレートを取得（updated_at 降順）
@@ -25,16 +165,7 @@ impl SqlTemplateRepository {
             "{} FROM sql_templates ORDER BY updated_at DESC",
             SELECT_COLUMNS
         ))?;
-        let rows = stmt.query_map([], |row| {
-            Ok(SqlTemplate {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                description: row.get(2).unwrap_or_else(|_| String::new()),
-                query: row.get(3)?,
-                created_at: row.get(4).unwrap_or_else(|_| String::new()),
-                updated_at: row.get(5).unwrap_or_else(|_| String::new()),
-            })
-        })?;
+        let rows = stmt.query_map([], row_to_template)?;
         rows.collect::<Result<Vec<_>, _>>()
     }
 
@@ -44,16 +175,7 @@ impl SqlTemplateRepository {
             "{} FROM sql_templates WHERE id = ?",
             SELECT_COLUMNS
         ))?;
-        let mut rows = stmt.query_map(params![id], |row| {
-            Ok(SqlTemplate {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                description: row.get(2).unwrap_or_else(|_| String::new()),
-                query: row.get(3)?,
-                created_at: row.get(4).unwrap_or_else(|_| String::new()),
-                updated_at: row.get(5).unwrap_or_else(|_| String::new()),
-            })
-        })?;
+        let mut rows = stmt.query_map(params![id], row_to_template)?;
         rows.next().transpose()
     }
 
@@ -64,19 +186,23 @@ impl SqlTemplateRepository {
         name: &str,
         description: &str,
         query: &str,
+        template_params: &[TemplateParam],
     ) -> Result<SqlTemplate, duckdb::Error> {
+        let params_json = serde_json::to_string(template_params)
+            .map_err(|e| duckdb::Error::ToSqlConversionFailure(Box::new(e)))?;
+
         let id = if id > 0 {
             conn.execute(
                 "UPDATE sql_templates 
-                 SET name = ?, description = ?, query = ?, updated_at = CURRENT_TIMESTAMP 
+                 SET name = ?, description = ?, query = ?, params = ?, updated_at = CURRENT_TIMESTAMP 
                  WHERE id = ?",
-                params![name, description, query, id],
+                params![name, description, query, params_json, id],
             )?;
             id
         } else {
             conn.execute(
-                "INSERT INTO sql_templates (name, description, query) VALUES (?, ?, ?)",
-                params![name, description, query],
+                "INSERT INTO sql_templates (name, description, query, params) VALUES (?, ?, ?, ?)",
+                params![name, description, query, params_json],
             )?;
             let mut stmt = conn.prepare("SELECT currval('sql_templates_id_seq')")?;
             stmt.query_row([], |row| row.get(0))?
@@ -91,3 +217,36 @@ impl SqlTemplateRepository {
         Ok(affected as u64)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{count_placeholders, named_placeholders, rewrite_named_placeholders};
+
+    #[test]
+    fn counts_bare_placeholders() {
+        assert_eq!(count_placeholders("SELECT * FROM streams"), 0);
+        assert_eq!(
+            count_placeholders("SELECT * FROM streams WHERE channel_id = ? AND id > ?"),
+            2
+        );
+    }
+
+    #[test]
+    fn ignores_literals_and_comments() {
+        let query =
+            "SELECT '?', \"col?\" FROM t -- why?\nWHERE a = ? /* b = ? */ AND c = 'it''s ?'";
+        assert_eq!(count_placeholders(query), 1);
+    }
+
+    #[test]
+    fn rewrites_named_placeholders() {
+        let query = "SELECT id::VARCHAR, ':x' FROM streams -- :y\nWHERE channel_id = :channel_id AND started_at >= :from_date OR channel_id = :channel_id";
+        let (rewritten, names) = rewrite_named_placeholders(query);
+        assert_eq!(
+            rewritten,
+            "SELECT id::VARCHAR, ':x' FROM streams -- :y\nWHERE channel_id = ? AND started_at >= ? OR channel_id = ?"
+        );
+        assert_eq!(names, vec!["channel_id", "from_date", "channel_id"]);
+        assert_eq!(named_placeholders(query), vec!["channel_id", "from_date"]);
+    }
+}
@@ -2,8 +2,11 @@
 ///
 /// streams / stream_stats / channels / chat_messages を用いた
 /// 配信一覧・MW計算・タイムラインポイント取得を提供します。
-use chrono::Local;
-use duckdb::Connection;
+use crate::database::query_helpers::stream_stats_query;
+use crate::database::repositories::base::with_transaction;
+use crate::database::utils;
+use chrono::{DateTime, Datelike, Local, TimeZone, Timelike, Utc};
+use duckdb::{Connection, OptionalExt};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,18 +27,236 @@ pub struct StreamInfo {
     pub total_chat_messages: i64,
     pub engagement_rate: f64,
     pub last_collected_at: String,
+    /// 配信サムネイルのURL（プラットフォームが提供する場合のみ）
+    pub thumbnail_url: Option<String>,
+    /// 配信中にチャットしたユニークユーザー数（user_name ベース）
+    pub unique_chatters: i64,
+    /// 平均視聴者数に対するユニークチャッター数の割合（%）。engagement_rate のチャッター数版
+    pub chatter_rate: f64,
+    /// 配信アーカイブ（Twitch VOD / YouTube アーカイブ）のURL。配信終了時に取得する
+    pub vod_url: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TimelinePoint {
     pub collected_at: String,
+    /// 数値の指標は値が無い時点（未収集・欠損補間の上限を超えた区間）では None
+    pub viewer_count: Option<i32>,
+    pub chat_rate_1min: Option<i32>,
+    pub category: String,
+    pub title: String,
+    pub follower_count: Option<i32>,
+    /// 配信開始からの経過時間（分）。開始時刻の異なる配信を同じX軸で比較するために使う
+    pub elapsed_minutes: f64,
+}
+
+/// リテンションカーブの基準（視聴者数を割る分母）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RetentionBaseline {
+    /// 配信中のピーク視聴者数
+    Peak,
+    /// 最初に視聴者数を記録した時点の視聴者数
+    Start,
+}
+
+/// リテンションカーブの1点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionPoint {
+    /// 配信開始からの経過時間（分）
+    pub minute_offset: f64,
+    /// 基準視聴者数に対する比率（1.0 = 基準と同数）
+    pub viewer_ratio: f64,
+}
+
+/// 配信中に最大同時視聴者数を記録した時点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeakMoment {
+    pub timestamp: String,
     pub viewer_count: i32,
-    pub chat_rate_1min: i32,
+    /// 記録時点の配信タイトル
+    pub title: Option<String>,
+    /// 記録時点のカテゴリ（ゲーム名）
+    pub category: Option<String>,
+}
+
+/// 複数チャンネルの合計同時視聴者数（時間バケット単位）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregateViewerPoint {
+    /// バケットの開始時刻
+    pub timestamp: String,
+    pub total_viewers: i64,
+}
+
+/// チャンネルのカテゴリ（ゲーム）別累計統計
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryStat {
     pub category: String,
+    /// そのカテゴリで配信した配信数
+    pub stream_count: i64,
+    /// 配信ごとのカテゴリ滞在中の最大同時視聴者数の平均
+    pub avg_peak_viewers: f64,
+    /// 配信ごとのカテゴリ滞在時間（分）の平均
+    pub avg_duration_minutes: f64,
+    pub minutes_watched: i64,
+}
+
+/// 配信開始の曜日×時間帯ごとの平均視聴者数（ヒートマップの1セル）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeatmapCell {
+    /// 曜日（0 = 日曜日 〜 6 = 土曜日）
+    pub weekday: u32,
+    /// 時（0〜23）
+    pub hour: u32,
+    /// このセルで開始した配信の平均視聴者数の平均（配信が無いセルは 0）
+    pub avg_viewers: f64,
+    pub stream_count: i64,
+}
+
+/// 配信開始時刻（UNIX秒）と配信中の平均視聴者数から、指定タイムゾーン基準の 7x24 グリッドを作る
+fn build_viewer_heatmap<Tz: TimeZone>(samples: &[(i64, f64)], tz: &Tz) -> Vec<HeatmapCell> {
+    let mut sums = [[(0.0_f64, 0_i64); 24]; 7];
+    for &(epoch, avg_viewers) in samples {
+        let Some(started_at) = DateTime::from_timestamp(epoch, 0) else {
+            continue;
+        };
+        let local = started_at.with_timezone(tz);
+        let cell =
+            &mut sums[local.weekday().num_days_from_sunday() as usize][local.hour() as usize];
+        cell.0 += avg_viewers;
+        cell.1 += 1;
+    }
+
+    let mut cells = Vec::with_capacity(7 * 24);
+    for (weekday, hours) in sums.iter().enumerate() {
+        for (hour, &(total, count)) in hours.iter().enumerate() {
+            cells.push(HeatmapCell {
+                weekday: weekday as u32,
+                hour: hour as u32,
+                avg_viewers: if count > 0 { total / count as f64 } else { 0.0 },
+                stream_count: count,
+            });
+        }
+    }
+    cells
+}
+
+/// 期間内に開始した配信の集計
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamPeriodSummary {
+    pub stream_count: i64,
+    pub total_duration_minutes: f64,
+    /// 配信ごとの平均視聴者数の平均
+    pub avg_viewers: f64,
+    pub peak_viewers: i32,
+    pub minutes_watched: i64,
+    /// 期間内の最初と最後の統計のフォロワー数の差
+    pub follower_gain: i64,
+}
+
+/// チャンネル単位の総合サマリー（全期間と直近30日、およびその前の30日との比較）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelSummary {
+    pub channel_id: i64,
+    pub all_time: StreamPeriodSummary,
+    pub last_30_days: StreamPeriodSummary,
+    pub previous_30_days: StreamPeriodSummary,
+    /// 直近30日の前期間比（0.1 = +10%、前期間の値が0の場合は None）
+    pub stream_count_change: Option<f64>,
+    pub duration_change: Option<f64>,
+    pub avg_viewers_change: Option<f64>,
+    pub minutes_watched_change: Option<f64>,
+}
+
+/// 前期間比（前期間の値が0の場合は None）
+fn change_rate(current: f64, previous: f64) -> Option<f64> {
+    (previous != 0.0).then(|| (current - previous) / previous)
+}
+
+/// 終了と次の開始が近接しており、1回の配信が分断されたとみられる配信のグループ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FragmentedStreamGroup {
+    pub channel_id: i64,
+    /// グループ内の配信ID（開始時刻順）
+    pub stream_ids: Vec<i64>,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+}
+
+/// 開始時刻順の配信（id, 開始, 終了, 直前の配信終了からの間隔秒）を近接したもの同士でまとめる
+///
+/// 間隔が `max_gap_secs` 以下の配信を直前の配信と同じグループに入れ、2件以上のグループのみ返す。
+fn group_fragmented_streams(
+    channel_id: i64,
+    rows: Vec<(i64, String, Option<String>, Option<i64>)>,
+    max_gap_secs: i64,
+) -> Vec<FragmentedStreamGroup> {
+    let mut groups: Vec<FragmentedStreamGroup> = Vec::new();
+    let mut current: Option<FragmentedStreamGroup> = None;
+
+    for (id, started_at, ended_at, gap_secs) in rows {
+        match current.as_mut() {
+            Some(group) if gap_secs.is_some_and(|gap| gap <= max_gap_secs) => {
+                group.stream_ids.push(id);
+                group.ended_at = ended_at;
+            }
+            _ => {
+                if let Some(group) = current.take() {
+                    if group.stream_ids.len() > 1 {
+                        groups.push(group);
+                    }
+                }
+                current = Some(FragmentedStreamGroup {
+                    channel_id,
+                    stream_ids: vec![id],
+                    started_at,
+                    ended_at,
+                });
+            }
+        }
+    }
+    if let Some(group) = current {
+        if group.stream_ids.len() > 1 {
+            groups.push(group);
+        }
+    }
+    groups
+}
+
+/// 配信ごとのストレージ使用量（推定）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamStorageUsage {
+    pub id: i64,
+    pub channel_id: i64,
+    pub channel_name: String,
+    pub title: String,
+    pub started_at: String,
+    pub stats_rows: i64,
+    pub chat_rows: i64,
+    pub estimated_bytes: i64,
+}
+
+/// 統計はあるがチャットが1件も無い配信（チャット収集漏れの診断用）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamMissingChat {
+    pub id: i64,
+    pub channel_id: i64,
+    pub channel_name: String,
+    pub platform: String,
     pub title: String,
-    pub follower_count: i32,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub stats_rows: i64,
+    pub first_collected_at: String,
+    pub last_collected_at: String,
 }
 
+/// stream_stats 1行あたりの固定長カラムの推定サイズ（バイト）
+const STATS_ROW_FIXED_BYTES: i64 = 48;
+
+/// chat_messages 1行あたりの固定長カラムの推定サイズ（バイト）
+const CHAT_ROW_FIXED_BYTES: i64 = 40;
+
 fn row_to_stream_info(row: &duckdb::Row) -> Result<StreamInfo, duckdb::Error> {
     Ok(StreamInfo {
         id: row.get::<_, i64>(0)?,
@@ -54,6 +275,10 @@ fn row_to_stream_info(row: &duckdb::Row) -> Result<StreamInfo, duckdb::Error> {
         total_chat_messages: row.get::<_, i64>(13)?,
         engagement_rate: row.get::<_, f64>(14)?,
         last_collected_at: row.get::<_, String>(15).unwrap_or_default(),
+        thumbnail_url: row.get::<_, Option<String>>(16)?,
+        unique_chatters: row.get::<_, i64>(17)?,
+        chatter_rate: row.get::<_, f64>(18)?,
+        vod_url: row.get::<_, Option<String>>(19)?,
     })
 }
 
@@ -65,6 +290,8 @@ const STREAM_METRICS_CTE: &str = r#"
             s.channel_id,
             s.title,
             s.category,
+            s.thumbnail_url,
+            s.vod_url,
             s.started_at,
             s.ended_at,
             COALESCE(MAX(ss.viewer_count), 0) as peak_viewers,
@@ -101,7 +328,15 @@ const STREAM_SELECT_TAIL: &str = r#"
                 THEN (COALESCE(cc.total_chat_messages, 0)::DOUBLE / mw.minutes_watched::DOUBLE) * 1000.0
                 ELSE 0.0
             END as engagement_rate,
-            CAST(sm.last_collected_at AS VARCHAR) as last_collected_at
+            CAST(sm.last_collected_at AS VARCHAR) as last_collected_at,
+            sm.thumbnail_url,
+            COALESCE(cc.unique_chatters, 0) as unique_chatters,
+            CASE
+                WHEN sm.avg_viewers > 0
+                THEN (COALESCE(cc.unique_chatters, 0)::DOUBLE / sm.avg_viewers::DOUBLE) * 100.0
+                ELSE 0.0
+            END as chatter_rate,
+            sm.vod_url
         FROM stream_metrics sm
         JOIN channels c ON sm.channel_id = c.id
         LEFT JOIN mw_calc mw ON sm.id = mw.stream_id
@@ -125,7 +360,7 @@ impl StreamRepository {
             r#"
         {}
         WHERE s.channel_id = ?
-        GROUP BY s.id, s.stream_id, s.channel_id, s.title, s.category, s.started_at, s.ended_at
+        GROUP BY s.id, s.stream_id, s.channel_id, s.title, s.category, s.thumbnail_url, s.vod_url, s.started_at, s.ended_at
         ),
         stats_with_next AS (
             SELECT ss.stream_id, ss.viewer_count, ss.collected_at,
@@ -145,7 +380,8 @@ impl StreamRepository {
             GROUP BY ss.stream_id
         ),
         chat_calc AS (
-            SELECT s.id, COALESCE(COUNT(cm.id), 0)::BIGINT as total_chat_messages
+            SELECT s.id, COALESCE(COUNT(cm.id), 0)::BIGINT as total_chat_messages,
+                COUNT(DISTINCT cm.user_name)::BIGINT as unique_chatters
             FROM streams s LEFT JOIN chat_messages cm ON s.id = cm.stream_id
             WHERE s.channel_id = ?
             GROUP BY s.id
@@ -174,7 +410,7 @@ impl StreamRepository {
             r#"
         {}
         WHERE CAST(s.started_at AS DATE) >= CAST(? AS DATE) AND CAST(s.started_at AS DATE) <= CAST(? AS DATE)
-        GROUP BY s.id, s.stream_id, s.channel_id, s.title, s.category, s.started_at, s.ended_at
+        GROUP BY s.id, s.stream_id, s.channel_id, s.title, s.category, s.thumbnail_url, s.vod_url, s.started_at, s.ended_at
         ),
         stats_with_next AS (
             SELECT ss.stream_id, ss.viewer_count, ss.collected_at,
@@ -194,7 +430,8 @@ impl StreamRepository {
             GROUP BY ss.stream_id
         ),
         chat_calc AS (
-            SELECT s.id, COALESCE(COUNT(cm.id), 0)::BIGINT as total_chat_messages
+            SELECT s.id, COALESCE(COUNT(cm.id), 0)::BIGINT as total_chat_messages,
+                COUNT(DISTINCT cm.user_name)::BIGINT as unique_chatters
             FROM streams s LEFT JOIN chat_messages cm ON s.id = cm.stream_id
             WHERE EXISTS (SELECT 1 FROM stream_metrics sm WHERE sm.id = s.id)
             GROUP BY s.id
@@ -208,6 +445,133 @@ impl StreamRepository {
         rows.collect::<Result<Vec<_>, _>>()
     }
 
+    /// 配信ごとのストレージ使用量を推定し、大きい順に取得
+    ///
+    /// 正確なバイト数は取得コストが高いため、行数 × 固定長カラムの推定サイズ
+    /// ＋ 可変長テキストカラムのバイト長合計で推定します。
+    /// `limit` は既定50件で、1〜1000件の範囲に丸めます。
+    pub fn get_storage_breakdown(
+        conn: &Connection,
+        limit: Option<i32>,
+    ) -> Result<Vec<StreamStorageUsage>, duckdb::Error> {
+        let limit_clause = limit.unwrap_or(50).clamp(1, 1000);
+        let query = format!(
+            r#"
+        WITH stats_usage AS (
+            SELECT stream_id,
+                COUNT(*) as stats_rows,
+                SUM(COALESCE(strlen(title), 0) + COALESCE(strlen(category), 0)
+                    + COALESCE(strlen(channel_name), 0) + COALESCE(strlen(twitch_user_id), 0)
+                    + COALESCE(strlen(game_id), 0)) as text_bytes
+            FROM stream_stats
+            WHERE stream_id IS NOT NULL
+            GROUP BY stream_id
+        ),
+        chat_usage AS (
+            SELECT stream_id,
+                COUNT(*) as chat_rows,
+                SUM(strlen(message) + strlen(user_name) + COALESCE(strlen(display_name), 0)
+                    + COALESCE(strlen(user_id), 0) + COALESCE(strlen(badge_info), 0)) as text_bytes
+            FROM chat_messages
+            WHERE stream_id IS NOT NULL
+            GROUP BY stream_id
+        )
+        SELECT
+            s.id,
+            s.channel_id,
+            c.channel_name,
+            COALESCE(s.title, '') as title,
+            CAST(s.started_at AS VARCHAR) as started_at,
+            COALESCE(su.stats_rows, 0)::BIGINT as stats_rows,
+            COALESCE(cu.chat_rows, 0)::BIGINT as chat_rows,
+            (COALESCE(su.stats_rows, 0) * {} + COALESCE(su.text_bytes, 0)
+                + COALESCE(cu.chat_rows, 0) * {} + COALESCE(cu.text_bytes, 0))::BIGINT as estimated_bytes
+        FROM streams s
+        JOIN channels c ON s.channel_id = c.id
+        LEFT JOIN stats_usage su ON s.id = su.stream_id
+        LEFT JOIN chat_usage cu ON s.id = cu.stream_id
+        ORDER BY estimated_bytes DESC
+        LIMIT {}
+        "#,
+            STATS_ROW_FIXED_BYTES, CHAT_ROW_FIXED_BYTES, limit_clause
+        );
+        let mut stmt = conn.prepare(&query)?;
+        let rows = stmt.query_map([], |row| {
+            Ok(StreamStorageUsage {
+                id: row.get(0)?,
+                channel_id: row.get(1)?,
+                channel_name: row.get(2)?,
+                title: row.get(3)?,
+                started_at: row.get(4)?,
+                stats_rows: row.get(5)?,
+                chat_rows: row.get(6)?,
+                estimated_bytes: row.get(7)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>()
+    }
+
+    /// stream_stats は存在するが chat_messages が0件の配信を取得（新しい順）
+    pub fn get_streams_missing_chat(
+        conn: &Connection,
+        channel_id: Option<i64>,
+    ) -> Result<Vec<StreamMissingChat>, duckdb::Error> {
+        let mut query = String::from(
+            r#"
+        WITH stats_summary AS (
+            SELECT stream_id,
+                COUNT(*) as stats_rows,
+                MIN(collected_at) as first_collected_at,
+                MAX(collected_at) as last_collected_at
+            FROM stream_stats
+            WHERE stream_id IS NOT NULL
+            GROUP BY stream_id
+        )
+        SELECT
+            s.id,
+            s.channel_id,
+            c.channel_name,
+            c.platform,
+            COALESCE(s.title, '') as title,
+            CAST(s.started_at AS VARCHAR) as started_at,
+            CAST(s.ended_at AS VARCHAR) as ended_at,
+            ss.stats_rows::BIGINT as stats_rows,
+            CAST(ss.first_collected_at AS VARCHAR) as first_collected_at,
+            CAST(ss.last_collected_at AS VARCHAR) as last_collected_at
+        FROM streams s
+        JOIN channels c ON s.channel_id = c.id
+        JOIN stats_summary ss ON s.id = ss.stream_id
+        WHERE NOT EXISTS (
+            SELECT 1 FROM chat_messages cm WHERE cm.stream_id = s.id
+        )
+        "#,
+        );
+
+        let mut params: Vec<String> = Vec::new();
+        if let Some(ch_id) = channel_id {
+            query.push_str(" AND s.channel_id = ?");
+            params.push(ch_id.to_string());
+        }
+        query.push_str(" ORDER BY s.started_at DESC");
+
+        let mut stmt = conn.prepare(&query)?;
+        let rows = utils::query_map_with_params(&mut stmt, &params, |row| {
+            Ok(StreamMissingChat {
+                id: row.get(0)?,
+                channel_id: row.get(1)?,
+                channel_name: row.get(2)?,
+                platform: row.get(3)?,
+                title: row.get(4)?,
+                started_at: row.get(5)?,
+                ended_at: row.get(6)?,
+                stats_rows: row.get(7)?,
+                first_collected_at: row.get(8)?,
+                last_collected_at: row.get(9)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>()
+    }
+
     /// 単一配信の詳細情報を取得
     pub fn get_stream_info_by_id(
         conn: &Connection,
@@ -217,7 +581,7 @@ impl StreamRepository {
             r#"
         {}
         WHERE s.id = ?
-        GROUP BY s.id, s.stream_id, s.channel_id, s.title, s.category, s.started_at, s.ended_at
+        GROUP BY s.id, s.stream_id, s.channel_id, s.title, s.category, s.thumbnail_url, s.vod_url, s.started_at, s.ended_at
         ),
         stats_with_next AS (
             SELECT ss.stream_id, ss.viewer_count, ss.collected_at,
@@ -234,7 +598,8 @@ impl StreamRepository {
             FROM stream_stats ss WHERE ss.stream_id = ? AND ss.follower_count IS NOT NULL GROUP BY ss.stream_id
         ),
         chat_calc AS (
-            SELECT s.id, COALESCE(COUNT(cm.id), 0)::BIGINT as total_chat_messages
+            SELECT s.id, COALESCE(COUNT(cm.id), 0)::BIGINT as total_chat_messages,
+                COUNT(DISTINCT cm.user_name)::BIGINT as unique_chatters
             FROM streams s LEFT JOIN chat_messages cm ON s.id = cm.stream_id WHERE s.id = ? GROUP BY s.id
         )
         {}
@@ -255,16 +620,27 @@ impl StreamRepository {
     }
 
     /// 比較用：基準配信と時間帯が重なる配信をサジェスト
+    ///
+    /// `viewer_range` に (下限倍率, 上限倍率) を指定すると、peak_viewers が基準配信の
+    /// その範囲内の配信に絞り込む。基準配信の peak_viewers が 0 の場合は絞り込まない。
     pub fn get_suggested_streams_for_comparison(
         conn: &Connection,
         base_stream_id: i64,
         limit: Option<i32>,
+        viewer_range: Option<(f64, f64)>,
     ) -> Result<Vec<StreamInfo>, duckdb::Error> {
         let base = Self::get_stream_info_by_id(conn, base_stream_id)?;
+        let viewer_bounds =
+            viewer_range
+                .filter(|_| base.peak_viewers > 0)
+                .map(|(min_ratio, max_ratio)| {
+                    let peak = f64::from(base.peak_viewers);
+                    (peak * min_ratio, peak * max_ratio)
+                });
         let limit_clause = limit.unwrap_or(50);
         let base_start = base.started_at.clone();
         let base_end = if base.ended_at.is_empty() {
-            Local::now().to_rfc3339()
+            Utc::now().to_rfc3339()
         } else {
             base.ended_at.clone()
         };
@@ -274,7 +650,7 @@ impl StreamRepository {
             SELECT id, channel_id, started_at, ended_at, category FROM streams WHERE id = ?
         ),
         stream_metrics AS (
-            SELECT s.id, s.stream_id, s.channel_id, s.title, s.category, s.started_at, s.ended_at,
+            SELECT s.id, s.stream_id, s.channel_id, s.title, s.category, s.thumbnail_url, s.vod_url, s.started_at, s.ended_at,
                 COALESCE(MAX(ss.viewer_count), 0) as peak_viewers,
                 COALESCE(AVG(ss.viewer_count), 0) as avg_viewers,
                 COALESCE(EXTRACT(EPOCH FROM (COALESCE(s.ended_at, CAST(CURRENT_TIMESTAMP AS TIMESTAMP)) - s.started_at)) / 60, 0) as duration_minutes,
@@ -282,7 +658,8 @@ impl StreamRepository {
             FROM streams s LEFT JOIN stream_stats ss ON s.id = ss.stream_id
             WHERE s.id != ? AND s.started_at < CAST(? AS TIMESTAMP)
               AND COALESCE(s.ended_at, CAST(CURRENT_TIMESTAMP AS TIMESTAMP)) > CAST(? AS TIMESTAMP)
-            GROUP BY s.id, s.stream_id, s.channel_id, s.title, s.category, s.started_at, s.ended_at
+            GROUP BY s.id, s.stream_id, s.channel_id, s.title, s.category, s.thumbnail_url, s.vod_url, s.started_at, s.ended_at
+            {}
         ),
         stats_with_next AS (
             SELECT ss.stream_id, ss.viewer_count, ss.collected_at,
@@ -302,57 +679,1091 @@ impl StreamRepository {
             GROUP BY ss.stream_id
         ),
         chat_calc AS (
-            SELECT s.id, COALESCE(COUNT(cm.id), 0)::BIGINT as total_chat_messages
+            SELECT s.id, COALESCE(COUNT(cm.id), 0)::BIGINT as total_chat_messages,
+                COUNT(DISTINCT cm.user_name)::BIGINT as unique_chatters
             FROM streams s LEFT JOIN chat_messages cm ON s.id = cm.stream_id
             WHERE EXISTS (SELECT 1 FROM stream_metrics sm WHERE sm.id = s.id) GROUP BY s.id
         )
         {} ORDER BY CASE WHEN sm.category = (SELECT category FROM base_stream) THEN 0 ELSE 1 END, sm.started_at ASC LIMIT {}
         "#,
-            STREAM_SELECT_TAIL, limit_clause
+            if viewer_bounds.is_some() {
+                "HAVING COALESCE(MAX(ss.viewer_count), 0) BETWEEN CAST(? AS DOUBLE) AND CAST(? AS DOUBLE)"
+            } else {
+                ""
+            },
+            STREAM_SELECT_TAIL,
+            limit_clause
         );
-        let base_id_str = base_stream_id.to_string();
+        let mut params: Vec<String> = vec![
+            base_stream_id.to_string(),
+            base_stream_id.to_string(),
+            base_end,
+            base_start,
+        ];
+        if let Some((min_viewers, max_viewers)) = viewer_bounds {
+            params.push(min_viewers.to_string());
+            params.push(max_viewers.to_string());
+        }
         let mut stmt = conn.prepare(&query)?;
-        let rows = stmt.query_map(
-            [&base_id_str, &base_id_str, &base_end, &base_start],
-            row_to_stream_info,
+        let rows = stmt.query_map(duckdb::params_from_iter(params.iter()), row_to_stream_info)?;
+        rows.collect::<Result<Vec<_>, _>>()
+    }
+
+    /// 配信の視聴者維持率（リテンション）カーブを取得
+    ///
+    /// 各収集時点の viewer_count を基準視聴者数で割った比率を返す。
+    /// 基準視聴者数が 0 の場合（視聴者数の記録が無い場合を含む）は空を返す。
+    pub fn get_retention_curve(
+        conn: &Connection,
+        stream_id: i64,
+        baseline: RetentionBaseline,
+    ) -> Result<Vec<RetentionPoint>, duckdb::Error> {
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT
+                EXTRACT(EPOCH FROM (ss.collected_at - s.started_at)) / 60.0 AS minute_offset,
+                ss.viewer_count
+            FROM stream_stats ss
+            INNER JOIN streams s ON s.id = ss.stream_id
+            WHERE ss.stream_id = ? AND ss.viewer_count IS NOT NULL
+            ORDER BY ss.collected_at ASC
+            "#,
         )?;
+        let samples = stmt
+            .query_map([stream_id], |row| {
+                Ok((row.get::<_, f64>(0)?, row.get::<_, i64>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let base = match baseline {
+            RetentionBaseline::Peak => samples.iter().map(|(_, v)| *v).max().unwrap_or(0),
+            RetentionBaseline::Start => samples.first().map(|(_, v)| *v).unwrap_or(0),
+        };
+        if base <= 0 {
+            return Ok(Vec::new());
+        }
+
+        Ok(samples
+            .into_iter()
+            .map(|(minute_offset, viewers)| RetentionPoint {
+                minute_offset,
+                viewer_ratio: viewers as f64 / base as f64,
+            })
+            .collect())
+    }
+
+    /// 配信の最大同時視聴者数を記録した時点と、その時のタイトル・カテゴリを取得
+    ///
+    /// 同じ視聴者数の時点が複数ある場合は最も早い時点を返す。統計が無い場合は None。
+    pub fn get_peak_moment(
+        conn: &Connection,
+        stream_id: i64,
+    ) -> Result<Option<PeakMoment>, duckdb::Error> {
+        conn.query_row(
+            r#"
+            SELECT
+                collected_at::VARCHAR,
+                viewer_count,
+                NULLIF(title, ''),
+                NULLIF(category, '')
+            FROM stream_stats
+            WHERE stream_id = ? AND viewer_count IS NOT NULL
+            ORDER BY viewer_count DESC, collected_at ASC
+            LIMIT 1
+            "#,
+            [stream_id],
+            |row| {
+                Ok(PeakMoment {
+                    timestamp: row.get(0)?,
+                    viewer_count: row.get(1)?,
+                    title: row.get(2)?,
+                    category: row.get(3)?,
+                })
+            },
+        )
+        .optional()
+    }
+
+    /// 複数チャンネルの同時視聴者数を時間バケットごとに合算する
+    ///
+    /// 各チャンネルはバケット内の viewer_count の平均を1件として合算する。
+    /// 配信していないチャンネル・バケットは0として扱い、`from`〜`to` の全バケットを返す。
+    pub fn get_aggregate_viewers(
+        conn: &Connection,
+        channel_ids: &[i64],
+        from: &str,
+        to: &str,
+        bucket_minutes: i32,
+    ) -> Result<Vec<AggregateViewerPoint>, duckdb::Error> {
+        if channel_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let bucket = format!("INTERVAL '{} minutes'", bucket_minutes.max(1));
+        let sql = format!(
+            r#"
+            WITH per_channel AS (
+                SELECT
+                    time_bucket({bucket}, ss.collected_at) AS bucket,
+                    s.channel_id,
+                    AVG(ss.viewer_count) AS viewers
+                FROM stream_stats ss
+                INNER JOIN streams s ON ss.stream_id = s.id
+                WHERE s.channel_id IN ({in_list})
+                  AND ss.viewer_count IS NOT NULL
+                  AND ss.collected_at >= ?::TIMESTAMP
+                  AND ss.collected_at <= ?::TIMESTAMP
+                GROUP BY 1, 2
+            ),
+            buckets AS (
+                SELECT UNNEST(generate_series(
+                    time_bucket({bucket}, ?::TIMESTAMP),
+                    ?::TIMESTAMP,
+                    {bucket}
+                )) AS bucket
+            )
+            SELECT
+                b.bucket::VARCHAR,
+                CAST(ROUND(COALESCE(SUM(pc.viewers), 0)) AS BIGINT)
+            FROM buckets b
+            LEFT JOIN per_channel pc ON b.bucket = pc.bucket
+            GROUP BY b.bucket
+            ORDER BY b.bucket
+            "#,
+            bucket = bucket,
+            in_list = vec!["?"; channel_ids.len()].join(", "),
+        );
+
+        let mut params: Vec<String> = channel_ids.iter().map(|id| id.to_string()).collect();
+        params.extend([from, to, from, to].iter().map(|s| s.to_string()));
+
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = utils::query_map_with_params(&mut stmt, &params, |row| {
+            Ok(AggregateViewerPoint {
+                timestamp: row.get(0)?,
+                total_viewers: row.get(1)?,
+            })
+        })?;
         rows.collect::<Result<Vec<_>, _>>()
     }
 
+    /// チャンネルのカテゴリ別累計統計を取得（MW降順）
+    ///
+    /// 配信途中でカテゴリが変わる場合があるため、stream_stats の各行を
+    /// その時点のカテゴリに按分して集計する（滞在時間・MW は次の収集までの間隔で重み付け）。
+    /// カテゴリが記録されていない行は対象外。
+    pub fn get_category_stats(
+        conn: &Connection,
+        channel_id: i64,
+    ) -> Result<Vec<CategoryStat>, duckdb::Error> {
+        // 間隔はカテゴリで絞り込む前に配信全体の並びで計算する
+        let sql = format!(
+            r#"
+            WITH stats_with_interval AS (
+                SELECT
+                    ss.stream_id,
+                    NULLIF(ss.category, '') AS category,
+                    ss.viewer_count,
+                    {}
+                FROM stream_stats ss
+                INNER JOIN streams s ON ss.stream_id = s.id
+                WHERE s.channel_id = ?
+            ),
+            per_stream AS (
+                SELECT
+                    stream_id,
+                    category,
+                    MAX(viewer_count) AS peak_viewers,
+                    SUM(COALESCE(interval_minutes, 1)) AS duration_minutes,
+                    COALESCE(SUM(viewer_count * COALESCE(interval_minutes, 1)), 0) AS minutes_watched
+                FROM stats_with_interval
+                WHERE category IS NOT NULL
+                GROUP BY stream_id, category
+            )
+            SELECT
+                category,
+                COUNT(*) AS stream_count,
+                COALESCE(AVG(peak_viewers), 0)::DOUBLE AS avg_peak_viewers,
+                AVG(duration_minutes)::DOUBLE AS avg_duration_minutes,
+                SUM(minutes_watched)::BIGINT AS minutes_watched
+            FROM per_stream
+            GROUP BY category
+            ORDER BY minutes_watched DESC, category ASC
+            "#,
+            stream_stats_query::interval_with_fallback("ss")
+        );
+
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map([channel_id], |row| {
+            Ok(CategoryStat {
+                category: row.get(0)?,
+                stream_count: row.get(1)?,
+                avg_peak_viewers: row.get(2)?,
+                avg_duration_minutes: row.get(3)?,
+                minutes_watched: row.get(4)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>()
+    }
+
+    /// 配信開始の曜日×時間帯ごとの平均視聴者数を取得（7x24 = 168セル、日曜0時から順）
+    ///
+    /// 配信ごとに stream_stats の平均視聴者数を求め、開始時刻のセルで平均する。
+    /// streams.started_at はUTCで保存されているため、曜日・時間はDB側の EXTRACT(DOW/HOUR) ではなく
+    /// `chrono::Local` で変換して求める（夏時間の切り替えも配信日ごとに反映される）。
+    pub fn get_viewer_heatmap(
+        conn: &Connection,
+        channel_id: i64,
+    ) -> Result<Vec<HeatmapCell>, duckdb::Error> {
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT
+                EXTRACT(EPOCH FROM s.started_at)::BIGINT AS started_epoch,
+                AVG(ss.viewer_count)::DOUBLE AS avg_viewers
+            FROM streams s
+            INNER JOIN stream_stats ss ON ss.stream_id = s.id
+            WHERE s.channel_id = ? AND ss.viewer_count IS NOT NULL
+            GROUP BY s.id, s.started_at
+            "#,
+        )?;
+        let samples = stmt
+            .query_map([channel_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<(i64, f64)>, _>>()?;
+
+        Ok(build_viewer_heatmap(&samples, &Local))
+    }
+
+    /// 配信の終了時点（ended_at、配信中は最新の collected_at）の開始からの経過分数
+    ///
+    /// 配信が存在しない、または統計が1件も無い配信中の場合は None。
+    pub fn get_stream_end_elapsed_minutes(
+        conn: &Connection,
+        stream_id: i64,
+    ) -> Result<Option<f64>, duckdb::Error> {
+        let minutes: Option<Option<f64>> = conn
+            .query_row(
+                r#"
+                SELECT EXTRACT(EPOCH FROM (COALESCE(s.ended_at, MAX(ss.collected_at)) - s.started_at)) / 60.0
+                FROM streams s
+                LEFT JOIN stream_stats ss ON ss.stream_id = s.id
+                WHERE s.id = ?
+                GROUP BY s.id, s.started_at, s.ended_at
+                "#,
+                [stream_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(minutes.flatten())
+    }
+
     /// 配信のタイムラインポイント一覧を取得
     pub fn get_timeline_stats(
         conn: &Connection,
         stream_id: i64,
     ) -> Result<Vec<TimelinePoint>, duckdb::Error> {
+        // chat_rate_1min は各収集時刻の直前1分 [collected_at - 1分, collected_at) のチャット数。
+        // 収集時刻は分境界に揃っていないため、分単位に丸めた集計では同じ値にならない。
+        // 対象配信のチャットを1度だけ絞り込み、範囲結合（DuckDB の IEJoin）で全ポイントを1パスで数える。
         let query = r#"
-        SELECT 
-            CAST(ss.collected_at AS VARCHAR) as collected_at,
-            ss.viewer_count,
-            COALESCE((
-                SELECT COUNT(*) FROM chat_messages cm
-                WHERE cm.stream_id = ss.stream_id
-                  AND cm.timestamp >= ss.collected_at - INTERVAL '1 minute'
-                  AND cm.timestamp < ss.collected_at
-            ), 0) AS chat_rate_1min,
-            ss.category,
-            ss.title,
-            ss.follower_count
-        FROM stream_stats ss
-        WHERE ss.stream_id = ?
-        ORDER BY ss.collected_at ASC
+        WITH points AS (
+            SELECT ss.id, ss.collected_at, ss.viewer_count, ss.category, ss.title,
+                   ss.follower_count, s.started_at
+            FROM stream_stats ss
+            JOIN streams s ON s.id = ss.stream_id
+            WHERE ss.stream_id = ?
+        ),
+        chat AS (
+            SELECT timestamp FROM chat_messages WHERE stream_id = ?
+        ),
+        chat_counts AS (
+            SELECT p.id, COUNT(*) AS chat_rate_1min
+            FROM points p
+            JOIN chat c
+              ON c.timestamp >= p.collected_at - INTERVAL '1 minute'
+             AND c.timestamp < p.collected_at
+            GROUP BY p.id
+        )
+        SELECT
+            CAST(p.collected_at AS VARCHAR) as collected_at,
+            p.viewer_count,
+            COALESCE(cc.chat_rate_1min, 0) AS chat_rate_1min,
+            p.category,
+            p.title,
+            p.follower_count,
+            COALESCE(EXTRACT(EPOCH FROM (p.collected_at - p.started_at)) / 60.0, 0) as elapsed_minutes
+        FROM points p
+        LEFT JOIN chat_counts cc ON cc.id = p.id
+        ORDER BY p.collected_at ASC
         "#;
         let mut stmt = conn.prepare(query)?;
         let stream_id_str = stream_id.to_string();
-        let rows = stmt.query_map([&stream_id_str], |row| {
+        let rows = stmt.query_map([&stream_id_str, &stream_id_str], |row| {
+            Ok(TimelinePoint {
+                collected_at: row.get::<_, String>(0)?,
+                viewer_count: row.get::<_, Option<i32>>(1)?,
+                chat_rate_1min: row.get::<_, Option<i32>>(2)?,
+                category: row.get::<_, String>(3).unwrap_or_default(),
+                title: row.get::<_, String>(4).unwrap_or_default(),
+                follower_count: row.get::<_, Option<i32>>(5)?,
+                elapsed_minutes: row.get::<_, f64>(6).unwrap_or_default(),
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>()
+    }
+
+    /// stream_stats_rollup から指定粒度のタイムラインを取得
+    ///
+    /// chat_rate_1min にはバケット内のチャット数を1分あたりに換算した値を入れる。
+    pub fn get_timeline_rollup(
+        conn: &Connection,
+        stream_id: i64,
+        resolution_minutes: i32,
+    ) -> Result<Vec<TimelinePoint>, duckdb::Error> {
+        let query = r#"
+        SELECT
+            CAST(r.bucket_start AS VARCHAR) as collected_at,
+            CAST(ROUND(r.avg_viewer_count) AS INTEGER) as viewer_count,
+            CAST(ROUND(r.chat_messages / r.resolution_minutes) AS INTEGER) as chat_rate_1min,
+            r.category,
+            r.title,
+            r.follower_count,
+            COALESCE(EXTRACT(EPOCH FROM (r.bucket_start - s.started_at)) / 60.0, 0) as elapsed_minutes
+        FROM stream_stats_rollup r
+        JOIN streams s ON s.id = r.stream_id
+        WHERE r.stream_id = ? AND r.resolution_minutes = ?
+        ORDER BY r.bucket_start ASC
+        "#;
+        let mut stmt = conn.prepare(query)?;
+        let params = [stream_id.to_string(), resolution_minutes.to_string()];
+        let rows = utils::query_map_with_params(&mut stmt, &params, |row| {
             Ok(TimelinePoint {
                 collected_at: row.get::<_, String>(0)?,
-                viewer_count: row.get::<_, i32>(1).unwrap_or_default(),
-                chat_rate_1min: row.get::<_, i32>(2)?,
+                viewer_count: row.get::<_, Option<i32>>(1)?,
+                chat_rate_1min: row.get::<_, Option<i32>>(2)?,
                 category: row.get::<_, String>(3).unwrap_or_default(),
                 title: row.get::<_, String>(4).unwrap_or_default(),
-                follower_count: row.get::<_, i32>(5).unwrap_or_default(),
+                follower_count: row.get::<_, Option<i32>>(5)?,
+                elapsed_minutes: row.get::<_, f64>(6).unwrap_or_default(),
             })
         })?;
         rows.collect::<Result<Vec<_>, _>>()
     }
+
+    /// チャンネルの全期間・直近30日・その前の30日のサマリーを取得
+    ///
+    /// 期間は配信の開始時刻で判定する。`now` は stream_stats と同じくRFC3339形式の現在時刻。
+    pub fn get_channel_summary(
+        conn: &Connection,
+        channel_id: i64,
+        now: &str,
+    ) -> Result<ChannelSummary, duckdb::Error> {
+        let all_time = Self::get_period_summary(conn, channel_id, None)?;
+        let last_30_days = Self::get_period_summary(conn, channel_id, Some((now, 30, 0)))?;
+        let previous_30_days = Self::get_period_summary(conn, channel_id, Some((now, 60, 30)))?;
+
+        Ok(ChannelSummary {
+            channel_id,
+            stream_count_change: change_rate(
+                last_30_days.stream_count as f64,
+                previous_30_days.stream_count as f64,
+            ),
+            duration_change: change_rate(
+                last_30_days.total_duration_minutes,
+                previous_30_days.total_duration_minutes,
+            ),
+            avg_viewers_change: change_rate(last_30_days.avg_viewers, previous_30_days.avg_viewers),
+            minutes_watched_change: change_rate(
+                last_30_days.minutes_watched as f64,
+                previous_30_days.minutes_watched as f64,
+            ),
+            all_time,
+            last_30_days,
+            previous_30_days,
+        })
+    }
+
+    /// 期間内に開始した配信を集計（period が None なら全期間）
+    ///
+    /// period は (現在時刻, 開始の日数前, 終了の日数前)。(now, 60, 30) なら60日前以上30日前未満。
+    /// 配信時間は ended_at（進行中は最後の統計時刻）から求める。
+    fn get_period_summary(
+        conn: &Connection,
+        channel_id: i64,
+        period: Option<(&str, i64, i64)>,
+    ) -> Result<StreamPeriodSummary, duckdb::Error> {
+        let (period_filter, period_params) = match period {
+            Some((now, from_days_ago, to_days_ago)) => (
+                format!(
+                    " AND s.started_at >= CAST(? AS TIMESTAMP) - INTERVAL {} DAY AND s.started_at < CAST(? AS TIMESTAMP) - INTERVAL {} DAY",
+                    from_days_ago, to_days_ago
+                ),
+                vec![now.to_string(), now.to_string()],
+            ),
+            None => (String::new(), Vec::new()),
+        };
+        let sql = format!(
+            r#"
+            WITH per_stream AS (
+                SELECT
+                    s.id,
+                    EXTRACT(EPOCH FROM (
+                        COALESCE(s.ended_at, MAX(ss.collected_at), s.started_at) - s.started_at
+                    )) / 60.0 AS duration_minutes,
+                    AVG(ss.viewer_count) AS avg_viewers,
+                    MAX(ss.viewer_count) AS peak_viewers
+                FROM streams s
+                LEFT JOIN stream_stats ss ON ss.stream_id = s.id
+                WHERE s.channel_id = ?{period_filter}
+                GROUP BY s.id, s.started_at, s.ended_at
+            ),
+            stats_with_interval AS (
+                SELECT ss.viewer_count, ss.follower_count, ss.collected_at, {interval}
+                FROM stream_stats ss
+                INNER JOIN streams s ON ss.stream_id = s.id
+                WHERE s.channel_id = ?{period_filter}
+            )
+            SELECT
+                (SELECT COUNT(*) FROM per_stream),
+                COALESCE((SELECT SUM(duration_minutes) FROM per_stream), 0)::DOUBLE,
+                COALESCE((SELECT AVG(avg_viewers) FROM per_stream), 0)::DOUBLE,
+                COALESCE((SELECT MAX(peak_viewers) FROM per_stream), 0)::INTEGER,
+                COALESCE((
+                    SELECT SUM(viewer_count * COALESCE(interval_minutes, 1)) FROM stats_with_interval
+                ), 0)::BIGINT,
+                COALESCE((
+                    SELECT arg_max(follower_count, collected_at) - arg_min(follower_count, collected_at)
+                    FROM stats_with_interval
+                    WHERE follower_count IS NOT NULL
+                ), 0)::BIGINT
+            "#,
+            period_filter = period_filter,
+            interval = stream_stats_query::interval_with_fallback("ss")
+        );
+
+        let mut params = vec![channel_id.to_string()];
+        params.extend(period_params.iter().cloned());
+        params.push(channel_id.to_string());
+        params.extend(period_params);
+
+        let mut stmt = conn.prepare(&sql)?;
+        stmt.query_row(duckdb::params_from_iter(params.iter()), |row| {
+            Ok(StreamPeriodSummary {
+                stream_count: row.get(0)?,
+                total_duration_minutes: row.get(1)?,
+                avg_viewers: row.get(2)?,
+                peak_viewers: row.get(3)?,
+                minutes_watched: row.get(4)?,
+                follower_gain: row.get(5)?,
+            })
+        })
+    }
+
+    /// 前の配信の終了から `max_gap_secs` 秒以内に次の配信が始まっている配信のグループを取得
+    ///
+    /// ネットワーク瞬断などで Twitch の stream ID が変わり、1回の配信が複数行に分かれたものの検出用。
+    pub fn find_fragmented_streams(
+        conn: &Connection,
+        channel_id: i64,
+        max_gap_secs: i64,
+    ) -> Result<Vec<FragmentedStreamGroup>, duckdb::Error> {
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT
+                id,
+                CAST(started_at AS VARCHAR),
+                CAST(ended_at AS VARCHAR),
+                EXTRACT(EPOCH FROM (started_at - LAG(ended_at) OVER (ORDER BY started_at, id)))::BIGINT
+            FROM streams
+            WHERE channel_id = ?
+            ORDER BY started_at, id
+            "#,
+        )?;
+        let rows = stmt
+            .query_map([channel_id], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(group_fragmented_streams(channel_id, rows, max_gap_secs))
+    }
+
+    /// 配信ID（開始時刻順）と所属チャンネルIDを取得（存在しないIDは含まれない）
+    pub fn get_stream_channels(
+        conn: &Connection,
+        stream_ids: &[i64],
+    ) -> Result<Vec<(i64, i64)>, duckdb::Error> {
+        let placeholders = vec!["?"; stream_ids.len()].join(", ");
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, channel_id FROM streams WHERE id IN ({}) ORDER BY started_at, id",
+            placeholders
+        ))?;
+        let params: Vec<String> = stream_ids.iter().map(|id| id.to_string()).collect();
+        let rows =
+            utils::query_map_with_params(&mut stmt, &params, |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect()
+    }
+
+    /// `source_ids` の配信を `target_id` に統合する
+    ///
+    /// stream_stats / chat_messages の stream_id を付け替え、統合先の started_at を最も早い開始時刻、
+    /// ended_at を最も遅い終了時刻（統合先が進行中なら NULL のまま）にしてから統合元の配信を削除する。
+    /// 同じ collected_at の統計が重複する場合は統合先の行を残す。
+    /// 統合した配信のロールアップは削除し、定期更新で統合先のみ再集計させる。
+    ///
+    /// DuckDB は同一トランザクション内で FK 参照先の削除を認識しないため、
+    /// 参照元の付け替え（第1段階）と streams の更新・削除（第2段階）は別トランザクションで実行する。
+    /// 第2段階が失敗しても統合元の配信と統合先の期間は元のまま残り、統合元は空の配信になるだけなので、
+    /// 同じ配信を指定して再度統合すれば（統合先の選び方も変わらず）残りの処理が完了する。
+    pub fn merge_streams(
+        conn: &Connection,
+        target_id: i64,
+        source_ids: &[i64],
+    ) -> Result<(), duckdb::Error> {
+        if source_ids.is_empty() {
+            return Ok(());
+        }
+        Self::move_merged_stream_data(conn, target_id, source_ids)?;
+        Self::finish_stream_merge(conn, target_id, source_ids)
+    }
+
+    /// 統合の第1段階: 統計・チャット・ロールアップを統合先へ付け替える（何度実行しても同じ結果になる）
+    fn move_merged_stream_data(
+        conn: &Connection,
+        target_id: i64,
+        source_ids: &[i64],
+    ) -> Result<(), duckdb::Error> {
+        let source_placeholders = vec!["?"; source_ids.len()].join(", ");
+        let all_placeholders = vec!["?"; source_ids.len() + 1].join(", ");
+        let sources: Vec<String> = source_ids.iter().map(|id| id.to_string()).collect();
+        let mut all = vec![target_id.to_string()];
+        all.extend(sources.iter().cloned());
+
+        with_transaction(conn, |conn| {
+            let mut params = sources.clone();
+            params.push(target_id.to_string());
+            params.extend(all.iter().cloned());
+            utils::execute_with_params(
+                conn,
+                &format!(
+                    r#"
+                    DELETE FROM stream_stats
+                    WHERE stream_id IN ({source_placeholders})
+                      AND id NOT IN (
+                          SELECT arg_min(id, CASE WHEN stream_id = ? THEN 0 ELSE 1 END)
+                          FROM stream_stats
+                          WHERE stream_id IN ({all_placeholders})
+                          GROUP BY collected_at
+                      )
+                    "#
+                ),
+                &params,
+            )?;
+
+            let mut params = vec![target_id.to_string()];
+            params.extend(sources.iter().cloned());
+            utils::execute_with_params(
+                conn,
+                &format!(
+                    "UPDATE stream_stats SET stream_id = ? WHERE stream_id IN ({})",
+                    source_placeholders
+                ),
+                &params,
+            )?;
+            utils::execute_with_params(
+                conn,
+                &format!(
+                    "UPDATE chat_messages SET stream_id = ? WHERE stream_id IN ({})",
+                    source_placeholders
+                ),
+                &params,
+            )?;
+
+            utils::execute_with_params(
+                conn,
+                &format!(
+                    "DELETE FROM stream_stats_rollup WHERE stream_id IN ({})",
+                    all_placeholders
+                ),
+                &all,
+            )?;
+            Ok(())
+        })
+    }
+
+    /// 統合の第2段階: 統合先の期間を広げ、統合元の streams を削除する（参照元は第1段階でコミット済み）
+    fn finish_stream_merge(
+        conn: &Connection,
+        target_id: i64,
+        source_ids: &[i64],
+    ) -> Result<(), duckdb::Error> {
+        let source_placeholders = vec!["?"; source_ids.len()].join(", ");
+        let all_placeholders = vec!["?"; source_ids.len() + 1].join(", ");
+        let sources: Vec<String> = source_ids.iter().map(|id| id.to_string()).collect();
+        let mut all = vec![target_id.to_string()];
+        all.extend(sources.iter().cloned());
+
+        with_transaction(conn, |conn| {
+            let mut params = all.clone();
+            params.extend(all.iter().cloned());
+            params.push(target_id.to_string());
+            utils::execute_with_params(
+                conn,
+                &format!(
+                    r#"
+                    UPDATE streams
+                    SET started_at = (SELECT MIN(started_at) FROM streams WHERE id IN ({all_placeholders})),
+                        ended_at = CASE
+                            WHEN ended_at IS NULL THEN NULL
+                            ELSE (SELECT MAX(ended_at) FROM streams WHERE id IN ({all_placeholders}))
+                        END
+                    WHERE id = ?
+                    "#
+                ),
+                &params,
+            )?;
+
+            utils::execute_with_params(
+                conn,
+                &format!("DELETE FROM streams WHERE id IN ({})", source_placeholders),
+                &sources,
+            )?;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{init_test_db, insert_channel, insert_stream};
+
+    /// 書き換え前の相関サブクエリによる chat_rate_1min（期待値の基準）
+    const LEGACY_CHAT_RATE_QUERY: &str = r#"
+        SELECT COALESCE((
+            SELECT COUNT(*) FROM chat_messages cm
+            WHERE cm.stream_id = ss.stream_id
+              AND cm.timestamp >= ss.collected_at - INTERVAL '1 minute'
+              AND cm.timestamp < ss.collected_at
+        ), 0)
+        FROM stream_stats ss
+        WHERE ss.stream_id = ?
+        ORDER BY ss.collected_at ASC
+    "#;
+
+    fn setup() -> Connection {
+        let conn = init_test_db();
+        insert_channel(&conn, 1, "twitch", "foo");
+        insert_stream(&conn, 1, 1, "2024-01-01 12:00:00", None);
+        insert_stream(&conn, 2, 1, "2024-01-01 12:00:00", None);
+        conn.execute_batch(
+            r#"
+            INSERT INTO stream_stats (id, stream_id, collected_at, viewer_count) VALUES
+                (1, 1, '2024-01-01 12:01:00', 10),
+                (2, 1, '2024-01-01 12:02:30', 20),
+                (3, 1, '2024-01-01 12:03:30', 30),
+                (4, 1, '2024-01-01 12:10:00', 40);
+            INSERT INTO chat_messages (stream_id, timestamp, platform, user_name, message) VALUES
+                -- 12:01:00 の窓 [12:00:00, 12:01:00): 開始境界は含み、終了境界は含まない
+                (1, '2024-01-01 12:00:00', 'twitch', 'viewer', 'hi'),
+                (1, '2024-01-01 12:00:59', 'twitch', 'viewer', 'hi'),
+                (1, '2024-01-01 12:01:00', 'twitch', 'viewer', 'hi'),
+                -- 12:02:30 の窓に1件、12:03:30 の窓に2件
+                (1, '2024-01-01 12:02:29', 'twitch', 'viewer', 'hi'),
+                (1, '2024-01-01 12:02:30', 'twitch', 'viewer', 'hi'),
+                (1, '2024-01-01 12:03:00', 'twitch', 'viewer', 'hi'),
+                -- 別配信のチャットは数えない
+                (2, '2024-01-01 12:00:30', 'twitch', 'viewer', 'hi');
+            "#,
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn storage_breakdown_clamps_limit() {
+        let conn = setup();
+
+        let all = StreamRepository::get_storage_breakdown(&conn, None).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].id, 1);
+        assert_eq!(all[0].stats_rows, 4);
+        assert_eq!(all[0].chat_rows, 6);
+
+        for limit in [0, -1, i32::MIN] {
+            let rows = StreamRepository::get_storage_breakdown(&conn, Some(limit)).unwrap();
+            assert_eq!(rows.len(), 1, "limit {}", limit);
+        }
+        assert_eq!(
+            StreamRepository::get_storage_breakdown(&conn, Some(i32::MAX))
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn retention_curve_normalizes_by_baseline() {
+        let conn = setup();
+
+        let peak =
+            StreamRepository::get_retention_curve(&conn, 1, RetentionBaseline::Peak).unwrap();
+        let ratios: Vec<f64> = peak.iter().map(|p| p.viewer_ratio).collect();
+        assert_eq!(ratios, vec![0.25, 0.5, 0.75, 1.0]);
+        assert_eq!(peak[1].minute_offset, 2.5);
+
+        let start =
+            StreamRepository::get_retention_curve(&conn, 1, RetentionBaseline::Start).unwrap();
+        let ratios: Vec<f64> = start.iter().map(|p| p.viewer_ratio).collect();
+        assert_eq!(ratios, vec![1.0, 2.0, 3.0, 4.0]);
+
+        // 統計の無い配信は空
+        assert!(
+            StreamRepository::get_retention_curve(&conn, 2, RetentionBaseline::Peak)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn peak_moment_returns_max_viewer_point() {
+        let conn = setup();
+
+        let peak = StreamRepository::get_peak_moment(&conn, 1)
+            .unwrap()
+            .unwrap();
+        assert_eq!(peak.timestamp, "2024-01-01 12:10:00");
+        assert_eq!(peak.viewer_count, 40);
+        assert!(StreamRepository::get_peak_moment(&conn, 2)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn timeline_chat_rate_matches_legacy_subquery() {
+        let conn = setup();
+
+        let points = StreamRepository::get_timeline_stats(&conn, 1).unwrap();
+        let rates: Vec<i32> = points.iter().filter_map(|p| p.chat_rate_1min).collect();
+
+        let mut stmt = conn.prepare(LEGACY_CHAT_RATE_QUERY).unwrap();
+        let legacy: Vec<i32> = stmt
+            .query_map([1], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(rates, legacy);
+        assert_eq!(rates, vec![2, 1, 2, 0]);
+        assert_eq!(points[1].elapsed_minutes, 2.5);
+    }
+
+    #[test]
+    fn aggregate_viewers_sums_channels_per_bucket() {
+        let conn = init_test_db();
+        for (id, channel_id) in [(1, 10), (2, 20), (3, 30)] {
+            insert_channel(&conn, channel_id, "twitch", &format!("c{}", channel_id));
+            insert_stream(&conn, id, channel_id, "2024-01-01 12:00:00", None);
+        }
+        conn.execute_batch(
+            r#"
+            INSERT INTO stream_stats (stream_id, collected_at, viewer_count) VALUES
+                (1, '2024-01-01 12:01:00', 10),
+                (1, '2024-01-01 12:03:00', 30),
+                (2, '2024-01-01 12:02:00', 5),
+                (2, '2024-01-01 12:12:00', 7),
+                -- 対象外のチャンネルは合算しない
+                (3, '2024-01-01 12:02:00', 1000);
+            "#,
+        )
+        .unwrap();
+
+        let points = StreamRepository::get_aggregate_viewers(
+            &conn,
+            &[10, 20],
+            "2024-01-01 12:00:00",
+            "2024-01-01 12:14:00",
+            5,
+        )
+        .unwrap();
+        let totals: Vec<(&str, i64)> = points
+            .iter()
+            .map(|p| (p.timestamp.as_str(), p.total_viewers))
+            .collect();
+
+        // 12:05 のバケットはどのチャンネルも配信していないため0
+        assert_eq!(
+            totals,
+            vec![
+                ("2024-01-01 12:00:00", 25),
+                ("2024-01-01 12:05:00", 0),
+                ("2024-01-01 12:10:00", 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn category_stats_split_streams_by_category() {
+        let conn = init_test_db();
+        insert_channel(&conn, 10, "twitch", "foo");
+        insert_channel(&conn, 20, "twitch", "bar");
+        insert_stream(&conn, 1, 10, "2024-01-01 12:00:00", None);
+        insert_stream(&conn, 2, 10, "2024-01-02 12:00:00", None);
+        insert_stream(&conn, 3, 20, "2024-01-01 12:00:00", None);
+        conn.execute_batch(
+            r#"
+            INSERT INTO stream_stats (stream_id, collected_at, viewer_count, category, channel_name) VALUES
+                -- 配信1: 雑談 10分 → ゲームA 20分（最終行は1分扱い）
+                (1, '2024-01-01 12:00:00', 10, 'Just Chatting', 'foo'),
+                (1, '2024-01-01 12:10:00', 30, 'Game A', 'foo'),
+                (1, '2024-01-01 12:20:00', 50, 'Game A', 'foo'),
+                -- 配信2: ゲームA のみ
+                (2, '2024-01-02 12:00:00', 20, 'Game A', 'foo'),
+                (2, '2024-01-02 12:30:00', 10, 'Game A', 'foo'),
+                -- 他チャンネルは対象外
+                (3, '2024-01-01 12:00:00', 1000, 'Game A', 'bar');
+            "#,
+        )
+        .unwrap();
+
+        let stats = StreamRepository::get_category_stats(&conn, 10).unwrap();
+        assert_eq!(stats.len(), 2);
+
+        let game_a = &stats[0];
+        assert_eq!(game_a.category, "Game A");
+        assert_eq!(game_a.stream_count, 2);
+        // 配信1: peak 50 / 11分 / MW 30*10 + 50*1、配信2: peak 20 / 31分 / MW 20*30 + 10*1
+        assert_eq!(game_a.avg_peak_viewers, 35.0);
+        assert_eq!(game_a.avg_duration_minutes, 21.0);
+        assert_eq!(game_a.minutes_watched, 960);
+
+        let chatting = &stats[1];
+        assert_eq!(chatting.category, "Just Chatting");
+        assert_eq!(chatting.stream_count, 1);
+        assert_eq!(chatting.avg_duration_minutes, 10.0);
+        assert_eq!(chatting.minutes_watched, 100);
+    }
+
+    #[test]
+    fn test_build_viewer_heatmap() {
+        // 2024-01-07 は日曜日
+        let sunday_20 = chrono::Utc
+            .with_ymd_and_hms(2024, 1, 7, 20, 0, 0)
+            .unwrap()
+            .timestamp();
+        let sunday_20_30 = sunday_20 + 30 * 60;
+        let monday_3 = chrono::Utc
+            .with_ymd_and_hms(2024, 1, 8, 3, 0, 0)
+            .unwrap()
+            .timestamp();
+
+        let cells = build_viewer_heatmap(
+            &[(sunday_20, 100.0), (sunday_20_30, 50.0), (monday_3, 10.0)],
+            &chrono::Utc,
+        );
+        assert_eq!(cells.len(), 7 * 24);
+
+        let sunday = &cells[20];
+        assert_eq!((sunday.weekday, sunday.hour), (0, 20));
+        assert_eq!(sunday.stream_count, 2);
+        assert_eq!(sunday.avg_viewers, 75.0);
+
+        let monday = &cells[24 + 3];
+        assert_eq!((monday.weekday, monday.hour), (1, 3));
+        assert_eq!(monday.stream_count, 1);
+
+        assert_eq!(cells.iter().map(|c| c.stream_count).sum::<i64>(), 3);
+    }
+
+    #[test]
+    fn test_get_channel_summary() {
+        let conn = init_test_db();
+        insert_channel(&conn, 1, "twitch", "a");
+        insert_channel(&conn, 2, "twitch", "b");
+        insert_stream(
+            &conn,
+            1,
+            1,
+            "2024-02-20 10:00:00",
+            Some("2024-02-20 11:00:00"),
+        );
+        insert_stream(
+            &conn,
+            2,
+            1,
+            "2024-01-20 10:00:00",
+            Some("2024-01-20 10:30:00"),
+        );
+        insert_stream(&conn, 3, 2, "2024-02-20 10:00:00", None);
+        conn.execute_batch(
+            r#"
+            INSERT INTO stream_stats (stream_id, collected_at, viewer_count, follower_count, channel_name) VALUES
+                (1, '2024-02-20 10:00:00', 100, 1000, 'a'),
+                (1, '2024-02-20 10:30:00', 200, 1010, 'a'),
+                (1, '2024-02-20 11:00:00', 300, 1020, 'a'),
+                (2, '2024-01-20 10:00:00', 50, 990, 'a'),
+                (2, '2024-01-20 10:30:00', 50, 1000, 'a'),
+                (3, '2024-02-20 10:00:00', 999, 1, 'b');
+            "#,
+        )
+        .unwrap();
+
+        let summary =
+            StreamRepository::get_channel_summary(&conn, 1, "2024-03-01T00:00:00+00:00").unwrap();
+
+        let all = &summary.all_time;
+        assert_eq!(all.stream_count, 2);
+        assert_eq!(all.total_duration_minutes, 90.0);
+        assert_eq!(all.avg_viewers, 125.0);
+        assert_eq!(all.peak_viewers, 300);
+        // 最後の統計は次の収集が無いため1分として数える
+        assert_eq!(
+            all.minutes_watched,
+            100 * 30 + 200 * 30 + 300 + 50 * 30 + 50
+        );
+        assert_eq!(all.follower_gain, 30);
+
+        assert_eq!(summary.last_30_days.stream_count, 1);
+        assert_eq!(summary.last_30_days.follower_gain, 20);
+        assert_eq!(summary.previous_30_days.stream_count, 1);
+        assert_eq!(summary.avg_viewers_change, Some(3.0));
+        assert_eq!(summary.stream_count_change, Some(0.0));
+    }
+
+    #[test]
+    fn test_find_and_merge_fragmented_streams() {
+        let conn = init_test_db();
+        insert_channel(&conn, 1, "twitch", "foo");
+        // 1 と 2 は2分の瞬断で分断、3 は翌日の別配信
+        insert_stream(
+            &conn,
+            1,
+            1,
+            "2024-01-01 12:00:00",
+            Some("2024-01-01 13:00:00"),
+        );
+        insert_stream(&conn, 2, 1, "2024-01-01 13:02:00", None);
+        insert_stream(
+            &conn,
+            3,
+            1,
+            "2024-01-02 12:00:00",
+            Some("2024-01-02 13:00:00"),
+        );
+        conn.execute_batch(
+            r#"
+            INSERT INTO stream_stats (id, stream_id, collected_at, viewer_count) VALUES
+                (1, 1, '2024-01-01 12:30:00', 10),
+                (2, 1, '2024-01-01 13:02:00', 11),
+                (3, 2, '2024-01-01 13:02:00', 12),
+                (4, 2, '2024-01-01 13:30:00', 20);
+            INSERT INTO stream_stats_rollup (stream_id, resolution_minutes, bucket_start) VALUES
+                (1, 5, '2024-01-01 12:30:00'),
+                (2, 5, '2024-01-01 13:30:00');
+            INSERT INTO chat_messages (stream_id, timestamp, platform, user_name, message) VALUES
+                (1, '2024-01-01 12:30:00', 'twitch', 'viewer', 'hi'),
+                (2, '2024-01-01 13:30:00', 'twitch', 'viewer', 'hi');
+            "#,
+        )
+        .unwrap();
+
+        let groups = StreamRepository::find_fragmented_streams(&conn, 1, 5 * 60).unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].stream_ids, vec![1, 2]);
+        assert_eq!(groups[0].ended_at, None);
+        assert!(StreamRepository::find_fragmented_streams(&conn, 1, 60)
+            .unwrap()
+            .is_empty());
+
+        // 進行中の配信（2）を統合先にすると、以降の収集もそのまま統合先に記録される
+        StreamRepository::merge_streams(&conn, 2, &[1]).unwrap();
+
+        let (started_at, ended_at): (String, Option<String>) = conn
+            .query_row(
+                "SELECT CAST(started_at AS VARCHAR), CAST(ended_at AS VARCHAR) FROM streams WHERE id = 2",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(started_at, "2024-01-01 12:00:00");
+        assert_eq!(ended_at, None);
+
+        let count = |sql: &str| -> i64 { conn.query_row(sql, [], |row| row.get(0)).unwrap() };
+        assert_eq!(count("SELECT COUNT(*) FROM streams WHERE id = 1"), 0);
+        // 重複した 13:02 の統計は統合先の行を残す
+        assert_eq!(
+            count("SELECT COUNT(*) FROM stream_stats WHERE stream_id = 2"),
+            3
+        );
+        assert_eq!(
+            count(
+                "SELECT viewer_count FROM stream_stats WHERE collected_at = '2024-01-01 13:02:00'"
+            ),
+            12
+        );
+        assert_eq!(
+            count("SELECT COUNT(*) FROM chat_messages WHERE stream_id = 2"),
+            2
+        );
+        assert_eq!(count("SELECT COUNT(*) FROM stream_stats_rollup"), 0);
+    }
+
+    #[test]
+    fn test_merge_streams_resumes_after_partial_merge() {
+        let conn = init_test_db();
+        insert_channel(&conn, 1, "twitch", "foo");
+        insert_stream(
+            &conn,
+            1,
+            1,
+            "2024-01-01 12:00:00",
+            Some("2024-01-01 13:00:00"),
+        );
+        insert_stream(
+            &conn,
+            2,
+            1,
+            "2024-01-01 13:02:00",
+            Some("2024-01-01 14:00:00"),
+        );
+        conn.execute_batch(
+            r#"
+            INSERT INTO stream_stats (id, stream_id, collected_at, viewer_count) VALUES
+                (1, 1, '2024-01-01 12:30:00', 10),
+                (2, 2, '2024-01-01 13:30:00', 20);
+            INSERT INTO chat_messages (stream_id, timestamp, platform, user_name, message) VALUES
+                (1, '2024-01-01 12:30:00', 'twitch', 'viewer', 'hi');
+            "#,
+        )
+        .unwrap();
+
+        // 第2段階の前に中断した状態: 統合元は空の配信として残り、開始時刻順（統合先の選択）も変わらない
+        StreamRepository::move_merged_stream_data(&conn, 2, &[1]).unwrap();
+        assert_eq!(
+            StreamRepository::get_stream_channels(&conn, &[1, 2]).unwrap(),
+            vec![(1, 1), (2, 1)]
+        );
+
+        // 同じ指定で再実行すると統合が完了する
+        StreamRepository::merge_streams(&conn, 2, &[1]).unwrap();
+
+        let count = |sql: &str| -> i64 { conn.query_row(sql, [], |row| row.get(0)).unwrap() };
+        assert_eq!(count("SELECT COUNT(*) FROM streams"), 1);
+        assert_eq!(
+            count("SELECT COUNT(*) FROM stream_stats WHERE stream_id = 2"),
+            2
+        );
+        assert_eq!(
+            count("SELECT COUNT(*) FROM chat_messages WHERE stream_id = 2"),
+            1
+        );
+        let (started_at, ended_at): (String, String) = conn
+            .query_row(
+                "SELECT CAST(started_at AS VARCHAR), CAST(ended_at AS VARCHAR) FROM streams WHERE id = 2",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(started_at, "2024-01-01 12:00:00");
+        assert_eq!(ended_at, "2024-01-01 14:00:00");
+    }
 }
@@ -31,6 +31,35 @@ pub struct TimeBucketViewerStats {
 
 pub struct StreamStatsRepository;
 
+/// stream_stats（ss）と streams（s）を結合したクエリに共通の絞り込み条件を追加する
+///
+/// get_stream_stats_filtered と count_stream_stats_filtered で同じ条件を使うため共通化している。
+fn push_stats_filters(
+    sql: &mut String,
+    params: &mut Vec<String>,
+    stream_id: Option<i64>,
+    channel_id: Option<i64>,
+    start_time: Option<&str>,
+    end_time: Option<&str>,
+) {
+    if let Some(sid) = stream_id {
+        sql.push_str(" AND ss.stream_id = ?");
+        params.push(sid.to_string());
+    }
+    if let Some(cid) = channel_id {
+        sql.push_str(" AND s.channel_id = ?");
+        params.push(cid.to_string());
+    }
+    if let Some(st) = start_time {
+        sql.push_str(" AND ss.collected_at >= ?");
+        params.push(st.to_string());
+    }
+    if let Some(et) = end_time {
+        sql.push_str(" AND ss.collected_at <= ?");
+        params.push(et.to_string());
+    }
+}
+
 impl StreamStatsRepository {
     /// フィルタ付きで stream_stats を取得（get_stream_stats / export 共用）
     ///
@@ -42,6 +71,43 @@ impl StreamStatsRepository {
         start_time: Option<&str>,
         end_time: Option<&str>,
         order_asc: bool,
+    ) -> Result<Vec<StreamStats>, duckdb::Error> {
+        Self::query_stream_stats(
+            conn, stream_id, channel_id, start_time, end_time, order_asc, None,
+        )
+    }
+
+    /// get_stream_stats_filtered と同じ条件で collected_at 昇順の1ページ分を取得（エクスポート用）
+    ///
+    /// 全行をメモリに載せずに書き出せるよう、`limit` 件ずつ `offset` をずらして呼び出す。
+    pub fn get_stream_stats_page(
+        conn: &Connection,
+        stream_id: Option<i64>,
+        channel_id: Option<i64>,
+        start_time: Option<&str>,
+        end_time: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<StreamStats>, duckdb::Error> {
+        Self::query_stream_stats(
+            conn,
+            stream_id,
+            channel_id,
+            start_time,
+            end_time,
+            true,
+            Some((limit, offset)),
+        )
+    }
+
+    fn query_stream_stats(
+        conn: &Connection,
+        stream_id: Option<i64>,
+        channel_id: Option<i64>,
+        start_time: Option<&str>,
+        end_time: Option<&str>,
+        order_asc: bool,
+        page: Option<(i64, i64)>,
     ) -> Result<Vec<StreamStats>, duckdb::Error> {
         let mut sql = String::from(
             "SELECT ss.id, ss.stream_id, CAST(ss.collected_at AS VARCHAR) as collected_at, ss.viewer_count,
@@ -52,35 +118,33 @@ impl StreamStatsRepository {
                    AND cm.timestamp >= ss.collected_at - INTERVAL '1 minute'
                    AND cm.timestamp < ss.collected_at
              ), 0) AS chat_rate_1min,
-             ss.category, ss.title, ss.follower_count, ss.twitch_user_id, ss.channel_name
+             ss.category, ss.title, ss.follower_count, ss.twitch_user_id, ss.channel_name,
+             ss.comment_count
              FROM stream_stats ss
              INNER JOIN streams s ON ss.stream_id = s.id
              WHERE 1=1",
         );
 
         let mut params: Vec<String> = Vec::new();
+        push_stats_filters(
+            &mut sql,
+            &mut params,
+            stream_id,
+            channel_id,
+            start_time,
+            end_time,
+        );
 
-        if let Some(sid) = stream_id {
-            sql.push_str(" AND ss.stream_id = ?");
-            params.push(sid.to_string());
-        }
-        if let Some(cid) = channel_id {
-            sql.push_str(" AND s.channel_id = ?");
-            params.push(cid.to_string());
-        }
-        if let Some(st) = start_time {
-            sql.push_str(" AND ss.collected_at >= ?");
-            params.push(st.to_string());
-        }
-        if let Some(et) = end_time {
-            sql.push_str(" AND ss.collected_at <= ?");
-            params.push(et.to_string());
-        }
-
+        // ページング時に行が重複・欠落しないよう id で順序を確定させる
         if order_asc {
-            sql.push_str(" ORDER BY ss.collected_at ASC");
+            sql.push_str(" ORDER BY ss.collected_at ASC, ss.id ASC");
         } else {
-            sql.push_str(" ORDER BY ss.collected_at DESC");
+            sql.push_str(" ORDER BY ss.collected_at DESC, ss.id DESC");
+        }
+        if let Some((limit, offset)) = page {
+            sql.push_str(" LIMIT ? OFFSET ?");
+            params.push(limit.to_string());
+            params.push(offset.to_string());
         }
 
         let mut stmt = conn.prepare(&sql)?;
@@ -97,11 +161,45 @@ impl StreamStatsRepository {
                 follower_count: row.get(7)?,
                 twitch_user_id: row.get(8)?,
                 channel_name: row.get(9)?,
+                comment_count: row.get(10)?,
             })
         })?;
         results.collect::<Result<Vec<_>, _>>()
     }
 
+    /// get_stream_stats_filtered と同じ条件に一致する行数を取得（エクスポート件数のプレビュー用）
+    ///
+    /// 戻り値: (行数, 最初と最後の collected_at の間隔（秒）)。補完後の件数の算出に使う。
+    pub fn count_stream_stats_filtered(
+        conn: &Connection,
+        stream_id: Option<i64>,
+        channel_id: Option<i64>,
+        start_time: Option<&str>,
+        end_time: Option<&str>,
+    ) -> Result<(i64, f64), duckdb::Error> {
+        let mut sql = String::from(
+            "SELECT COUNT(*),
+             COALESCE(EXTRACT(EPOCH FROM (MAX(ss.collected_at) - MIN(ss.collected_at))), 0)::DOUBLE
+             FROM stream_stats ss
+             INNER JOIN streams s ON ss.stream_id = s.id
+             WHERE 1=1",
+        );
+        let mut params: Vec<String> = Vec::new();
+        push_stats_filters(
+            &mut sql,
+            &mut params,
+            stream_id,
+            channel_id,
+            start_time,
+            end_time,
+        );
+
+        let mut stmt = conn.prepare(&sql)?;
+        let mut rows =
+            utils::query_map_with_params(&mut stmt, &params, |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.next().unwrap_or(Ok((0, 0.0)))
+    }
+
     /// 指定した時間範囲と間隔で線形補完した統計データを取得（エクスポート用）
     ///
     /// - 元データは get_stream_stats_filtered で取得
@@ -263,6 +361,7 @@ impl StreamStatsRepository {
                 follower_count: base.follower_count,
                 twitch_user_id: base.twitch_user_id.clone(),
                 channel_name: base.channel_name.clone(),
+                comment_count: base.comment_count,
             });
         }
 
@@ -302,11 +401,31 @@ impl StreamStatsRepository {
         Ok(())
     }
 
+    /// 指定期間の統計データを削除し、削除件数を返す
+    ///
+    /// channel_id を指定した場合は、そのチャンネルの配信に紐づく行のみ削除します。
+    pub fn delete_in_range(
+        conn: &Connection,
+        channel_id: Option<i64>,
+        from: &str,
+        to: &str,
+    ) -> Result<usize, duckdb::Error> {
+        let mut sql =
+            String::from("DELETE FROM stream_stats WHERE collected_at >= ? AND collected_at <= ?");
+        let mut params = vec![from.to_string(), to.to_string()];
+
+        if let Some(cid) = channel_id {
+            sql.push_str(" AND stream_id IN (SELECT id FROM streams WHERE channel_id = ?)");
+            params.push(cid.to_string());
+        }
+
+        utils::execute_with_params(conn, &sql, &params)
+    }
+
     /// インターバル計算付きで統計を取得
     ///
     /// LEAD関数を使用して次のレコードとの時間差を計算します。
     /// これはMW（Minutes Watched）計算の基礎となります。
-    #[allow(dead_code)]
     pub fn get_stats_with_interval(
         conn: &Connection,
         channel_name: Option<&str>,
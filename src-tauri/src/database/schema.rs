@@ -1,3 +1,4 @@
+use crate::database::repositories::base::with_transaction;
 use duckdb::Connection;
 
 pub fn init_database(conn: &Connection) -> Result<(), duckdb::Error> {
@@ -13,7 +14,7 @@ pub fn init_database(conn: &Connection) -> Result<(), duckdb::Error> {
         r#"
         CREATE TABLE IF NOT EXISTS channels (
             id BIGINT PRIMARY KEY DEFAULT nextval('channels_id_seq'),
-            platform TEXT NOT NULL CHECK(platform IN ('twitch', 'youtube')),
+            platform TEXT NOT NULL CHECK(platform IN ('twitch', 'youtube', 'kick', 'niconico')),
             channel_id TEXT NOT NULL,
             channel_name TEXT NOT NULL,
             enabled BOOLEAN NOT NULL DEFAULT 1,
@@ -76,7 +77,11 @@ pub fn init_database(conn: &Connection) -> Result<(), duckdb::Error> {
             viewer_count INTEGER,
             twitch_user_id TEXT,
             channel_name TEXT,
-            FOREIGN KEY (stream_id) REFERENCES streams(id)
+            category TEXT,
+            title TEXT,
+            follower_count INTEGER,
+            FOREIGN KEY (stream_id) REFERENCES streams(id),
+            UNIQUE(stream_id, collected_at)
         )
         "#,
         [],
@@ -134,10 +139,91 @@ pub fn init_database(conn: &Connection) -> Result<(), duckdb::Error> {
     )?;
     eprintln!("[Schema] Step 4.1: sql_templates table created");
 
-    eprintln!("[Schema] Step 4.5: Running database migrations...");
-    // 既存テーブルにフィールドを追加（マイグレーション）
-    migrate_database_schema(conn)?;
-    eprintln!("[Schema] Step 4.5: Migrations completed");
+    eprintln!("[Schema] Step 4.2: Creating stream_stats_archive table...");
+    // stream_stats_archive テーブル: リテンションで削除した配信の集計値
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS stream_stats_archive (
+            stream_id BIGINT PRIMARY KEY,
+            channel_id BIGINT,
+            peak_viewers INTEGER,
+            avg_viewers DOUBLE,
+            minutes_watched BIGINT,
+            stats_rows BIGINT,
+            chat_messages BIGINT,
+            first_collected_at TIMESTAMP,
+            last_collected_at TIMESTAMP,
+            archived_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+        [],
+    )?;
+    eprintln!("[Schema] Step 4.2: stream_stats_archive table created");
+
+    eprintln!("[Schema] Step 4.3: Creating stream_stats_rollup table...");
+    // stream_stats_rollup テーブル: タイムライン表示用のダウンサンプリング集計（5分/15分粒度）
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS stream_stats_rollup (
+            stream_id BIGINT NOT NULL,
+            resolution_minutes INTEGER NOT NULL,
+            bucket_start TIMESTAMP NOT NULL,
+            avg_viewer_count DOUBLE,
+            max_viewer_count INTEGER,
+            chat_messages BIGINT,
+            category TEXT,
+            title TEXT,
+            follower_count INTEGER,
+            data_points INTEGER,
+            last_collected_at TIMESTAMP,
+            PRIMARY KEY (stream_id, resolution_minutes, bucket_start)
+        )
+        "#,
+        [],
+    )?;
+    eprintln!("[Schema] Step 4.3: stream_stats_rollup table created");
+
+    eprintln!("[Schema] Step 4.4: Creating channel_tags table...");
+    // channel_tags テーブル: チャンネルのタグ付け・グルーピング
+    // channels はマイグレーションで再作成されるため外部キーは張らない
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS channel_tags (
+            channel_id BIGINT NOT NULL,
+            tag TEXT NOT NULL,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (channel_id, tag)
+        )
+        "#,
+        [],
+    )?;
+    eprintln!("[Schema] Step 4.4: channel_tags table created");
+
+    eprintln!("[Schema] Step 4.5: Creating scheduled_streams table...");
+    // scheduled_streams テーブル: プラットフォームから取得した配信予定（時刻はUTC）
+    // channels はマイグレーションで再作成されるため外部キーは張らない
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS scheduled_streams (
+            channel_id BIGINT NOT NULL,
+            scheduled_start TIMESTAMP NOT NULL,
+            scheduled_end TIMESTAMP,
+            title TEXT,
+            category TEXT,
+            reminded BOOLEAN DEFAULT FALSE,
+            fetched_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (channel_id, scheduled_start)
+        )
+        "#,
+        [],
+    )?;
+    eprintln!("[Schema] Step 4.5: scheduled_streams table created");
+
+    eprintln!("[Schema] Step 4.6: Running database migrations...");
+    // バージョン付きマイグレーションを適用
+    run_migrations(conn)?;
+    backfill_chat_message_channel_ids(conn);
+    eprintln!("[Schema] Step 4.6: Migrations completed");
 
     eprintln!("[Schema] Step 5: Creating indexes...");
     // インデックス作成
@@ -147,11 +233,16 @@ pub fn init_database(conn: &Connection) -> Result<(), duckdb::Error> {
     )?;
     eprintln!("[Schema] Index 1 created");
 
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_streams_started_at ON streams(started_at)",
-        [],
+    // DuckDB はインデックスを含む列の UPDATE を DELETE + INSERT で処理するため、
+    // stream_stats / chat_messages から参照されている配信の started_at を更新すると FK 違反になる。
+    // started_at は配信開始時刻の補正や配信の統合で更新するので、インデックスを張らない（既存DBからは削除する）
+    conn.execute_batch(
+        r#"
+        DROP INDEX IF EXISTS idx_streams_started_at;
+        DROP INDEX IF EXISTS idx_streams_channel_started;
+        "#,
     )?;
-    eprintln!("[Schema] Index 2 created");
+    eprintln!("[Schema] Index 2: streams.started_at indexes dropped");
 
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_stream_stats_stream_id ON stream_stats(stream_id)",
@@ -224,13 +315,6 @@ pub fn init_database(conn: &Connection) -> Result<(), duckdb::Error> {
     )?;
     eprintln!("[Schema] Index 13: chat_messages(user_name, timestamp) created");
 
-    // streams テーブルの複合インデックス
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_streams_channel_started ON streams(channel_id, started_at)",
-        [],
-    )?;
-    eprintln!("[Schema] Index 14: streams(channel_id, started_at) created");
-
     // channels テーブルの最適化インデックス
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_channels_platform ON channels(platform)",
@@ -243,8 +327,508 @@ pub fn init_database(conn: &Connection) -> Result<(), duckdb::Error> {
 }
 
 /// データベーススキーマのマイグレーションを行う関数
-/// 既存のテーブルにフィールドを追加する
-fn migrate_database_schema(conn: &Connection) -> Result<(), duckdb::Error> {
+/// バージョン付きマイグレーション
+struct Migration {
+    version: i32,
+    description: &'static str,
+    apply: fn(&Connection) -> Result<(), duckdb::Error>,
+    /// schema_migrations 導入前のDBで、このマイグレーションが適用済みかをカラム状態から推定する
+    is_applied: fn(&Connection) -> Result<bool, duckdb::Error>,
+}
+
+/// 適用順に並べたマイグレーション一覧（version は連番で追加すること）
+/// channels.platform の CHECK 制約で許可する最新のプラットフォーム一覧
+///
+/// version 4 / 5 はどちらもこの一覧で作り直すため、version 4 を適用した時点で
+/// version 5 は適用済み扱いになり、テーブルの作り直しは1回で済む。
+const CHANNEL_PLATFORMS: &[&str] = &["twitch", "youtube", "kick", "niconico"];
+
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "legacy column additions",
+        apply: migrate_legacy_schema,
+        is_applied: is_legacy_schema_applied,
+    },
+    Migration {
+        version: 2,
+        description: "add chat_messages.bits",
+        apply: |conn| add_column_if_missing(conn, "chat_messages", "bits", "INTEGER"),
+        is_applied: |conn| column_exists(conn, "chat_messages", "bits"),
+    },
+    Migration {
+        version: 3,
+        description: "add sql_templates.params",
+        apply: |conn| add_column_if_missing(conn, "sql_templates", "params", "TEXT"),
+        is_applied: |conn| column_exists(conn, "sql_templates", "params"),
+    },
+    Migration {
+        version: 4,
+        description: "allow 'kick' in channels.platform",
+        apply: |conn| rebuild_channels_platform_check(conn, CHANNEL_PLATFORMS),
+        is_applied: |conn| channels_platform_allows(conn, "kick"),
+    },
+    Migration {
+        version: 5,
+        description: "allow 'niconico' in channels.platform",
+        apply: |conn| rebuild_channels_platform_check(conn, CHANNEL_PLATFORMS),
+        is_applied: |conn| channels_platform_allows(conn, "niconico"),
+    },
+    Migration {
+        version: 6,
+        description: "ensure stream_stats category/title/follower_count",
+        apply: migrate_stream_stats_metadata_columns,
+        is_applied: |conn| {
+            Ok(column_exists(conn, "stream_stats", "category")?
+                && column_exists(conn, "stream_stats", "title")?
+                && column_exists(conn, "stream_stats", "follower_count")?)
+        },
+    },
+    Migration {
+        version: 7,
+        description: "add channels.collect_chat/collect_stats",
+        apply: |conn| {
+            add_column_if_missing(conn, "channels", "collect_chat", "BOOLEAN DEFAULT TRUE")?;
+            add_column_if_missing(conn, "channels", "collect_stats", "BOOLEAN DEFAULT TRUE")
+        },
+        is_applied: |conn| {
+            Ok(column_exists(conn, "channels", "collect_chat")?
+                && column_exists(conn, "channels", "collect_stats")?)
+        },
+    },
+    Migration {
+        version: 8,
+        description: "deduplicate stream_stats and add UNIQUE(stream_id, collected_at)",
+        apply: rebuild_stream_stats_unique_key,
+        is_applied: stream_stats_has_unique_key,
+    },
+    Migration {
+        version: 9,
+        description: "add channels.alert_viewer_threshold",
+        apply: |conn| add_column_if_missing(conn, "channels", "alert_viewer_threshold", "INTEGER"),
+        is_applied: |conn| column_exists(conn, "channels", "alert_viewer_threshold"),
+    },
+    Migration {
+        version: 10,
+        description: "add streams.vod_url",
+        apply: |conn| add_column_if_missing(conn, "streams", "vod_url", "TEXT"),
+        is_applied: |conn| column_exists(conn, "streams", "vod_url"),
+    },
+    Migration {
+        version: 11,
+        description: "add channels.deleted_at",
+        apply: |conn| add_column_if_missing(conn, "channels", "deleted_at", "TIMESTAMP"),
+        is_applied: |conn| column_exists(conn, "channels", "deleted_at"),
+    },
+    Migration {
+        version: 12,
+        description: "normalize CURRENT_TIMESTAMP columns to UTC",
+        apply: normalize_current_timestamp_columns,
+        // データの補正のみでカラム状態からは判定できないため、未記録のDBには常に適用する
+        is_applied: |_| Ok(false),
+    },
+    Migration {
+        version: 13,
+        description: "add stream_stats.comment_count",
+        apply: |conn| add_column_if_missing(conn, "stream_stats", "comment_count", "BIGINT"),
+        is_applied: |conn| column_exists(conn, "stream_stats", "comment_count"),
+    },
+];
+
+/// CURRENT_TIMESTAMP を既定値・更新値に使う TIMESTAMP 列
+const CURRENT_TIMESTAMP_COLUMNS: &[(&str, &str)] = &[
+    ("channels", "created_at"),
+    ("channels", "updated_at"),
+    ("channels", "deleted_at"),
+    ("sql_templates", "created_at"),
+    ("sql_templates", "updated_at"),
+    ("stream_stats_archive", "archived_at"),
+    ("channel_tags", "created_at"),
+    ("scheduled_streams", "fetched_at"),
+];
+
+/// CURRENT_TIMESTAMP 由来の TIMESTAMP 列をUTCに補正する
+///
+/// ICU 拡張が読み込まれていると TimeZone の既定値がOSのタイムゾーンになり、
+/// CURRENT_TIMESTAMP（TIMESTAMPTZ）を TIMESTAMP 列に保存した値はローカル時刻になっていた。
+/// RFC3339 文字列から保存した列（started_at / collected_at など）は保存時にUTCへ変換されているため対象外。
+/// 接続を UTC に切り替える前（init_database 中）に実行されるので、セッションの TimeZone で解釈し直す。
+fn normalize_current_timestamp_columns(conn: &Connection) -> Result<(), duckdb::Error> {
+    // ICU 未読み込みの場合は TimeZone 設定が無く、常にUTCで保存されている
+    let Ok(time_zone) = conn.query_row("SELECT current_setting('TimeZone')", [], |row| {
+        row.get::<_, String>(0)
+    }) else {
+        return Ok(());
+    };
+    if matches!(time_zone.as_str(), "UTC" | "Etc/UTC" | "GMT" | "Etc/GMT") {
+        return Ok(());
+    }
+
+    eprintln!(
+        "[Migration] Converting CURRENT_TIMESTAMP columns from {} to UTC",
+        time_zone
+    );
+    for (table, column) in CURRENT_TIMESTAMP_COLUMNS {
+        if !column_exists(conn, table, column)? {
+            continue;
+        }
+        let updated = conn.execute(
+            &format!(
+                "UPDATE {table} SET {column} = timezone('UTC', CAST({column} AS TIMESTAMPTZ)) WHERE {column} IS NOT NULL"
+            ),
+            [],
+        )?;
+        if updated > 0 {
+            eprintln!(
+                "[Migration] Normalized {} rows of {}.{}",
+                updated, table, column
+            );
+        }
+    }
+    Ok(())
+}
+
+/// stream_stats の配信メタデータ列を追加する
+///
+/// version 1 の適用判定はこれらの列を見ていないため、version 1 適用済みと記録されたDBでも
+/// 欠けている場合がある。欠けていると follower_gain やタイトル変化検出が常に空になる。
+fn migrate_stream_stats_metadata_columns(conn: &Connection) -> Result<(), duckdb::Error> {
+    add_column_if_missing(conn, "stream_stats", "category", "TEXT")?;
+    add_column_if_missing(conn, "stream_stats", "title", "TEXT")?;
+    add_column_if_missing(conn, "stream_stats", "follower_count", "INTEGER")?;
+    Ok(())
+}
+
+/// テーブルにカラムが存在するか
+fn column_exists(conn: &Connection, table: &str, column: &str) -> Result<bool, duckdb::Error> {
+    let count: i64 = conn.query_row(
+        &format!(
+            "SELECT COUNT(*) FROM pragma_table_info('{}') WHERE name = ?",
+            table
+        ),
+        [column],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+/// カラムが存在しない場合のみ ALTER TABLE で追加する
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    column_type: &str,
+) -> Result<(), duckdb::Error> {
+    if !column_exists(conn, table, column)? {
+        eprintln!("[Migration] Adding {} column to {} table", column, table);
+        conn.execute(
+            &format!(
+                "ALTER TABLE {} ADD COLUMN {} {}",
+                table, column, column_type
+            ),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+/// schema_migrations を作成し、未適用のマイグレーションを順に適用する
+fn run_migrations(conn: &Connection) -> Result<(), duckdb::Error> {
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS schema_migrations (
+            version INTEGER PRIMARY KEY,
+            description TEXT,
+            applied_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+        [],
+    )?;
+
+    let recorded: i64 = conn.query_row("SELECT COUNT(*) FROM schema_migrations", [], |row| {
+        row.get(0)
+    })?;
+    if recorded == 0 {
+        // schema_migrations 導入前のDB: カラム状態から適用済みのバージョンを推定して記録する
+        for migration in MIGRATIONS {
+            if !(migration.is_applied)(conn)? {
+                break;
+            }
+            eprintln!(
+                "[Migration] Detected existing schema at version {} ({})",
+                migration.version, migration.description
+            );
+            record_migration(conn, migration)?;
+        }
+    }
+
+    let current_version: i32 = conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
+        [],
+        |row| row.get(0),
+    )?;
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current_version) {
+        eprintln!(
+            "[Migration] Applying version {}: {}",
+            migration.version, migration.description
+        );
+        (migration.apply)(conn)?;
+        record_migration(conn, migration)?;
+    }
+
+    Ok(())
+}
+
+fn record_migration(conn: &Connection, migration: &Migration) -> Result<(), duckdb::Error> {
+    conn.execute(
+        "INSERT OR IGNORE INTO schema_migrations (version, description) VALUES (?, ?)",
+        duckdb::params![migration.version, migration.description],
+    )?;
+    Ok(())
+}
+
+/// version 1 が適用済みか（legacy マイグレーションの最終状態になっているか）
+fn is_legacy_schema_applied(conn: &Connection) -> Result<bool, duckdb::Error> {
+    Ok(column_exists(conn, "stream_stats", "game_id")?
+        && !column_exists(conn, "stream_stats", "chat_rate_1min")?
+        && column_exists(conn, "chat_messages", "display_name")?
+        && column_exists(conn, "channels", "twitch_user_id")?)
+}
+
+/// channels.platform の CHECK 制約に指定プラットフォームが含まれているか
+fn channels_platform_allows(conn: &Connection, platform: &str) -> Result<bool, duckdb::Error> {
+    let count: i64 = conn.query_row(
+        r#"
+        SELECT COUNT(*) FROM duckdb_constraints()
+        WHERE table_name = 'channels'
+          AND constraint_type = 'CHECK'
+          AND constraint_text LIKE ?
+        "#,
+        [format!("%'{}'%", platform)],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+/// channels.platform の CHECK 制約を指定プラットフォーム一覧で作り直す
+///
+/// DuckDB は CHECK 制約の変更も、外部キーで参照されているテーブルの DROP もできないため、
+/// channels と参照元の streams / stream_stats をバックアップから作り直す。
+/// インデックスはこの後の Step 5 で再作成される。
+fn rebuild_channels_platform_check(
+    conn: &Connection,
+    platforms: &[&str],
+) -> Result<(), duckdb::Error> {
+    let mut already_allowed = true;
+    for platform in platforms {
+        already_allowed &= channels_platform_allows(conn, platform)?;
+    }
+    if already_allowed {
+        return Ok(());
+    }
+
+    let platform_list = platforms
+        .iter()
+        .map(|p| format!("'{}'", p))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    eprintln!(
+        "[Migration] Rebuilding channels/streams/stream_stats to allow platforms: {}",
+        platform_list
+    );
+    with_transaction(conn, |conn| {
+        conn.execute_batch(&format!(
+            r#"
+            CREATE TABLE channels_backup AS SELECT * FROM channels;
+            CREATE TABLE streams_backup AS SELECT * FROM streams;
+            CREATE TABLE stream_stats_backup AS SELECT * FROM stream_stats;
+
+            DROP TABLE stream_stats;
+            DROP TABLE streams;
+            DROP TABLE channels;
+
+            CREATE TABLE channels (
+                id BIGINT PRIMARY KEY DEFAULT nextval('channels_id_seq'),
+                platform TEXT NOT NULL CHECK(platform IN ({platform_list})),
+                channel_id TEXT NOT NULL,
+                channel_name TEXT NOT NULL,
+                enabled BOOLEAN NOT NULL DEFAULT 1,
+                poll_interval INTEGER NOT NULL DEFAULT 60,
+                created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                display_name TEXT DEFAULT '',
+                profile_image_url TEXT DEFAULT '',
+                follower_count INTEGER DEFAULT 0,
+                broadcaster_type TEXT DEFAULT '',
+                view_count INTEGER DEFAULT 0,
+                is_auto_discovered BOOLEAN DEFAULT FALSE,
+                discovered_at TEXT DEFAULT '',
+                current_viewer_count INTEGER,
+                current_category TEXT,
+                twitch_user_id BIGINT,
+                UNIQUE(platform, channel_id)
+            );
+            CREATE TABLE streams (
+                id BIGINT PRIMARY KEY DEFAULT nextval('streams_id_seq'),
+                channel_id BIGINT NOT NULL,
+                stream_id TEXT NOT NULL,
+                title TEXT,
+                category TEXT,
+                started_at TIMESTAMP NOT NULL,
+                ended_at TIMESTAMP,
+                thumbnail_url TEXT,
+                FOREIGN KEY (channel_id) REFERENCES channels(id),
+                UNIQUE(channel_id, stream_id)
+            );
+            CREATE TABLE stream_stats (
+                id BIGINT PRIMARY KEY DEFAULT nextval('stream_stats_id_seq'),
+                stream_id BIGINT,
+                collected_at TIMESTAMP NOT NULL,
+                viewer_count INTEGER,
+                twitch_user_id TEXT,
+                channel_name TEXT,
+                category TEXT,
+                title TEXT,
+                follower_count INTEGER,
+                game_id TEXT,
+                FOREIGN KEY (stream_id) REFERENCES streams(id)
+            );
+
+            INSERT INTO channels BY NAME SELECT * FROM channels_backup;
+            INSERT INTO streams BY NAME SELECT * FROM streams_backup;
+            INSERT INTO stream_stats BY NAME SELECT * FROM stream_stats_backup;
+
+            DROP TABLE stream_stats_backup;
+            DROP TABLE streams_backup;
+            DROP TABLE channels_backup;
+
+            CREATE INDEX IF NOT EXISTS idx_channels_twitch_user_id ON channels(twitch_user_id);
+            CREATE INDEX IF NOT EXISTS idx_stream_stats_game_id ON stream_stats(game_id);
+            "#
+        ))
+    })?;
+    eprintln!("[Migration] channels.platform CHECK constraint rebuilt");
+    Ok(())
+}
+
+/// stream_stats に (stream_id, collected_at) の UNIQUE 制約があるか
+fn stream_stats_has_unique_key(conn: &Connection) -> Result<bool, duckdb::Error> {
+    let count: i64 = conn.query_row(
+        r#"
+        SELECT COUNT(*) FROM duckdb_constraints()
+        WHERE table_name = 'stream_stats'
+          AND constraint_type = 'UNIQUE'
+          AND list_sort(constraint_column_names) = ['collected_at', 'stream_id']
+        "#,
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+/// stream_stats の重複行を除去し、UNIQUE(stream_id, collected_at) 付きで作り直す
+///
+/// 二重起動やポーリングの重なりで同じ配信・同じ収集時刻の行が重複して入ることがあるため、
+/// 各組の最小 id の行だけを残す。DuckDB は既存テーブルに制約を追加できないため作り直す。
+/// stream_id が NULL の行（自動発見チャンネルの統計）は UNIQUE の対象外なのですべて残す。
+/// インデックスはこの後の Step 5 で再作成される。
+fn rebuild_stream_stats_unique_key(conn: &Connection) -> Result<(), duckdb::Error> {
+    if stream_stats_has_unique_key(conn)? {
+        return Ok(());
+    }
+
+    let duplicates: i64 = conn.query_row(
+        r#"
+        SELECT COUNT(*) - COUNT(DISTINCT (stream_id, collected_at))
+        FROM stream_stats
+        WHERE stream_id IS NOT NULL
+        "#,
+        [],
+        |row| row.get(0),
+    )?;
+    eprintln!(
+        "[Migration] Rebuilding stream_stats with UNIQUE(stream_id, collected_at), removing {} duplicate rows",
+        duplicates
+    );
+
+    with_transaction(conn, |conn| {
+        conn.execute_batch(
+            r#"
+            CREATE TABLE stream_stats_backup AS
+                SELECT * FROM stream_stats
+                WHERE stream_id IS NULL
+                   OR id IN (
+                       SELECT MIN(id) FROM stream_stats
+                       WHERE stream_id IS NOT NULL
+                       GROUP BY stream_id, collected_at
+                   );
+
+            DROP TABLE stream_stats;
+
+            CREATE TABLE stream_stats (
+                id BIGINT PRIMARY KEY DEFAULT nextval('stream_stats_id_seq'),
+                stream_id BIGINT,
+                collected_at TIMESTAMP NOT NULL,
+                viewer_count INTEGER,
+                twitch_user_id TEXT,
+                channel_name TEXT,
+                category TEXT,
+                title TEXT,
+                follower_count INTEGER,
+                game_id TEXT,
+                FOREIGN KEY (stream_id) REFERENCES streams(id),
+                UNIQUE(stream_id, collected_at)
+            );
+
+            INSERT INTO stream_stats BY NAME SELECT * FROM stream_stats_backup;
+            DROP TABLE stream_stats_backup;
+
+            CREATE INDEX IF NOT EXISTS idx_stream_stats_game_id ON stream_stats(game_id);
+            "#,
+        )
+    })?;
+    eprintln!("[Migration] stream_stats UNIQUE(stream_id, collected_at) added");
+    Ok(())
+}
+
+/// 配信に紐づく chat_messages の channel_id を streams から補完する（起動ごとに実行）
+fn backfill_chat_message_channel_ids(conn: &Connection) {
+    // 既存のchat_messagesのchannel_idをstreams経由で更新
+    eprintln!("[Maintenance] Updating chat_messages.channel_id from streams table");
+    let update_result = conn.execute(
+        r#"
+        UPDATE chat_messages cm
+        SET channel_id = (
+            SELECT s.channel_id 
+            FROM streams s 
+            WHERE s.id = cm.stream_id
+        )
+        WHERE cm.channel_id IS NULL 
+            AND cm.stream_id IS NOT NULL
+        "#,
+        [],
+    );
+
+    match update_result {
+        Ok(updated_rows) => {
+            eprintln!(
+                "[Maintenance] Updated {} chat_messages with channel_id from streams",
+                updated_rows
+            );
+        }
+        Err(e) => {
+            eprintln!(
+                "[Maintenance] Warning: Failed to update chat_messages.channel_id: {}",
+                e
+            );
+        }
+    }
+}
+
+/// schema_migrations 導入前に pragma_table_info ベースで行っていたマイグレーション（version 1）
+fn migrate_legacy_schema(conn: &Connection) -> Result<(), duckdb::Error> {
     // streamsテーブルにthumbnail_urlフィールドを追加
     let mut streams_has_thumbnail = conn.prepare(
         "SELECT COUNT(*) FROM pragma_table_info('streams') WHERE name = 'thumbnail_url'",
@@ -648,37 +1232,6 @@ fn migrate_database_schema(conn: &Connection) -> Result<(), duckdb::Error> {
         eprintln!("[Migration] display_name column added successfully");
     }
 
-    // 既存のchat_messagesのchannel_idをstreams経由で更新
-    eprintln!("[Migration] Updating chat_messages.channel_id from streams table");
-    let update_result = conn.execute(
-        r#"
-        UPDATE chat_messages cm
-        SET channel_id = (
-            SELECT s.channel_id 
-            FROM streams s 
-            WHERE s.id = cm.stream_id
-        )
-        WHERE cm.channel_id IS NULL 
-            AND cm.stream_id IS NOT NULL
-        "#,
-        [],
-    );
-
-    match update_result {
-        Ok(updated_rows) => {
-            eprintln!(
-                "[Migration] Updated {} chat_messages with channel_id from streams",
-                updated_rows
-            );
-        }
-        Err(e) => {
-            eprintln!(
-                "[Migration] Warning: Failed to update chat_messages.channel_id: {}",
-                e
-            );
-        }
-    }
-
     // chat_rate_1min列を削除（存在する場合）
     let mut stream_stats_has_chat_rate = conn.prepare(
         "SELECT COUNT(*) FROM pragma_table_info('stream_stats') WHERE name = 'chat_rate_1min'",
@@ -743,3 +1296,141 @@ fn migrate_database_schema(conn: &Connection) -> Result<(), duckdb::Error> {
     eprintln!("[Migration] All migrations completed successfully");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// kick 対応前（version 4 適用前）の channels / streams / stream_stats
+    fn create_baseline_schema(conn: &Connection) {
+        conn.execute_batch(
+            r#"
+            CREATE SEQUENCE channels_id_seq START 1;
+            CREATE SEQUENCE streams_id_seq START 1;
+            CREATE SEQUENCE stream_stats_id_seq START 1;
+            CREATE TABLE channels (
+                id BIGINT PRIMARY KEY DEFAULT nextval('channels_id_seq'),
+                platform TEXT NOT NULL CHECK(platform IN ('twitch', 'youtube')),
+                channel_id TEXT NOT NULL,
+                channel_name TEXT NOT NULL,
+                enabled BOOLEAN NOT NULL DEFAULT 1,
+                poll_interval INTEGER NOT NULL DEFAULT 60,
+                created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                UNIQUE(platform, channel_id)
+            );
+            CREATE TABLE streams (
+                id BIGINT PRIMARY KEY DEFAULT nextval('streams_id_seq'),
+                channel_id BIGINT NOT NULL,
+                stream_id TEXT NOT NULL,
+                title TEXT,
+                category TEXT,
+                started_at TIMESTAMP NOT NULL,
+                ended_at TIMESTAMP,
+                FOREIGN KEY (channel_id) REFERENCES channels(id),
+                UNIQUE(channel_id, stream_id)
+            );
+            CREATE TABLE stream_stats (
+                id BIGINT PRIMARY KEY DEFAULT nextval('stream_stats_id_seq'),
+                stream_id BIGINT,
+                collected_at TIMESTAMP NOT NULL,
+                viewer_count INTEGER,
+                FOREIGN KEY (stream_id) REFERENCES streams(id)
+            );
+
+            INSERT INTO channels (platform, channel_id, channel_name, poll_interval)
+                VALUES ('twitch', 'streamer_a', 'Streamer A', 30);
+            INSERT INTO streams (channel_id, stream_id, title, started_at)
+                VALUES (1, 'live-1', 'First stream', '2024-01-01 10:00:00');
+            INSERT INTO stream_stats (stream_id, collected_at, viewer_count)
+                VALUES (1, '2024-01-01 10:01:00', 120), (1, '2024-01-01 10:02:00', 150);
+            "#,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(
+        target_os = "windows",
+        ignore = "Database tests are unstable on Windows local environment"
+    )]
+    fn test_allow_kick_migration_also_satisfies_niconico_migration() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_baseline_schema(&conn);
+
+        let kick = MIGRATIONS.iter().find(|m| m.version == 4).unwrap();
+        let niconico = MIGRATIONS.iter().find(|m| m.version == 5).unwrap();
+        assert!(!(niconico.is_applied)(&conn).unwrap());
+
+        (kick.apply)(&conn).unwrap();
+
+        // version 4 が最終的な CHECK 制約を作るため、version 5 で再度作り直す必要はない
+        assert!((niconico.is_applied)(&conn).unwrap());
+    }
+
+    #[test]
+    #[cfg_attr(
+        target_os = "windows",
+        ignore = "Database tests are unstable on Windows local environment"
+    )]
+    fn test_allow_kick_migration_preserves_baseline_data() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_baseline_schema(&conn);
+        assert!(!channels_platform_allows(&conn, "kick").unwrap());
+
+        init_database(&conn).unwrap();
+
+        assert!(channels_platform_allows(&conn, "kick").unwrap());
+        assert!(channels_platform_allows(&conn, "niconico").unwrap());
+        let max_version: i32 = conn
+            .query_row("SELECT MAX(version) FROM schema_migrations", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(max_version, MIGRATIONS.last().unwrap().version);
+
+        // 作り直したテーブルに既存データが引き継がれている
+        let (channel_name, poll_interval, display_name): (String, i32, String) = conn
+            .query_row(
+                "SELECT channel_name, poll_interval, display_name FROM channels WHERE id = 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(channel_name, "Streamer A");
+        assert_eq!(poll_interval, 30);
+        assert_eq!(display_name, "Streamer A");
+        let title: String = conn
+            .query_row(
+                "SELECT title FROM streams WHERE channel_id = 1 AND stream_id = 'live-1'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(title, "First stream");
+        let (stats_rows, total_viewers): (i64, i64) = conn
+            .query_row(
+                "SELECT COUNT(*), SUM(viewer_count) FROM stream_stats WHERE stream_id = 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((stats_rows, total_viewers), (2, 270));
+
+        // 外部キーとシーケンスが引き続き機能し、kick チャンネルを登録できる
+        let kick_id: i64 = conn
+            .query_row(
+                "INSERT INTO channels (platform, channel_id, channel_name) VALUES ('kick', 'kicker', 'Kicker') RETURNING id",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(kick_id > 1);
+        assert!(conn
+            .execute(
+                "INSERT INTO streams (channel_id, stream_id, started_at) VALUES (999, 'orphan', '2024-01-02 00:00:00')",
+                [],
+            )
+            .is_err());
+    }
+}
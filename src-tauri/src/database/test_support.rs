@@ -0,0 +1,37 @@
+/// テスト用のDB初期化ヘルパー
+///
+/// 本番と同じ `schema::init_database` でスキーマを作成し、テストごとの手書きテーブル定義が
+/// 実際のスキーマ（列・外部キー・UNIQUE 制約）と食い違わないようにします。
+use crate::database::schema;
+use duckdb::Connection;
+
+/// 本番と同じスキーマで初期化したインメモリDB
+pub fn init_test_db() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    schema::init_database(&conn).unwrap();
+    conn
+}
+
+/// チャンネルを追加する（channel_id / channel_name は `name`）
+pub fn insert_channel(conn: &Connection, id: i64, platform: &str, name: &str) {
+    conn.execute(
+        "INSERT INTO channels (id, platform, channel_id, channel_name) VALUES (?, ?, ?, ?)",
+        duckdb::params![id, platform, name, name],
+    )
+    .unwrap();
+}
+
+/// 配信を追加する（streams.stream_id は `s{id}`）
+pub fn insert_stream(
+    conn: &Connection,
+    id: i64,
+    channel_id: i64,
+    started_at: &str,
+    ended_at: Option<&str>,
+) {
+    conn.execute(
+        "INSERT INTO streams (id, channel_id, stream_id, started_at, ended_at) VALUES (?, ?, ?, ?, ?)",
+        duckdb::params![id, channel_id, format!("s{}", id), started_at, ended_at],
+    )
+    .unwrap();
+}
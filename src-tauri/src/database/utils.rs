@@ -1,71 +1,10 @@
 use crate::database::models::ChatMessage;
-use duckdb::{Connection, Result as DuckResult, Row};
-
-/// 動的パラメータ数に対応したマクロ
-/// DuckDBのパラメータを処理し、適切な数のパラメータで実行する
-macro_rules! dispatch_params {
-    ($method:expr, $params:expr) => {{
-        let params_slice = $params;
-        match params_slice.len() {
-            0 => $method([]),
-            1 => $method([params_slice[0].as_str()]),
-            2 => $method([params_slice[0].as_str(), params_slice[1].as_str()]),
-            3 => $method([
-                params_slice[0].as_str(),
-                params_slice[1].as_str(),
-                params_slice[2].as_str(),
-            ]),
-            4 => $method([
-                params_slice[0].as_str(),
-                params_slice[1].as_str(),
-                params_slice[2].as_str(),
-                params_slice[3].as_str(),
-            ]),
-            5 => $method([
-                params_slice[0].as_str(),
-                params_slice[1].as_str(),
-                params_slice[2].as_str(),
-                params_slice[3].as_str(),
-                params_slice[4].as_str(),
-            ]),
-            6 => $method([
-                params_slice[0].as_str(),
-                params_slice[1].as_str(),
-                params_slice[2].as_str(),
-                params_slice[3].as_str(),
-                params_slice[4].as_str(),
-                params_slice[5].as_str(),
-            ]),
-            7 => $method([
-                params_slice[0].as_str(),
-                params_slice[1].as_str(),
-                params_slice[2].as_str(),
-                params_slice[3].as_str(),
-                params_slice[4].as_str(),
-                params_slice[5].as_str(),
-                params_slice[6].as_str(),
-            ]),
-            8 => $method([
-                params_slice[0].as_str(),
-                params_slice[1].as_str(),
-                params_slice[2].as_str(),
-                params_slice[3].as_str(),
-                params_slice[4].as_str(),
-                params_slice[5].as_str(),
-                params_slice[6].as_str(),
-                params_slice[7].as_str(),
-            ]),
-            _ => Err(duckdb::Error::InvalidParameterName(
-                "Too many parameters (max 8 supported)".to_string(),
-            )),
-        }
-    }};
-}
+use duckdb::{params_from_iter, Connection, Result as DuckResult, Row};
 
 /// DuckDBの動的パラメータを処理するヘルパー関数
-/// パラメータが0-8個の場合にのみサポート
+/// パラメータはすべて文字列としてバインドされる（個数の上限なし）
 pub fn execute_with_params(conn: &Connection, sql: &str, params: &[String]) -> DuckResult<usize> {
-    dispatch_params!(|p| conn.execute(sql, p), params)
+    conn.execute(sql, params_from_iter(params.iter().map(String::as_str)))
 }
 
 pub fn query_map_with_params<'stmt, T, F>(
@@ -76,7 +15,13 @@ pub fn query_map_with_params<'stmt, T, F>(
 where
     F: FnMut(&duckdb::Row) -> DuckResult<T>,
 {
-    dispatch_params!(|p| stmt.query_map(p, f), params)
+    stmt.query_map(params_from_iter(params.iter().map(String::as_str)), f)
+}
+
+/// 文字列をSQLの文字列リテラルとしてエスケープする
+/// COPY / CREATE SECRET などパラメータバインドが使えない文でのみ使用すること
+pub fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
 }
 
 /// RowからChatMessageを作成するヘルパー関数
@@ -96,6 +41,7 @@ pub fn row_to_chat_message(row: &Row) -> DuckResult<ChatMessage> {
     // 9: message_type
     // 10: badges (CAST(... AS VARCHAR))
     // 11: badge_info
+    // 12: bits（SELECTに含まれない場合は None）
     let badges: Option<Vec<String>> = match row.get::<_, Option<String>>(10)? {
         None => None,
         Some(badges_str) if badges_str.is_empty() => None,
@@ -137,6 +83,7 @@ pub fn row_to_chat_message(row: &Row) -> DuckResult<ChatMessage> {
         message_type: row.get(9)?,
         badges,
         badge_info: row.get::<_, Option<String>>(11).ok().flatten(),
+        bits: row.get::<_, Option<i64>>(12).ok().flatten(),
     })
 }
 
@@ -178,3 +125,37 @@ pub fn query_chat_messages(
     let rows = query_map_with_params(&mut stmt, params, row_to_chat_message)?;
     rows.collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_execute_and_query_with_many_params() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("CREATE TABLE t (v INTEGER)", []).unwrap();
+
+        let values: Vec<String> = (1..=12).map(|i| i.to_string()).collect();
+        let placeholders = vec!["(?)"; values.len()].join(", ");
+        let inserted = execute_with_params(
+            &conn,
+            &format!("INSERT INTO t VALUES {}", placeholders),
+            &values,
+        )
+        .unwrap();
+        assert_eq!(inserted, 12);
+
+        let in_list = vec!["?"; values.len()].join(", ");
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT v FROM t WHERE v IN ({}) ORDER BY v",
+                in_list
+            ))
+            .unwrap();
+        let rows: Vec<i32> = query_map_with_params(&mut stmt, &values, |row| row.get(0))
+            .unwrap()
+            .collect::<DuckResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(rows, (1..=12).collect::<Vec<_>>());
+    }
+}
@@ -1,5 +1,21 @@
+use crate::constants::database as db_constants;
 use crate::database::models::{ChatMessage, Stream, StreamStats};
+use crate::database::repositories::base::with_transaction;
+use crate::database::utils;
+use chrono::DateTime;
 use duckdb::{Connection, OptionalExt};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// 二重起動やポーリングの重なりで同じ (stream_id, collected_at) を挿入しても
+/// UNIQUE 制約違反にせず、先に入った行を残す
+const INSERT_STREAM_STATS_SQL: &str = "INSERT INTO stream_stats (stream_id, collected_at, viewer_count, category, title, follower_count, twitch_user_id, channel_name, game_id, comment_count)
+     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+     ON CONFLICT DO NOTHING";
+
+/// 同一ユーザーの同一メッセージを重複とみなす時間幅（秒）。0の場合は重複除去しない
+static CHAT_DEDUPE_WINDOW_SECS: AtomicU32 =
+    AtomicU32::new(db_constants::DEFAULT_CHAT_DEDUPE_WINDOW_SECS);
 
 pub struct DatabaseWriter;
 
@@ -10,43 +26,19 @@ impl DatabaseWriter {
         stream: &Stream,
     ) -> Result<i64, duckdb::Error> {
         // 外部キー制約の問題を回避するため、SELECTでチェックしてからINSERT/UPDATEを実行
-        let ended_at_value = stream.ended_at.as_deref();
-
-        // まず既存レコードを検索
-        let existing_id: Option<i64> = conn
-            .query_row(
-                "SELECT id FROM streams WHERE channel_id = ? AND stream_id = ?",
-                duckdb::params![channel_id, &stream.stream_id],
-                |row| row.get(0),
-            )
-            .optional()?;
-
-        match existing_id {
+        match Self::find_stream_id(conn, channel_id, &stream.stream_id)? {
             Some(id) => {
                 // 既存レコードがあればUPDATE
-                conn.execute(
-                    r#"
-                    UPDATE streams 
-                    SET title = ?,
-                        category = ?,
-                        ended_at = ?
-                    WHERE id = ?
-                    "#,
-                    duckdb::params![
-                        stream.title.as_deref().unwrap_or(""),
-                        stream.category.as_deref().unwrap_or(""),
-                        ended_at_value,
-                        id,
-                    ],
-                )?;
+                Self::update_stream(conn, id, stream)?;
                 Ok(id)
             }
             None => {
                 // 新規レコードならINSERT
-                let id: i64 = conn.query_row(
+                let ended_at_value = stream.ended_at.as_deref();
+                let inserted: Result<i64, duckdb::Error> = conn.query_row(
                     r#"
-                    INSERT INTO streams (channel_id, stream_id, title, category, started_at, ended_at) 
-                    VALUES (?, ?, ?, ?, ?, ?)
+                    INSERT INTO streams (channel_id, stream_id, title, category, thumbnail_url, started_at, ended_at) 
+                    VALUES (?, ?, ?, ?, ?, ?, ?)
                     RETURNING id
                     "#,
                     duckdb::params![
@@ -54,44 +46,289 @@ impl DatabaseWriter {
                         &stream.stream_id,
                         stream.title.as_deref().unwrap_or(""),
                         stream.category.as_deref().unwrap_or(""),
+                        stream.thumbnail_url.as_deref(),
                         &stream.started_at,
                         ended_at_value,
                     ],
                     |row| row.get(0)
-                )?;
-                Ok(id)
+                );
+
+                match inserted {
+                    Ok(id) => Ok(id),
+                    Err(e) => {
+                        // 同じ配信IDが並行して挿入された場合は UNIQUE(channel_id, stream_id) 違反になるため、
+                        // 既存レコードを引き直して UPDATE に切り替える
+                        match Self::find_stream_id(conn, channel_id, &stream.stream_id)? {
+                            Some(id) => {
+                                Self::update_stream(conn, id, stream)?;
+                                Ok(id)
+                            }
+                            None => Err(e),
+                        }
+                    }
+                }
             }
         }
     }
 
-    pub fn insert_stream_stats(
+    /// プラットフォーム固有の配信ID（Twitch stream ID / YouTube video ID）から streams.id を取得
+    pub fn find_stream_id(
         conn: &Connection,
-        stats: &StreamStats,
-    ) -> Result<(), duckdb::Error> {
+        channel_id: i64,
+        platform_stream_id: &str,
+    ) -> Result<Option<i64>, duckdb::Error> {
+        conn.query_row(
+            "SELECT id FROM streams WHERE channel_id = ? AND stream_id = ?",
+            duckdb::params![channel_id, platform_stream_id],
+            |row| row.get(0),
+        )
+        .optional()
+    }
+
+    fn update_stream(conn: &Connection, id: i64, stream: &Stream) -> Result<(), duckdb::Error> {
+        // サムネイルURLが取得できなかったポーリングで既存の値を消さないよう COALESCE する。
+        // started_at はプラットフォームが返す実際の配信開始時刻（Twitch Helix の started_at 等）で補正し、
+        // 配信途中から検知した場合でも正しい配信時間になるようにする。
+        // 開始時刻を取得できないプラットフォームは現在時刻で代用するため、早まる方向にのみ更新する
         conn.execute(
-            "INSERT INTO stream_stats (stream_id, collected_at, viewer_count, category, title, follower_count, twitch_user_id, channel_name, game_id)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
-            [
-                &stats.stream_id.to_string(),
-                &stats.collected_at,
-                &stats
-                    .viewer_count
-                    .map(|v| v.to_string())
-                    .unwrap_or_default(),
-                stats.category.as_deref().unwrap_or(""),
-                stats.title.as_deref().unwrap_or(""),
-                &stats
-                    .follower_count
-                    .map(|v| v.to_string())
-                    .unwrap_or_default(),
-                stats.twitch_user_id.as_deref().unwrap_or(""),
-                stats.channel_name.as_deref().unwrap_or(""),
-                stats.game_id.as_deref().unwrap_or(""),
+            r#"
+            UPDATE streams 
+            SET title = ?,
+                category = ?,
+                thumbnail_url = COALESCE(?, thumbnail_url),
+                started_at = COALESCE(LEAST(started_at, TRY_CAST(NULLIF(?, '') AS TIMESTAMP)), started_at),
+                ended_at = ?
+            WHERE id = ?
+            "#,
+            duckdb::params![
+                stream.title.as_deref().unwrap_or(""),
+                stream.category.as_deref().unwrap_or(""),
+                stream.thumbnail_url.as_deref(),
+                &stream.started_at,
+                stream.ended_at.as_deref(),
+                id,
             ],
         )?;
         Ok(())
     }
 
+    /// 配信のアーカイブ（VOD）URLを保存する
+    pub fn set_vod_url(
+        conn: &Connection,
+        stream_db_id: i64,
+        vod_url: &str,
+    ) -> Result<usize, duckdb::Error> {
+        conn.execute(
+            "UPDATE streams SET vod_url = ? WHERE id = ?",
+            duckdb::params![vod_url, stream_db_id],
+        )
+    }
+
+    /// チャンネルの進行中（ended_at IS NULL）の配信一覧を取得
+    /// 戻り値: (streams.id, プラットフォーム固有の配信ID, タイトル)
+    pub fn find_open_streams(
+        conn: &Connection,
+        channel_id: i64,
+    ) -> Result<Vec<(i64, String, Option<String>)>, duckdb::Error> {
+        let mut stmt = conn.prepare(
+            "SELECT id, stream_id, NULLIF(title, '') FROM streams WHERE channel_id = ? AND ended_at IS NULL",
+        )?;
+        let rows = stmt.query_map(duckdb::params![channel_id], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?;
+        rows.collect()
+    }
+
+    /// チャンネルの進行中（ended_at IS NULL）の配信を終了済みにする
+    ///
+    /// ended_at には最後に収集した stream_stats の時刻を使用し、統計が無い場合は `ended_at` を使う。
+    /// `exclude_stream_db_id` を指定した場合、その配信は対象外とする（ライブ中の配信を除外するため）。
+    /// 戻り値: 更新した配信数
+    pub fn close_open_streams(
+        conn: &Connection,
+        channel_id: i64,
+        exclude_stream_db_id: Option<i64>,
+        ended_at: &str,
+    ) -> Result<usize, duckdb::Error> {
+        let mut sql = String::from(
+            r#"
+            UPDATE streams
+            SET ended_at = COALESCE(
+                (SELECT MAX(ss.collected_at) FROM stream_stats ss WHERE ss.stream_id = streams.id),
+                CAST(? AS TIMESTAMP)
+            )
+            WHERE channel_id = ?
+              AND ended_at IS NULL
+            "#,
+        );
+
+        match exclude_stream_db_id {
+            Some(exclude_id) => {
+                sql.push_str(" AND id != ?");
+                conn.execute(&sql, duckdb::params![ended_at, channel_id, exclude_id])
+            }
+            None => conn.execute(&sql, duckdb::params![ended_at, channel_id]),
+        }
+    }
+
+    /// アプリのクラッシュ等で ended_at IS NULL のまま残った配信を終了済みにする
+    ///
+    /// 最後の stream_stats（統計が無い場合は配信開始時刻）が `now` から
+    /// チャンネルのポーリング間隔 × `poll_interval_multiplier` 以上前の配信を対象とし、
+    /// ended_at には最後に収集した stream_stats の時刻を使用する。
+    /// 実際には配信が続いていた場合も、次回のポーリングで同じ配信IDが見つかれば再び進行中に戻る。
+    /// 戻り値: 終了済みにした配信数
+    pub fn close_orphan_streams(
+        conn: &Connection,
+        now: &str,
+        poll_interval_multiplier: i64,
+    ) -> Result<usize, duckdb::Error> {
+        conn.execute(
+            r#"
+            UPDATE streams
+            SET ended_at = orphans.last_seen_at
+            FROM (
+                SELECT s.id, COALESCE(MAX(ss.collected_at), s.started_at) AS last_seen_at
+                FROM streams s
+                INNER JOIN channels c ON c.id = s.channel_id
+                LEFT JOIN stream_stats ss ON ss.stream_id = s.id
+                WHERE s.ended_at IS NULL
+                GROUP BY s.id, s.started_at, c.poll_interval
+                HAVING COALESCE(MAX(ss.collected_at), s.started_at)
+                    < CAST(? AS TIMESTAMP) - to_seconds(c.poll_interval * ?)
+            ) AS orphans
+            WHERE streams.id = orphans.id
+            "#,
+            duckdb::params![now, poll_interval_multiplier],
+        )
+    }
+
+    pub fn insert_stream_stats(
+        conn: &Connection,
+        stats: &StreamStats,
+    ) -> Result<(), duckdb::Error> {
+        let mut stmt = conn.prepare_cached(INSERT_STREAM_STATS_SQL)?;
+        Self::execute_stream_stats(&mut stmt, stats)
+    }
+
+    /// 複数の stream_stats を1トランザクションでまとめて挿入する
+    ///
+    /// 多数チャンネルを同時にポーリングした際、1件ずつの auto-commit による
+    /// 書き込み競合とコミット回数を減らすために使用する。
+    /// Appender API は全列の値を要求し、シーケンスの DEFAULT で採番される stream_stats.id と
+    /// 両立しないため使わず、準備済みステートメントを行ごとに再利用する。
+    pub fn insert_stream_stats_batch(
+        conn: &Connection,
+        stats: &[StreamStats],
+    ) -> Result<(), duckdb::Error> {
+        if stats.is_empty() {
+            return Ok(());
+        }
+
+        with_transaction(conn, |conn| {
+            let mut stmt = conn.prepare_cached(INSERT_STREAM_STATS_SQL)?;
+            for row in stats {
+                Self::execute_stream_stats(&mut stmt, row)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn execute_stream_stats(
+        stmt: &mut duckdb::CachedStatement<'_>,
+        stats: &StreamStats,
+    ) -> Result<(), duckdb::Error> {
+        // 数値列は未取得（None）を NULL として保存する。空文字を渡すと INTEGER への変換に失敗する
+        stmt.execute(duckdb::params![
+            stats.stream_id,
+            &stats.collected_at,
+            stats.viewer_count,
+            stats.category.as_deref().unwrap_or(""),
+            stats.title.as_deref().unwrap_or(""),
+            stats.follower_count,
+            stats.twitch_user_id.as_deref().unwrap_or(""),
+            stats.channel_name.as_deref().unwrap_or(""),
+            stats.game_id.as_deref().unwrap_or(""),
+            stats.comment_count,
+        ])?;
+        Ok(())
+    }
+
+    /// チャットの重複除去に使う時間幅（秒）を設定する（DatabaseSettings.chat_dedupe_window_secs）
+    pub fn set_chat_dedupe_window_secs(secs: u32) {
+        CHAT_DEDUPE_WINDOW_SECS.store(secs, Ordering::Relaxed);
+    }
+
+    /// 重複とみなすメッセージを除いたチャットを返す
+    ///
+    /// 同じチャンネルで同一 user_name・同一本文のメッセージが `window_secs` 秒以内に
+    /// 保存済み（またはバッチ内で先行）の場合は重複とみなす。時刻を解釈できないメッセージは常に残す。
+    fn dedupe_chat_messages<'a>(
+        conn: &Connection,
+        messages: &'a [ChatMessage],
+        window_secs: u32,
+    ) -> Result<Vec<&'a ChatMessage>, duckdb::Error> {
+        let timestamp_ms = |msg: &ChatMessage| {
+            DateTime::parse_from_rfc3339(&msg.timestamp)
+                .ok()
+                .map(|dt| dt.timestamp_millis())
+        };
+        let window_ms = i64::from(window_secs) * 1000;
+
+        // (channel_id, user_name, message) ごとの直近の時刻（epoch ミリ秒）
+        let mut last_seen: HashMap<(i64, String, String), i64> = HashMap::new();
+        let channel_ids: Vec<i64> = {
+            let mut ids: Vec<i64> = messages.iter().filter_map(|m| m.channel_id).collect();
+            ids.sort_unstable();
+            ids.dedup();
+            ids
+        };
+        // timestamp 列をそのまま比較し、DuckDB が行グループの min/max で範囲外のブロックを読み飛ばせるようにする
+        let since = messages
+            .iter()
+            .filter_map(timestamp_ms)
+            .min()
+            .and_then(|oldest_ms| DateTime::from_timestamp_millis(oldest_ms - window_ms));
+        if let Some(since) = since.filter(|_| !channel_ids.is_empty()) {
+            let placeholders = vec!["?"; channel_ids.len()].join(", ");
+            let sql = format!(
+                "SELECT channel_id, user_name, message, MAX(epoch_ms(timestamp))
+                 FROM chat_messages
+                 WHERE channel_id IN ({}) AND timestamp >= CAST(? AS TIMESTAMP)
+                 GROUP BY channel_id, user_name, message",
+                placeholders
+            );
+            let mut params: Vec<String> = channel_ids.iter().map(|id| id.to_string()).collect();
+            params.push(since.format("%Y-%m-%d %H:%M:%S%.3f").to_string());
+
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = utils::query_map_with_params(&mut stmt, &params, |row| {
+                Ok(((row.get(0)?, row.get(1)?, row.get(2)?), row.get(3)?))
+            })?;
+            for row in rows {
+                let (key, ms) = row?;
+                last_seen.insert(key, ms);
+            }
+        }
+
+        Ok(messages
+            .iter()
+            .filter(|msg| {
+                let (Some(channel_id), Some(ms)) = (msg.channel_id, timestamp_ms(msg)) else {
+                    return true;
+                };
+                let key = (channel_id, msg.user_name.clone(), msg.message.clone());
+                let duplicate = last_seen
+                    .get(&key)
+                    .is_some_and(|&prev| (ms - prev).abs() <= window_ms);
+                if !duplicate {
+                    last_seen.insert(key, ms);
+                }
+                !duplicate
+            })
+            .collect())
+    }
+
     pub fn insert_chat_messages_batch(
         conn: &Connection,
         messages: &[ChatMessage],
@@ -100,6 +337,17 @@ impl DatabaseWriter {
             return Ok(());
         }
 
+        // IRC再接続時の再送などによる重複を除く
+        let window_secs = CHAT_DEDUPE_WINDOW_SECS.load(Ordering::Relaxed);
+        let messages: Vec<&ChatMessage> = if window_secs == 0 {
+            messages.iter().collect()
+        } else {
+            Self::dedupe_chat_messages(conn, messages, window_secs)?
+        };
+        if messages.is_empty() {
+            return Ok(());
+        }
+
         // バッチインサート用のトランザクション開始
         conn.execute("BEGIN TRANSACTION", [])?;
 
@@ -124,18 +372,18 @@ impl DatabaseWriter {
                             format!("ARRAY[{}]", escaped_badges.join(", "))
                         }
                     };
-                    format!("(?, ?, ?, ?, ?, ?, ?, ?, ?, {}, ?)", badges_literal)
+                    format!("(?, ?, ?, ?, ?, ?, ?, ?, ?, {}, ?, ?)", badges_literal)
                 })
                 .collect();
 
             let sql = format!(
-                "INSERT INTO chat_messages (channel_id, stream_id, timestamp, platform, user_id, user_name, display_name, message, message_type, badges, badge_info) VALUES {}",
+                "INSERT INTO chat_messages (channel_id, stream_id, timestamp, platform, user_id, user_name, display_name, message, message_type, badges, badge_info, bits) VALUES {}",
                 values_placeholders.join(", ")
             );
 
             // すべてのパラメータを順番に配列に格納
             let mut params: Vec<Box<dyn duckdb::ToSql>> = Vec::new();
-            for message in messages {
+            for message in &messages {
                 params.push(Box::new(message.channel_id));
                 params.push(Box::new(message.stream_id));
                 params.push(Box::new(message.timestamp.clone()));
@@ -147,6 +395,7 @@ impl DatabaseWriter {
                 params.push(Box::new(message.message_type.clone()));
                 // badges はリテラルで埋め込み済みのためスキップ
                 params.push(Box::new(message.badge_info.clone()));
+                params.push(Box::new(message.bits));
             }
 
             // パラメータ参照を作成
@@ -173,3 +422,226 @@ impl DatabaseWriter {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{init_test_db, insert_channel, insert_stream};
+
+    fn stats(stream_id: i64, collected_at: &str, viewer_count: Option<i32>) -> StreamStats {
+        StreamStats {
+            id: None,
+            stream_id,
+            collected_at: collected_at.to_string(),
+            viewer_count,
+            chat_rate_1min: None,
+            category: None,
+            game_id: None,
+            title: Some("title".to_string()),
+            follower_count: None,
+            twitch_user_id: None,
+            channel_name: None,
+            comment_count: None,
+        }
+    }
+
+    #[test]
+    fn insert_stream_stats_batch_is_atomic() {
+        let conn = init_test_db();
+        insert_channel(&conn, 1, "twitch", "foo");
+        for id in 1..=4 {
+            insert_stream(&conn, id, 1, "2024-01-01 11:00:00", None);
+        }
+
+        let rows = vec![
+            stats(1, "2024-01-01 12:00:00", Some(10)),
+            stats(2, "2024-01-01 12:00:00", None),
+        ];
+        DatabaseWriter::insert_stream_stats_batch(&conn, &rows).unwrap();
+
+        // 途中の行が失敗した場合は、バッチ全体がロールバックされる
+        let invalid = vec![
+            stats(3, "2024-01-01 12:01:00", Some(30)),
+            stats(4, "not a timestamp", Some(40)),
+        ];
+        assert!(DatabaseWriter::insert_stream_stats_batch(&conn, &invalid).is_err());
+
+        // 同じ (stream_id, collected_at) の再挿入は無視され、先に入った行が残る
+        DatabaseWriter::insert_stream_stats(&conn, &stats(1, "2024-01-01 12:00:00", Some(99)))
+            .unwrap();
+
+        let (count, total): (i64, i64) = conn
+            .query_row(
+                "SELECT COUNT(*), COALESCE(SUM(viewer_count), 0) FROM stream_stats",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((count, total), (2, 10));
+    }
+
+    fn chat(user_name: &str, message: &str, timestamp: &str) -> ChatMessage {
+        ChatMessage {
+            id: None,
+            channel_id: Some(1),
+            stream_id: Some(1),
+            timestamp: timestamp.to_string(),
+            platform: crate::constants::database::PLATFORM_TWITCH.to_string(),
+            user_id: None,
+            user_name: user_name.to_string(),
+            display_name: None,
+            message: message.to_string(),
+            message_type: "normal".to_string(),
+            badges: None,
+            badge_info: None,
+            bits: None,
+        }
+    }
+
+    #[test]
+    fn dedupe_chat_messages_skips_recent_repeats() {
+        let conn = init_test_db();
+        conn.execute_batch(
+            r#"
+            INSERT INTO chat_messages (channel_id, platform, user_name, message, timestamp)
+            VALUES (1, 'twitch', 'alice', 'hello', '2024-01-01 12:00:00');
+            "#,
+        )
+        .unwrap();
+
+        let messages = vec![
+            // 保存済みのメッセージの再送
+            chat("alice", "hello", "2024-01-01T12:00:02Z"),
+            // 時間幅を過ぎた同一メッセージ
+            chat("alice", "hello", "2024-01-01T12:00:10Z"),
+            // バッチ内での重複
+            chat("bob", "gg", "2024-01-01T12:00:03Z"),
+            chat("bob", "gg", "2024-01-01T12:00:04Z"),
+            chat("bob", "gg!", "2024-01-01T12:00:04Z"),
+        ];
+        let kept = DatabaseWriter::dedupe_chat_messages(&conn, &messages, 3).unwrap();
+        let kept: Vec<(&str, &str)> = kept
+            .iter()
+            .map(|m| (m.user_name.as_str(), m.timestamp.as_str()))
+            .collect();
+        assert_eq!(
+            kept,
+            vec![
+                ("alice", "2024-01-01T12:00:10Z"),
+                ("bob", "2024-01-01T12:00:03Z"),
+                ("bob", "2024-01-01T12:00:04Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn dedupe_chat_messages_only_looks_back_within_window() {
+        let conn = init_test_db();
+        conn.execute_batch(
+            r#"
+            INSERT INTO chat_messages (channel_id, platform, user_name, message, timestamp) VALUES
+                (1, 'twitch', 'alice', 'hello', '2024-01-01 11:59:55'),
+                (1, 'twitch', 'bob', 'gg', '2024-01-01 12:00:00.500');
+            "#,
+        )
+        .unwrap();
+
+        let messages = vec![
+            // 時間幅（3秒）より前に保存されたメッセージとは重複とみなさない
+            chat("alice", "hello", "2024-01-01T12:00:00Z"),
+            // 時間幅内に保存されたメッセージの再送は除外する
+            chat("bob", "gg", "2024-01-01T12:00:03Z"),
+        ];
+        let kept = DatabaseWriter::dedupe_chat_messages(&conn, &messages, 3).unwrap();
+        let kept: Vec<&str> = kept.iter().map(|m| m.user_name.as_str()).collect();
+        assert_eq!(kept, vec!["alice"]);
+    }
+
+    #[test]
+    fn close_orphan_streams_uses_last_collected_at() {
+        let conn = init_test_db();
+        // poll_interval は既定の60秒
+        insert_channel(&conn, 1, "twitch", "foo");
+        insert_stream(&conn, 1, 1, "2024-01-01 10:00:00", None);
+        insert_stream(&conn, 2, 1, "2024-01-01 11:00:00", None);
+        insert_stream(&conn, 3, 1, "2024-01-01 11:58:00", None);
+        insert_stream(
+            &conn,
+            4,
+            1,
+            "2024-01-01 09:00:00",
+            Some("2024-01-01 09:30:00"),
+        );
+        conn.execute_batch(
+            r#"
+            INSERT INTO stream_stats (stream_id, collected_at) VALUES
+                (1, '2024-01-01 10:30:00'),
+                (2, '2024-01-01 11:59:00');
+            "#,
+        )
+        .unwrap();
+
+        // 12:00 時点でポーリング間隔60秒 × 3 = 3分以上更新の無い配信のみ終了する
+        let closed =
+            DatabaseWriter::close_orphan_streams(&conn, "2024-01-01T12:00:00+00:00", 3).unwrap();
+        assert_eq!(closed, 1);
+
+        let mut stmt = conn
+            .prepare("SELECT id, CAST(ended_at AS VARCHAR) FROM streams ORDER BY id")
+            .unwrap();
+        let ended: Vec<(i64, Option<String>)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            ended,
+            vec![
+                (1, Some("2024-01-01 10:30:00".to_string())),
+                (2, None),
+                (3, None),
+                (4, Some("2024-01-01 09:30:00".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn update_stream_corrects_started_at() {
+        let conn = init_test_db();
+        insert_channel(&conn, 1, "twitch", "foo");
+
+        let mut stream = Stream {
+            id: None,
+            channel_id: 1,
+            stream_id: "s1".to_string(),
+            title: None,
+            category: None,
+            thumbnail_url: None,
+            started_at: "2024-01-01 12:00:00".to_string(),
+            ended_at: None,
+        };
+        let id = DatabaseWriter::insert_or_update_stream(&conn, 1, &stream).unwrap();
+        let started_at = |conn: &Connection| -> String {
+            conn.query_row(
+                "SELECT CAST(started_at AS VARCHAR) FROM streams WHERE id = ?",
+                [id],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+
+        // 統計が記録済み（streams が参照されている）でも、実際の開始時刻が検知時刻より前なら補正される
+        DatabaseWriter::insert_stream_stats(&conn, &stats(id, "2024-01-01 12:00:00", Some(10)))
+            .unwrap();
+        stream.started_at = "2024-01-01T11:30:00Z".to_string();
+        DatabaseWriter::insert_or_update_stream(&conn, 1, &stream).unwrap();
+        assert_eq!(started_at(&conn), "2024-01-01 11:30:00");
+
+        // 現在時刻で代用された開始時刻や空文字では後ろにずれない
+        stream.started_at = "2024-01-01 13:00:00".to_string();
+        DatabaseWriter::insert_or_update_stream(&conn, 1, &stream).unwrap();
+        stream.started_at = String::new();
+        DatabaseWriter::insert_or_update_stream(&conn, 1, &stream).unwrap();
+        assert_eq!(started_at(&conn), "2024-01-01 11:30:00");
+    }
+}
@@ -14,62 +14,88 @@ use std::sync::atomic::AtomicBool;
 use tauri::{
     menu::{Menu, MenuItem},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    Emitter, Manager, WindowEvent,
+    Emitter, Manager, RunEvent, WindowEvent,
 };
 use tokio::sync::Mutex;
 
 use collectors::{
-    auto_discovery::AutoDiscoveryPoller, poller::ChannelPoller, twitch::TwitchCollector,
+    auto_discovery::AutoDiscoveryPoller, kick::KickCollector, niconico::NiconicoCollector,
+    poller::ChannelPoller, thumbnail_cache::ThumbnailCache, twitch::TwitchCollector,
     youtube::YouTubeCollector,
 };
 use commands::{
     analytics::{
         detect_chat_spikes, get_broadcaster_analytics, get_channel_daily_stats,
-        get_chat_engagement_timeline, get_chatter_behavior_stats, get_data_availability,
-        get_game_analytics, get_game_daily_stats, get_time_pattern_stats, get_top_chatters,
-        get_user_segment_stats, list_game_categories,
+        get_chat_aggregates, get_chat_engagement_timeline, get_chat_momentum,
+        get_chatter_behavior_stats, get_data_availability, get_game_analytics,
+        get_game_daily_stats, get_stream_outro_metrics, get_time_pattern_stats, get_top_chatters,
+        get_unique_chatters_timeline, get_user_activity, get_user_segment_stats,
+        list_game_categories,
     },
     channels::{
-        add_channel, list_channels, list_channels_basic, remove_channel, toggle_channel,
-        update_channel,
+        add_channel, add_channel_tag, export_channels, get_channel_icon_path, import_channels,
+        list_channels, list_channels_basic, list_channels_by_tag, list_channels_with_stats,
+        remove_channel, remove_channel_tag, set_channel_alert_threshold, set_channels_enabled,
+        set_tag_channels_enabled, toggle_channel, update_channel,
     },
-    chat::{get_chat_messages, get_chat_messages_around_timestamp},
+    chat::{get_chat_messages, get_chat_messages_around_timestamp, search_chat_messages},
     config::{
-        delete_oauth_config, delete_token, get_build_info, get_database_init_status,
-        get_oauth_config, has_oauth_config, recreate_database, save_oauth_config, save_token,
-        verify_token,
+        delete_oauth_config, delete_token, get_build_info, get_cache_thumbnails,
+        get_chat_filter_settings, get_database_init_status, get_oauth_config,
+        get_settings_encryption, get_twitch_irc_authenticated, has_oauth_config, recreate_database,
+        save_chat_filter_settings, save_oauth_config, save_token, set_cache_thumbnails,
+        set_settings_encryption, set_twitch_irc_authenticated, verify_token,
     },
     data_science::{
         detect_anomalies, get_category_change_impact, get_chatter_activity_scores,
         get_emote_analysis, get_message_length_stats, get_viewer_chat_correlation,
         get_word_frequency_analysis,
     },
-    database::get_database_info,
+    database::{
+        apply_retention_policy, backup_database, delete_data_in_range, get_database_info,
+        get_database_settings, get_database_stats, get_storage_breakdown, get_streams_missing_chat,
+        restore_database, save_database_settings, set_database_path,
+    },
     discovery::{
         get_auto_discovery_settings, get_discovered_streams, get_games_by_ids,
         promote_discovered_channel, promote_discovered_channels, save_auto_discovery_settings,
         search_twitch_games, toggle_auto_discovery, DiscoveredStreamInfo,
     },
-    export::{export_to_delimited, preview_export_data},
+    export::{
+        count_export_rows, export_chat_to_csv, export_stats, export_stream_summary_to_json,
+        export_to_delimited, export_to_parquet, export_to_s3, get_s3_export_settings,
+        preview_export_data, save_s3_export_settings,
+    },
     game_categories::{
         delete_game_category, get_game_categories, get_game_category, search_game_categories,
         upsert_game_category,
     },
     logs::get_logs,
-    oauth::{poll_twitch_device_token, reinitialize_twitch_collector, start_twitch_device_auth},
+    oauth::{
+        login_with_youtube_device, poll_twitch_device_token, reinitialize_twitch_collector,
+        start_twitch_device_auth, verify_twitch_token,
+    },
     sql::{
-        delete_sql_template, execute_sql, list_database_tables, list_sql_templates,
-        save_sql_template,
+        delete_sql_template, execute_sql, execute_sql_query, import_external_data,
+        list_database_tables, list_sql_templates, save_sql_template, validate_template_params,
+    },
+    stats::{
+        get_channel_stats, get_live_channels, get_realtime_chat_rate, get_scheduled_streams,
+        get_stream_stats,
     },
-    stats::{get_realtime_chat_rate, get_stream_stats},
-    system::is_backend_ready,
+    system::{get_active_chat_connections, get_live_snapshot, is_backend_ready},
     timeline::{
-        get_channel_streams, get_stream_timeline, get_streams_by_date_range,
-        get_suggested_streams_for_comparison,
+        detect_highlights, get_aggregate_viewers, get_cached_thumbnail_path,
+        get_category_durations, get_category_stats, get_channel_streams, get_fragmented_streams,
+        get_peak_moment, get_retention_curve, get_stream_timeline, get_streams_by_date_range,
+        get_streams_comparison, get_suggested_streams_for_comparison, get_viewer_heatmap,
+        merge_streams,
     },
     twitch::{get_twitch_rate_limit_status, validate_twitch_channel},
     window::show_main_window,
+    youtube::{get_youtube_quota_usage, set_youtube_quota_settings},
 };
+use config::keyring_store::SettingsKeyCache;
 use config::settings::SettingsManager;
 use database::DatabaseManager;
 use logger::AppLogger;
@@ -135,6 +161,37 @@ async fn check_for_updates(app: tauri::AppHandle) {
     }
 }
 
+/// Helper function to stop IRC chat collection (flushes buffered chat messages)
+async fn shutdown_irc_collection(app_handle: &tauri::AppHandle) {
+    if let Some(poller) = app_handle.try_state::<Arc<Mutex<ChannelPoller>>>() {
+        let twitch_collector = poller.lock().await.get_twitch_collector().cloned();
+        if let Some(twitch_collector) = twitch_collector {
+            twitch_collector.shutdown_irc().await;
+        }
+    }
+}
+
+/// アプリ終了時の後処理（IRC切断とDBの最終同期）
+///
+/// 終了経路（トレイの終了メニュー・RunEvent::Exit・シグナル）のいずれからも呼ばれるため、
+/// DatabaseManager::shutdown 側で2回目以降は何もしないようにしている。
+fn graceful_shutdown(app_handle: &tauri::AppHandle) {
+    let Some(db_manager) = app_handle.try_state::<DatabaseManager>() else {
+        return;
+    };
+    eprintln!("[App Exit] Performing graceful shutdown...");
+    let result = tauri::async_runtime::block_on(async {
+        shutdown_irc_collection(app_handle).await;
+        db_manager.shutdown().await
+    });
+    if let Err(e) = result {
+        eprintln!("[App Exit] Shutdown failed: {}", e);
+        if let Some(logger) = app_handle.try_state::<AppLogger>() {
+            logger.error(&format!("Shutdown failed: {}", e));
+        }
+    }
+}
+
 /// Helper function to start polling for existing enabled channels
 async fn start_existing_channels_polling(
     db_manager: &tauri::State<'_, DatabaseManager>,
@@ -168,6 +225,7 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_autostart::Builder::new().build())
         .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
@@ -189,6 +247,7 @@ pub fn run() {
             app.handle()
                 .plugin(tauri_plugin_keyring::init())
                 .expect("failed to initialize keyring plugin");
+            app.manage(SettingsKeyCache::default());
 
             // Initialize AppLogger
             let log_path = app_handle
@@ -201,7 +260,17 @@ pub fn run() {
             app.manage(logger.clone());
 
             // DatabaseManagerを初期化して管理（失敗時はパニックせずログして終了）
-            let db_manager = match DatabaseManager::new(&app_handle) {
+            let database_settings = match SettingsManager::load_settings(&app_handle) {
+                Ok(settings) => settings.database,
+                Err(e) => {
+                    logger.error(&format!(
+                        "Failed to load settings for database, using default settings: {}",
+                        e
+                    ));
+                    Default::default()
+                }
+            };
+            let db_manager = match DatabaseManager::new(&app_handle, &database_settings) {
                 Ok(m) => m,
                 Err(e) => {
                     let msg = format!("Failed to create DatabaseManager: {}", e);
@@ -213,18 +282,13 @@ pub fn run() {
             app.manage(db_manager.clone());
 
             // Ctrl+C / SIGTERMシグナルハンドラを設定（ホットリロード対策）
-            let db_manager_for_signal = db_manager.clone();
             let logger_for_signal = logger.clone();
+            let app_handle_for_signal = app_handle.clone();
             std::thread::spawn(move || {
                 if let Err(e) = ctrlc::set_handler(move || {
                     eprintln!("[Signal] Received termination signal, performing cleanup...");
                     logger_for_signal.info("Received termination signal, performing cleanup...");
-                    if let Err(e) = tauri::async_runtime::block_on(async {
-                        db_manager_for_signal.shutdown().await
-                    }) {
-                        eprintln!("[Signal] Cleanup failed: {}", e);
-                        logger_for_signal.error(&format!("Cleanup failed: {}", e));
-                    }
+                    graceful_shutdown(&app_handle_for_signal);
                     std::process::exit(0);
                 }) {
                     eprintln!("[Signal] Failed to set signal handler: {}", e);
@@ -272,6 +336,16 @@ pub fn run() {
                             logger_for_init.info("Database schema initialization successful, notifying frontend...");
                             // フロントエンドにDB初期化成功を通知
                             let _ = app_handle_for_init.emit("database-init-success", ());
+                            // 設定のDBパスを使用できず既定のパスにフォールバックした場合は警告を通知
+                            if let Some(warning) = db_manager.path_warning() {
+                                logger_for_init.error(warning);
+                                let _ = app_handle_for_init.emit("database-path-warning", warning.to_string());
+                            }
+                            // 破損したDBファイルを退避して新規DBで起動した場合は退避先を通知
+                            if let Some(path) = db_manager.quarantined_path() {
+                                logger_for_init.error(&format!("Corrupted database was quarantined to {}", path.display()));
+                                let _ = app_handle_for_init.emit("database-recovered", path.display().to_string());
+                            }
                         }
                         Err(e) => {
                             logger_for_init.error(&format!("Database schema initialization failed: {}", e));
@@ -294,6 +368,7 @@ pub fn run() {
                                 return;
                             }
                         };
+                        ThumbnailCache::set_enabled(settings.cache_thumbnails);
 
                         // Initialize Twitch collector if credentials are available
                         // Device Code Flow uses only client_id (no client_secret required)
@@ -304,6 +379,7 @@ pub fn run() {
                                 app_handle_for_init.clone(),
                                 Arc::new(db_manager.inner().clone()),
                                 Arc::new(logger_for_init.clone()),
+                                settings.twitch.irc_authenticated,
                             ));
                             // IRC DB ハンドラーを初期化
                             collector.initialize_irc().await;
@@ -325,6 +401,11 @@ pub fn run() {
                                 client_secret.clone(),
                                 "http://localhost:8081/callback".to_string(),
                                 Arc::new(db_manager.inner().clone()),
+                                settings
+                                    .youtube
+                                    .daily_quota_limit
+                                    .unwrap_or(crate::constants::youtube::DEFAULT_DAILY_QUOTA_LIMIT),
+                                settings.youtube.adaptive_quota,
                             )
                             .await
                             {
@@ -332,10 +413,7 @@ pub fn run() {
                                     // Register collector - lock only for registration
                                     {
                                         let mut poller = poller_for_init.lock().await;
-                                        poller.register_collector(
-                                            crate::constants::database::PLATFORM_YOUTUBE.to_string(),
-                                            Arc::new(collector),
-                                        );
+                                        poller.register_youtube_collector(Arc::new(collector));
                                     }
                                     logger_for_init
                                         .info("YouTube collector initialized successfully");
@@ -350,6 +428,26 @@ pub fn run() {
                                 .info("YouTube credentials not configured, skipping collector initialization");
                         }
 
+                        // Kick collector uses the public API and needs no credentials
+                        {
+                            let mut poller = poller_for_init.lock().await;
+                            poller.register_collector(
+                                crate::constants::database::PLATFORM_KICK.to_string(),
+                                Arc::new(KickCollector::new()),
+                            );
+                        }
+                        logger_for_init.info("Kick collector initialized successfully");
+
+                        // Niconico collector scrapes the public watch page and needs no credentials
+                        {
+                            let mut poller = poller_for_init.lock().await;
+                            poller.register_niconico_collector(Arc::new(NiconicoCollector::new(
+                                Arc::new(db_manager.inner().clone()),
+                                Arc::new(logger_for_init.clone()),
+                            )));
+                        }
+                        logger_for_init.info("Niconico collector initialized successfully");
+
                         // Start polling for existing enabled channels
                         logger_for_init.info("Starting polling for existing enabled channels...");
                         {
@@ -456,13 +554,7 @@ pub fn run() {
                             });
                         }
                         "quit" => {
-                            // グレースフルシャットダウン
-                            if let Some(db_manager) = _app.try_state::<DatabaseManager>() {
-                                eprintln!("[App Exit] Performing graceful shutdown...");
-                                let _ = tauri::async_runtime::block_on(async {
-                                    db_manager.shutdown().await
-                                });
-                            }
+                            // グレースフルシャットダウンは RunEvent::Exit で行う
                             _app.exit(0);
                         }
                         _ => {}
@@ -504,11 +596,16 @@ pub fn run() {
             get_data_availability,
             get_game_daily_stats,
             get_channel_daily_stats,
+            get_stream_outro_metrics,
             // Chat Analytics commands
             get_chat_engagement_timeline,
+            get_unique_chatters_timeline,
+            get_chat_aggregates,
             detect_chat_spikes,
+            get_chat_momentum,
             get_user_segment_stats,
             get_top_chatters,
+            get_user_activity,
             get_time_pattern_stats,
             get_chatter_behavior_stats,
             // Data Science commands
@@ -525,12 +622,25 @@ pub fn run() {
             update_channel,
             list_channels,
             list_channels_basic,
+            list_channels_with_stats,
+            export_channels,
+            import_channels,
             toggle_channel,
+            set_channels_enabled,
+            set_tag_channels_enabled,
+            add_channel_tag,
+            remove_channel_tag,
+            list_channels_by_tag,
+            set_channel_alert_threshold,
+            get_channel_icon_path,
             // System commands
             is_backend_ready,
+            get_live_snapshot,
+            get_active_chat_connections,
             // Chat commands
             get_chat_messages,
             get_chat_messages_around_timestamp,
+            search_chat_messages,
             // Config commands
             save_token,
             delete_token,
@@ -542,8 +652,26 @@ pub fn run() {
             save_oauth_config,
             delete_oauth_config,
             has_oauth_config,
+            get_cache_thumbnails,
+            set_cache_thumbnails,
+            get_twitch_irc_authenticated,
+            set_twitch_irc_authenticated,
+            get_settings_encryption,
+            set_settings_encryption,
+            get_chat_filter_settings,
+            save_chat_filter_settings,
             // Database commands
             get_database_info,
+            get_database_stats,
+            get_database_settings,
+            save_database_settings,
+            set_database_path,
+            delete_data_in_range,
+            get_storage_breakdown,
+            get_streams_missing_chat,
+            apply_retention_policy,
+            backup_database,
+            restore_database,
             // Discovery commands
             get_auto_discovery_settings,
             save_auto_discovery_settings,
@@ -561,33 +689,69 @@ pub fn run() {
             search_game_categories,
             // SQL commands
             execute_sql,
+            execute_sql_query,
             list_sql_templates,
             save_sql_template,
             delete_sql_template,
+            validate_template_params,
             list_database_tables,
+            import_external_data,
             // OAuth commands
             start_twitch_device_auth,
             poll_twitch_device_token,
             reinitialize_twitch_collector,
+            login_with_youtube_device,
+            verify_twitch_token,
             // Stats commands
             get_stream_stats,
             get_realtime_chat_rate,
+            get_live_channels,
+            get_scheduled_streams,
+            get_channel_stats,
             // Timeline commands
             get_channel_streams,
             get_stream_timeline,
+            get_retention_curve,
+            get_peak_moment,
+            get_aggregate_viewers,
+            get_category_stats,
+            get_viewer_heatmap,
+            get_streams_comparison,
+            detect_highlights,
+            get_cached_thumbnail_path,
             get_streams_by_date_range,
             get_suggested_streams_for_comparison,
+            get_fragmented_streams,
+            merge_streams,
+            get_category_durations,
             // Export commands
             export_to_delimited,
+            export_stats,
+            export_chat_to_csv,
+            export_stream_summary_to_json,
+            export_to_s3,
+            export_to_parquet,
+            get_s3_export_settings,
+            save_s3_export_settings,
             preview_export_data,
+            count_export_rows,
             // Logs commands
             get_logs,
             // Twitch commands
             validate_twitch_channel,
             get_twitch_rate_limit_status,
+            // YouTube commands
+            get_youtube_quota_usage,
+            set_youtube_quota_settings,
             // Window commands
             show_main_window,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            // 終了経路に関わらず、イベントループ終了前にDBの最終同期を行う
+            if let RunEvent::Exit = event {
+                graceful_shutdown(app_handle);
+            }
+        });
 }
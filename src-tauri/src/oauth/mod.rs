@@ -1 +1,7 @@
+//! OAuth 認証（Twitch / YouTube）
+//!
+//! どちらも Device Code Flow（RFC 8628）で認証するため、ローカルにリダイレクト受信用の
+//! HTTP サーバーは起動しない。redirect_uri やコールバックポートの設定・競合回避は不要。
+
 pub mod twitch;
+pub mod youtube;
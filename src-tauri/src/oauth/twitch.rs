@@ -1,4 +1,4 @@
-use crate::config::keyring_store::{KeyringStore, TokenMetadata};
+use crate::config::keyring_store::{KeyringStore, TokenKind, TokenMetadata};
 use crate::constants::database as db_constants;
 use chrono::{Duration, Local};
 use reqwest::Client;
@@ -8,6 +8,7 @@ use tauri::Emitter;
 
 const TWITCH_TOKEN_URL: &str = "https://id.twitch.tv/oauth2/token";
 const TWITCH_DEVICE_URL: &str = "https://id.twitch.tv/oauth2/device";
+const TWITCH_VALIDATE_URL: &str = "https://id.twitch.tv/oauth2/validate";
 
 #[derive(Debug, Serialize, Deserialize)]
 struct TwitchTokenResponse {
@@ -36,6 +37,25 @@ pub struct DeviceAuthStatus {
     pub interval: u64,
 }
 
+/// Device Code Flow のポーリング進捗（twitch-auth-progress イベントのペイロード）
+#[derive(Debug, Serialize, Clone)]
+pub struct DeviceAuthProgress {
+    /// デバイスコードの有効期限までの残り秒数
+    pub remaining_secs: u64,
+    pub expires_in: u64,
+}
+
+/// validate エンドポイントで確認したトークン情報
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TwitchTokenValidation {
+    pub login: Option<String>,
+    pub user_id: Option<String>,
+    pub client_id: String,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    pub expires_in: u64,
+}
+
 pub struct TwitchOAuth {
     client_id: String,
     http_client: Client,
@@ -121,6 +141,7 @@ impl TwitchOAuth {
         &self,
         device_code: &str,
         interval_secs: u64,
+        expires_in: u64,
         app_handle: Option<tauri::AppHandle>,
     ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
         let mut params = HashMap::new();
@@ -130,12 +151,36 @@ impl TwitchOAuth {
 
         eprintln!("[Twitch Device Flow] Starting token polling");
         eprintln!("  - Polling interval: {} seconds", interval_secs);
+        eprintln!("  - Expires in: {} seconds", expires_in);
+
+        // デバイスコードの有効期限を過ぎたらポーリングを打ち切る
+        let deadline = tokio::time::Instant::now() + tokio::time::Duration::from_secs(expires_in);
 
         // ポーリング開始
         loop {
             // 指定された間隔で待機
             tokio::time::sleep(tokio::time::Duration::from_secs(interval_secs)).await;
 
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                eprintln!("[Twitch Device Flow] Device code expired, stopping polling");
+                return Err("Device code expired".into());
+            }
+
+            // 残り時間をフロントエンドに通知
+            if let Some(ref handle) = app_handle {
+                let progress = DeviceAuthProgress {
+                    remaining_secs: deadline.duration_since(now).as_secs(),
+                    expires_in,
+                };
+                if let Err(e) = handle.emit("twitch-auth-progress", &progress) {
+                    eprintln!(
+                        "[Twitch Device Flow] Failed to emit auth progress event: {}",
+                        e
+                    );
+                }
+            }
+
             let response = self
                 .http_client
                 .post(TWITCH_TOKEN_URL)
@@ -157,10 +202,13 @@ impl TwitchOAuth {
                     match KeyringStore::save_token_with_app(
                         handle,
                         crate::constants::database::PLATFORM_TWITCH,
+                        TokenKind::Access,
                         &token_response.access_token,
                     ) {
                         Ok(_) => {
-                            eprintln!("[Twitch Device Flow] Access token saved successfully to Stronghold");
+                            eprintln!(
+                                "[Twitch Device Flow] Access token saved successfully to keyring"
+                            );
                         }
                         Err(e) => {
                             eprintln!("[Twitch Device Flow] CRITICAL ERROR: Failed to save access token: {}", e);
@@ -173,11 +221,12 @@ impl TwitchOAuth {
                         eprintln!("[Twitch Device Flow] About to save refresh token...");
                         match KeyringStore::save_token_with_app(
                             handle,
-                            "twitch_refresh",
+                            db_constants::PLATFORM_TWITCH,
+                            TokenKind::Refresh,
                             refresh_token,
                         ) {
                             Ok(_) => {
-                                eprintln!("[Twitch Device Flow] Refresh token saved successfully to Stronghold");
+                                eprintln!("[Twitch Device Flow] Refresh token saved successfully to keyring");
                             }
                             Err(e) => {
                                 eprintln!("[Twitch Device Flow] WARNING: Failed to save refresh token: {}", e);
@@ -206,13 +255,6 @@ impl TwitchOAuth {
                             // メタデータ保存失敗は致命的ではないので続行
                         }
                     }
-
-                    // Give frontend time to process the save event
-                    eprintln!("[Twitch Device Flow] Token save event sent, waiting for frontend processing...");
-                    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                    eprintln!(
-                        "[Twitch Device Flow] Token should now be saved in Stronghold by frontend"
-                    );
                 } else {
                     eprintln!("[Twitch Device Flow] WARNING: No AppHandle available, tokens will not be persisted");
                 }
@@ -253,8 +295,12 @@ impl TwitchOAuth {
                                     .await;
                                 continue;
                             }
-                            "expired_token" | "invalid device code" => {
-                                // デバイスコードが期限切れまたは無効
+                            "expired_token" => {
+                                // デバイスコードが期限切れ
+                                return Err("Device code expired".into());
+                            }
+                            "invalid device code" => {
+                                // デバイスコードが無効
                                 return Err(format!("Device code error: {}", message).into());
                             }
                             "access_denied" => {
@@ -286,8 +332,12 @@ impl TwitchOAuth {
             .or_else(|| self.app_handle.clone())
             .ok_or("No app handle available")?;
 
-        let refresh_token = KeyringStore::get_token_with_app(&handle, "twitch_refresh")
-            .map_err(|_| "No refresh token found")?;
+        let refresh_token = KeyringStore::get_token_with_app(
+            &handle,
+            db_constants::PLATFORM_TWITCH,
+            TokenKind::Refresh,
+        )
+        .map_err(|_| "No refresh token found")?;
 
         let mut params = HashMap::new();
         params.insert("client_id", self.client_id.as_str());
@@ -318,12 +368,18 @@ impl TwitchOAuth {
         KeyringStore::save_token_with_app(
             &handle,
             db_constants::PLATFORM_TWITCH,
+            TokenKind::Access,
             &token_response.access_token,
         )?;
 
         // 新しいリフレッシュトークンがある場合は保存（1回限り使用）
         if let Some(new_refresh_token) = &token_response.refresh_token {
-            KeyringStore::save_token_with_app(&handle, "twitch_refresh", new_refresh_token)?;
+            KeyringStore::save_token_with_app(
+                &handle,
+                db_constants::PLATFORM_TWITCH,
+                TokenKind::Refresh,
+                new_refresh_token,
+            )?;
             eprintln!("[Twitch Device Flow] New refresh token saved (one-time use)");
         }
 
@@ -363,4 +419,83 @@ impl TwitchOAuth {
 
         Ok(token_response.access_token)
     }
+
+    /// validate エンドポイントでトークンを検証
+    ///
+    /// トークンが無効（401）の場合は Ok(None) を返します。
+    pub async fn validate_token(
+        &self,
+        access_token: &str,
+    ) -> Result<Option<TwitchTokenValidation>, Box<dyn std::error::Error + Send + Sync>> {
+        let response = self
+            .http_client
+            .get(TWITCH_VALIDATE_URL)
+            .header("Authorization", format!("OAuth {}", access_token))
+            .send()
+            .await?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            let error_text = response.text().await?;
+            return Err(format!("Token validation failed ({}): {}", status, error_text).into());
+        }
+
+        Ok(Some(response.json().await?))
+    }
+
+    /// 保存済みトークンを検証し、無効であればリフレッシュを試みて再検証
+    ///
+    /// 検証に成功した場合は expires_in からトークンメタデータを更新します。
+    pub async fn verify_twitch_token(
+        &self,
+        app_handle: &tauri::AppHandle,
+    ) -> Result<TwitchTokenValidation, Box<dyn std::error::Error + Send + Sync>> {
+        let access_token = KeyringStore::get_token_with_app(
+            app_handle,
+            db_constants::PLATFORM_TWITCH,
+            TokenKind::Access,
+        )
+        .map_err(|_| "No Twitch access token found. Please authenticate first.")?;
+
+        let validation = match self.validate_token(&access_token).await? {
+            Some(validation) => validation,
+            None => {
+                eprintln!("[Twitch Validate] Token is invalid, attempting refresh...");
+                let refreshed_token = self
+                    .refresh_device_token(Some(app_handle.clone()))
+                    .await
+                    .map_err(|e| {
+                        format!(
+                            "Twitch token is invalid and refresh failed: {}. Please re-authenticate.",
+                            e
+                        )
+                    })?;
+
+                self.validate_token(&refreshed_token)
+                    .await?
+                    .ok_or("Twitch token is still invalid after refresh. Please re-authenticate.")?
+            }
+        };
+
+        let now = Local::now();
+        let metadata = TokenMetadata {
+            expires_at: (now + Duration::seconds(validation.expires_in as i64)).to_rfc3339(),
+            obtained_at: now.to_rfc3339(),
+        };
+        if let Err(e) = KeyringStore::save_token_metadata_with_app(
+            app_handle,
+            db_constants::PLATFORM_TWITCH,
+            &metadata,
+        ) {
+            eprintln!(
+                "[Twitch Validate] WARNING: Failed to save token metadata: {}",
+                e
+            );
+        }
+
+        Ok(validation)
+    }
 }
@@ -0,0 +1,230 @@
+use crate::config::keyring_store::{KeyringStore, TokenKind, TokenMetadata};
+use crate::constants::{database as db_constants, youtube};
+use chrono::{Duration, Local};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::Emitter;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GoogleTokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<u64>,
+    token_type: String,
+    scope: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GoogleDeviceCodeResponse {
+    device_code: String,
+    user_code: String,
+    verification_url: String,
+    expires_in: u64,
+    #[serde(default = "default_interval")]
+    interval: u64,
+}
+
+fn default_interval() -> u64 {
+    5
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct YouTubeDeviceAuthStatus {
+    pub user_code: String,
+    pub verification_url: String,
+    pub expires_in: u64,
+    pub device_code: String,
+    pub interval: u64,
+}
+
+/// Google OAuth 2.0 Limited Input Device flow による YouTube 認証
+pub struct YouTubeOAuth {
+    client_id: String,
+    client_secret: String,
+    http_client: Client,
+}
+
+impl YouTubeOAuth {
+    pub fn new(client_id: String, client_secret: String) -> Self {
+        Self {
+            client_id,
+            client_secret,
+            http_client: Client::new(),
+        }
+    }
+
+    /// Device Code Flow を開始
+    ///
+    /// ユーザーはブラウザで verification_url にアクセスして user_code を入力します。
+    pub async fn start_device_flow(
+        &self,
+        scopes: Vec<&str>,
+    ) -> Result<YouTubeDeviceAuthStatus, Box<dyn std::error::Error + Send + Sync>> {
+        let scope_string = scopes.join(" ");
+
+        let mut params = HashMap::new();
+        params.insert("client_id", self.client_id.as_str());
+        params.insert("scope", scope_string.as_str());
+
+        eprintln!("[YouTube Device Flow] Starting device authorization flow");
+        eprintln!("  - Scopes: {}", scope_string);
+
+        let response = self
+            .http_client
+            .post(youtube::OAUTH_DEVICE_CODE_URL)
+            .form(&params)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await?;
+            eprintln!(
+                "[YouTube Device Flow] Device code error response: {}",
+                error_text
+            );
+            return Err(format!("Device code request failed: {}", error_text).into());
+        }
+
+        let device_response: GoogleDeviceCodeResponse = response.json().await?;
+
+        eprintln!("[YouTube Device Flow] Device code obtained successfully");
+        eprintln!("  - User code: {}", device_response.user_code);
+        eprintln!("  - Verification URL: {}", device_response.verification_url);
+        eprintln!("  - Expires in: {} seconds", device_response.expires_in);
+
+        Ok(YouTubeDeviceAuthStatus {
+            user_code: device_response.user_code,
+            verification_url: device_response.verification_url,
+            expires_in: device_response.expires_in,
+            device_code: device_response.device_code,
+            interval: device_response.interval,
+        })
+    }
+
+    /// Device Code を使用してアクセストークンを取得
+    ///
+    /// ユーザーが認証を完了するかデバイスコードが失効するまでポーリングし、
+    /// 取得したトークンを Keyring に保存します。
+    pub async fn poll_for_device_token(
+        &self,
+        app_handle: &tauri::AppHandle,
+        device_code: &str,
+        interval_secs: u64,
+        expires_in: u64,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let mut params = HashMap::new();
+        params.insert("client_id", self.client_id.as_str());
+        params.insert("client_secret", self.client_secret.as_str());
+        params.insert("device_code", device_code);
+        params.insert("grant_type", "urn:ietf:params:oauth:grant-type:device_code");
+
+        let deadline = tokio::time::Instant::now() + tokio::time::Duration::from_secs(expires_in);
+        let mut interval_secs = interval_secs.max(1);
+
+        eprintln!("[YouTube Device Flow] Starting token polling");
+
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(interval_secs)).await;
+
+            if tokio::time::Instant::now() >= deadline {
+                return Err("Device code expired".into());
+            }
+
+            let response = self
+                .http_client
+                .post(youtube::OAUTH_TOKEN_URL)
+                .form(&params)
+                .send()
+                .await?;
+
+            if response.status().is_success() {
+                let token_response: GoogleTokenResponse = response.json().await?;
+                eprintln!("[YouTube Device Flow] Token obtained successfully");
+
+                self.save_tokens(app_handle, &token_response)?;
+
+                if let Err(e) = app_handle.emit("youtube-auth-success", ()) {
+                    eprintln!(
+                        "[YouTube Device Flow] Failed to emit auth success event: {}",
+                        e
+                    );
+                }
+
+                return Ok(token_response.access_token);
+            }
+
+            let error_text = response.text().await?;
+            let error_code = serde_json::from_str::<serde_json::Value>(&error_text)
+                .ok()
+                .and_then(|v| v.get("error").and_then(|e| e.as_str()).map(String::from));
+
+            match error_code.as_deref() {
+                Some("authorization_pending") => continue,
+                Some("slow_down") => {
+                    interval_secs += youtube::DEVICE_FLOW_SLOW_DOWN_SECS;
+                    eprintln!(
+                        "[YouTube Device Flow] Slow down requested, interval is now {} seconds",
+                        interval_secs
+                    );
+                    continue;
+                }
+                Some("access_denied") => return Err("User denied authorization".into()),
+                Some("expired_token") => return Err("Device code expired".into()),
+                _ => return Err(format!("Token polling failed: {}", error_text).into()),
+            }
+        }
+    }
+
+    /// アクセストークン・リフレッシュトークン・有効期限を保存
+    fn save_tokens(
+        &self,
+        app_handle: &tauri::AppHandle,
+        token_response: &GoogleTokenResponse,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        KeyringStore::save_token_with_app(
+            app_handle,
+            db_constants::PLATFORM_YOUTUBE,
+            TokenKind::Access,
+            &token_response.access_token,
+        )
+        .map_err(|e| format!("Failed to save access token: {}", e))?;
+
+        if let Some(refresh_token) = &token_response.refresh_token {
+            if let Err(e) = KeyringStore::save_token_with_app(
+                app_handle,
+                db_constants::PLATFORM_YOUTUBE,
+                TokenKind::Refresh,
+                refresh_token,
+            ) {
+                // リフレッシュトークンは失敗しても続行
+                eprintln!(
+                    "[YouTube Device Flow] WARNING: Failed to save refresh token: {}",
+                    e
+                );
+            }
+        }
+
+        if let Some(expires_in) = token_response.expires_in {
+            let now = Local::now();
+            let metadata = TokenMetadata {
+                expires_at: (now + Duration::seconds(expires_in as i64)).to_rfc3339(),
+                obtained_at: now.to_rfc3339(),
+            };
+
+            if let Err(e) = KeyringStore::save_token_metadata_with_app(
+                app_handle,
+                db_constants::PLATFORM_YOUTUBE,
+                &metadata,
+            ) {
+                eprintln!(
+                    "[YouTube Device Flow] WARNING: Failed to save token metadata: {}",
+                    e
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
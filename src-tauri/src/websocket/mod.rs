@@ -1 +1,3 @@
+pub mod niconico_comment;
+pub mod niconico_ndgr;
 pub mod twitch_irc;
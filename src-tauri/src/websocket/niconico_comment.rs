@@ -0,0 +1,296 @@
+use crate::constants::database as db_constants;
+use crate::constants::niconico::{DEFAULT_KEEP_SEAT_INTERVAL_SECS, USER_AGENT};
+use crate::database::models::ChatMessage;
+use crate::database::writer::DatabaseWriter;
+use crate::database::DatabaseManager;
+use crate::logger::AppLogger;
+use crate::websocket::niconico_ndgr::{self, NdgrChat};
+use chrono::Utc;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, watch};
+use tokio::task::{JoinHandle, JoinSet};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::Message;
+
+/// ニコニコ生放送のコメント受信セッション
+///
+/// 視聴セッション（watch WebSocket）で座席を確保し、`messageServer` メッセージで通知される
+/// 新コメントサーバー（NDGR）からコメントを受信して chat_messages に保存する。
+pub struct NiconicoCommentSession {
+    pub program_id: String,
+    shutdown_tx: watch::Sender<bool>,
+    task: JoinHandle<()>,
+}
+
+impl NiconicoCommentSession {
+    pub fn start(
+        web_socket_url: String,
+        channel_id: i64,
+        program_id: String,
+        db_manager: Arc<DatabaseManager>,
+        logger: Arc<AppLogger>,
+    ) -> Self {
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let task_program_id = program_id.clone();
+
+        let task = tokio::spawn(async move {
+            if let Err(e) = Self::run(
+                &web_socket_url,
+                channel_id,
+                &task_program_id,
+                &db_manager,
+                &logger,
+                shutdown_rx,
+            )
+            .await
+            {
+                logger.error(&format!(
+                    "[Niconico] Comment session for {} ended with error: {}",
+                    task_program_id, e
+                ));
+            }
+        });
+
+        Self {
+            program_id,
+            shutdown_tx,
+            task,
+        }
+    }
+
+    /// セッションが終了しているか（切断・エラー時に再接続判定に使う）
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    /// セッションを停止（未保存のコメントは保存してから終了する）
+    pub fn stop(&self) {
+        let _ = self.shutdown_tx.send(true);
+    }
+
+    async fn run(
+        web_socket_url: &str,
+        channel_id: i64,
+        program_id: &str,
+        db_manager: &Arc<DatabaseManager>,
+        logger: &Arc<AppLogger>,
+        mut shutdown_rx: watch::Receiver<bool>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut request = web_socket_url.into_client_request()?;
+        request
+            .headers_mut()
+            .insert("User-Agent", HeaderValue::from_static(USER_AGENT));
+        let (mut watch_ws, _) = connect_async(request).await?;
+
+        watch_ws
+            .send(Message::text(
+                json!({
+                    "type": "startWatching",
+                    "data": {
+                        "stream": {
+                            "quality": "abr",
+                            "protocol": "hls",
+                            "latency": "low",
+                            "chasePlay": false
+                        },
+                        "room": { "protocol": "webSocket", "commentable": false },
+                        "reconnect": false
+                    }
+                })
+                .to_string(),
+            ))
+            .await?;
+
+        logger.info(&format!(
+            "[Niconico] Watch session started for {}",
+            program_id
+        ));
+
+        let http_client = reqwest::Client::builder().user_agent(USER_AGENT).build()?;
+        let (chat_tx, mut chat_rx) = mpsc::unbounded_channel::<NdgrChat>();
+        // セッション終了時（エラーによる早期リターンを含む）に JoinSet ごと破棄され、受信も停止する
+        let mut ndgr_task = JoinSet::new();
+        let mut keep_seat =
+            tokio::time::interval(Duration::from_secs(DEFAULT_KEEP_SEAT_INTERVAL_SECS));
+        let mut flush_timer =
+            tokio::time::interval(Duration::from_secs(db_constants::BATCH_FLUSH_INTERVAL_SECS));
+        let mut batch: Vec<ChatMessage> = Vec::new();
+
+        loop {
+            tokio::select! {
+                _ = shutdown_rx.changed() => break,
+                _ = keep_seat.tick() => {
+                    watch_ws
+                        .send(Message::text(json!({ "type": "keepSeat" }).to_string()))
+                        .await?;
+                }
+                _ = flush_timer.tick() => {
+                    Self::flush_batch(db_manager, channel_id, program_id, &mut batch, logger).await;
+                }
+                message = watch_ws.next() => {
+                    let Some(message) = message else { break };
+                    let message = message?;
+                    if !message.is_text() {
+                        continue;
+                    }
+                    let value: Value = match serde_json::from_str(message.to_text()?) {
+                        Ok(value) => value,
+                        Err(_) => continue,
+                    };
+
+                    match value["type"].as_str() {
+                        Some("ping") => {
+                            watch_ws
+                                .send(Message::text(json!({ "type": "pong" }).to_string()))
+                                .await?;
+                        }
+                        Some("seat") => {
+                            if let Some(secs) = value["data"]["keepIntervalSec"].as_u64() {
+                                keep_seat = tokio::time::interval(Duration::from_secs(secs.max(1)));
+                            }
+                        }
+                        Some("messageServer") => {
+                            let view_uri = value["data"]["viewUri"].as_str();
+                            if let (Some(view_uri), true) = (view_uri, ndgr_task.is_empty()) {
+                                ndgr_task.spawn(niconico_ndgr::run(
+                                    http_client.clone(),
+                                    view_uri.to_string(),
+                                    chat_tx.clone(),
+                                ));
+                                logger.info(&format!(
+                                    "[Niconico] Connected to message server for {}",
+                                    program_id
+                                ));
+                            }
+                        }
+                        Some("disconnect") => {
+                            logger.info(&format!(
+                                "[Niconico] Watch session for {} disconnected: {}",
+                                program_id, value["data"]["reason"]
+                            ));
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+                Some(result) = ndgr_task.join_next() => {
+                    // コメントサーバーから切断された場合はセッションごと張り直す（次回ポーリングで再開）
+                    match result {
+                        Ok(Ok(())) => {}
+                        Ok(Err(e)) => logger.error(&format!(
+                            "[Niconico] Message server for {} failed: {}",
+                            program_id, e
+                        )),
+                        Err(e) => logger.error(&format!(
+                            "[Niconico] Message server task for {} panicked: {}",
+                            program_id, e
+                        )),
+                    }
+                    break;
+                }
+                Some(chat) = chat_rx.recv() => {
+                    batch.push(Self::to_chat_message(chat, channel_id));
+                    if batch.len() >= db_constants::CHAT_BATCH_SIZE {
+                        Self::flush_batch(db_manager, channel_id, program_id, &mut batch, logger).await;
+                    }
+                }
+            }
+        }
+
+        ndgr_task.abort_all();
+        while let Ok(chat) = chat_rx.try_recv() {
+            batch.push(Self::to_chat_message(chat, channel_id));
+        }
+        Self::flush_batch(db_manager, channel_id, program_id, &mut batch, logger).await;
+        let _ = watch_ws.close(None).await;
+        Ok(())
+    }
+
+    /// NDGR のコメントを ChatMessage に変換（stream_id は保存時に解決する）
+    fn to_chat_message(chat: NdgrChat, channel_id: i64) -> ChatMessage {
+        // 184（匿名）コメントは生IDの代わりにハッシュ化されたIDが届く
+        let user_id = chat
+            .raw_user_id
+            .map(|id| id.to_string())
+            .or(chat.hashed_user_id);
+        let timestamp = chat.at.unwrap_or_else(Utc::now).to_rfc3339();
+        let badges = chat.is_premium.then(|| vec!["premium".to_string()]);
+
+        ChatMessage {
+            id: None,
+            channel_id: Some(channel_id),
+            stream_id: None,
+            timestamp,
+            platform: db_constants::PLATFORM_NICONICO.to_string(),
+            user_name: chat
+                .name
+                .clone()
+                .or_else(|| user_id.clone())
+                .unwrap_or_default(),
+            user_id,
+            display_name: chat.name,
+            message: chat.content,
+            message_type: "normal".to_string(),
+            badges,
+            badge_info: None,
+            bits: None,
+        }
+    }
+
+    async fn flush_batch(
+        db_manager: &Arc<DatabaseManager>,
+        channel_id: i64,
+        program_id: &str,
+        batch: &mut Vec<ChatMessage>,
+        logger: &Arc<AppLogger>,
+    ) {
+        if batch.is_empty() {
+            return;
+        }
+
+        let result = db_manager
+            .with_connection(|conn| {
+                let stream_id = DatabaseWriter::find_stream_id(conn, channel_id, program_id)?;
+                for message in batch.iter_mut() {
+                    message.stream_id = stream_id;
+                }
+                DatabaseWriter::insert_chat_messages_batch(conn, batch)
+            })
+            .await;
+
+        match result {
+            Ok(_) => {
+                logger.info(&format!(
+                    "[Niconico] Saved {} comments for {}",
+                    batch.len(),
+                    program_id
+                ));
+                batch.clear();
+            }
+            Err(e) => {
+                logger.error(&format!("[Niconico] Failed to save comments: {}", e));
+                // エラー時はバッチを保持して次回再試行する。
+                // 書き込みが失敗し続けてもメモリを使い切らないよう、上限を超えた分は古いものから破棄する
+                if batch.len() > db_constants::MAX_PENDING_CHAT_MESSAGES {
+                    let dropped = batch.len() - db_constants::MAX_PENDING_CHAT_MESSAGES;
+                    batch.drain(..dropped);
+                    logger.error(&format!(
+                        "[Niconico] Dropped {} unsaved comments for {}",
+                        dropped, program_id
+                    ));
+                }
+            }
+        }
+    }
+}
+
+impl Drop for NiconicoCommentSession {
+    fn drop(&mut self) {
+        let _ = self.shutdown_tx.send(true);
+    }
+}
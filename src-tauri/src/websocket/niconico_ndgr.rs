@@ -0,0 +1,338 @@
+/// ニコニコ生放送の新コメントサーバー（NDGR）クライアント
+///
+/// watch WebSocket の `messageServer` で通知される viewUri を起点に、
+/// 長さ区切りの Protocol Buffers ストリームを HTTP で読み出す。
+/// 1. `GET {viewUri}?at=now` で ChunkedEntry を受信し、`segment` のURIと次回の `next.at` を得る
+/// 2. 各 segment の URI から ChunkedMessage を受信し、chat を取り出す
+/// 3. view のストリームが終わったら `at={next.at}` で 1 に戻る
+///
+/// 必要なフィールドはごく一部のため、.proto からのコード生成は行わず wire format を直接デコードする。
+use chrono::{DateTime, TimeZone, Utc};
+use reqwest::{Client, RequestBuilder};
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+
+/// protobuf の wire format の値（固定長の値はこのクライアントでは使わない）
+enum WireValue<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Fixed,
+}
+
+fn read_varint(buf: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *buf.get(*pos)?;
+        *pos += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// メッセージのフィールドを (フィールド番号, 値) で列挙する（不正なデータに達したら打ち切る）
+fn fields(buf: &[u8]) -> impl Iterator<Item = (u64, WireValue<'_>)> {
+    let mut pos = 0;
+    std::iter::from_fn(move || {
+        if pos >= buf.len() {
+            return None;
+        }
+        let key = read_varint(buf, &mut pos)?;
+        let value = match key & 0x7 {
+            0 => WireValue::Varint(read_varint(buf, &mut pos)?),
+            1 | 5 => {
+                let len = if key & 0x7 == 1 { 8 } else { 4 };
+                buf.get(pos..pos + len)?;
+                pos += len;
+                WireValue::Fixed
+            }
+            2 => {
+                let len = usize::try_from(read_varint(buf, &mut pos)?).ok()?;
+                let end = pos.checked_add(len)?;
+                let bytes = buf.get(pos..end)?;
+                pos = end;
+                WireValue::Bytes(bytes)
+            }
+            _ => return None,
+        };
+        Some((key >> 3, value))
+    })
+}
+
+fn message_field(buf: &[u8], number: u64) -> Option<&[u8]> {
+    fields(buf).find_map(|(n, value)| match value {
+        WireValue::Bytes(bytes) if n == number => Some(bytes),
+        _ => None,
+    })
+}
+
+fn string_field(buf: &[u8], number: u64) -> Option<String> {
+    message_field(buf, number)
+        .and_then(|bytes| std::str::from_utf8(bytes).ok())
+        .map(String::from)
+}
+
+fn varint_field(buf: &[u8], number: u64) -> Option<u64> {
+    fields(buf).find_map(|(n, value)| match value {
+        WireValue::Varint(v) if n == number => Some(v),
+        _ => None,
+    })
+}
+
+/// google.protobuf.Timestamp をデコード
+fn decode_timestamp(buf: &[u8]) -> Option<DateTime<Utc>> {
+    let seconds = varint_field(buf, 1).unwrap_or(0) as i64;
+    let nanos = varint_field(buf, 2).unwrap_or(0) as u32;
+    Utc.timestamp_opt(seconds, nanos).single()
+}
+
+/// view API から受信する ChunkedEntry
+#[derive(Debug, PartialEq)]
+pub enum ChunkedEntry {
+    /// コメントを受信するセグメント
+    Segment { uri: String },
+    /// 次に view API を呼ぶときの `at`
+    Next { at: i64 },
+    /// 過去コメント（backward / previous）など、ライブ受信では使わないエントリ
+    Other,
+}
+
+impl ChunkedEntry {
+    pub fn decode(buf: &[u8]) -> Self {
+        for (number, value) in fields(buf) {
+            match (number, value) {
+                (1, WireValue::Bytes(segment)) => {
+                    if let Some(uri) = string_field(segment, 3) {
+                        return Self::Segment { uri };
+                    }
+                }
+                (4, WireValue::Bytes(next)) => {
+                    if let Some(at) = varint_field(next, 1) {
+                        return Self::Next { at: at as i64 };
+                    }
+                }
+                _ => {}
+            }
+        }
+        Self::Other
+    }
+}
+
+/// セグメントの ChunkedMessage から取り出したコメント
+#[derive(Debug, PartialEq)]
+pub struct NdgrChat {
+    pub content: String,
+    /// コテハン（設定されている場合のみ）
+    pub name: Option<String>,
+    /// 生ID（184 コメントの場合は None）
+    pub raw_user_id: Option<i64>,
+    /// 184 コメントのハッシュ化されたユーザーID
+    pub hashed_user_id: Option<String>,
+    pub is_premium: bool,
+    /// コメントサーバーが受け付けた時刻（meta.at）
+    pub at: Option<DateTime<Utc>>,
+}
+
+impl NdgrChat {
+    /// ChunkedMessage をデコードし、chat / overflowed_chat の場合のみ返す
+    pub fn decode(buf: &[u8]) -> Option<Self> {
+        let at = message_field(buf, 1)
+            .and_then(|meta| message_field(meta, 2))
+            .and_then(decode_timestamp);
+        let message = message_field(buf, 2)?;
+        let chat = message_field(message, 1).or_else(|| message_field(message, 20))?;
+
+        Some(Self {
+            content: string_field(chat, 1)?,
+            name: string_field(chat, 2).filter(|name| !name.is_empty()),
+            raw_user_id: varint_field(chat, 5).map(|id| id as i64),
+            hashed_user_id: string_field(chat, 6).filter(|id| !id.is_empty()),
+            // AccountStatus: 0=一般, 1=プレミアム
+            is_premium: varint_field(chat, 4) == Some(1),
+            at,
+        })
+    }
+}
+
+/// HTTP レスポンスのチャンクから長さ区切りのメッセージを切り出すバッファ
+#[derive(Default)]
+struct DelimitedReader {
+    buf: Vec<u8>,
+}
+
+impl DelimitedReader {
+    fn push(&mut self, chunk: &[u8]) {
+        self.buf.extend_from_slice(chunk);
+    }
+
+    /// 受信し終えたメッセージを1件取り出す（途中までしか届いていない場合は None）
+    fn next_message(&mut self) -> Option<Vec<u8>> {
+        let mut pos = 0;
+        let len = usize::try_from(read_varint(&self.buf, &mut pos)?).ok()?;
+        let end = pos.checked_add(len)?;
+        if self.buf.len() < end {
+            return None;
+        }
+        let message = self.buf[pos..end].to_vec();
+        self.buf.drain(..end);
+        Some(message)
+    }
+}
+
+/// 長さ区切りのストリームを読み、受信したメッセージごとに `on_message` を呼ぶ
+async fn read_stream(
+    request: RequestBuilder,
+    mut on_message: impl FnMut(&[u8]),
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut response = request.send().await?.error_for_status()?;
+    let mut reader = DelimitedReader::default();
+    while let Some(chunk) = response.chunk().await? {
+        reader.push(&chunk);
+        while let Some(message) = reader.next_message() {
+            on_message(&message);
+        }
+    }
+    Ok(())
+}
+
+/// セグメントのコメントを受信し、`tx` に送る
+async fn read_segment(client: Client, uri: String, tx: mpsc::UnboundedSender<NdgrChat>) {
+    let result = read_stream(client.get(&uri), |message| {
+        if let Some(chat) = NdgrChat::decode(message) {
+            let _ = tx.send(chat);
+        }
+    })
+    .await;
+    if let Err(e) = result {
+        eprintln!("[Niconico] Failed to read NDGR segment {}: {}", uri, e);
+    }
+}
+
+/// view API を辿ってコメントを受信し続け、`tx` に送る
+///
+/// 受信側が閉じられると終了する。view API の読み出しに失敗した場合はエラーを返す
+/// （セッションごと張り直すのは呼び出し側の責務）。
+pub async fn run(
+    client: Client,
+    view_uri: String,
+    tx: mpsc::UnboundedSender<NdgrChat>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // このタスクが中断されると JoinSet ごと破棄され、受信中のセグメントも停止する
+    let mut segments = JoinSet::new();
+    let mut at = "now".to_string();
+
+    while !tx.is_closed() {
+        let mut next_at = None;
+        read_stream(client.get(&view_uri).query(&[("at", &at)]), |message| {
+            match ChunkedEntry::decode(message) {
+                ChunkedEntry::Segment { uri } => {
+                    segments.spawn(read_segment(client.clone(), uri, tx.clone()));
+                }
+                ChunkedEntry::Next { at } => next_at = Some(at),
+                ChunkedEntry::Other => {}
+            }
+        })
+        .await?;
+
+        // 受信し終えたセグメントのタスクを回収する
+        while segments.try_join_next().is_some() {}
+
+        at = next_at
+            .ok_or("NDGR view stream ended without a next entry")?
+            .to_string();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn varint(mut value: u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                out.push(byte);
+                return out;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    fn varint_entry(number: u64, value: u64) -> Vec<u8> {
+        [varint(number << 3), varint(value)].concat()
+    }
+
+    fn bytes_entry(number: u64, bytes: &[u8]) -> Vec<u8> {
+        [
+            varint((number << 3) | 2),
+            varint(bytes.len() as u64),
+            bytes.to_vec(),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn test_decode_entries_and_chat() {
+        let segment = bytes_entry(1, &bytes_entry(3, b"https://example.com/segment"));
+        assert_eq!(
+            ChunkedEntry::decode(&segment),
+            ChunkedEntry::Segment {
+                uri: "https://example.com/segment".to_string()
+            }
+        );
+        let next = bytes_entry(4, &varint_entry(1, 1_700_000_000));
+        assert_eq!(
+            ChunkedEntry::decode(&next),
+            ChunkedEntry::Next { at: 1_700_000_000 }
+        );
+
+        let chat = [
+            bytes_entry(1, "こんにちは".as_bytes()),
+            varint_entry(3, 1234),
+            varint_entry(4, 1),
+            varint_entry(5, 12345),
+            varint_entry(8, 7),
+        ]
+        .concat();
+        let meta = [
+            bytes_entry(1, b"id"),
+            bytes_entry(2, &varint_entry(1, 1_700_000_000)),
+        ]
+        .concat();
+        let message = [
+            bytes_entry(1, &meta),
+            bytes_entry(2, &bytes_entry(1, &chat)),
+        ]
+        .concat();
+        let decoded = NdgrChat::decode(&message).unwrap();
+        assert_eq!(decoded.content, "こんにちは");
+        assert_eq!(decoded.raw_user_id, Some(12345));
+        assert!(decoded.is_premium);
+        assert_eq!(decoded.at, Utc.timestamp_opt(1_700_000_000, 0).single());
+
+        // chat 以外のメッセージ（ギフト等）は無視する
+        let gift = bytes_entry(2, &bytes_entry(8, b"gift"));
+        assert_eq!(NdgrChat::decode(&gift), None);
+
+        // 分割されて届いたストリームも1件ずつ切り出せる
+        let stream = [
+            varint(segment.len() as u64),
+            segment.clone(),
+            varint(next.len() as u64),
+            next.clone(),
+        ]
+        .concat();
+        let mut reader = DelimitedReader::default();
+        reader.push(&stream[..3]);
+        assert_eq!(reader.next_message(), None);
+        reader.push(&stream[3..]);
+        assert_eq!(reader.next_message(), Some(segment));
+        assert_eq!(reader.next_message(), Some(next));
+        assert_eq!(reader.next_message(), None);
+    }
+}
@@ -1,17 +1,96 @@
+use crate::constants::database as db_constants;
+use crate::constants::twitch::{
+    IRC_ANONYMOUS_LOGIN, IRC_REJOIN_INITIAL_BACKOFF_SECS, IRC_REJOIN_MAX_BACKOFF_SECS,
+    IRC_WATCHDOG_INTERVAL_SECS,
+};
 use crate::database::models::ChatMessage;
 use crate::database::DatabaseManager;
 use crate::logger::AppLogger;
-use chrono::Local;
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::Mutex;
-use twitch_irc::login::StaticLoginCredentials;
-use twitch_irc::message::ServerMessage;
+use std::time::{Duration, Instant};
+use tokio::sync::{watch, Mutex};
+use tokio::task::JoinHandle;
+use twitch_irc::login::{CredentialsPair, LoginCredentials};
+use twitch_irc::message::{Badge, ServerMessage, TwitchUserBasics};
 use twitch_irc::ClientConfig;
 use twitch_irc::SecureTCPTransport;
 use twitch_irc::TwitchIRCClient;
 
+/// IRC接続の認証情報
+///
+/// 匿名モードでは justinfan としてトークン無しで接続する（読み取り専用）。
+/// 認証モードではユーザートークンの持ち主のログイン名で接続する。
+/// twitch-irc は接続を張るたびに認証情報を取得するため、トークンの更新は次回の（再）接続から反映される。
+#[derive(Clone, Default)]
+pub struct IrcLoginCredentials {
+    /// (ログイン名, アクセストークン)。None の場合は匿名接続
+    auth: Arc<std::sync::RwLock<Option<(String, String)>>>,
+}
+
+impl IrcLoginCredentials {
+    /// 認証モードのログイン名とトークンを設定する
+    pub fn set_user(&self, login: String, token: String) {
+        if let Ok(mut auth) = self.auth.write() {
+            *auth = Some((login.to_lowercase(), token));
+        }
+    }
+
+    /// 認証モードのトークンのみ更新する（匿名接続の場合は何もしない）
+    pub fn update_token(&self, token: String) -> bool {
+        match self.auth.write() {
+            Ok(mut auth) => match auth.as_mut() {
+                Some((_, current)) => {
+                    *current = token;
+                    true
+                }
+                None => false,
+            },
+            Err(_) => false,
+        }
+    }
+
+    /// 認証済みアカウントで接続するか
+    pub fn is_authenticated(&self) -> bool {
+        self.auth.read().map(|auth| auth.is_some()).unwrap_or(false)
+    }
+}
+
+impl std::fmt::Debug for IrcLoginCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // トークンはログに出さない
+        f.debug_struct("IrcLoginCredentials")
+            .field("authenticated", &self.is_authenticated())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl LoginCredentials for IrcLoginCredentials {
+    type Error = std::convert::Infallible;
+
+    async fn get_credentials(&self) -> Result<CredentialsPair, Self::Error> {
+        let auth = self.auth.read().ok().and_then(|auth| auth.clone());
+        Ok(match auth {
+            Some((login, token)) => CredentialsPair {
+                login,
+                token: Some(token),
+            },
+            None => CredentialsPair {
+                login: IRC_ANONYMOUS_LOGIN.to_string(),
+                token: None,
+            },
+        })
+    }
+}
+
+/// JOIN 監視の対象（channel_id, ログイン名, 接続フラグ, 接続開始時刻）
+type RejoinTarget = (i64, String, Arc<AtomicBool>, Arc<Mutex<Option<String>>>);
+
 /// チャンネルごとのIRC接続管理
 struct ChannelConnection {
     channel_id: i64,
@@ -20,21 +99,50 @@ struct ChannelConnection {
     is_connected: Arc<AtomicBool>,
     message_count: Arc<AtomicU64>,
     last_message_at: Arc<Mutex<Option<String>>>,
+    /// 直近でJOINが確認された時刻（未参加の間は None）
+    connected_since: Arc<Mutex<Option<String>>>,
+}
+
+/// チャンネルごとのIRC接続状態（UI向け）
+#[derive(Debug, Clone, Serialize)]
+pub struct IrcChannelStatus {
+    pub channel_id: i64,
+    pub channel_name: String,
+    pub stream_id: Option<i64>,
+    pub is_connected: bool,
+    pub message_count: u64,
+    pub last_message_at: Option<String>,
+}
+
+/// 接続中（JOIN済み）のIRCチャンネルの収集状況
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatConnectionStatus {
+    pub channel_id: i64,
+    pub channel_name: String,
+    pub connected_since: Option<String>,
+    /// 収集開始からの受信メッセージ数
+    pub messages_collected: u64,
 }
 
 /// 複数のTwitch IRC接続を管理するマネージャー
 pub struct TwitchIrcManager {
     channels: Arc<Mutex<HashMap<i64, ChannelConnection>>>,
-    client: Arc<TwitchIRCClient<SecureTCPTransport, StaticLoginCredentials>>,
+    client: Arc<TwitchIRCClient<SecureTCPTransport, IrcLoginCredentials>>,
+    credentials: IrcLoginCredentials,
     logger: Arc<AppLogger>,
+    shutdown_tx: watch::Sender<bool>,
+    tasks: std::sync::Mutex<Vec<JoinHandle<()>>>,
 }
 
 impl TwitchIrcManager {
-    pub fn new(db_manager: Arc<DatabaseManager>, logger: Arc<AppLogger>) -> Self {
-        // 匿名ログインの設定
-        let config = ClientConfig::default();
+    pub fn new(
+        db_manager: Arc<DatabaseManager>,
+        logger: Arc<AppLogger>,
+        credentials: IrcLoginCredentials,
+    ) -> Self {
+        let config = ClientConfig::new_simple(credentials.clone());
         let (mut incoming_messages, client) =
-            TwitchIRCClient::<SecureTCPTransport, StaticLoginCredentials>::new(config);
+            TwitchIRCClient::<SecureTCPTransport, IrcLoginCredentials>::new(config);
 
         let client = Arc::new(client);
         let channels: Arc<Mutex<HashMap<i64, ChannelConnection>>> =
@@ -42,86 +150,96 @@ impl TwitchIrcManager {
         let db_manager_clone = Arc::clone(&db_manager);
         let logger_clone = Arc::clone(&logger);
         let channels_clone = Arc::clone(&channels);
+        let (shutdown_tx, mut shutdown_rx) = watch::channel(false);
+
+        // メッセージ受信タスクを開始（シャットダウンシグナルまで継続実行）
+        let incoming_task = tokio::spawn(async move {
+            let mut batch = Vec::with_capacity(db_constants::CHAT_BATCH_SIZE);
+            // チャットが途切れても溜まった分を書き込めるよう、受信とは独立したタイマーでもフラッシュする
+            let mut flush_ticker =
+                tokio::time::interval(Duration::from_secs(db_constants::BATCH_FLUSH_INTERVAL_SECS));
+            flush_ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                let message = tokio::select! {
+                    _ = shutdown_rx.changed() => {
+                        logger_clone.info("[IRC] Shutdown signal received, stopping receiver");
+                        break;
+                    }
+                    _ = flush_ticker.tick() => {
+                        Self::flush_batch(&db_manager_clone, &mut batch, &logger_clone).await;
+                        continue;
+                    }
+                    message = incoming_messages.recv() => match message {
+                        Some(message) => message,
+                        None => break,
+                    },
+                };
 
-        // メッセージ受信タスクを開始（バックグラウンドで継続実行）
-        let _incoming_task = tokio::spawn(async move {
-            let mut batch = Vec::new();
-            let mut last_flush = std::time::Instant::now();
-
-            while let Some(message) = incoming_messages.recv().await {
                 match message {
                     ServerMessage::Privmsg(msg) => {
-                        // チャンネル名から channel_id と stream_id を取得
-                        let channel_name = msg.channel_login.as_str();
-                        let channels_lock = channels_clone.lock().await;
-
-                        if let Some(conn) = channels_lock
-                            .values()
-                            .find(|c| c.channel_name.to_lowercase() == channel_name)
+                        // cheer付きメッセージは bits タグで判別する
+                        let message_type = if msg.bits.is_some() {
+                            "cheer"
+                        } else {
+                            "normal"
+                        };
+
+                        if let Some(chat_message) = Self::build_chat_message(
+                            &channels_clone,
+                            msg.channel_login.as_str(),
+                            &msg.sender,
+                            msg.message_text.clone(),
+                            message_type,
+                            msg.bits.map(|b| b as i64),
+                            &msg.badges,
+                            &msg.badge_info,
+                        )
+                        .await
+                        {
+                            batch.push(chat_message);
+                        }
+                    }
+                    ServerMessage::UserNotice(msg) => {
+                        // msg-id（sub / resub / subgift / raid など）をそのまま message_type にする
+                        // ユーザーのメッセージが無い場合はシステムメッセージを保存する
+                        let message_text = msg
+                            .message_text
+                            .clone()
+                            .unwrap_or_else(|| msg.system_message.clone());
+
+                        if let Some(chat_message) = Self::build_chat_message(
+                            &channels_clone,
+                            msg.channel_login.as_str(),
+                            &msg.sender,
+                            message_text,
+                            msg.event_id.as_str(),
+                            None,
+                            &msg.badges,
+                            &msg.badge_info,
+                        )
+                        .await
                         {
-                            let channel_id = conn.channel_id;
-                            let stream_id = *conn.stream_id.lock().await;
-
-                            // 統計を更新
-                            conn.message_count.fetch_add(1, Ordering::SeqCst);
-                            *conn.last_message_at.lock().await = Some(Local::now().to_rfc3339());
-
-                            // バッジ情報を配列として取得（バッジ名のみ）
-                            let badges = if msg.badges.is_empty() {
-                                None
-                            } else {
-                                Some(
-                                    msg.badges
-                                        .iter()
-                                        .map(|badge| badge.name.clone())
-                                        .collect::<Vec<String>>(),
-                                )
-                            };
-
-                            // badge_info（サブスク月数等の詳細情報）を取得
-                            let badge_info = if msg.badge_info.is_empty() {
-                                None
-                            } else {
-                                Some(
-                                    msg.badge_info
-                                        .iter()
-                                        .map(|bi| format!("{}:{}", bi.name, bi.version))
-                                        .collect::<Vec<_>>()
-                                        .join(","),
-                                )
-                            };
-
-                            let chat_message = ChatMessage {
-                                id: None,
-                                channel_id: Some(channel_id),
-                                stream_id,
-                                timestamp: Local::now().to_rfc3339(),
-                                platform: crate::constants::database::PLATFORM_TWITCH.to_string(),
-                                user_id: Some(msg.sender.id.clone()),
-                                user_name: msg.sender.login.clone(),
-                                display_name: Some(msg.sender.name.clone()), // Twitch表示名を保存
-                                message: msg.message_text.clone(),
-                                message_type: "normal".to_string(),
-                                badges,
-                                badge_info,
-                            };
-
                             batch.push(chat_message);
                         }
                     }
                     ServerMessage::Join(_) => {}
                     ServerMessage::Part(_) => {}
                     ServerMessage::Reconnect(_) => {
+                        // 接続の張り直しと再JOINは twitch-irc が即座に行う。
+                        // 再参加が確認できるまでは未接続として扱う
                         logger_clone.info("[IRC] Server requested reconnect");
+                        for conn in channels_clone.lock().await.values() {
+                            conn.is_connected.store(false, Ordering::SeqCst);
+                        }
                     }
                     _ => {}
                 }
 
-                // バッチフラッシュ（100件または5秒ごと）
-                if (batch.len() >= 100 || last_flush.elapsed().as_secs() >= 5) && !batch.is_empty()
-                {
+                // 件数が溜まったら即フラッシュ（時間経過分は flush_ticker で処理）
+                if batch.len() >= db_constants::CHAT_BATCH_SIZE {
                     Self::flush_batch(&db_manager_clone, &mut batch, &logger_clone).await;
-                    last_flush = std::time::Instant::now();
+                    flush_ticker.reset();
                 }
             }
 
@@ -131,16 +249,188 @@ impl TwitchIrcManager {
             }
         });
 
-        // incoming_taskを保持しないため、バックグラウンドで継続実行される
         // db_managerは直接保持せず、flush_batch内で使用する
+        let watchdog_task = Self::spawn_join_watchdog(
+            Arc::clone(&client),
+            Arc::clone(&channels),
+            Arc::clone(&logger),
+            shutdown_tx.subscribe(),
+        );
 
         Self {
             channels,
             client,
+            credentials,
             logger,
+            shutdown_tx,
+            tasks: std::sync::Mutex::new(vec![incoming_task, watchdog_task]),
         }
     }
 
+    /// チャンネル参加状態を監視し、外れたチャンネルを指数バックオフで再参加させる
+    ///
+    /// 接続断・RECONNECT後の再接続自体は twitch-irc が行うが、JOINが通らないまま
+    /// になるケースがあるため、参加が確認できるまで 1秒→30秒 の間隔で JOIN を再送する。
+    fn spawn_join_watchdog(
+        client: Arc<TwitchIRCClient<SecureTCPTransport, IrcLoginCredentials>>,
+        channels: Arc<Mutex<HashMap<i64, ChannelConnection>>>,
+        logger: Arc<AppLogger>,
+        mut shutdown_rx: watch::Receiver<bool>,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            // channel_id -> (次回のバックオフ, 次回再試行時刻)
+            let mut backoffs: HashMap<i64, (Duration, Instant)> = HashMap::new();
+
+            loop {
+                tokio::select! {
+                    _ = shutdown_rx.changed() => break,
+                    _ = tokio::time::sleep(Duration::from_secs(IRC_WATCHDOG_INTERVAL_SECS)) => {}
+                }
+
+                let targets: Vec<RejoinTarget> = channels
+                    .lock()
+                    .await
+                    .values()
+                    .map(|c| {
+                        (
+                            c.channel_id,
+                            c.channel_name.to_lowercase(),
+                            Arc::clone(&c.is_connected),
+                            Arc::clone(&c.connected_since),
+                        )
+                    })
+                    .collect();
+                backoffs.retain(|id, _| targets.iter().any(|(t, _, _, _)| t == id));
+
+                for (channel_id, login, is_connected, connected_since) in targets {
+                    let (_wanted, joined) = client.get_channel_status(login.clone()).await;
+                    let was_connected = is_connected.swap(joined, Ordering::SeqCst);
+                    if joined != was_connected {
+                        *connected_since.lock().await = joined.then(|| Utc::now().to_rfc3339());
+                    }
+
+                    if joined {
+                        if backoffs.remove(&channel_id).is_some() {
+                            logger.info(&format!("[IRC] Rejoined channel {}", login));
+                        }
+                        continue;
+                    }
+
+                    let now = Instant::now();
+                    let (backoff, next_attempt) = backoffs
+                        .entry(channel_id)
+                        .or_insert((Duration::from_secs(IRC_REJOIN_INITIAL_BACKOFF_SECS), now));
+                    if now < *next_attempt {
+                        continue;
+                    }
+
+                    logger.info(&format!(
+                        "[IRC] Channel {} is not joined, retrying JOIN (next retry in {:?})",
+                        login, backoff
+                    ));
+                    if let Err(e) = client.join(login.clone()) {
+                        logger.error(&format!("[IRC] Failed to rejoin {}: {}", login, e));
+                    }
+
+                    *next_attempt = now + *backoff;
+                    *backoff = (*backoff * 2).min(Duration::from_secs(IRC_REJOIN_MAX_BACKOFF_SECS));
+                }
+            }
+        })
+    }
+
+    /// 受信タスクと監視タスクを停止する（未保存のチャットはフラッシュしてから終了）
+    pub async fn shutdown(&self) {
+        let _ = self.shutdown_tx.send(true);
+
+        let tasks: Vec<JoinHandle<()>> = match self.tasks.lock() {
+            Ok(mut tasks) => tasks.drain(..).collect(),
+            Err(_) => Vec::new(),
+        };
+        for task in tasks {
+            if let Err(e) = task.await {
+                self.logger
+                    .error(&format!("[IRC] Background task ended abnormally: {}", e));
+            }
+        }
+
+        self.logger.info("[IRC] Shutdown completed");
+    }
+
+    /// 受信メッセージを監視中チャンネルの ChatMessage に変換し、接続統計を更新する
+    ///
+    /// 監視対象外のチャンネルの場合は None を返す
+    #[allow(clippy::too_many_arguments)]
+    async fn build_chat_message(
+        channels: &Arc<Mutex<HashMap<i64, ChannelConnection>>>,
+        channel_login: &str,
+        sender: &TwitchUserBasics,
+        message_text: String,
+        message_type: &str,
+        bits: Option<i64>,
+        badges: &[Badge],
+        badge_info: &[Badge],
+    ) -> Option<ChatMessage> {
+        // チャンネル名から channel_id と stream_id を取得
+        let channels_lock = channels.lock().await;
+        let conn = channels_lock
+            .values()
+            .find(|c| c.channel_name.to_lowercase() == channel_login)?;
+
+        let channel_id = conn.channel_id;
+        let stream_id = *conn.stream_id.lock().await;
+
+        // 統計を更新
+        conn.message_count.fetch_add(1, Ordering::SeqCst);
+        *conn.last_message_at.lock().await = Some(Utc::now().to_rfc3339());
+
+        // バッジ情報を配列として取得（バッジ名のみ）
+        let badges = if badges.is_empty() {
+            None
+        } else {
+            Some(
+                badges
+                    .iter()
+                    .map(|badge| badge.name.clone())
+                    .collect::<Vec<String>>(),
+            )
+        };
+
+        // badge_info（サブスク月数等の詳細情報）を取得
+        let badge_info = if badge_info.is_empty() {
+            None
+        } else {
+            Some(
+                badge_info
+                    .iter()
+                    .map(|bi| format!("{}:{}", bi.name, bi.version))
+                    .collect::<Vec<_>>()
+                    .join(","),
+            )
+        };
+
+        // IRCv3タグ（user-id / display-name）は twitch-irc がデコード済み。
+        // タグが欠落している場合は空文字になるため None として保存する
+        let user_id = Some(sender.id.clone()).filter(|id| !id.is_empty());
+        let display_name = Some(sender.name.clone()).filter(|name| !name.is_empty());
+
+        Some(ChatMessage {
+            id: None,
+            channel_id: Some(channel_id),
+            stream_id,
+            timestamp: Utc::now().to_rfc3339(),
+            platform: crate::constants::database::PLATFORM_TWITCH.to_string(),
+            user_id,
+            user_name: sender.login.clone(),
+            display_name, // Twitch表示名を保存
+            message: message_text,
+            message_type: message_type.to_string(),
+            badges,
+            badge_info,
+            bits,
+        })
+    }
+
     /// データベース書き込みハンドラーを起動（twitch-ircでは不要だが互換性のために残す）
     pub async fn start_db_handler(&self) {
         self.logger
@@ -183,7 +473,6 @@ impl TwitchIrcManager {
         &self,
         channel_id: i64,
         channel_name: &str,
-        _access_token: &str,
     ) -> Result<(), String> {
         let mut channels = self.channels.lock().await;
 
@@ -205,9 +494,11 @@ impl TwitchIrcManager {
             channel_id,
             channel_name: channel_name.to_string(),
             stream_id: Arc::new(Mutex::new(None)),
-            is_connected: Arc::new(AtomicBool::new(true)),
+            // JOIN完了は監視タスクが get_channel_status で確認して反映する
+            is_connected: Arc::new(AtomicBool::new(false)),
             message_count: Arc::new(AtomicU64::new(0)),
             last_message_at: Arc::new(Mutex::new(None)),
+            connected_since: Arc::new(Mutex::new(None)),
         };
 
         channels.insert(channel_id, connection);
@@ -256,9 +547,51 @@ impl TwitchIrcManager {
         }
     }
 
-    /// アクセストークンを更新（twitch-ircでは認証なし接続のため不要だが互換性のために残す）
-    pub async fn update_access_token(&self, _token: String) {
-        self.logger
-            .info("[IRC] Token update not needed for anonymous connection");
+    /// 接続中の全チャンネルのIRC状態を取得
+    pub async fn get_channel_statuses(&self) -> Vec<IrcChannelStatus> {
+        let channels = self.channels.lock().await;
+        let mut statuses = Vec::with_capacity(channels.len());
+
+        for connection in channels.values() {
+            statuses.push(IrcChannelStatus {
+                channel_id: connection.channel_id,
+                channel_name: connection.channel_name.clone(),
+                stream_id: *connection.stream_id.lock().await,
+                is_connected: connection.is_connected.load(Ordering::SeqCst),
+                message_count: connection.message_count.load(Ordering::SeqCst),
+                last_message_at: connection.last_message_at.lock().await.clone(),
+            });
+        }
+
+        statuses
+    }
+
+    /// JOIN済みのチャンネルと収集状況を取得（チャンネル名順）
+    pub async fn get_active_connections(&self) -> Vec<ChatConnectionStatus> {
+        let channels = self.channels.lock().await;
+        let mut connections = Vec::new();
+
+        for connection in channels.values() {
+            if !connection.is_connected.load(Ordering::SeqCst) {
+                continue;
+            }
+            connections.push(ChatConnectionStatus {
+                channel_id: connection.channel_id,
+                channel_name: connection.channel_name.clone(),
+                connected_since: connection.connected_since.lock().await.clone(),
+                messages_collected: connection.message_count.load(Ordering::SeqCst),
+            });
+        }
+
+        connections.sort_by(|a, b| a.channel_name.cmp(&b.channel_name));
+        connections
+    }
+
+    /// アクセストークンを更新（認証モードのみ。次回の再接続から反映される）
+    pub async fn update_access_token(&self, token: String) {
+        if self.credentials.update_token(token) {
+            self.logger
+                .info("[IRC] Access token updated for authenticated connection");
+        }
     }
 }
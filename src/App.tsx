@@ -20,6 +20,8 @@ import "./App.css";
 import { SQLViewer } from "./components/SQL";
 import Timeline from "./components/Timeline";
 import { listen } from "@tauri-apps/api/event";
+import type { StreamLifecycleEvent } from "./types";
+import type { ScheduledStream } from "./schemas";
 import { NavigationProvider } from "./contexts/NavigationContext";
 
 const queryClient = new QueryClient({
@@ -128,6 +130,16 @@ function App() {
         console.log("Channel stats updated (no automatic channels refetch to avoid Twitch API overuse)");
       });
 
+      // 配信開始・終了イベント（頻度が低いため、ライブ状態を即座に反映する）
+      const streamStartedUnlisten = await listen<StreamLifecycleEvent>("stream-started", (event) => {
+        console.log("Stream started:", event.payload);
+        queryClient.invalidateQueries({ queryKey: ["channels"] });
+      });
+      const streamEndedUnlisten = await listen<StreamLifecycleEvent>("stream-ended", (event) => {
+        console.log("Stream ended:", event.payload);
+        queryClient.invalidateQueries({ queryKey: ["channels"] });
+      });
+
       // チャンネル追加イベント（自動発見で新チャンネル追加時）
       const channelsUpdatedUnlisten = await listen("channels-updated", () => {
         console.log("Channels list updated, refreshing channels");
@@ -140,6 +152,35 @@ function App() {
         queryClient.invalidateQueries({ queryKey: ["channels"] });
       });
 
+      // DB保存先が使えずデフォルトパスにフォールバックした場合の警告
+      const databasePathWarningUnlisten = await listen<string>("database-path-warning", (event) => {
+        console.warn("Database path fallback:", event.payload);
+        addToast(event.payload, "warning", 10000);
+      });
+
+      // 破損したDBファイルを退避して新規DBで起動した場合の通知
+      const databaseRecoveredUnlisten = await listen<string>("database-recovered", (event) => {
+        console.warn("Corrupted database quarantined:", event.payload);
+        addToast(
+          `データベースファイルが破損していたため退避し、新しいデータベースで起動しました（退避先: ${event.payload}）`,
+          "error",
+          15000
+        );
+      });
+
+      // 定期同期（CHECKPOINT）の失敗。ディスクフル等で永続化が止まっている可能性がある
+      const databaseSyncFailedUnlisten = await listen<{
+        error: string;
+        consecutive_failures: number;
+      }>("database-sync-failed", (event) => {
+        console.error("Database sync failed:", event.payload);
+        addToast(
+          `データベースの保存に失敗しました（${event.payload.consecutive_failures}回連続）。ディスクの空き容量などを確認してください: ${event.payload.error}`,
+          "error",
+          15000
+        );
+      });
+
       // 自動発見ストリーム更新イベント
       const discoveredStreamsUnlisten = await listen("discovered-streams-updated", () => {
         console.log("Discovered streams updated, refreshing discovered streams");
@@ -156,6 +197,51 @@ function App() {
         );
       });
 
+      // ポーリング連続失敗による一時停止イベント
+      const channelPollFailedUnlisten = await listen<{
+        channel_name: string;
+        consecutive_failures: number;
+        last_error: string;
+        pause_secs: number;
+      }>("channel-poll-failed", (event) => {
+        console.error("Channel polling paused:", event.payload);
+        addToast(
+          `${event.payload.channel_name} のポーリングが${event.payload.consecutive_failures}回連続で失敗したため、${Math.round(event.payload.pause_secs / 60)}分間一時停止します（その後自動で再試行します）`,
+          "error",
+          10000
+        );
+      });
+
+      // 視聴者数アラート（チャンネルごとの閾値超過）
+      const viewerThresholdUnlisten = await listen<{
+        channel_name: string;
+        viewer_count: number;
+        threshold: number;
+      }>("viewer-threshold-exceeded", (event) => {
+        addToast(
+          `${event.payload.channel_name} の視聴者数が${event.payload.threshold}人を超えました（現在 ${event.payload.viewer_count}人）`,
+          "info",
+          10000
+        );
+      });
+
+      // 配信予定のリマインダー（開始予定の少し前に1回だけ通知される）
+      const scheduledStreamReminderUnlisten = await listen<ScheduledStream>(
+        "scheduled-stream-reminder",
+        (event) => {
+          const { display_name, channel_name, scheduled_start, title } = event.payload;
+          const startAt = new Date(scheduled_start).toLocaleTimeString([], {
+            hour: "2-digit",
+            minute: "2-digit",
+          });
+          addToast(
+            `${display_name ?? channel_name} の配信が ${startAt} に始まる予定です${title ? `: ${title}` : ""}`,
+            "info",
+            10000
+          );
+        }
+      );
+
       // 自動発見エラーイベント
       const autoDiscoveryErrorUnlisten = await listen<string>("auto-discovery-error", (event) => {
         console.error("Auto-discovery error:", event.payload);
@@ -168,11 +254,19 @@ function App() {
         twitchAuthRequiredUnlisten();
         backendReadyUnlisten();
         channelStatsUnlisten();
+        streamStartedUnlisten();
+        streamEndedUnlisten();
         channelsUpdatedUnlisten();
         channelRemovedUnlisten();
         discoveredStreamsUnlisten();
         authErrorUnlisten();
         autoDiscoveryErrorUnlisten();
+        channelPollFailedUnlisten();
+        viewerThresholdUnlisten();
+        scheduledStreamReminderUnlisten();
+        databasePathWarningUnlisten();
+        databaseRecoveredUnlisten();
+        databaseSyncFailedUnlisten();
       };
     };
 
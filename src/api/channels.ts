@@ -3,10 +3,16 @@ import { z } from 'zod';
 import {
   ChannelWithStatsSchema,
   ChannelSchema,
+  LiveChannelSchema,
+  ScheduledStreamSchema,
+  ChannelSummarySchema,
   AddChannelRequestSchema,
   UpdateChannelRequestSchema,
   type ChannelWithStats,
   type Channel,
+  type LiveChannel,
+  type ScheduledStream,
+  type ChannelSummary,
   type AddChannelRequest,
   type UpdateChannelRequest,
 } from '../schemas';
@@ -41,9 +47,10 @@ export const addChannel = async (request: AddChannelRequest): Promise<Channel> =
 
 /**
  * チャンネルを削除
+ * @param deleteData false の場合は論理削除のみ行い、過去の配信・統計・チャットデータを残す（省略時は true）
  */
-export const removeChannel = async (id: number): Promise<void> => {
-  await invoke('remove_channel', { id });
+export const removeChannel = async (id: number, deleteData?: boolean): Promise<void> => {
+  await invoke('remove_channel', { id, deleteData });
 };
 
 /**
@@ -51,12 +58,7 @@ export const removeChannel = async (id: number): Promise<void> => {
  */
 export const updateChannel = async (request: UpdateChannelRequest): Promise<Channel> => {
   const validatedRequest = UpdateChannelRequestSchema.parse(request);
-  const result = await invoke<unknown>('update_channel', {
-    id: validatedRequest.id,
-    channel_name: validatedRequest.channel_name,
-    poll_interval: validatedRequest.poll_interval,
-    enabled: validatedRequest.enabled,
-  });
+  const result = await invoke<unknown>('update_channel', { request: validatedRequest });
   return ChannelSchema.parse(result);
 };
 
@@ -67,3 +69,96 @@ export const toggleChannel = async (id: number): Promise<Channel> => {
   const result = await invoke<unknown>('toggle_channel', { id });
   return ChannelSchema.parse(result);
 };
+
+/**
+ * 複数チャンネルの有効/無効を一括で設定
+ */
+export const setChannelsEnabled = async (
+  channelIds: number[],
+  enabled: boolean
+): Promise<Channel[]> => {
+  const result = await invoke<unknown>('set_channels_enabled', { channelIds, enabled });
+  return z.array(ChannelSchema).parse(result);
+};
+
+/**
+ * 指定タグが付いたチャンネルの有効/無効を一括で設定
+ */
+export const setTagChannelsEnabled = async (tag: string, enabled: boolean): Promise<Channel[]> => {
+  const result = await invoke<unknown>('set_tag_channels_enabled', { tag, enabled });
+  return z.array(ChannelSchema).parse(result);
+};
+
+/**
+ * 視聴者数アラートの閾値を設定（null で解除）
+ */
+export const setChannelAlertThreshold = async (
+  channelId: number,
+  threshold: number | null
+): Promise<Channel> => {
+  const result = await invoke<unknown>('set_channel_alert_threshold', { channelId, threshold });
+  return ChannelSchema.parse(result);
+};
+
+/**
+ * キャッシュ済みのチャンネルアイコン画像のローカルパスを取得
+ *
+ * 未キャッシュの場合はバックエンドでダウンロードしてからパスを返す。
+ * プロフィール画像が未登録、またはダウンロードできない場合は null（`profile_image_url` を直接使う）
+ */
+export const getChannelIconPath = async (channelId: number): Promise<string | null> => {
+  const result = await invoke<unknown>('get_channel_icon_path', { channelId });
+  return z.string().nullable().parse(result);
+};
+
+/**
+ * チャンネルにタグを付与
+ * @returns 付与後のチャンネルのタグ一覧
+ */
+export const addChannelTag = async (channelId: number, tag: string): Promise<string[]> => {
+  const result = await invoke<unknown>('add_channel_tag', { channelId, tag });
+  return z.array(z.string()).parse(result);
+};
+
+/**
+ * チャンネルからタグを外す
+ * @returns 削除後のチャンネルのタグ一覧
+ */
+export const removeChannelTag = async (channelId: number, tag: string): Promise<string[]> => {
+  const result = await invoke<unknown>('remove_channel_tag', { channelId, tag });
+  return z.array(z.string()).parse(result);
+};
+
+/**
+ * 指定タグが付いたチャンネル一覧を取得
+ */
+export const listChannelsByTag = async (tag: string): Promise<Channel[]> => {
+  const result = await invoke<unknown>('list_channels_by_tag', { tag });
+  return z.array(ChannelSchema).parse(result);
+};
+
+/**
+ * 現在ライブ中のチャンネル一覧を取得（視聴者数降順）
+ * @param staleMinutes この時間（分）統計が更新されていない配信はゴースト配信として除外する
+ */
+export const getLiveChannels = async (staleMinutes?: number): Promise<LiveChannel[]> => {
+  const result = await invoke<unknown>('get_live_channels', { staleMinutes });
+  return z.array(LiveChannelSchema).parse(result);
+};
+
+/**
+ * 今後の配信予定を開始予定順に取得
+ * @param channelId 省略時は全チャンネル
+ */
+export const getScheduledStreams = async (channelId?: number): Promise<ScheduledStream[]> => {
+  const result = await invoke<unknown>('get_scheduled_streams', { channelId });
+  return z.array(ScheduledStreamSchema).parse(result);
+};
+
+/**
+ * チャンネルの総合サマリー（全期間・直近30日・前期間比）を取得
+ */
+export const getChannelStats = async (channelId: number): Promise<ChannelSummary> => {
+  const result = await invoke<unknown>('get_channel_stats', { channelId });
+  return ChannelSummarySchema.parse(result);
+};
@@ -3,8 +3,12 @@ import { z } from 'zod';
 import {
   OAuthConfigSchema,
   TwitchRateLimitStatusSchema,
+  YouTubeQuotaStatusSchema,
+  ChatFilterSettingsSchema,
   type OAuthConfig,
   type TwitchRateLimitStatus,
+  type YouTubeQuotaStatus,
+  type ChatFilterSettings,
 } from '../schemas';
 
 /**
@@ -50,12 +54,14 @@ export const startTwitchDeviceAuth = async (): Promise<any> => {
 export const pollTwitchDeviceToken = async (
   deviceCode: string,
   interval: number,
-  clientId: string
+  clientId: string,
+  expiresIn?: number
 ): Promise<string> => {
   return await invoke('poll_twitch_device_token', {
     deviceCode,
     interval,
     clientId,
+    expiresIn,
   });
 };
 
@@ -89,6 +95,24 @@ export const hasOAuthConfig = async (platform: string): Promise<boolean> => {
   return z.boolean().parse(result);
 };
 
+/**
+ * チャット集計時の bot 除外設定を取得
+ */
+export const getChatFilterSettings = async (): Promise<ChatFilterSettings> => {
+  const result = await invoke<unknown>('get_chat_filter_settings');
+  return ChatFilterSettingsSchema.parse(result);
+};
+
+/**
+ * チャット集計時の bot 除外設定を保存（正規表現が不正な場合はエラー）
+ */
+export const saveChatFilterSettings = async (
+  chatFilter: ChatFilterSettings
+): Promise<ChatFilterSettings> => {
+  const result = await invoke<unknown>('save_chat_filter_settings', { chatFilter });
+  return ChatFilterSettingsSchema.parse(result);
+};
+
 /**
  * Twitch APIレート制限状態を取得
  */
@@ -97,6 +121,39 @@ export const getTwitchRateLimitStatus = async (): Promise<TwitchRateLimitStatus>
   return TwitchRateLimitStatusSchema.parse(result);
 };
 
+/** Twitch IRC に認証済みアカウントで接続するか（false は匿名接続） */
+export const getTwitchIrcAuthenticated = async (): Promise<boolean> => {
+  return await invoke<boolean>('get_twitch_irc_authenticated');
+};
+
+/** Twitch IRC の接続モードを保存（Twitchコレクターの次回初期化時から反映） */
+export const setTwitchIrcAuthenticated = async (authenticated: boolean): Promise<void> => {
+  await invoke('set_twitch_irc_authenticated', { authenticated });
+};
+
+/** 設定ファイルの暗号化が有効か */
+export const getSettingsEncryption = async (): Promise<boolean> => {
+  return await invoke<boolean>('get_settings_encryption');
+};
+
+/** 設定ファイルの暗号化を切り替える（有効にすると既存の平文設定を暗号化して保存し直す） */
+export const setSettingsEncryption = async (enabled: boolean): Promise<void> => {
+  await invoke('set_settings_encryption', { enabled });
+};
+
+export const getYouTubeQuotaUsage = async (): Promise<YouTubeQuotaStatus> => {
+  const result = await invoke<unknown>('get_youtube_quota_usage');
+  return YouTubeQuotaStatusSchema.parse(result);
+};
+
+/** dailyQuotaLimit 未指定時はデフォルト（10,000ユニット） */
+export const setYouTubeQuotaSettings = async (
+  dailyQuotaLimit: number | null,
+  adaptive: boolean
+): Promise<void> => {
+  await invoke('set_youtube_quota_settings', { dailyQuotaLimit, adaptive });
+};
+
 export interface TwitchChannelInfo {
   channel_id: string;
   twitch_user_id: number;
@@ -1,4 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import type { ExportQuery } from '../schemas';
 
 /**
@@ -14,17 +15,82 @@ export async function previewExportData(
   });
 }
 
+/**
+ * エクスポートで出力される件数を取得（大量エクスポート前の確認用）
+ */
+export async function countExportRows(query: ExportQuery): Promise<number> {
+  return await invoke<number>('count_export_rows', { query });
+}
+
 /**
  * 区切り形式でエクスポート
+ * compress を指定するか filePath が .zip の場合は ZIP 圧縮して出力される
  */
 export async function exportToDelimited(
   query: ExportQuery,
   filePath: string,
-  includeBom?: boolean
+  includeBom?: boolean,
+  compress?: boolean
 ): Promise<string> {
   return await invoke<string>('export_to_delimited', {
     query,
     filePath,
     includeBom,
+    compress,
   });
 }
+
+/**
+ * 統計データを指定フォーマット（csv / tsv / xlsx）でエクスポート
+ * xlsx は streams / stats / chat の3シートに分けて出力される
+ * csv / tsv は compress を指定するか filePath が .zip の場合に ZIP 圧縮される
+ */
+export async function exportStats(
+  query: ExportQuery,
+  filePath: string,
+  format: 'csv' | 'tsv' | 'xlsx',
+  includeBom?: boolean,
+  compress?: boolean
+): Promise<string> {
+  return await invoke<string>('export_stats', {
+    query,
+    filePath,
+    format,
+    includeBom,
+    compress,
+  });
+}
+
+/**
+ * streams / stream_stats / chat_messages を Parquet 形式でエクスポート
+ * filePath が .zip の場合は複数テーブルを1つのアーカイブにまとめる（table 省略時は全テーブル）
+ */
+export async function exportToParquet(
+  query: ExportQuery,
+  filePath: string,
+  table?: 'streams' | 'stream_stats' | 'chat_messages'
+): Promise<string> {
+  return await invoke<string>('export_to_parquet', {
+    query,
+    filePath,
+    table,
+  });
+}
+
+/**
+ * エクスポート進捗イベント（export-progress）のペイロード
+ */
+export interface ExportProgress {
+  file_path: string;
+  processed: number;
+  total: number;
+}
+
+/**
+ * CSV / 区切り形式エクスポートの進捗を購読
+ */
+export async function onExportProgress(
+  callback: (progress: ExportProgress) => void
+): Promise<UnlistenFn> {
+  return await listen<ExportProgress>('export-progress', (event) => callback(event.payload));
+}
@@ -16,6 +16,23 @@ const DatabaseInfoSchema = z.object({
   size_bytes: z.number(),
 });
 
+const DatabaseStatsSchema = z.object({
+  channels: z.number(),
+  streams: z.number(),
+  stream_stats_rows: z.number(),
+  chat_messages_rows: z.number(),
+  file_size_bytes: z.number(),
+  oldest_record: z.string().nullable(),
+  newest_record: z.string().nullable(),
+  storage_usage: z
+    .object({
+      used_block_bytes: z.number(),
+      memory_usage_bytes: z.number(),
+      temporary_storage_bytes: z.number(),
+    })
+    .nullable(),
+});
+
 /**
  * SQLクエリを実行
  */
@@ -24,6 +41,19 @@ export const executeSqlQuery = async (query: string): Promise<SqlQueryResult> =>
   return SqlQueryResultSchema.parse(result);
 };
 
+/**
+ * 読み取り専用でSQLクエリを実行（`:name` プレースホルダーには params の値をバインド）
+ * templateId を指定するとテンプレートのパラメータ定義（型・デフォルト値）を使用する
+ */
+export const executeReadOnlyQuery = async (
+  query: string,
+  params?: Record<string, unknown>,
+  templateId?: number
+): Promise<SqlQueryResult> => {
+  const result = await invoke<unknown>('execute_sql_query', { query, params, templateId });
+  return SqlQueryResultSchema.parse(result);
+};
+
 /**
  * SQLテンプレート一覧を取得
  */
@@ -55,6 +85,18 @@ export const listDatabaseTables = async (): Promise<TableInfo[]> => {
   return z.array(TableInfoSchema).parse(result);
 };
 
+/**
+ * 外部の Parquet / CSV ファイルを一時ビューとして登録
+ */
+export const importExternalData = async (
+  path: string,
+  tableName: string,
+  format: 'parquet' | 'csv'
+): Promise<string> => {
+  const result = await invoke<unknown>('import_external_data', { path, tableName, format });
+  return z.string().parse(result);
+};
+
 /**
  * データベース情報を取得
  */
@@ -62,3 +104,21 @@ export const getDatabaseInfo = async (): Promise<{ path: string; size_bytes: num
   const result = await invoke<unknown>('get_database_info');
   return DatabaseInfoSchema.parse(result);
 };
+
+/**
+ * DBに蓄積されたデータ量（行数・記録期間）とサイズ・メモリ使用量を取得
+ */
+export const getDatabaseStats = async (): Promise<z.infer<typeof DatabaseStatsSchema>> => {
+  const result = await invoke<unknown>('get_database_stats');
+  return DatabaseStatsSchema.parse(result);
+};
+
+/**
+ * DBファイルの保存先を変更（次回起動時から有効）
+ * path を null にすると既定の保存先に戻す。migrate が true の場合は現在のDBを新しい保存先へコピーする
+ * @returns 次回起動時に使用されるDBファイルパス
+ */
+export const setDatabasePath = async (path: string | null, migrate: boolean): Promise<string> => {
+  const result = await invoke<unknown>('set_database_path', { path, migrate });
+  return z.string().parse(result);
+};
@@ -7,8 +7,10 @@ import {
   DataAvailabilitySchema,
   ChatEngagementStatsSchema,
   ChatSpikeSchema,
+  ChatMomentumPointSchema,
   UserSegmentStatsSchema,
   TopChatterSchema,
+  UserStreamActivitySchema,
   TimePatternStatsSchema,
   ChatterBehaviorStatsSchema,
   ChatAnalyticsQuerySchema,
@@ -20,14 +22,20 @@ import {
   ChatterScoreResultSchema,
   AnomalyResultSchema,
   ChatMessageSchema,
+  AggregateViewerPointSchema,
+  CategoryStatSchema,
+  HeatmapCellSchema,
+  ChatBucketSchema,
   type BroadcasterAnalytics,
   type GameAnalytics,
   type DailyStats,
   type DataAvailability,
   type ChatEngagementStats,
   type ChatSpike,
+  type ChatMomentumPoint,
   type UserSegmentStats,
   type TopChatter,
+  type UserStreamActivity,
   type TimePatternStats,
   type ChatterBehaviorStats,
   type ChatAnalyticsQuery,
@@ -39,6 +47,10 @@ import {
   type ChatterScoreResult,
   type AnomalyResult,
   type ChatMessage,
+  type AggregateViewerPoint,
+  type CategoryStat,
+  type HeatmapCell,
+  type ChatBucket,
 } from '../schemas';
 
 // ========== Broadcaster & Game Analytics ==========
@@ -111,17 +123,57 @@ export const getChannelDailyStats = async (params: {
   return z.array(DailyStatsSchema).parse(result);
 };
 
+/**
+ * 複数チャンネルの合計同時視聴者数の推移を取得（配信していないチャンネルは0として合算）
+ */
+export const getAggregateViewers = async (params: {
+  channelIds: number[];
+  from: string;
+  to: string;
+  bucketMinutes?: number;
+}): Promise<AggregateViewerPoint[]> => {
+  const result = await invoke<unknown>('get_aggregate_viewers', {
+    channelIds: params.channelIds,
+    from: params.from,
+    to: params.to,
+    bucketMinutes: params.bucketMinutes,
+  });
+  return z.array(AggregateViewerPointSchema).parse(result);
+};
+
+/**
+ * チャンネルのカテゴリ（ゲーム）別累計統計を取得（MW降順）
+ */
+export const getCategoryStats = async (channelId: number): Promise<CategoryStat[]> => {
+  const result = await invoke<unknown>('get_category_stats', { channelId });
+  return z.array(CategoryStatSchema).parse(result);
+};
+
+/**
+ * 配信開始の曜日×時間帯ごとの平均視聴者数を取得（ローカル時刻基準の 7x24 グリッド）
+ */
+export const getViewerHeatmap = async (channelId: number): Promise<HeatmapCell[]> => {
+  const result = await invoke<unknown>('get_viewer_heatmap', { channelId });
+  return z.array(HeatmapCellSchema).parse(result);
+};
+
 // ========== Chat Analytics ==========
 
+/** チャット分析コマンド共通の絞り込み条件（Rust 側の ChatAnalyticsQuery） */
+const toChatAnalyticsFilter = (query: ChatAnalyticsQuery) => ({
+  channel_id: query.channelId,
+  stream_id: query.streamId,
+  start_time: query.startTime,
+  end_time: query.endTime,
+  exclude_bots: query.excludeBots,
+});
+
 export const getChatEngagementTimeline = async (
   query: ChatAnalyticsQuery
 ): Promise<ChatEngagementStats[]> => {
   const validatedQuery = ChatAnalyticsQuerySchema.parse(query);
   const result = await invoke<unknown>('get_chat_engagement_timeline', {
-    channelId: validatedQuery.channelId,
-    streamId: validatedQuery.streamId,
-    startTime: validatedQuery.startTime,
-    endTime: validatedQuery.endTime,
+    query: toChatAnalyticsFilter(validatedQuery),
     intervalMinutes: validatedQuery.intervalMinutes ?? 5,
   });
   return z.array(ChatEngagementStatsSchema).parse(result);
@@ -132,24 +184,42 @@ export const detectChatSpikes = async (
 ): Promise<ChatSpike[]> => {
   const validatedQuery = ChatAnalyticsQuerySchema.parse(query);
   const result = await invoke<unknown>('detect_chat_spikes', {
-    channelId: validatedQuery.channelId,
-    streamId: validatedQuery.streamId,
-    startTime: validatedQuery.startTime,
-    endTime: validatedQuery.endTime,
+    query: toChatAnalyticsFilter(validatedQuery),
     minSpikeRatio: validatedQuery.minSpikeRatio ?? 2.0,
   });
   return z.array(ChatSpikeSchema).parse(result);
 };
 
+export const getChatMomentum = async (streamId: number): Promise<ChatMomentumPoint[]> => {
+  const result = await invoke<unknown>('get_chat_momentum', { streamId });
+  return z.array(ChatMomentumPointSchema).parse(result);
+};
+
+/**
+ * 配信のチャットを時間バケットごとに集計（感情分析の前段となる生データ）
+ * @param keywords 出現メッセージ数を数えるキーワード（keywordCounts は指定順）
+ */
+export const getChatAggregates = async (params: {
+  streamId: number;
+  bucketSeconds?: number;
+  keywords?: string[];
+  excludeBots?: boolean;
+}): Promise<ChatBucket[]> => {
+  const result = await invoke<unknown>('get_chat_aggregates', {
+    streamId: params.streamId,
+    bucketSeconds: params.bucketSeconds,
+    keywords: params.keywords,
+    excludeBots: params.excludeBots,
+  });
+  return z.array(ChatBucketSchema).parse(result);
+};
+
 export const getUserSegmentStats = async (
   query: ChatAnalyticsQuery
 ): Promise<UserSegmentStats[]> => {
   const validatedQuery = ChatAnalyticsQuerySchema.parse(query);
   const result = await invoke<unknown>('get_user_segment_stats', {
-    channelId: validatedQuery.channelId,
-    streamId: validatedQuery.streamId,
-    startTime: validatedQuery.startTime,
-    endTime: validatedQuery.endTime,
+    query: toChatAnalyticsFilter(validatedQuery),
   });
   return z.array(UserSegmentStatsSchema).parse(result);
 };
@@ -159,15 +229,20 @@ export const getTopChatters = async (
 ): Promise<TopChatter[]> => {
   const validatedQuery = ChatAnalyticsQuerySchema.parse(query);
   const result = await invoke<unknown>('get_top_chatters', {
-    channelId: validatedQuery.channelId,
-    streamId: validatedQuery.streamId,
-    startTime: validatedQuery.startTime,
-    endTime: validatedQuery.endTime,
+    query: toChatAnalyticsFilter(validatedQuery),
     limit: validatedQuery.limit ?? 50,
   });
   return z.array(TopChatterSchema).parse(result);
 };
 
+export const getUserActivity = async (
+  userId: string,
+  limit?: number
+): Promise<UserStreamActivity[]> => {
+  const result = await invoke<unknown>('get_user_activity', { userId, limit });
+  return z.array(UserStreamActivitySchema).parse(result);
+};
+
 export const getTimePatternStats = async (
   query: ChatAnalyticsQuery
 ): Promise<TimePatternStats[]> => {
@@ -177,6 +252,7 @@ export const getTimePatternStats = async (
     startTime: validatedQuery.startTime,
     endTime: validatedQuery.endTime,
     groupByDay: validatedQuery.groupByDay ?? false,
+    excludeBots: validatedQuery.excludeBots,
   });
   return z.array(TimePatternStatsSchema).parse(result);
 };
@@ -189,6 +265,7 @@ export const getChatterBehaviorStats = async (
     channelId: validatedQuery.channelId,
     startTime: validatedQuery.startTime,
     endTime: validatedQuery.endTime,
+    excludeBots: validatedQuery.excludeBots,
   });
   return ChatterBehaviorStatsSchema.parse(result);
 };
@@ -283,16 +360,12 @@ export const detectAnomalies = async (params: {
 
 // ========== Real-time Statistics ==========
 
-export const getRealtimeChatRate = async (): Promise<
-  { channel_id: number; stream_id: number; chat_rate_1min: number }[]
-> => {
-  const RealtimeChatRateItemSchema = z.object({
-    channel_id: z.number(),
-    stream_id: z.number(),
-    chat_rate_1min: z.number(),
-  });
-  const result = await invoke<unknown>('get_realtime_chat_rate');
-  return z.array(RealtimeChatRateItemSchema).parse(result);
+/**
+ * 直近1分間のチャットメッセージ数を取得（streamId 指定時はその配信のみ）
+ */
+export const getRealtimeChatRate = async (streamId?: number): Promise<number> => {
+  const result = await invoke<unknown>('get_realtime_chat_rate', { streamId });
+  return z.number().parse(result);
 };
 
 export const getChatMessagesAroundTimestamp = async (params: {
@@ -1,6 +1,17 @@
 import { invoke } from '@tauri-apps/api/core';
-import { StreamInfoSchema, StreamTimelineDataSchema } from '../schemas';
-import type { StreamInfo, StreamTimelineData } from '../types';
+import { z } from 'zod';
+import {
+  CategoryDurationSchema,
+  FragmentedStreamGroupSchema,
+  StreamInfoSchema,
+  StreamTimelineDataSchema,
+} from '../schemas';
+import type {
+  CategoryDuration,
+  FragmentedStreamGroup,
+  StreamInfo,
+  StreamTimelineData,
+} from '../types';
 
 /**
  * チャンネルの配信一覧を取得
@@ -22,12 +33,17 @@ export const getChannelStreams = async (params: {
 
 /**
  * 配信のタイムラインデータを取得
+ * fillGaps を指定すると intervalSeconds（既定60秒）間隔に揃え、欠損を直前の値で補間する
+ * （2間隔を超えて収集が途切れた時刻は数値が null になる）
  */
 export const getStreamTimeline = async (
-  streamId: number
+  streamId: number,
+  options?: { fillGaps?: boolean; intervalSeconds?: number }
 ): Promise<StreamTimelineData> => {
   const result = await invoke<unknown>('get_stream_timeline', {
     streamId,
+    fillGaps: options?.fillGaps,
+    intervalSeconds: options?.intervalSeconds,
   });
   return StreamTimelineDataSchema.parse(result);
 };
@@ -55,16 +71,50 @@ export const getStreamsByDateRange = async (params: {
 
 /**
  * 比較用：基準配信と時間帯・カテゴリが近い配信をサジェスト（全チャンネル）
+ * viewer_range を指定すると peak_viewers が基準配信の [下限倍率, 上限倍率] の範囲内の配信に絞り込む
  */
 export const getSuggestedStreamsForComparison = async (params: {
   base_stream_id: number;
   limit?: number;
+  viewer_range?: [number, number];
 }): Promise<StreamInfo[]> => {
   const result = await invoke<unknown>('get_suggested_streams_for_comparison', {
     baseStreamId: params.base_stream_id,
     limit: params.limit ?? 50,
+    viewerRange: params.viewer_range,
   });
   return Array.isArray(result)
     ? result.map((r) => StreamInfoSchema.parse(r))
     : [];
 };
+
+/**
+ * 瞬断などで複数の配信レコードに分断されたとみられる配信のグループを取得
+ * maxGapMinutes 省略時はバックエンドの既定値（10分）以内の間隔を分断とみなす
+ */
+export const getFragmentedStreams = async (
+  channelId: number,
+  maxGapMinutes?: number
+): Promise<FragmentedStreamGroup[]> => {
+  const result = await invoke<unknown>('get_fragmented_streams', {
+    channelId,
+    maxGapMinutes,
+  });
+  return z.array(FragmentedStreamGroupSchema).parse(result);
+};
+
+/**
+ * 同一チャンネルの複数の配信を1本に統合し、統合先の配信IDを返す
+ */
+export const mergeStreams = async (streamIds: number[]): Promise<number> => {
+  const result = await invoke<unknown>('merge_streams', { streamIds });
+  return z.number().parse(result);
+};
+
+/**
+ * 配信内のカテゴリ別の合計時間（分）を長い順に取得
+ */
+export const getCategoryDurations = async (streamId: number): Promise<CategoryDuration[]> => {
+  const result = await invoke<unknown>('get_category_durations', { streamId });
+  return z.array(CategoryDurationSchema).parse(result);
+};
@@ -64,3 +64,17 @@ export interface GetLogsQuery {
 export async function getLogs(query: GetLogsQuery): Promise<LogEntry[]> {
   return await invoke<LogEntry[]>("get_logs", { query });
 }
+
+export interface ChatConnectionStatus {
+  channel_id: number;
+  channel_name: string;
+  connected_since: string | null;
+  messages_collected: number;
+}
+
+/**
+ * 接続中（JOIN済み）のTwitch IRCチャンネル一覧と収集状況を取得
+ */
+export async function getActiveChatConnections(): Promise<ChatConnectionStatus[]> {
+  return await invoke<ChatConnectionStatus[]>("get_active_chat_connections");
+}
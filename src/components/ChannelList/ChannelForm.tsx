@@ -63,12 +63,12 @@ export function ChannelForm({ onSuccess, onCancel }: ChannelFormProps) {
     setValidatedInfo(null);
 
     try {
-      // Get access token from Stronghold
+      // Get access token from the OS keychain
       let accessToken: string | null = null;
       try {
         accessToken = await getToken('twitch');
       } catch (e) {
-        console.error('Failed to get token from Stronghold:', e);
+        console.error('Failed to get token from keychain:', e);
       }
 
       const info = await configApi.validateTwitchChannel(
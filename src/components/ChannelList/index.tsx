@@ -30,8 +30,8 @@ export function ChannelList() {
 
   // チャンネル削除ミューテーション（削除後に一覧を再取得してUIを1回で更新）
   const deleteMutation = useMutation({
-    mutationFn: async (channelId: number) => {
-      await channelsApi.removeChannel(channelId);
+    mutationFn: async ({ channelId, deleteData }: { channelId: number; deleteData: boolean }) => {
+      await channelsApi.removeChannel(channelId, deleteData);
     },
     onSuccess: async () => {
       queryClient.invalidateQueries({ queryKey: ["channels"] });
@@ -59,8 +59,15 @@ export function ChannelList() {
     });
     
     if (confirmed) {
+      const deleteData = await confirm({
+        title: '過去データの削除',
+        message: 'このチャンネルの配信・統計・チャットの記録も削除しますか？\n「データを残す」を選ぶと、チャンネルは一覧から消えますが過去の分析データは保持されます。',
+        confirmText: 'データも削除',
+        cancelText: 'データを残す',
+        type: 'danger',
+      });
       try {
-        await deleteMutation.mutateAsync(channelId);
+        await deleteMutation.mutateAsync({ channelId, deleteData });
       } catch (error) {
         toast.error("チャンネルの削除に失敗しました: " + String(error));
       }
@@ -258,6 +258,13 @@ export function Dashboard() {
     refetchInterval: 10000, // 10秒ごとに更新
   });
 
+  // 配信予定を取得（スケジュールはバックエンドが1時間ごとに更新する）
+  const { data: scheduledStreams } = useQuery({
+    queryKey: ["scheduled-streams"],
+    queryFn: () => channelsApi.getScheduledStreams(),
+    refetchInterval: 60000, // 1分ごとに更新
+  });
+
   // Twitch APIレート制限状態を取得
   const { data: rateLimitStatus } = useQuery({
     queryKey: ["twitch-rate-limit"],
@@ -265,6 +272,13 @@ export function Dashboard() {
     refetchInterval: 5000, // 5秒ごとに更新
   });
 
+  // YouTube APIクォータ使用量を取得
+  const { data: youtubeQuota } = useQuery({
+    queryKey: ["youtube-quota"],
+    queryFn: configApi.getYouTubeQuotaUsage,
+    refetchInterval: 30000, // 30秒ごとに更新
+  });
+
   // 自動発見された配信を取得
   const { data: discoveredStreams, isLoading: isLoadingDiscovered } = useQuery({
     queryKey: ["discovered-streams"],
@@ -499,6 +513,29 @@ export function Dashboard() {
               </div>
             </CustomTooltip>
           )}
+
+          {/* YouTube APIクォータインジケーター（使用量がある場合のみ表示） */}
+          {youtubeQuota && youtubeQuota.used > 0 && (
+            <CustomTooltip content={
+              <div className="text-xs space-y-1">
+                <div className="font-semibold mb-1">YouTube APIクォータ（推定）</div>
+                <div>使用: {youtubeQuota.used} / {youtubeQuota.estimated_limit} ユニット</div>
+                <div>残り: {youtubeQuota.remaining} ユニット</div>
+                <div>リセット: {new Date(youtubeQuota.resets_at).toLocaleString('ja-JP')}</div>
+                {youtubeQuota.adaptive && <div>アダプティブモード有効</div>}
+              </div>
+            }>
+              <div className="flex items-center space-x-2 px-3 py-2 rounded-lg bg-gray-50 dark:bg-slate-800 hover:bg-gray-100 dark:hover:bg-slate-700 transition-colors cursor-help">
+                <div className={`w-2 h-2 rounded-full ${getRateLimitColor(youtubeQuota.usage_percent)}`}></div>
+                <div className="text-xs">
+                  <div className="font-medium text-gray-600 dark:text-gray-400">YouTube</div>
+                  <div className={`font-semibold ${getRateLimitTextColor(youtubeQuota.usage_percent)}`}>
+                    {youtubeQuota.used}/{youtubeQuota.estimated_limit}
+                  </div>
+                </div>
+              </div>
+            </CustomTooltip>
+          )}
           
           <div className="text-right">
             <div className="text-sm font-medium text-gray-600 dark:text-gray-400">最終更新</div>
@@ -617,6 +654,43 @@ export function Dashboard() {
           )}
       </div>
 
+      {/* 配信予定 */}
+      {scheduledStreams && scheduledStreams.length > 0 && (
+        <div className="card p-6 animate-fade-in mt-6">
+          <div className="flex items-center justify-between mb-4">
+            <h3 className="text-lg font-semibold text-gray-900 dark:text-gray-100">配信予定</h3>
+            <span className="text-xs font-medium text-gray-500 dark:text-gray-400 bg-gray-100 dark:bg-slate-700 px-2 py-1 rounded-full">
+              {scheduledStreams.length}件
+            </span>
+          </div>
+          <ul className="divide-y divide-gray-100 dark:divide-slate-700">
+            {scheduledStreams.map((schedule) => (
+              <li
+                key={`${schedule.channel_id}-${schedule.scheduled_start}`}
+                className="py-2 flex items-center justify-between gap-4"
+              >
+                <div className="min-w-0">
+                  <p className="text-sm font-medium text-gray-900 dark:text-gray-100 truncate">
+                    {schedule.display_name ?? schedule.channel_name}
+                  </p>
+                  <p className="text-xs text-gray-500 dark:text-gray-400 truncate">
+                    {[schedule.title, schedule.category].filter(Boolean).join(" / ") || "タイトル未定"}
+                  </p>
+                </div>
+                <span className="text-sm text-gray-700 dark:text-gray-300 whitespace-nowrap">
+                  {new Date(schedule.scheduled_start).toLocaleString([], {
+                    month: "numeric",
+                    day: "numeric",
+                    hour: "2-digit",
+                    minute: "2-digit",
+                  })}
+                </span>
+              </li>
+            ))}
+          </ul>
+        </div>
+      )}
+
       {/* 自動発見された配信 */}
       <div className="card p-6 animate-fade-in mt-6">
         <div className="flex flex-col gap-4 mb-6">
@@ -15,6 +15,8 @@ export function AutoDiscoveryForm() {
       languages: [],
       min_viewers: 0,
     },
+    auto_collect: false,
+    max_auto_collect: 10,
   });
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
@@ -322,6 +324,45 @@ export function AutoDiscoveryForm() {
             </p>
           </div>
 
+          {/* 自動収集 */}
+          <div>
+            <label className="flex items-center space-x-3">
+              <input
+                type="checkbox"
+                checked={settings.auto_collect}
+                onChange={(e) =>
+                  setSettings((prev) => ({
+                    ...prev,
+                    auto_collect: e.target.checked,
+                  }))
+                }
+                className="rounded border-gray-300 text-blue-600 shadow-sm focus:border-blue-300 focus:ring focus:ring-blue-200 focus:ring-opacity-50 dark:border-gray-600 dark:bg-gray-700 dark:focus:ring-gray-600"
+              />
+              <span className="text-sm font-medium text-gray-700 dark:text-gray-300">
+                発見した配信を自動で収集する
+              </span>
+            </label>
+            <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
+              配信終了で収集を解除します（手動登録済みのチャンネルは対象外）
+            </p>
+            {settings.auto_collect && (
+              <input
+                type="number"
+                min="1"
+                max="100"
+                value={settings.max_auto_collect}
+                onChange={(e) =>
+                  setSettings((prev) => ({
+                    ...prev,
+                    max_auto_collect: parseInt(e.target.value) || 10,
+                  }))
+                }
+                className="input-field mt-2"
+                aria-label="自動収集の上限チャンネル数"
+              />
+            )}
+          </div>
+
           {/* 最小視聴者数 */}
           <div>
             <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
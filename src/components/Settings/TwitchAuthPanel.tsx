@@ -39,15 +39,22 @@ export function TwitchAuthPanel({ onClose, onSuccess }: TwitchAuthPanelProps) {
   useEffect(() => {
     let unlistenSuccess: (() => void) | undefined;
     let unlistenRequired: (() => void) | undefined;
+    let unlistenProgress: (() => void) | undefined;
 
     const setupListeners = async () => {
+      // ポーリング進捗イベント（バックエンドの残り時間に同期）
+      unlistenProgress = await listen<{ remaining_secs: number; expires_in: number }>(
+        'twitch-auth-progress',
+        (event) => {
+          setTimeRemaining(event.payload.remaining_secs);
+        }
+      );
+
       // 認証成功イベント
       unlistenSuccess = await listen('twitch-auth-success', async () => {
         console.log('[TwitchAuthPanel] Received twitch-auth-success event');
 
-        // Strongholdへの保存が完全に完了するまで少し待機
-        await new Promise((resolve) => setTimeout(resolve, 800));
-
+        // トークンはキーチェーンへの保存後にイベントが発行されるため、待機せずに確認できる
         console.log('[TwitchAuthPanel] Checking token status after auth success...');
 
         // トークンステータスを更新
@@ -105,6 +112,9 @@ export function TwitchAuthPanel({ onClose, onSuccess }: TwitchAuthPanelProps) {
       if (unlistenRequired) {
         unlistenRequired();
       }
+      if (unlistenProgress) {
+        unlistenProgress();
+      }
     };
   }, [checkTokens, onSuccess, onClose]);
 
@@ -140,7 +150,8 @@ export function TwitchAuthPanel({ onClose, onSuccess }: TwitchAuthPanelProps) {
       await configApi.pollTwitchDeviceToken(
         authStatus.device_code,
         authStatus.interval,
-        await getClientId()
+        await getClientId(),
+        authStatus.expires_in
       );
       
       console.log('[TwitchAuthPanel] Token polling completed, waiting for event...');
@@ -37,7 +37,7 @@ const TimelineChart: React.FC<TimelineChartProps> = ({ timelineData }) => {
       return {
         time: timeStr,
         timestamp: stat.collected_at,
-        viewers: stat.viewer_count || 0,
+        viewers: stat.viewer_count,
         chatRate: stat.chat_rate_1min,
         followers: currentFollowers,
         followerGain: followerGain,
@@ -59,10 +59,10 @@ const TimelineChart: React.FC<TimelineChartProps> = ({ timelineData }) => {
           <p className="font-medium text-gray-900 dark:text-white mb-2">{data.time}</p>
           <div className="space-y-1 text-sm">
             <p className="text-blue-600 dark:text-blue-400">
-              視聴者数: <span className="font-medium">{data.viewers.toLocaleString()}</span>
+              視聴者数: <span className="font-medium">{data.viewers?.toLocaleString() ?? '-'}</span>
             </p>
             <p className="text-green-600 dark:text-green-400">
-              チャットレート: <span className="font-medium">{data.chatRate}</span>
+              チャットレート: <span className="font-medium">{data.chatRate ?? '-'}</span>
             </p>
             <p className="text-purple-600 dark:text-purple-400">
               フォロワー増減: <span className="font-medium">{followerGainDisplay}</span>
@@ -3,7 +3,7 @@ import { z } from 'zod';
 /**
  * Platform enum
  */
-export const PlatformSchema = z.enum(['twitch', 'youtube']);
+export const PlatformSchema = z.enum(['twitch', 'youtube', 'kick', 'niconico']);
 
 /**
  * Channel schema (database model)
@@ -25,6 +25,9 @@ export const ChannelSchema = z.object({
   twitch_user_id: z.number().optional(),
   created_at: z.string(),
   updated_at: z.string(),
+  collect_chat: z.boolean().default(true),
+  collect_stats: z.boolean().default(true),
+  alert_viewer_threshold: z.number().nullable().optional(),
 });
 
 /**
@@ -34,6 +37,63 @@ export const ChannelWithStatsSchema = ChannelSchema.extend({
   is_live: z.boolean(),
   current_viewers: z.number(),
   current_title: z.string(),
+  tags: z.array(z.string()).default([]),
+});
+
+/**
+ * Live channel schema (ongoing stream with latest stats)
+ */
+export const LiveChannelSchema = z.object({
+  channel_id: z.number(),
+  platform: z.string(),
+  channel_name: z.string(),
+  display_name: z.string().nullable(),
+  stream_id: z.number(),
+  started_at: z.string(),
+  viewer_count: z.number().nullable(),
+  title: z.string().nullable(),
+  category: z.string().nullable(),
+  last_collected_at: z.string().nullable(),
+});
+
+/**
+ * Scheduled stream schema (upcoming stream from platform schedule, times in UTC)
+ */
+export const ScheduledStreamSchema = z.object({
+  channel_id: z.number(),
+  platform: z.string(),
+  channel_name: z.string(),
+  display_name: z.string().nullable(),
+  scheduled_start: z.string(),
+  scheduled_end: z.string().nullable(),
+  title: z.string().nullable(),
+  category: z.string().nullable(),
+});
+
+/**
+ * Stream summary for a period (streams started within the period)
+ */
+export const StreamPeriodSummarySchema = z.object({
+  stream_count: z.number(),
+  total_duration_minutes: z.number(),
+  avg_viewers: z.number(),
+  peak_viewers: z.number(),
+  minutes_watched: z.number(),
+  follower_gain: z.number(),
+});
+
+/**
+ * Channel summary (all time, last 30 days and the previous 30 days; change rates are 0.1 = +10%)
+ */
+export const ChannelSummarySchema = z.object({
+  channel_id: z.number(),
+  all_time: StreamPeriodSummarySchema,
+  last_30_days: StreamPeriodSummarySchema,
+  previous_30_days: StreamPeriodSummarySchema,
+  stream_count_change: z.number().nullable(),
+  duration_change: z.number().nullable(),
+  avg_viewers_change: z.number().nullable(),
+  minutes_watched_change: z.number().nullable(),
 });
 
 /**
@@ -55,11 +115,17 @@ export const UpdateChannelRequestSchema = z.object({
   channel_name: z.string().optional(),
   poll_interval: z.number().optional(),
   enabled: z.boolean().optional(),
+  collect_chat: z.boolean().optional(),
+  collect_stats: z.boolean().optional(),
 });
 
 // Export types
 export type Platform = z.infer<typeof PlatformSchema>;
 export type Channel = z.infer<typeof ChannelSchema>;
 export type ChannelWithStats = z.infer<typeof ChannelWithStatsSchema>;
+export type LiveChannel = z.infer<typeof LiveChannelSchema>;
+export type ScheduledStream = z.infer<typeof ScheduledStreamSchema>;
+export type StreamPeriodSummary = z.infer<typeof StreamPeriodSummarySchema>;
+export type ChannelSummary = z.infer<typeof ChannelSummarySchema>;
 export type AddChannelRequest = z.infer<typeof AddChannelRequestSchema>;
 export type UpdateChannelRequest = z.infer<typeof UpdateChannelRequestSchema>;
@@ -65,6 +65,26 @@ export const ChatSpikeSchema = z.object({
   prevCount: z.number(),
 });
 
+/**
+ * Chat momentum schema (per-minute z-score of chat count)
+ */
+export const ChatMomentumPointSchema = z.object({
+  timestamp: z.string(),
+  chatCount: z.number(),
+  score: z.number(),
+});
+
+/**
+ * Chat aggregate bucket schema (raw counts for client-side sentiment analysis)
+ */
+export const ChatBucketSchema = z.object({
+  bucket: z.string(),
+  messageCount: z.number(),
+  uniqueUsers: z.number(),
+  emoteCount: z.number(),
+  keywordCounts: z.array(z.number()),
+});
+
 /**
  * User segment enum
  */
@@ -95,6 +115,21 @@ export const TopChatterSchema = z.object({
   streamCount: z.number(),
 });
 
+/**
+ * User activity across streams schema
+ */
+export const UserStreamActivitySchema = z.object({
+  streamId: z.number(),
+  channelId: z.number().nullable(),
+  channelName: z.string().nullable(),
+  streamTitle: z.string().nullable(),
+  streamStartedAt: z.string().nullable(),
+  userName: z.string(),
+  messageCount: z.number(),
+  firstMessageAt: z.string(),
+  lastMessageAt: z.string(),
+});
+
 /**
  * Time pattern stats schema
  */
@@ -129,6 +164,8 @@ export const ChatAnalyticsQuerySchema = z.object({
   minSpikeRatio: z.number().optional(),
   limit: z.number().optional(),
   groupByDay: z.boolean().optional(),
+  // 設定の bot ユーザー名・パターンに一致するメッセージを集計から除外する
+  excludeBots: z.boolean().optional(),
 });
 
 // Export types
@@ -137,6 +174,9 @@ export type ChatMessagesQuery = z.infer<typeof ChatMessagesQuerySchema>;
 export type AggregatedChatStats = z.infer<typeof AggregatedChatStatsSchema>;
 export type ChatEngagementStats = z.infer<typeof ChatEngagementStatsSchema>;
 export type ChatSpike = z.infer<typeof ChatSpikeSchema>;
+export type ChatMomentumPoint = z.infer<typeof ChatMomentumPointSchema>;
+export type ChatBucket = z.infer<typeof ChatBucketSchema>;
+export type UserStreamActivity = z.infer<typeof UserStreamActivitySchema>;
 export type UserSegment = z.infer<typeof UserSegmentSchema>;
 export type UserSegmentStats = z.infer<typeof UserSegmentStatsSchema>;
 export type TopChatter = z.infer<typeof TopChatterSchema>;
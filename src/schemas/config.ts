@@ -40,6 +40,8 @@ export const CollectorStatusSchema = z.object({
   last_error: z.string().optional(),
   poll_count: z.number(),
   error_count: z.number(),
+  consecutive_failures: z.number(),
+  paused_until: z.string().nullable().optional(),
 });
 
 /**
@@ -54,9 +56,32 @@ export const TwitchRateLimitStatusSchema = z.object({
   request_count: z.number(),
 });
 
+/**
+ * YouTube Data API quota usage schema
+ */
+export const YouTubeQuotaStatusSchema = z.object({
+  used: z.number(),
+  estimated_limit: z.number(),
+  remaining: z.number(),
+  usage_percent: z.number(),
+  resets_at: z.string(),
+  adaptive: z.boolean(),
+});
+
+/**
+ * Chat filter (bot exclusion) settings schema
+ */
+export const ChatFilterSettingsSchema = z.object({
+  bot_user_names: z.array(z.string()),
+  message_patterns: z.array(z.string()),
+  exclude_commands: z.boolean(),
+});
+
 // Export types
 export type OAuthConfig = z.infer<typeof OAuthConfigSchema>;
 export type DbInitStatus = z.infer<typeof DbInitStatusSchema>;
 export type DeviceAuthStatus = z.infer<typeof DeviceAuthStatusSchema>;
 export type CollectorStatus = z.infer<typeof CollectorStatusSchema>;
 export type TwitchRateLimitStatus = z.infer<typeof TwitchRateLimitStatusSchema>;
+export type YouTubeQuotaStatus = z.infer<typeof YouTubeQuotaStatusSchema>;
+export type ChatFilterSettings = z.infer<typeof ChatFilterSettingsSchema>;
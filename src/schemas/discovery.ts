@@ -52,6 +52,8 @@ export const AutoDiscoverySettingsSchema = z.object({
   poll_interval: z.number(),
   max_streams: z.number(),
   filters: AutoDiscoveryFiltersSchema,
+  auto_collect: z.boolean().default(false),
+  max_auto_collect: z.number().default(10),
 });
 
 // Export types
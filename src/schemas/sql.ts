@@ -10,6 +10,15 @@ export const SqlQueryResultSchema = z.object({
   execution_time_ms: z.number(),
 });
 
+/**
+ * SQL template parameter schema
+ */
+export const TemplateParamSchema = z.object({
+  name: z.string(),
+  type: z.enum(['text', 'integer', 'real', 'boolean', 'timestamp']),
+  default: z.unknown().optional().nullable(),
+});
+
 /**
  * SQL template schema
  */
@@ -18,6 +27,7 @@ export const SqlTemplateSchema = z.object({
   name: z.string(),
   description: z.string(),
   query: z.string(),
+  params: z.array(TemplateParamSchema).default([]),
   created_at: z.string(),
   updated_at: z.string(),
 });
@@ -30,6 +40,7 @@ export const SaveTemplateRequestSchema = z.object({
   name: z.string(),
   description: z.string(),
   query: z.string(),
+  params: z.array(TemplateParamSchema).optional(),
 });
 
 /**
@@ -42,6 +53,7 @@ export const TableInfoSchema = z.object({
 
 // Export types
 export type SqlQueryResult = z.infer<typeof SqlQueryResultSchema>;
+export type TemplateParam = z.infer<typeof TemplateParamSchema>;
 export type SqlTemplate = z.infer<typeof SqlTemplateSchema>;
 export type SaveTemplateRequest = z.infer<typeof SaveTemplateRequestSchema>;
 export type TableInfo = z.infer<typeof TableInfoSchema>;
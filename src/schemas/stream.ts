@@ -43,7 +43,7 @@ export const StreamInfoSchema = z.object({
   stream_id: z.string(),
   channel_id: z.number(),
   channel_name: z.string(),
-  platform: z.enum(['twitch', 'youtube']).optional(),
+  platform: z.enum(['twitch', 'youtube', 'kick', 'niconico']).optional(),
   title: z.string(),
   category: z.string(),
   started_at: z.string(),
@@ -56,6 +56,10 @@ export const StreamInfoSchema = z.object({
   total_chat_messages: z.number(),
   engagement_rate: z.number(),
   last_collected_at: z.string(),
+  thumbnail_url: z.string().nullable().optional(),
+  unique_chatters: z.number().optional(),
+  chatter_rate: z.number().optional(),
+  vod_url: z.string().nullable().optional(),
 });
 
 /**
@@ -63,11 +67,11 @@ export const StreamInfoSchema = z.object({
  */
 export const TimelinePointSchema = z.object({
   collected_at: z.string(),
-  viewer_count: z.number(),
-  chat_rate_1min: z.number(),
+  viewer_count: z.number().nullable(),
+  chat_rate_1min: z.number().nullable(),
   category: z.string(),
   title: z.string(),
-  follower_count: z.number(),
+  follower_count: z.number().nullable(),
 });
 
 /**
@@ -134,6 +138,61 @@ export const SelectedStreamSchema = z.object({
   color: z.string(),
 });
 
+/**
+ * Stream lifecycle event schema (stream-started / stream-ended)
+ */
+export const StreamLifecycleEventSchema = z.object({
+  channel_id: z.number(),
+  stream_id: z.number(),
+  platform_stream_id: z.string(),
+  title: z.string().nullable().optional(),
+});
+
+/**
+ * Aggregate viewers point schema (複数チャンネルの合計同時視聴者数)
+ */
+export const AggregateViewerPointSchema = z.object({
+  timestamp: z.string(),
+  total_viewers: z.number(),
+});
+
+// チャンネルのカテゴリ別累計統計
+export const CategoryStatSchema = z.object({
+  category: z.string(),
+  stream_count: z.number(),
+  avg_peak_viewers: z.number(),
+  avg_duration_minutes: z.number(),
+  minutes_watched: z.number(),
+});
+
+/**
+ * Viewer heatmap cell (weekday 0 = Sunday, hour in local time)
+ */
+export const HeatmapCellSchema = z.object({
+  weekday: z.number(),
+  hour: z.number(),
+  avg_viewers: z.number(),
+  stream_count: z.number(),
+});
+
+/**
+ * Group of stream records split by short disconnects (stream_ids ordered by start time)
+ */
+export const FragmentedStreamGroupSchema = z.object({
+  channel_id: z.number(),
+  stream_ids: z.array(z.number()),
+  started_at: z.string(),
+  ended_at: z.string().nullable(),
+});
+
+/**
+ * Total minutes spent in a category within a single stream
+ */
+export const CategoryDurationSchema = z.object({
+  category: z.string(),
+  minutes: z.number(),
+});
+
 // Export types
 export type StreamStats = z.infer<typeof StreamStatsSchema>;
 export type StreamStatsQuery = z.infer<typeof StreamStatsQuerySchema>;
@@ -146,3 +205,9 @@ export type StreamTimelineData = z.infer<typeof StreamTimelineDataSchema>;
 export type NormalizedTimelinePoint = z.infer<typeof NormalizedTimelinePointSchema>;
 export type ComparisonEvent = z.infer<typeof ComparisonEventSchema>;
 export type SelectedStream = z.infer<typeof SelectedStreamSchema>;
+export type StreamLifecycleEvent = z.infer<typeof StreamLifecycleEventSchema>;
+export type AggregateViewerPoint = z.infer<typeof AggregateViewerPointSchema>;
+export type CategoryStat = z.infer<typeof CategoryStatSchema>;
+export type HeatmapCell = z.infer<typeof HeatmapCellSchema>;
+export type FragmentedStreamGroup = z.infer<typeof FragmentedStreamGroupSchema>;
+export type CategoryDuration = z.infer<typeof CategoryDurationSchema>;
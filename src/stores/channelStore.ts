@@ -8,7 +8,7 @@ interface ChannelStore {
   error: string | null;
   fetchChannels: () => Promise<void>;
   addChannel: (channel: Omit<Channel, 'id' | 'created_at' | 'updated_at'>) => Promise<void>;
-  removeChannel: (id: number) => Promise<void>;
+  removeChannel: (id: number, deleteData?: boolean) => Promise<void>;
   updateChannel: (id: number, updates: Partial<Channel>) => Promise<void>;
   toggleChannel: (id: number) => Promise<void>;
 }
@@ -45,9 +45,9 @@ export const useChannelStore = create<ChannelStore>((set) => ({
     }
   },
 
-  removeChannel: async (id) => {
+  removeChannel: async (id, deleteData) => {
     try {
-      await channelsApi.removeChannel(id);
+      await channelsApi.removeChannel(id, deleteData);
       set((state) => ({
         channels: state.channels.filter((ch) => ch.id !== id),
       }));
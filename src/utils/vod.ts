@@ -0,0 +1,36 @@
+// 配信開始からの経過時間を VOD の再生位置パラメータ（?t=）に変換する
+const formatTwitchOffset = (seconds: number): string => {
+  const h = Math.floor(seconds / 3600);
+  const m = Math.floor((seconds % 3600) / 60);
+  const s = seconds % 60;
+  return `${h}h${m}m${s}s`;
+};
+
+/**
+ * VOD の該当時刻へのディープリンクを生成する
+ * startedAt: 配信開始時刻、at: ジャンプしたい時刻（タイムラインのハイライトなど）
+ * vodUrl が無い、または時刻が解釈できない場合は null
+ */
+export const buildVodDeepLink = (
+  vodUrl: string | null | undefined,
+  startedAt: string,
+  at: string
+): string | null => {
+  if (!vodUrl) return null;
+
+  const start = new Date(startedAt).getTime();
+  const target = new Date(at).getTime();
+  if (Number.isNaN(start) || Number.isNaN(target)) return null;
+
+  const offsetSeconds = Math.max(0, Math.floor((target - start) / 1000));
+
+  try {
+    const url = new URL(vodUrl);
+    // Twitch は 1h2m3s 形式、YouTube は秒数で指定する
+    const isTwitch = url.hostname.endsWith('twitch.tv');
+    url.searchParams.set('t', isTwitch ? formatTwitchOffset(offsetSeconds) : `${offsetSeconds}s`);
+    return url.toString();
+  } catch {
+    return null;
+  }
+};